@@ -0,0 +1,469 @@
+//! Architecture layering rules checked against the code graph
+//!
+//! Layers group files by glob and imported modules by pattern. Each layer
+//! lists the layers it may or must not depend on, and every `Imports` or
+//! `Calls` edge that crosses layers is checked against those rules.
+//!
+//! ```toml
+//! [[layers]]
+//! name = "domain"
+//! files = ["src/domain/**"]
+//! modules = ["app.domain.**"]
+//! must_not_depend_on = ["infra"]
+//!
+//! [[layers]]
+//! name = "infra"
+//! files = ["src/infra/**"]
+//! may_depend_on = ["domain"]
+//! ```
+
+use anyhow::{bail, Context, Result};
+use codeprism_core::glob::{glob_regex, name_glob_regex};
+use codeprism_core::{EdgeKind, GraphStore, Node, NodeKind};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Config files looked up in the repository root, in order
+pub const CONFIG_FILE_NAMES: &[&str] = &[
+    "architecture.toml",
+    "architecture.yaml",
+    "architecture.yml",
+    "architecture.json",
+];
+
+/// Declared architecture layers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchitectureConfig {
+    pub layers: Vec<Layer>,
+}
+
+/// A group of files and modules with its allowed dependencies
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Layer {
+    pub name: String,
+    /// Globs matched against file paths, e.g. `src/domain/**`
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Patterns matched against imported module names, e.g. `app.domain.*`
+    /// or `github.com/org/app/domain/**`
+    #[serde(default)]
+    pub modules: Vec<String>,
+    /// Layers this layer may depend on; any other layer is a violation
+    #[serde(default)]
+    pub may_depend_on: Option<Vec<String>>,
+    /// Layers this layer must not depend on
+    #[serde(default)]
+    pub must_not_depend_on: Vec<String>,
+}
+
+/// An edge from one layer to a layer it must not depend on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerViolation {
+    pub from_layer: String,
+    pub to_layer: String,
+    pub edge_kind: EdgeKind,
+    /// Import or call site that creates the dependency
+    pub source: Node,
+    /// Module or symbol depended on, or the import itself when it names a
+    /// module that was not resolved to a file
+    pub target: Node,
+}
+
+/// Number of `Imports` and `Calls` edges between each pair of layers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyMatrix {
+    pub layers: Vec<String>,
+    /// `counts[i][j]` is the number of edges from `layers[i]` to `layers[j]`
+    pub counts: Vec<Vec<usize>>,
+}
+
+/// Result of checking the layering rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchitectureReport {
+    /// Violations ordered by source file and position
+    pub violations: Vec<LayerViolation>,
+    pub matrix: DependencyMatrix,
+    /// Files that belong to no layer
+    pub unassigned_files: Vec<PathBuf>,
+}
+
+impl ArchitectureConfig {
+    /// Read a config from a TOML, YAML or JSON file, chosen by extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read architecture config {}", path.display()))?;
+        let config = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&content)?,
+            Some("json") => serde_json::from_str(&content)?,
+            _ => toml::from_str(&content)?,
+        };
+        Ok(config)
+    }
+
+    /// Find the config file in a repository root
+    pub fn find(root: impl AsRef<Path>) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| root.as_ref().join(name))
+            .find(|path| path.is_file())
+    }
+}
+
+/// Checks layering rules over the code graph
+pub struct ArchitectureAnalyzer {
+    config: ArchitectureConfig,
+    files: Vec<Vec<Regex>>,
+    modules: Vec<Vec<Regex>>,
+}
+
+impl ArchitectureAnalyzer {
+    /// Compile the layer patterns, rejecting unknown or duplicate layer names
+    pub fn new(config: ArchitectureConfig) -> Result<Self> {
+        let mut names = HashSet::new();
+        for layer in &config.layers {
+            if !names.insert(layer.name.as_str()) {
+                bail!("Duplicate architecture layer: {}", layer.name);
+            }
+        }
+        for layer in &config.layers {
+            let rules = layer.may_depend_on.iter().flatten();
+            for name in rules.chain(&layer.must_not_depend_on) {
+                if !names.contains(name.as_str()) {
+                    bail!("Layer {} refers to unknown layer {}", layer.name, name);
+                }
+            }
+        }
+
+        let files = config
+            .layers
+            .iter()
+            .map(|layer| layer.files.iter().map(|glob| glob_regex(glob)).collect())
+            .collect();
+        let modules = config
+            .layers
+            .iter()
+            .map(|layer| {
+                layer
+                    .modules
+                    .iter()
+                    .map(|pattern| module_regex(pattern))
+                    .collect()
+            })
+            .collect();
+
+        Ok(Self {
+            config,
+            files,
+            modules,
+        })
+    }
+
+    pub fn config(&self) -> &ArchitectureConfig {
+        &self.config
+    }
+
+    /// Check every `Imports` and `Calls` edge that crosses layers
+    pub fn analyze(&self, graph: &GraphStore) -> Result<ArchitectureReport> {
+        let layer_count = self.config.layers.len();
+        let mut counts = vec![vec![0; layer_count]; layer_count];
+        let mut violations = Vec::new();
+        let mut unassigned_files = Vec::new();
+
+        for (file, node_ids) in graph.iter_file_index() {
+            if self.file_layer(&file).is_none() {
+                unassigned_files.push(file);
+            }
+            for node_id in node_ids {
+                let Some(source) = graph.get_node(&node_id) else {
+                    continue;
+                };
+                let Some(from) = self.layer_of(&source) else {
+                    continue;
+                };
+                for edge in graph.get_outgoing_edges(&node_id) {
+                    if !matches!(edge.kind, EdgeKind::Imports | EdgeKind::Calls) {
+                        continue;
+                    }
+                    let Some(target) = graph.get_node(&edge.target) else {
+                        continue;
+                    };
+                    let Some(to) = self.layer_of(&target) else {
+                        continue;
+                    };
+                    if from == to {
+                        continue;
+                    }
+
+                    counts[from][to] += 1;
+                    if !self.allows(from, to) {
+                        violations.push(LayerViolation {
+                            from_layer: self.config.layers[from].name.clone(),
+                            to_layer: self.config.layers[to].name.clone(),
+                            edge_kind: edge.kind,
+                            // A module's import edge points at the import site
+                            source: if target.kind == NodeKind::Import {
+                                target.clone()
+                            } else {
+                                source.clone()
+                            },
+                            target,
+                        });
+                    }
+                }
+            }
+        }
+
+        violations.sort_by(|a, b| {
+            (&a.source.file, a.source.span.start_byte, &a.target.file).cmp(&(
+                &b.source.file,
+                b.source.span.start_byte,
+                &b.target.file,
+            ))
+        });
+        unassigned_files.sort();
+
+        Ok(ArchitectureReport {
+            violations,
+            matrix: DependencyMatrix {
+                layers: self
+                    .config
+                    .layers
+                    .iter()
+                    .map(|layer| layer.name.clone())
+                    .collect(),
+                counts,
+            },
+            unassigned_files,
+        })
+    }
+
+    /// Layer of a node: imports by the module they name, everything else by file
+    fn layer_of(&self, node: &Node) -> Option<usize> {
+        if node.kind == NodeKind::Import {
+            let module = node.name.trim_matches(|c| c == '"' || c == '\'');
+            let layer = self
+                .modules
+                .iter()
+                .position(|patterns| patterns.iter().any(|pattern| pattern.is_match(module)));
+            if layer.is_some() {
+                return layer;
+            }
+        }
+        self.file_layer(&node.file)
+    }
+
+    /// First layer whose file globs match
+    fn file_layer(&self, file: &Path) -> Option<usize> {
+        let path = file.to_string_lossy().replace('\\', "/");
+        self.files
+            .iter()
+            .position(|globs| globs.iter().any(|glob| glob.is_match(&path)))
+    }
+
+    fn allows(&self, from: usize, to: usize) -> bool {
+        let layer = &self.config.layers[from];
+        let target = &self.config.layers[to].name;
+        !layer.must_not_depend_on.contains(target)
+            && layer
+                .may_depend_on
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(target))
+    }
+}
+
+/// Regex for a module pattern, with `.`, `/` and `::` as separators
+fn module_regex(pattern: &str) -> Regex {
+    name_glob_regex(pattern, "./:")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeprism_core::ast::NodeBuilder;
+    use codeprism_core::{Edge, NodeId, Span};
+
+    /// Node on `line` of `file`; violations report the source line
+    fn node(graph: &GraphStore, kind: NodeKind, name: &str, file: &str, line: usize) -> NodeId {
+        let node = NodeBuilder::new("repo", kind)
+            .name(name)
+            .file(file)
+            .span(Span::new(line, line + 1, line, line, 1, 1))
+            .build();
+        let id = node.id;
+        graph.add_node(node);
+        id
+    }
+
+    fn layers_config() -> ArchitectureConfig {
+        toml::from_str(
+            r#"
+[[layers]]
+name = "api"
+files = ["src/api/**"]
+may_depend_on = ["domain"]
+
+[[layers]]
+name = "domain"
+files = ["src/domain/**"]
+modules = ["app.domain", "app.domain.**"]
+must_not_depend_on = ["infra"]
+
+[[layers]]
+name = "infra"
+files = ["src/infra/*.py"]
+modules = ["app.infra.*", "sqlalchemy"]
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_layer_violations_and_matrix() {
+        let graph = GraphStore::new();
+        let add = |source, target, kind| graph.add_edge(Edge::new(source, target, kind));
+
+        let api = node(
+            &graph,
+            NodeKind::Module,
+            "views",
+            "/repo/src/api/views.py",
+            1,
+        );
+        let handler = node(
+            &graph,
+            NodeKind::Function,
+            "get",
+            "/repo/src/api/views.py",
+            2,
+        );
+        let domain = node(
+            &graph,
+            NodeKind::Module,
+            "user",
+            "/repo/src/domain/user.py",
+            1,
+        );
+        let save = node(
+            &graph,
+            NodeKind::Function,
+            "save",
+            "/repo/src/domain/user.py",
+            2,
+        );
+        let infra = node(&graph, NodeKind::Module, "db", "/repo/src/infra/db.py", 1);
+        let insert = node(
+            &graph,
+            NodeKind::Function,
+            "insert",
+            "/repo/src/infra/db.py",
+            2,
+        );
+        let script = node(&graph, NodeKind::Module, "run", "/repo/scripts/run.py", 1);
+
+        // api imports domain, resolved to the module
+        let import = node(
+            &graph,
+            NodeKind::Import,
+            "app.domain.user",
+            "/repo/src/api/views.py",
+            3,
+        );
+        add(api, import, EdgeKind::Imports);
+        add(import, domain, EdgeKind::Imports);
+        // domain imports an infra module that was not resolved
+        let import = node(
+            &graph,
+            NodeKind::Import,
+            "sqlalchemy",
+            "/repo/src/domain/user.py",
+            3,
+        );
+        add(domain, import, EdgeKind::Imports);
+        // api and domain both call into infra
+        for (caller, file) in [
+            (handler, "/repo/src/api/views.py"),
+            (save, "/repo/src/domain/user.py"),
+        ] {
+            let call = node(&graph, NodeKind::Call, "insert", file, 4);
+            add(caller, call, EdgeKind::Calls);
+            add(call, insert, EdgeKind::Calls);
+        }
+        // Containment and unassigned files are not dependencies
+        add(infra, insert, EdgeKind::Calls);
+        add(script, insert, EdgeKind::Calls);
+
+        let analyzer = ArchitectureAnalyzer::new(layers_config()).unwrap();
+        let report = analyzer.analyze(&graph).unwrap();
+
+        let violations: Vec<(&str, &str, EdgeKind, &str, usize)> = report
+            .violations
+            .iter()
+            .map(|v| {
+                (
+                    v.from_layer.as_str(),
+                    v.to_layer.as_str(),
+                    v.edge_kind,
+                    v.target.name.as_str(),
+                    v.source.span.start_line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            violations,
+            vec![
+                ("api", "infra", EdgeKind::Calls, "insert", 4),
+                ("domain", "infra", EdgeKind::Imports, "sqlalchemy", 3),
+                ("domain", "infra", EdgeKind::Calls, "insert", 4),
+            ]
+        );
+        assert_eq!(report.matrix.layers, vec!["api", "domain", "infra"]);
+        assert_eq!(
+            report.matrix.counts,
+            vec![vec![0, 1, 1], vec![0, 0, 2], vec![0, 0, 0]]
+        );
+        assert_eq!(
+            report.unassigned_files,
+            vec![PathBuf::from("/repo/scripts/run.py")]
+        );
+    }
+
+    #[test]
+    fn test_config_validation_and_patterns() {
+        let mut config = layers_config();
+        config.layers[0].must_not_depend_on.push("web".to_string());
+        assert!(ArchitectureAnalyzer::new(config).is_err());
+
+        let mut config = layers_config();
+        config.layers[1].name = "api".to_string();
+        assert!(ArchitectureAnalyzer::new(config).is_err());
+
+        let files = glob_regex("src/infra/*.py");
+        assert!(files.is_match("/repo/src/infra/db.py"));
+        assert!(!files.is_match("/repo/src/infra/sql/db.py"));
+        assert!(!files.is_match("/repo/lib/src/infra.py"));
+        let modules = module_regex("app.infra.*");
+        assert!(modules.is_match("app.infra.db"));
+        assert!(!modules.is_match("app.infra.db.session"));
+        assert!(module_regex("github.com/org/app/**").is_match("github.com/org/app/infra/db"));
+    }
+
+    #[test]
+    fn test_config_from_yaml_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(ArchitectureConfig::find(temp_dir.path()).is_none());
+        std::fs::write(
+            temp_dir.path().join("architecture.yaml"),
+            "layers:\n  - name: domain\n    files: [\"domain/**\"]\n    must_not_depend_on: [infra]\n  - name: infra\n    files: [\"infra/**\"]\n",
+        )
+        .unwrap();
+
+        let path = ArchitectureConfig::find(temp_dir.path()).unwrap();
+        let config = ArchitectureConfig::from_file(path).unwrap();
+        assert_eq!(config.layers.len(), 2);
+        assert_eq!(config.layers[0].must_not_depend_on, vec!["infra"]);
+        assert!(config.layers[1].may_depend_on.is_none());
+    }
+}
@@ -0,0 +1,777 @@
+//! Call-graph analysis over the code graph: cycles, dead code and centrality
+//!
+//! Parsers link a caller to a `Call` node and the symbol resolver links that
+//! call site to its target, so the call graph here connects functions through
+//! `Call` nodes. `Import` nodes connect files the same way.
+
+use anyhow::{Context, Result};
+use codeprism_core::graph::is_test_file;
+use codeprism_core::{Edge, EdgeKind, GraphStore, Language, Node, NodeId, NodeKind};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+
+/// Call graphs larger than this use sampled sources for betweenness
+const MAX_EXACT_BETWEENNESS: usize = 2000;
+
+/// Which symbols count as entry points for reachability
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntryPoints {
+    /// Functions named `main`, and `init` in Go
+    pub main_functions: bool,
+    /// HTTP routes and their handlers
+    pub routes: bool,
+    /// Symbols in test files and test functions such as `test_*` or `TestX`
+    pub tests: bool,
+    /// Calls and imports made by top-level module code
+    pub module_code: bool,
+    /// Regexes matched against symbol names, for frameworks that call by convention
+    pub names: Vec<String>,
+}
+
+impl Default for EntryPoints {
+    fn default() -> Self {
+        Self {
+            main_functions: true,
+            routes: true,
+            tests: true,
+            module_code: true,
+            names: Vec::new(),
+        }
+    }
+}
+
+/// Functions that call each other in a cycle; one function means direct recursion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallCycle {
+    pub symbols: Vec<Node>,
+}
+
+/// Files that import each other in a cycle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportCycle {
+    pub files: Vec<PathBuf>,
+}
+
+/// Symbols reachable from the entry points and those that are not
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReachabilityReport {
+    /// Entry points that were found, excluding modules
+    pub entry_points: Vec<Node>,
+    /// Functions, methods and classes reachable from an entry point
+    pub reachable: usize,
+    /// Functions, methods and classes in the graph
+    pub total_symbols: usize,
+    /// Functions, methods and classes no entry point reaches
+    pub unreferenced: Vec<Node>,
+}
+
+/// Position of a function in the call graph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolCentrality {
+    pub node: Node,
+    /// Distinct functions calling this one
+    pub fan_in: usize,
+    /// Distinct functions this one calls
+    pub fan_out: usize,
+    /// Share of shortest call paths between other functions passing through
+    /// this one, from 0.0 to 1.0
+    pub betweenness: f64,
+}
+
+/// Everything the call-graph analyzer reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallGraphReport {
+    pub call_cycles: Vec<CallCycle>,
+    pub import_cycles: Vec<ImportCycle>,
+    pub reachability: ReachabilityReport,
+    /// Functions whose fan-out or betweenness exceeds the thresholds
+    pub god_functions: Vec<SymbolCentrality>,
+}
+
+/// Graph algorithms over calls and imports
+pub struct CallGraphAnalyzer {
+    pub entry_points: EntryPoints,
+    /// Fan-out from which a function counts as a god function
+    pub god_function_fan_out: usize,
+    /// Betweenness from which a function counts as a god function
+    pub god_function_betweenness: f64,
+}
+
+impl CallGraphAnalyzer {
+    pub fn new() -> Self {
+        Self {
+            entry_points: EntryPoints::default(),
+            god_function_fan_out: 10,
+            god_function_betweenness: 0.1,
+        }
+    }
+
+    /// Use custom entry points for reachability
+    pub fn with_entry_points(mut self, entry_points: EntryPoints) -> Self {
+        self.entry_points = entry_points;
+        self
+    }
+
+    /// Run every analysis
+    pub fn analyze(&self, graph: &GraphStore) -> Result<CallGraphReport> {
+        let centrality = self.centrality(graph);
+        Ok(CallGraphReport {
+            call_cycles: self.call_cycles(graph),
+            import_cycles: self.import_cycles(graph),
+            reachability: self.reachability(graph)?,
+            god_functions: self.god_functions(centrality),
+        })
+    }
+
+    /// Recursive functions and groups of mutually recursive functions
+    pub fn call_cycles(&self, graph: &GraphStore) -> Vec<CallCycle> {
+        let calls = CallGraph::build(graph);
+        strongly_connected_components(&calls.callees)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || calls.callees[component[0]].contains(&component[0])
+            })
+            .map(|component| CallCycle {
+                symbols: component
+                    .into_iter()
+                    .map(|index| calls.nodes[index].clone())
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Groups of files that import each other
+    pub fn import_cycles(&self, graph: &GraphStore) -> Vec<ImportCycle> {
+        let mut files: Vec<PathBuf> = graph.get_all_files();
+        files.sort();
+        let index: HashMap<&PathBuf, usize> = files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (file, i))
+            .collect();
+
+        let mut imports = vec![BTreeSet::new(); files.len()];
+        for (file, node_ids) in graph.iter_file_index() {
+            let Some(&from) = index.get(&file) else {
+                continue;
+            };
+            for node_id in node_ids {
+                for edge in graph.get_outgoing_edges(&node_id) {
+                    if edge.kind != EdgeKind::Imports {
+                        continue;
+                    }
+                    let target_file = graph.get_node(&edge.target).map(|node| node.file);
+                    if let Some(&to) = target_file.as_ref().and_then(|file| index.get(file)) {
+                        if to != from {
+                            imports[from].insert(to);
+                        }
+                    }
+                }
+            }
+        }
+
+        let adjacency: Vec<Vec<usize>> = imports
+            .into_iter()
+            .map(|set| set.into_iter().collect())
+            .collect();
+        strongly_connected_components(&adjacency)
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .map(|component| ImportCycle {
+                files: component.into_iter().map(|i| files[i].clone()).collect(),
+            })
+            .collect()
+    }
+
+    /// Functions, methods and classes that no entry point reaches
+    ///
+    /// References are followed through calls, imports, routes, inheritance
+    /// and data edges. A reachable class makes its members reachable and a
+    /// reachable member makes its class reachable. Calls the resolver could
+    /// not link, such as method calls on objects, reach every symbol with the
+    /// called name so that they do not cause false positives.
+    pub fn reachability(&self, graph: &GraphStore) -> Result<ReachabilityReport> {
+        let names = self
+            .entry_points
+            .names
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("Invalid entry point pattern: {pattern}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let symbols = symbols(graph);
+        let mut roots: Vec<Node> = symbols
+            .iter()
+            .filter(|node| self.is_entry_point(node, &names))
+            .cloned()
+            .collect();
+        if self.entry_points.routes {
+            roots.extend(graph.get_nodes_by_kind(NodeKind::Route));
+        }
+        let mut queue: VecDeque<NodeId> = roots.iter().map(|node| node.id).collect();
+        if self.entry_points.module_code {
+            queue.extend(
+                graph
+                    .get_nodes_by_kind(NodeKind::Module)
+                    .iter()
+                    .map(|node| node.id),
+            );
+        }
+
+        let mut visited: HashSet<NodeId> = queue.iter().copied().collect();
+        while let Some(id) = queue.pop_front() {
+            let Some(node) = graph.get_node(&id) else {
+                continue;
+            };
+            for next in references(graph, &node) {
+                if visited.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        sort_nodes(&mut roots);
+        let unreferenced: Vec<Node> = symbols
+            .iter()
+            .filter(|node| !visited.contains(&node.id))
+            .cloned()
+            .collect();
+        Ok(ReachabilityReport {
+            entry_points: roots,
+            reachable: symbols.len() - unreferenced.len(),
+            total_symbols: symbols.len(),
+            unreferenced,
+        })
+    }
+
+    /// Fan-in, fan-out and betweenness of every function and method, most
+    /// central first
+    pub fn centrality(&self, graph: &GraphStore) -> Vec<SymbolCentrality> {
+        let calls = CallGraph::build(graph);
+        let betweenness = betweenness(&calls.callees);
+
+        let mut fan_in = vec![0; calls.nodes.len()];
+        for callees in &calls.callees {
+            for &callee in callees {
+                fan_in[callee] += 1;
+            }
+        }
+
+        let mut centrality: Vec<SymbolCentrality> = calls
+            .nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, node)| SymbolCentrality {
+                node,
+                fan_in: fan_in[i],
+                fan_out: calls.callees[i].len(),
+                betweenness: betweenness[i],
+            })
+            .collect();
+        centrality.sort_by(|a, b| {
+            b.betweenness
+                .total_cmp(&a.betweenness)
+                .then(b.fan_out.cmp(&a.fan_out))
+                .then(b.fan_in.cmp(&a.fan_in))
+        });
+        centrality
+    }
+
+    /// Functions above the fan-out or betweenness threshold
+    pub fn god_functions(&self, centrality: Vec<SymbolCentrality>) -> Vec<SymbolCentrality> {
+        centrality
+            .into_iter()
+            .filter(|symbol| {
+                symbol.fan_out >= self.god_function_fan_out
+                    || symbol.betweenness >= self.god_function_betweenness
+            })
+            .collect()
+    }
+
+    fn is_entry_point(&self, node: &Node, names: &[Regex]) -> bool {
+        let entry = &self.entry_points;
+        let is_function = matches!(node.kind, NodeKind::Function | NodeKind::Method);
+        (entry.main_functions
+            && is_function
+            && (node.name == "main" || (node.lang == Language::Go && node.name == "init")))
+            || (entry.tests
+                && (is_test_file(&node.file) || is_function && is_test_name(&node.name)))
+            || names.iter().any(|name| name.is_match(&node.name))
+    }
+}
+
+impl Default for CallGraphAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Functions and methods with the calls between them
+struct CallGraph {
+    nodes: Vec<Node>,
+    /// Sorted, distinct callees of each node
+    callees: Vec<Vec<usize>>,
+}
+
+impl CallGraph {
+    fn build(graph: &GraphStore) -> Self {
+        let nodes: Vec<Node> = symbols(graph)
+            .into_iter()
+            .filter(|node| matches!(node.kind, NodeKind::Function | NodeKind::Method))
+            .collect();
+        let index: HashMap<NodeId, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.id, i))
+            .collect();
+
+        let callees = nodes
+            .iter()
+            .map(|node| {
+                let mut callees = BTreeSet::new();
+                for edge in calls(graph, &node.id) {
+                    match graph.get_node(&edge.target) {
+                        Some(target) if target.kind == NodeKind::Call => {
+                            callees.extend(
+                                calls(graph, &target.id)
+                                    .filter_map(|resolved| index.get(&resolved.target).copied()),
+                            );
+                        }
+                        Some(target) => callees.extend(index.get(&target.id).copied()),
+                        None => {}
+                    }
+                }
+                callees.into_iter().collect()
+            })
+            .collect();
+
+        Self { nodes, callees }
+    }
+}
+
+fn calls(graph: &GraphStore, id: &NodeId) -> impl Iterator<Item = Edge> {
+    graph
+        .get_outgoing_edges(id)
+        .into_iter()
+        .filter(|edge| edge.kind == EdgeKind::Calls)
+}
+
+/// Functions, methods and classes in a stable order
+fn symbols(graph: &GraphStore) -> Vec<Node> {
+    let mut nodes: Vec<Node> = [NodeKind::Function, NodeKind::Method, NodeKind::Class]
+        .into_iter()
+        .flat_map(|kind| graph.get_nodes_by_kind(kind))
+        .collect();
+    sort_nodes(&mut nodes);
+    nodes.dedup_by_key(|node| node.id);
+    nodes
+}
+
+fn sort_nodes(nodes: &mut [Node]) {
+    nodes.sort_by(|a, b| (&a.file, a.span.start_byte).cmp(&(&b.file, b.span.start_byte)));
+}
+
+fn is_test_name(name: &str) -> bool {
+    name.starts_with("test_")
+        || name
+            .strip_prefix("Test")
+            .is_some_and(|rest| rest.chars().next().is_some_and(|c| c.is_uppercase()))
+}
+
+/// Nodes that `node` makes reachable
+fn references(graph: &GraphStore, node: &Node) -> Vec<NodeId> {
+    let mut next = Vec::new();
+    let mut resolved = false;
+    for edge in graph.get_outgoing_edges(&node.id) {
+        let Some(target) = graph.get_node(&edge.target) else {
+            continue;
+        };
+        resolved |= edge.kind == EdgeKind::Calls;
+        // Modules list their functions and classes with containment edges;
+        // only their own calls and imports are references
+        if node.kind == NodeKind::Module
+            && edge.kind == EdgeKind::Calls
+            && !matches!(target.kind, NodeKind::Call | NodeKind::Import)
+        {
+            continue;
+        }
+        next.push(target.id);
+    }
+
+    match node.kind {
+        // A method is only called if its class is in use
+        NodeKind::Function | NodeKind::Method => {
+            next.extend(
+                graph
+                    .get_incoming_edges(&node.id)
+                    .into_iter()
+                    .filter(|edge| edge.kind == EdgeKind::Calls)
+                    .filter(|edge| {
+                        graph
+                            .get_node(&edge.source)
+                            .is_some_and(|source| source.kind == NodeKind::Class)
+                    })
+                    .map(|edge| edge.source),
+            );
+        }
+        NodeKind::Call if !resolved => {
+            let name = node.name.rsplit('.').next().unwrap_or(&node.name);
+            next.extend(
+                graph
+                    .get_nodes_by_name(name)
+                    .into_iter()
+                    .filter_map(|candidate| {
+                        matches!(
+                            candidate.kind,
+                            NodeKind::Function | NodeKind::Method | NodeKind::Class
+                        )
+                        .then_some(candidate.id)
+                    }),
+            );
+        }
+        _ => {}
+    }
+    next
+}
+
+/// Strongly connected components with Tarjan's algorithm, without recursion
+///
+/// Components and their members are in ascending index order.
+fn strongly_connected_components(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = adjacency.len();
+    let mut index = vec![usize::MAX; n];
+    let mut lowlink = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..n {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // (node, position of the next edge to visit)
+        let mut work = vec![(root, 0)];
+        while let Some(&mut (node, ref mut edge)) = work.last_mut() {
+            if *edge == 0 && index[node] == usize::MAX {
+                index[node] = next_index;
+                lowlink[node] = next_index;
+                next_index += 1;
+                stack.push(node);
+                on_stack[node] = true;
+            }
+
+            if let Some(&next) = adjacency[node].get(*edge) {
+                *edge += 1;
+                if index[next] == usize::MAX {
+                    work.push((next, 0));
+                } else if on_stack[next] {
+                    lowlink[node] = lowlink[node].min(index[next]);
+                }
+                continue;
+            }
+
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                lowlink[parent] = lowlink[parent].min(lowlink[node]);
+            }
+            if lowlink[node] == index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack[member] = false;
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort_unstable();
+                components.push(component);
+            }
+        }
+    }
+
+    components.sort();
+    components
+}
+
+/// Normalized betweenness centrality with Brandes' algorithm
+///
+/// Large graphs use evenly spaced sample sources and scale the result.
+fn betweenness(adjacency: &[Vec<usize>]) -> Vec<f64> {
+    let n = adjacency.len();
+    let mut centrality = vec![0.0; n];
+    if n < 3 {
+        return centrality;
+    }
+
+    let step = n.div_ceil(MAX_EXACT_BETWEENNESS);
+    let sources: Vec<usize> = (0..n).step_by(step).collect();
+
+    for &source in &sources {
+        let mut order = Vec::new();
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        let mut paths = vec![0.0; n];
+        let mut distance = vec![usize::MAX; n];
+        paths[source] = 1.0;
+        distance[source] = 0;
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &adjacency[node] {
+                if distance[next] == usize::MAX {
+                    distance[next] = distance[node] + 1;
+                    queue.push_back(next);
+                }
+                if distance[next] == distance[node] + 1 {
+                    paths[next] += paths[node];
+                    predecessors[next].push(node);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0; n];
+        for &node in order.iter().rev() {
+            for &previous in &predecessors[node] {
+                dependency[previous] += paths[previous] / paths[node] * (1.0 + dependency[node]);
+            }
+            if node != source {
+                centrality[node] += dependency[node];
+            }
+        }
+    }
+
+    let scale = n as f64 / sources.len() as f64 / ((n - 1) * (n - 2)) as f64;
+    centrality.iter_mut().for_each(|value| *value *= scale);
+    centrality
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeprism_core::ast::NodeBuilder;
+    use codeprism_core::Span;
+
+    struct Fixture {
+        graph: GraphStore,
+        next_offset: std::cell::Cell<usize>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self {
+                graph: GraphStore::new(),
+                next_offset: std::cell::Cell::new(0),
+            }
+        }
+
+        fn node(&self, kind: NodeKind, name: &str, file: &str) -> NodeId {
+            // Only reachability matters here; distinct offsets keep ids distinct
+            let offset = self.next_offset.replace(self.next_offset.get() + 1);
+            let node = NodeBuilder::new("repo", kind)
+                .name(name)
+                .file(file)
+                .span(Span::new(offset, offset + 1, 1, 1, 1, 1))
+                .build();
+            let id = node.id;
+            self.graph.add_node(node);
+            id
+        }
+
+        fn edge(&self, source: NodeId, target: NodeId, kind: EdgeKind) {
+            self.graph.add_edge(Edge::new(source, target, kind));
+        }
+
+        /// `caller()` calling `callee` through a call node, as parsers link it
+        fn call(&self, caller: NodeId, callee: NodeId) {
+            let file = self.graph.get_node(&caller).unwrap().file;
+            let name = self.graph.get_node(&callee).unwrap().name;
+            let call = self.node(NodeKind::Call, &name, file.to_str().unwrap());
+            self.edge(caller, call, EdgeKind::Calls);
+            self.edge(call, callee, EdgeKind::Calls);
+        }
+    }
+
+    fn names(nodes: &[Node]) -> Vec<&str> {
+        nodes.iter().map(|node| node.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_call_and_import_cycles() {
+        let f = Fixture::new();
+        let even = f.node(NodeKind::Function, "is_even", "/repo/parity.py");
+        let odd = f.node(NodeKind::Function, "is_odd", "/repo/parity.py");
+        let fact = f.node(NodeKind::Function, "factorial", "/repo/math.py");
+        let main = f.node(NodeKind::Function, "main", "/repo/main.py");
+        f.call(even, odd);
+        f.call(odd, even);
+        f.call(fact, fact);
+        f.call(main, even);
+
+        let analyzer = CallGraphAnalyzer::new();
+        let cycles = analyzer.call_cycles(&f.graph);
+        let cycles: Vec<Vec<&str>> = cycles.iter().map(|cycle| names(&cycle.symbols)).collect();
+        assert_eq!(cycles, vec![vec!["factorial"], vec!["is_even", "is_odd"]]);
+
+        // a.py imports b.py imports c.py imports a.py; d.py only imports a.py
+        let modules: Vec<NodeId> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| f.node(NodeKind::Module, name, &format!("/repo/{name}.py")))
+            .collect();
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 0)] {
+            let file = f.graph.get_node(&modules[from]).unwrap().file;
+            let import = f.node(NodeKind::Import, "module", file.to_str().unwrap());
+            f.edge(modules[from], import, EdgeKind::Imports);
+            f.edge(import, modules[to], EdgeKind::Imports);
+        }
+        let import_cycles = analyzer.import_cycles(&f.graph);
+        assert_eq!(import_cycles.len(), 1);
+        assert_eq!(
+            import_cycles[0].files,
+            vec![
+                PathBuf::from("/repo/a.py"),
+                PathBuf::from("/repo/b.py"),
+                PathBuf::from("/repo/c.py")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unreferenced_symbols_from_entry_points() {
+        let f = Fixture::new();
+        let module = f.node(NodeKind::Module, "app", "/repo/app.py");
+        let main = f.node(NodeKind::Function, "main", "/repo/app.py");
+        let helper = f.node(NodeKind::Function, "helper", "/repo/app.py");
+        let unused = f.node(NodeKind::Function, "unused_helper", "/repo/app.py");
+        let class = f.node(NodeKind::Class, "Service", "/repo/app.py");
+        let init = f.node(NodeKind::Method, "__init__", "/repo/app.py");
+        let method = f.node(NodeKind::Method, "process", "/repo/app.py");
+        let unused_class = f.node(NodeKind::Class, "UnusedService", "/repo/app.py");
+        let route = f.node(NodeKind::Route, "/health", "/repo/app.py");
+        let handler = f.node(NodeKind::Function, "health", "/repo/app.py");
+        let on_startup = f.node(NodeKind::Function, "on_startup", "/repo/app.py");
+        let test = f.node(NodeKind::Function, "test_helper", "/repo/tests/test_app.py");
+        let tested = f.node(NodeKind::Function, "only_tested", "/repo/app.py");
+
+        // Containment edges from the module are not references
+        for symbol in [
+            main,
+            helper,
+            unused,
+            class,
+            unused_class,
+            handler,
+            on_startup,
+            tested,
+        ] {
+            f.edge(module, symbol, EdgeKind::Calls);
+        }
+        f.edge(class, init, EdgeKind::Calls);
+        f.edge(class, method, EdgeKind::Calls);
+        f.call(main, helper);
+        // `Service()` resolves to `__init__`, which makes the class reachable
+        f.call(helper, init);
+        f.edge(route, handler, EdgeKind::RoutesTo);
+        f.call(test, tested);
+        // An unresolved `obj.process()` call
+        let call = f.node(NodeKind::Call, "service.process", "/repo/app.py");
+        f.edge(handler, call, EdgeKind::Calls);
+
+        let analyzer = CallGraphAnalyzer::new();
+        let report = analyzer.reachability(&f.graph).unwrap();
+        assert_eq!(
+            names(&report.unreferenced),
+            vec!["unused_helper", "UnusedService", "on_startup"]
+        );
+        assert_eq!(
+            names(&report.entry_points),
+            vec!["main", "/health", "test_helper"]
+        );
+        assert_eq!(report.total_symbols, 11);
+        assert_eq!(report.reachable, 8);
+
+        let analyzer = CallGraphAnalyzer::new().with_entry_points(EntryPoints {
+            tests: false,
+            names: vec!["^on_".to_string()],
+            ..EntryPoints::default()
+        });
+        let report = analyzer.reachability(&f.graph).unwrap();
+        assert_eq!(
+            names(&report.unreferenced),
+            vec![
+                "unused_helper",
+                "UnusedService",
+                "only_tested",
+                "test_helper"
+            ]
+        );
+
+        let invalid = CallGraphAnalyzer::new().with_entry_points(EntryPoints {
+            names: vec!["(".to_string()],
+            ..EntryPoints::default()
+        });
+        assert!(invalid.reachability(&f.graph).is_err());
+    }
+
+    #[test]
+    fn test_centrality_finds_god_functions() {
+        let f = Fixture::new();
+        // Callers funnel through `dispatch`, which calls many workers
+        let callers: Vec<NodeId> = (0..3)
+            .map(|i| f.node(NodeKind::Function, &format!("caller{i}"), "/repo/app.py"))
+            .collect();
+        let dispatch = f.node(NodeKind::Function, "dispatch", "/repo/app.py");
+        let workers: Vec<NodeId> = (0..10)
+            .map(|i| f.node(NodeKind::Function, &format!("worker{i}"), "/repo/app.py"))
+            .collect();
+        for &caller in &callers {
+            f.call(caller, dispatch);
+        }
+        for &worker in &workers {
+            f.call(dispatch, worker);
+        }
+        f.call(workers[0], workers[1]);
+
+        let analyzer = CallGraphAnalyzer::new();
+        let centrality = analyzer.centrality(&f.graph);
+        let top = &centrality[0];
+        assert_eq!(top.node.name, "dispatch");
+        assert_eq!((top.fan_in, top.fan_out), (3, 10));
+        // 3 callers × 10 workers of the 13 × 12 ordered pairs
+        assert!((top.betweenness - 30.0 / 156.0).abs() < 1e-9);
+        let worker = centrality
+            .iter()
+            .find(|symbol| symbol.node.name == "worker1")
+            .unwrap();
+        assert_eq!((worker.fan_in, worker.fan_out), (2, 0));
+        assert_eq!(worker.betweenness, 0.0);
+
+        let god_functions = analyzer.god_functions(centrality);
+        assert_eq!(
+            names(
+                &god_functions
+                    .iter()
+                    .map(|s| s.node.clone())
+                    .collect::<Vec<_>>()
+            ),
+            vec!["dispatch"]
+        );
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let components = strongly_connected_components(&[
+            vec![1],
+            vec![2, 3],
+            vec![0],
+            vec![4],
+            vec![3],
+            vec![],
+        ]);
+        assert_eq!(components, vec![vec![0, 1, 2], vec![3, 4], vec![5]]);
+    }
+}
@@ -1,6 +1,8 @@
 //! Language-agnostic code analysis tools for CodePrism
 
 pub mod api_surface;
+pub mod architecture;
+pub mod call_graph;
 pub mod complexity;
 pub mod duplicates;
 pub mod performance;
@@ -8,6 +10,8 @@ pub mod security;
 pub mod semantic;
 
 pub use api_surface::ApiSurfaceAnalyzer;
+pub use architecture::{ArchitectureAnalyzer, ArchitectureConfig};
+pub use call_graph::CallGraphAnalyzer;
 pub use complexity::ComplexityAnalyzer;
 pub use duplicates::DuplicateAnalyzer;
 pub use performance::PerformanceAnalyzer;
@@ -22,6 +26,7 @@ pub struct CodeAnalyzer {
     pub security: SecurityAnalyzer,
     pub performance: PerformanceAnalyzer,
     pub api_surface: ApiSurfaceAnalyzer,
+    pub call_graph: CallGraphAnalyzer,
 }
 
 impl CodeAnalyzer {
@@ -32,6 +37,7 @@ impl CodeAnalyzer {
             security: SecurityAnalyzer::new(),
             performance: PerformanceAnalyzer::new(),
             api_surface: ApiSurfaceAnalyzer::new(),
+            call_graph: CallGraphAnalyzer::new(),
         }
     }
 }
@@ -492,6 +492,16 @@ impl Error {
         }
     }
 
+    /// Create a git error (mapped to generic error)
+    pub fn git(message: impl Into<String>) -> Self {
+        Self::Generic {
+            message: format!("Git: {}", message.into()),
+            severity: ErrorSeverity::Error,
+            recovery_strategy: RecoveryStrategy::UserIntervention,
+            context: None,
+        }
+    }
+
     /// Create an indexing error (mapped to generic error)
     pub fn indexing(message: impl Into<String>) -> Self {
         Self::Generic {
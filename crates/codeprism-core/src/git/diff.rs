@@ -0,0 +1,663 @@
+//! Semantic diff between two parsed versions of a file
+//!
+//! Node IDs are derived from spans, so they change whenever code moves. Symbols
+//! are therefore matched by kind and qualified name (`Class.method`), while the
+//! [`AstPatch`] for each file is computed on node and edge IDs so that applying
+//! it to a graph of the older revision yields the graph of the newer one.
+
+use super::FileStatus;
+use crate::ast::{Edge, Node, NodeId, NodeKind};
+use crate::patch::{AstPatch, PatchBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Caller name used for calls made outside any function or class
+const MODULE_CALLER: &str = "<module>";
+
+/// How a symbol changed between two revisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolChangeKind {
+    /// The symbol only exists in the newer revision
+    Added,
+    /// The symbol only exists in the older revision
+    Removed,
+    /// The signature or parameter list changed
+    SignatureChanged,
+    /// The source text changed but the signature did not
+    BodyChanged,
+}
+
+/// A symbol that differs between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolChange {
+    /// How the symbol changed
+    pub kind: SymbolChangeKind,
+    /// Name qualified by the enclosing classes and functions
+    pub symbol: String,
+    /// Kind of the symbol
+    pub node_kind: NodeKind,
+    /// Node in the older revision
+    pub old: Option<Node>,
+    /// Node in the newer revision
+    pub new: Option<Node>,
+    /// Signature in the older revision
+    pub old_signature: Option<String>,
+    /// Signature in the newer revision
+    pub new_signature: Option<String>,
+}
+
+/// A call from a symbol to a callee name
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CallEdge {
+    /// Qualified name of the calling symbol, or `<module>` for top-level calls
+    pub caller: String,
+    /// Name of the called function as written at the call site
+    pub callee: String,
+}
+
+/// Changes to a single file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    /// Path of the file joined to the repository root
+    pub path: PathBuf,
+    /// How the file changed
+    pub status: FileStatus,
+    /// Symbols that were added, removed or changed
+    pub symbols: Vec<SymbolChange>,
+    /// Calls only present in the newer revision
+    pub calls_added: Vec<CallEdge>,
+    /// Calls only present in the older revision
+    pub calls_removed: Vec<CallEdge>,
+    /// Patch turning the graph of the older revision into the newer one
+    pub patch: AstPatch,
+}
+
+/// Graph-level differences between two revisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticDiff {
+    /// Repository ID
+    pub repo_id: String,
+    /// Resolved commit of the older revision
+    pub from: String,
+    /// Resolved commit of the newer revision
+    pub to: String,
+    /// Changed files that contain supported source code, sorted by path
+    pub files: Vec<FileDiff>,
+    /// Files that could not be parsed, with the error message
+    pub failed_files: Vec<(PathBuf, String)>,
+}
+
+impl SemanticDiff {
+    /// Patches of all files, in path order
+    pub fn patches(&self) -> Vec<AstPatch> {
+        self.files.iter().map(|file| file.patch.clone()).collect()
+    }
+
+    /// Check if no symbol or call changed
+    pub fn is_empty(&self) -> bool {
+        self.files.iter().all(|file| {
+            file.symbols.is_empty() && file.calls_added.is_empty() && file.calls_removed.is_empty()
+        })
+    }
+}
+
+/// Nodes and edges of one version of a file
+pub(crate) struct ParsedFile {
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) edges: Vec<Edge>,
+    pub(crate) content: String,
+}
+
+/// A symbol of one version of a file
+struct Symbol<'a> {
+    node: &'a Node,
+    name: String,
+    signature: Option<String>,
+    body_hash: blake3::Hash,
+}
+
+/// Compare two versions of a file
+///
+/// A missing side stands for a file that does not exist in that revision.
+pub(crate) fn diff_file(
+    repo_id: &str,
+    commit: &str,
+    path: PathBuf,
+    status: FileStatus,
+    old: Option<&ParsedFile>,
+    new: Option<&ParsedFile>,
+) -> FileDiff {
+    let old_symbols = old.map(symbols).unwrap_or_default();
+    let new_symbols = new.map(symbols).unwrap_or_default();
+
+    let mut changes = Vec::new();
+    for (key, before) in &old_symbols {
+        let Some(after) = new_symbols.get(key) else {
+            changes.push(symbol_change(SymbolChangeKind::Removed, Some(before), None));
+            continue;
+        };
+        if before.signature != after.signature {
+            changes.push(symbol_change(
+                SymbolChangeKind::SignatureChanged,
+                Some(before),
+                Some(after),
+            ));
+        } else if before.body_hash != after.body_hash {
+            changes.push(symbol_change(
+                SymbolChangeKind::BodyChanged,
+                Some(before),
+                Some(after),
+            ));
+        }
+    }
+    for (key, after) in &new_symbols {
+        if !old_symbols.contains_key(key) {
+            changes.push(symbol_change(SymbolChangeKind::Added, None, Some(after)));
+        }
+    }
+    changes.sort_by_key(|change| {
+        change
+            .new
+            .as_ref()
+            .or(change.old.as_ref())
+            .map(|node| node.span.start_byte)
+    });
+
+    let old_calls = old.map(calls).unwrap_or_default();
+    let new_calls = new.map(calls).unwrap_or_default();
+
+    FileDiff {
+        path,
+        status,
+        symbols: changes,
+        calls_added: new_calls.difference(&old_calls).cloned().collect(),
+        calls_removed: old_calls.difference(&new_calls).cloned().collect(),
+        patch: patch(repo_id, commit, old, new),
+    }
+}
+
+fn symbol_change(
+    kind: SymbolChangeKind,
+    old: Option<&Symbol>,
+    new: Option<&Symbol>,
+) -> SymbolChange {
+    let symbol = new.or(old).expect("a change has at least one side");
+    SymbolChange {
+        kind,
+        symbol: symbol.name.clone(),
+        node_kind: symbol.node.kind,
+        old: old.map(|s| s.node.clone()),
+        new: new.map(|s| s.node.clone()),
+        old_signature: old.and_then(|s| s.signature.clone()),
+        new_signature: new.and_then(|s| s.signature.clone()),
+    }
+}
+
+/// Symbols of a file keyed by kind and qualified name
+///
+/// Symbols sharing a name, such as overloads, get an occurrence suffix.
+fn symbols(file: &ParsedFile) -> BTreeMap<String, Symbol<'_>> {
+    let mut symbols = BTreeMap::new();
+    let mut nodes: Vec<&Node> = file.nodes.iter().collect();
+    nodes.sort_by_key(|node| (node.span.start_byte, node.span.end_byte));
+
+    for node in nodes {
+        let is_symbol = match node.kind {
+            NodeKind::Class | NodeKind::Function | NodeKind::Method | NodeKind::Route => true,
+            NodeKind::Variable => enclosing(file, node)
+                .iter()
+                .all(|outer| outer.kind == NodeKind::Class),
+            _ => false,
+        };
+        if !is_symbol {
+            continue;
+        }
+
+        let name = qualified_name(file, node);
+        let mut key = format!("{:?}:{}", node.kind, name);
+        let mut occurrence = 1;
+        while symbols.contains_key(&key) {
+            occurrence += 1;
+            key = format!("{:?}:{}#{}", node.kind, name, occurrence);
+        }
+        symbols.insert(
+            key,
+            Symbol {
+                node,
+                name,
+                signature: signature(file, node),
+                body_hash: blake3::hash(
+                    file.content
+                        .as_bytes()
+                        .get(node.span.start_byte..node.span.end_byte)
+                        .unwrap_or_default(),
+                ),
+            },
+        );
+    }
+
+    symbols
+}
+
+/// Calls made by each symbol of a file
+fn calls(file: &ParsedFile) -> BTreeSet<CallEdge> {
+    file.nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Call)
+        .map(|call| CallEdge {
+            caller: enclosing(file, call)
+                .last()
+                .map(|caller| qualified_name(file, caller))
+                .unwrap_or_else(|| MODULE_CALLER.to_string()),
+            callee: call.name.clone(),
+        })
+        .collect()
+}
+
+/// Classes, functions and methods around `node`, outermost first
+fn enclosing<'a>(file: &'a ParsedFile, node: &Node) -> Vec<&'a Node> {
+    let mut containers: Vec<&Node> = file
+        .nodes
+        .iter()
+        .filter(|outer| {
+            matches!(
+                outer.kind,
+                NodeKind::Class | NodeKind::Function | NodeKind::Method
+            ) && outer.id != node.id
+                && outer.span.start_byte <= node.span.start_byte
+                && outer.span.end_byte >= node.span.end_byte
+                && outer.span != node.span
+        })
+        .collect();
+    containers.sort_by_key(|outer| {
+        (
+            std::cmp::Reverse(outer.span.end_byte - outer.span.start_byte),
+            outer.span.start_byte,
+        )
+    });
+    containers
+}
+
+fn qualified_name(file: &ParsedFile, node: &Node) -> String {
+    let mut parts: Vec<&str> = enclosing(file, node)
+        .iter()
+        .map(|outer| outer.name.as_str())
+        .collect();
+    parts.push(&node.name);
+    parts.join(".")
+}
+
+/// Signature of a symbol, falling back to the parameter list of functions
+fn signature(file: &ParsedFile, node: &Node) -> Option<String> {
+    if node.signature.is_some() {
+        return node.signature.clone();
+    }
+    if !matches!(node.kind, NodeKind::Function | NodeKind::Method) {
+        return None;
+    }
+
+    let mut parameters: Vec<&Node> = file
+        .nodes
+        .iter()
+        .filter(|param| {
+            param.kind == NodeKind::Parameter
+                && enclosing(file, param)
+                    .last()
+                    .is_some_and(|owner| owner.id == node.id)
+        })
+        .collect();
+    parameters.sort_by_key(|param| param.span.start_byte);
+    let names: Vec<&str> = parameters.iter().map(|param| param.name.as_str()).collect();
+    Some(format!("({})", names.join(", ")))
+}
+
+/// Patch from the nodes and edges of `old` to those of `new`
+///
+/// Nodes are kept when a node with the same ID, name, signature and metadata
+/// exists on both sides. Deleting a node drops its edges from the graph, so
+/// edges of replaced nodes are added again.
+fn patch(
+    repo_id: &str,
+    commit: &str,
+    old: Option<&ParsedFile>,
+    new: Option<&ParsedFile>,
+) -> AstPatch {
+    let old_nodes: HashMap<NodeId, &Node> = old
+        .map(|file| file.nodes.iter().map(|node| (node.id, node)).collect())
+        .unwrap_or_default();
+    let new_nodes: HashMap<NodeId, &Node> = new
+        .map(|file| file.nodes.iter().map(|node| (node.id, node)).collect())
+        .unwrap_or_default();
+    let old_edges: HashSet<&Edge> = old
+        .map(|file| file.edges.iter().collect())
+        .unwrap_or_default();
+    let new_edges: HashSet<&Edge> = new
+        .map(|file| file.edges.iter().collect())
+        .unwrap_or_default();
+
+    let same = |a: &Node, b: &Node| {
+        a.name == b.name && a.signature == b.signature && a.metadata == b.metadata
+    };
+    let deleted: HashSet<NodeId> = old_nodes
+        .iter()
+        .filter(|(id, node)| !new_nodes.get(id).is_some_and(|other| same(node, other)))
+        .map(|(id, _)| *id)
+        .collect();
+
+    let mut nodes_delete: Vec<&Node> = deleted.iter().map(|id| old_nodes[id]).collect();
+    nodes_delete.sort_by_key(|node| node.span.start_byte);
+    let mut nodes_add: Vec<Node> = new
+        .map(|file| file.nodes.iter())
+        .into_iter()
+        .flatten()
+        .filter(|node| {
+            !old_nodes
+                .get(&node.id)
+                .is_some_and(|other| same(node, other))
+        })
+        .cloned()
+        .collect();
+    nodes_add.sort_by_key(|node| node.span.start_byte);
+
+    let edges_delete: Vec<String> = old
+        .map(|file| file.edges.iter())
+        .into_iter()
+        .flatten()
+        .filter(|edge| {
+            !new_edges.contains(edge)
+                && !deleted.contains(&edge.source)
+                && !deleted.contains(&edge.target)
+        })
+        .map(Edge::id)
+        .collect();
+    let edges_add: Vec<Edge> = new
+        .map(|file| file.edges.iter())
+        .into_iter()
+        .flatten()
+        .filter(|edge| {
+            !old_edges.contains(edge)
+                || deleted.contains(&edge.source)
+                || deleted.contains(&edge.target)
+        })
+        .cloned()
+        .collect();
+
+    PatchBuilder::new(repo_id.to_string(), commit.to_string())
+        .delete_nodes(nodes_delete.iter().map(|node| node.id.to_hex()).collect())
+        .delete_edges(edges_delete)
+        .add_nodes(nodes_add)
+        .add_edges(edges_add)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{EdgeKind, Language, Span};
+    use crate::error::Result;
+    use crate::git::tests::commit;
+    use crate::git::GitRepository;
+    use crate::graph::GraphStore;
+    use crate::indexer::{BulkIndexer, IndexingConfig};
+    use crate::parser::{
+        LanguageParser, LanguageRegistry, ParseContext, ParseResult, ParserEngine,
+    };
+    use git2::Repository;
+    use std::sync::Arc;
+
+    /// Maps JavaScript functions, parameters and calls to graph nodes
+    struct TestParser;
+
+    impl LanguageParser for TestParser {
+        fn language(&self) -> Language {
+            Language::JavaScript
+        }
+
+        fn parse(&self, context: &ParseContext) -> Result<ParseResult> {
+            let mut parser = tree_sitter::Parser::new();
+            parser
+                .set_language(&tree_sitter_javascript::LANGUAGE.into())
+                .unwrap();
+            let tree = parser.parse(&context.content, None).unwrap();
+
+            let node = |kind, name: &str, ts: tree_sitter::Node| {
+                let span = Span::new(
+                    ts.start_byte(),
+                    ts.end_byte(),
+                    ts.start_position().row + 1,
+                    ts.end_position().row + 1,
+                    ts.start_position().column + 1,
+                    ts.end_position().column + 1,
+                );
+                Node::new(
+                    &context.repo_id,
+                    kind,
+                    name.to_string(),
+                    Language::JavaScript,
+                    context.file_path.clone(),
+                    span,
+                )
+            };
+            let text = |ts: tree_sitter::Node| context.content[ts.byte_range()].to_string();
+
+            let module = node(
+                NodeKind::Module,
+                &context.file_path.to_string_lossy(),
+                tree.root_node(),
+            );
+            let mut nodes = vec![module.clone()];
+            let mut edges = Vec::new();
+            let mut stack = vec![(tree.root_node(), module.id)];
+            while let Some((ts, owner)) = stack.pop() {
+                let mut owner = owner;
+                match ts.kind() {
+                    "function_declaration" => {
+                        let name = text(ts.child_by_field_name("name").unwrap());
+                        let function = node(NodeKind::Function, &name, ts);
+                        edges.push(Edge::new(owner, function.id, EdgeKind::Calls));
+                        owner = function.id;
+                        nodes.push(function);
+                    }
+                    "formal_parameters" => {
+                        let mut cursor = ts.walk();
+                        for param in ts.named_children(&mut cursor) {
+                            nodes.push(node(NodeKind::Parameter, &text(param), param));
+                        }
+                    }
+                    "call_expression" => {
+                        let name = text(ts.child_by_field_name("function").unwrap());
+                        let call = node(NodeKind::Call, &name, ts);
+                        edges.push(Edge::new(owner, call.id, EdgeKind::Calls));
+                        nodes.push(call);
+                    }
+                    _ => {}
+                }
+                let mut cursor = ts.walk();
+                for child in ts.named_children(&mut cursor) {
+                    stack.push((child, owner));
+                }
+            }
+
+            Ok(ParseResult { tree, nodes, edges })
+        }
+    }
+
+    fn test_indexer() -> BulkIndexer {
+        let registry = Arc::new(LanguageRegistry::new());
+        registry.register(Arc::new(TestParser));
+        let mut config = IndexingConfig::new("repo".to_string(), "unused".to_string());
+        config.enable_cross_file_linking = false;
+        BulkIndexer::new(config, Arc::new(ParserEngine::new(registry)))
+    }
+
+    fn graph_of(patches: &[AstPatch]) -> GraphStore {
+        let graph = GraphStore::new();
+        for patch in patches {
+            graph.apply_patch(patch);
+        }
+        graph
+    }
+
+    /// Nodes and edges of a graph in a comparable form
+    fn contents(graph: &GraphStore) -> (BTreeSet<String>, BTreeSet<String>) {
+        let mut nodes = BTreeSet::new();
+        let mut edges = BTreeSet::new();
+        for (_, ids) in graph.iter_file_index() {
+            for id in ids {
+                let node = graph.get_node(&id).unwrap();
+                nodes.insert(format!("{}:{:?}:{}", id, node.kind, node.name));
+                for edge in graph.get_outgoing_edges(&id) {
+                    edges.insert(edge.id());
+                }
+            }
+        }
+        (nodes, edges)
+    }
+
+    #[test]
+    fn test_diff_revisions_reports_symbols_and_calls() {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("gitdiff")
+            .tempdir()
+            .unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let first = commit(
+            &repo,
+            &[
+                (
+                    "app.js",
+                    Some(
+                        "function main() { load(); save(1); }\n\
+                         function load() { return 1; }\n\
+                         function save(value) { return value; }\n\
+                         function unused() {}\n",
+                    ),
+                ),
+                ("gone.js", Some("function gone() { main(); }\n")),
+                ("notes.txt", Some("not parsed\n")),
+            ],
+            "first",
+        );
+        let second = commit(
+            &repo,
+            &[
+                (
+                    "app.js",
+                    Some(
+                        "// entry point\n\
+                         function main() { load(); audit(); }\n\
+                         function load() { return 2; }\n\
+                         function save(value, force) { return value; }\n\
+                         function audit() {}\n",
+                    ),
+                ),
+                ("gone.js", None),
+                ("notes.txt", Some("still not parsed\n")),
+            ],
+            "second",
+        );
+
+        let repository = GitRepository::open(temp_dir.path()).unwrap();
+        let indexer = test_indexer();
+        let diff = indexer
+            .diff_revisions(&repository, "HEAD~1", "HEAD")
+            .unwrap();
+        assert_eq!(diff.from, first);
+        assert_eq!(diff.to, second);
+        assert!(diff.failed_files.is_empty());
+        assert!(!diff.is_empty());
+
+        let root = repository.root();
+        let paths: Vec<_> = diff.files.iter().map(|file| file.path.clone()).collect();
+        assert_eq!(paths, vec![root.join("app.js"), root.join("gone.js")]);
+
+        let app = &diff.files[0];
+        assert_eq!(app.status, FileStatus::Modified);
+        let changes: Vec<_> = app
+            .symbols
+            .iter()
+            .map(|change| (change.symbol.as_str(), change.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("main", SymbolChangeKind::BodyChanged),
+                ("load", SymbolChangeKind::BodyChanged),
+                ("save", SymbolChangeKind::SignatureChanged),
+                ("unused", SymbolChangeKind::Removed),
+                ("audit", SymbolChangeKind::Added),
+            ]
+        );
+        let save = &app.symbols[2];
+        assert_eq!(save.old_signature.as_deref(), Some("(value)"));
+        assert_eq!(save.new_signature.as_deref(), Some("(value, force)"));
+
+        let call = |caller: &str, callee: &str| CallEdge {
+            caller: caller.to_string(),
+            callee: callee.to_string(),
+        };
+        assert_eq!(app.calls_added, vec![call("main", "audit")]);
+        assert_eq!(app.calls_removed, vec![call("main", "save")]);
+
+        let gone = &diff.files[1];
+        assert_eq!(gone.status, FileStatus::Deleted);
+        assert_eq!(gone.symbols[0].kind, SymbolChangeKind::Removed);
+        assert_eq!(gone.calls_removed, vec![call("gone", "main")]);
+        assert!(gone.patch.nodes_add.is_empty());
+        assert!(diff.patches().iter().all(|patch| patch.commit == second));
+    }
+
+    #[test]
+    fn test_patches_turn_old_graph_into_new_graph() {
+        let temp_dir = tempfile::Builder::new()
+            .prefix("gitdiff")
+            .tempdir()
+            .unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        commit(
+            &repo,
+            &[
+                (
+                    "a.js",
+                    Some("function a(x) { b(x); }\nfunction b(y) { return y; }\n"),
+                ),
+                ("b.js", Some("function keep() {}\n")),
+                ("c.js", Some("function c() { a(1); }\n")),
+            ],
+            "first",
+        );
+        commit(
+            &repo,
+            &[
+                (
+                    "a.js",
+                    Some("function b(y) { return y; }\nfunction a(x) { b(x); c(); }\n"),
+                ),
+                ("c.js", None),
+                ("d.js", Some("function d() { keep(); }\n")),
+            ],
+            "second",
+        );
+
+        let repository = GitRepository::open(temp_dir.path()).unwrap();
+        let indexer = test_indexer();
+        let old = indexer.index_revision(&repository, "HEAD~1").unwrap();
+        let new = indexer.index_revision(&repository, "HEAD").unwrap();
+        assert_eq!(old.stats.files_processed, 3);
+        assert!(old.failed_files.is_empty());
+
+        let graph = graph_of(&old.patches);
+        let diff = indexer
+            .diff_revisions(&repository, "HEAD~1", "HEAD")
+            .unwrap();
+        // b.js is unchanged and produces no file diff
+        assert_eq!(diff.files.len(), 3);
+        for patch in diff.patches() {
+            graph.apply_patch(&patch);
+        }
+
+        assert_eq!(contents(&graph), contents(&graph_of(&new.patches)));
+    }
+}
@@ -0,0 +1,317 @@
+//! Reading repository revisions from the git object database
+//!
+//! Files are read from commit trees instead of the working tree, so any
+//! revision of a local repository can be indexed without a checkout and
+//! without network access. See [`crate::BulkIndexer::index_revision`] and
+//! [`crate::BulkIndexer::diff_revisions`].
+
+pub mod diff;
+
+pub use diff::{CallEdge, FileDiff, SemanticDiff, SymbolChange, SymbolChangeKind};
+
+use crate::error::{Error, Result};
+use git2::{Delta, FileMode, ObjectType, Oid, Repository, Tree, TreeWalkMode, TreeWalkResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A git repository opened for reading revisions
+pub struct GitRepository {
+    repo: Repository,
+    root: PathBuf,
+}
+
+/// A file read from a revision
+#[derive(Debug, Clone)]
+pub struct RevisionFile {
+    /// Path of the file joined to the repository root
+    pub path: PathBuf,
+    /// ID of the blob holding the content
+    pub blob_id: String,
+    /// File content
+    pub content: String,
+}
+
+/// How a file changed between two revisions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStatus {
+    /// The file only exists in the newer revision
+    Added,
+    /// The file only exists in the older revision
+    Deleted,
+    /// The file exists in both revisions with different content
+    Modified,
+}
+
+/// A file that differs between two revisions
+#[derive(Debug, Clone)]
+pub struct ChangedFile {
+    /// Path of the file joined to the repository root
+    pub path: PathBuf,
+    /// How the file changed
+    pub status: FileStatus,
+    /// Content in the older revision
+    pub old: Option<RevisionFile>,
+    /// Content in the newer revision
+    pub new: Option<RevisionFile>,
+}
+
+impl GitRepository {
+    /// Open the repository containing `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let repo = Repository::discover(path).map_err(|e| {
+            Error::git(format!(
+                "Failed to open repository at {}: {}",
+                path.display(),
+                e.message()
+            ))
+        })?;
+        let root = repo.workdir().unwrap_or_else(|| repo.path()).to_path_buf();
+        Ok(Self { repo, root })
+    }
+
+    /// Directory that file paths are joined to
+    ///
+    /// This is the working tree, so that nodes from a revision have the same
+    /// paths as nodes indexed from a checkout. Bare repositories use the git
+    /// directory instead.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Resolve a revision such as `HEAD~2`, a branch or a tag to a commit ID
+    pub fn resolve_commit(&self, revision: &str) -> Result<String> {
+        Ok(self.commit_tree(revision)?.0.to_string())
+    }
+
+    /// Read the files of a revision whose paths are accepted by `filter`
+    ///
+    /// Binary files and files that are not valid UTF-8 are skipped.
+    pub fn files_at(
+        &self,
+        revision: &str,
+        filter: impl Fn(&Path) -> bool,
+    ) -> Result<Vec<RevisionFile>> {
+        let (_, tree) = self.commit_tree(revision)?;
+
+        let mut blobs = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                if let Some(name) = entry.name() {
+                    let path = self.root.join(dir).join(name);
+                    if filter(&path) {
+                        blobs.push((path, entry.id()));
+                    }
+                }
+            }
+            TreeWalkResult::Ok
+        })
+        .map_err(git_error)?;
+
+        let mut files = Vec::with_capacity(blobs.len());
+        for (path, blob_id) in blobs {
+            files.extend(self.read_blob(path, blob_id)?);
+        }
+        Ok(files)
+    }
+
+    /// Files that differ between two revisions, with their content on each side
+    ///
+    /// Renames are reported as a deletion and an addition. A side that is
+    /// binary or not valid UTF-8 has no content.
+    pub fn changed_files(
+        &self,
+        from: &str,
+        to: &str,
+        filter: impl Fn(&Path) -> bool,
+    ) -> Result<Vec<ChangedFile>> {
+        let (_, old_tree) = self.commit_tree(from)?;
+        let (_, new_tree) = self.commit_tree(to)?;
+        let diff = self
+            .repo
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+            .map_err(git_error)?;
+
+        let mut changed = Vec::new();
+        for delta in diff.deltas() {
+            let status = match delta.status() {
+                Delta::Added => FileStatus::Added,
+                Delta::Deleted => FileStatus::Deleted,
+                Delta::Modified | Delta::Typechange => FileStatus::Modified,
+                _ => continue,
+            };
+            let (old_file, new_file) = (delta.old_file(), delta.new_file());
+            // Submodules are commits, not blobs
+            if old_file.mode() == FileMode::Commit || new_file.mode() == FileMode::Commit {
+                continue;
+            }
+            let Some(relative) = new_file.path().or(old_file.path()) else {
+                continue;
+            };
+            let path = self.root.join(relative);
+            if !filter(&path) {
+                continue;
+            }
+
+            let old = match status {
+                FileStatus::Added => None,
+                _ => self.read_blob(path.clone(), old_file.id())?,
+            };
+            let new = match status {
+                FileStatus::Deleted => None,
+                _ => self.read_blob(path.clone(), new_file.id())?,
+            };
+            changed.push(ChangedFile {
+                path,
+                status,
+                old,
+                new,
+            });
+        }
+
+        Ok(changed)
+    }
+
+    /// Resolve a revision to its commit ID and tree
+    fn commit_tree(&self, revision: &str) -> Result<(Oid, Tree<'_>)> {
+        let commit = self
+            .repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| Error::git(format!("Unknown revision {}: {}", revision, e.message())))?;
+        let tree = commit.tree().map_err(git_error)?;
+        Ok((commit.id(), tree))
+    }
+
+    /// Read a blob as UTF-8 text, or `None` if it is binary or not UTF-8
+    fn read_blob(&self, path: PathBuf, blob_id: Oid) -> Result<Option<RevisionFile>> {
+        let blob = self.repo.find_blob(blob_id).map_err(git_error)?;
+        if blob.is_binary() {
+            return Ok(None);
+        }
+        Ok(std::str::from_utf8(blob.content())
+            .ok()
+            .map(|content| RevisionFile {
+                path,
+                blob_id: blob_id.to_string(),
+                content: content.to_string(),
+            }))
+    }
+}
+
+impl std::fmt::Debug for GitRepository {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GitRepository")
+            .field("root", &self.root)
+            .finish()
+    }
+}
+
+fn git_error(error: git2::Error) -> Error {
+    Error::git(error.message())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use git2::Signature;
+
+    /// Write `files` into the work tree, stage them and commit, returning the commit ID
+    ///
+    /// A file with `None` content is deleted.
+    pub(crate) fn commit(
+        repo: &Repository,
+        files: &[(&str, Option<&str>)],
+        message: &str,
+    ) -> String {
+        let root = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+        for (path, content) in files {
+            match content {
+                Some(content) => {
+                    let full = root.join(path);
+                    std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+                    std::fs::write(&full, content).unwrap();
+                    index.add_path(Path::new(path)).unwrap();
+                }
+                None => {
+                    std::fs::remove_file(root.join(path)).unwrap();
+                    index.remove_path(Path::new(path)).unwrap();
+                }
+            }
+        }
+        index.write().unwrap();
+
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    #[test]
+    fn test_read_files_and_changes_between_revisions() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let first = commit(
+            &repo,
+            &[
+                ("src/app.js", Some("function a() {}\n")),
+                ("src/old.js", Some("function old() {}\n")),
+                ("README.md", Some("# readme\n")),
+            ],
+            "first",
+        );
+        let second = commit(
+            &repo,
+            &[
+                ("src/app.js", Some("function a() { b(); }\n")),
+                ("src/old.js", None),
+                ("src/new.js", Some("function b() {}\n")),
+            ],
+            "second",
+        );
+        // The work tree moves on without affecting committed revisions
+        std::fs::write(temp_dir.path().join("src/app.js"), "uncommitted").unwrap();
+
+        let repository = GitRepository::open(temp_dir.path().join("src")).unwrap();
+        assert_eq!(repository.resolve_commit("HEAD").unwrap(), second);
+        assert_eq!(repository.resolve_commit("HEAD~1").unwrap(), first);
+        assert!(repository.resolve_commit("no-such-branch").is_err());
+
+        let is_js = |path: &Path| path.extension().is_some_and(|ext| ext == "js");
+        let files = repository.files_at(&first, is_js).unwrap();
+        let mut paths: Vec<_> = files.iter().map(|file| file.path.clone()).collect();
+        paths.sort();
+        let root = repository.root().to_path_buf();
+        assert_eq!(
+            paths,
+            vec![root.join("src/app.js"), root.join("src/old.js")]
+        );
+        assert!(files.iter().any(|file| file.content == "function a() {}\n"));
+
+        let mut changed = repository.changed_files(&first, &second, is_js).unwrap();
+        changed.sort_by(|a, b| a.path.cmp(&b.path));
+        let statuses: Vec<_> = changed.iter().map(|file| file.status).collect();
+        assert_eq!(
+            statuses,
+            vec![FileStatus::Modified, FileStatus::Added, FileStatus::Deleted]
+        );
+        assert_eq!(
+            changed[0].new.as_ref().unwrap().content,
+            "function a() { b(); }\n"
+        );
+        assert!(changed[1].old.is_none());
+        assert!(changed[2].new.is_none());
+    }
+}
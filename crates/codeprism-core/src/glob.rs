@@ -0,0 +1,96 @@
+//! Glob patterns compiled to regular expressions
+
+use regex::Regex;
+
+/// Regex for a file glob
+///
+/// `*` and `?` stay within a path component and `**` spans components, so
+/// `src/**/app.py` also matches `src/app.py`. Relative patterns match at any
+/// directory boundary, so `src/*.py` matches `/repo/src/app.py`.
+pub fn glob_regex(glob: &str) -> Regex {
+    let prefix = if glob.starts_with('/') {
+        "^"
+    } else {
+        "(?:^|/)"
+    };
+    compile(prefix, glob, "/")
+}
+
+/// Regex for a glob over a name whose components are split by any of the
+/// `separators` characters, such as `.`, `/` and `::` in module paths
+///
+/// The pattern is anchored at the start of the name.
+pub fn name_glob_regex(pattern: &str, separators: &str) -> Regex {
+    compile("^", pattern, separators)
+}
+
+fn compile(prefix: &str, glob: &str, separators: &str) -> Regex {
+    let component = format!("[^{}]", regex::escape(separators));
+    let mut regex = String::from(prefix);
+    let mut rest = glob;
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("**") {
+            // `**` followed by separators may also match no components at all
+            let tail = after.trim_start_matches(|c| separators.contains(c));
+            let run = &after[..after.len() - tail.len()];
+            if run.is_empty() {
+                regex.push_str(".*");
+            } else {
+                regex.push_str(&format!("(?:.*{})?", regex::escape(run)));
+            }
+            rest = tail;
+            continue;
+        }
+        match c {
+            '*' => regex.push_str(&format!("{component}*")),
+            '?' => regex.push_str(&component),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    regex.push('$');
+    Regex::new(&regex).expect("escaped glob is a valid regex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_regex() {
+        let glob = glob_regex("src/**/*.py");
+        assert!(glob.is_match("/repo/src/app.py"));
+        assert!(glob.is_match("/repo/src/api/v1/app.py"));
+        assert!(!glob.is_match("/repo/src/app.pyc"));
+        assert!(!glob.is_match("/repo/mysrc/app.py"));
+
+        assert!(glob_regex("*.go").is_match("/repo/pkg/main.go"));
+        assert!(!glob_regex("/src/*.go").is_match("/repo/src/main.go"));
+        assert!(glob_regex("src/**").is_match("/repo/src/api/app.py"));
+    }
+
+    #[test]
+    fn test_double_star_matches_no_components() {
+        let glob = glob_regex("src/**/models.py");
+        assert!(glob.is_match("src/models.py"));
+        assert!(glob.is_match("src/api/models.py"));
+        assert!(!glob.is_match("src/api_models.py"));
+
+        let modules = name_glob_regex("app::**::db", "./:");
+        assert!(modules.is_match("app::db"));
+        assert!(modules.is_match("app::infra::db"));
+    }
+
+    #[test]
+    fn test_question_mark_stays_in_component() {
+        let glob = glob_regex("src/app?.py");
+        assert!(glob.is_match("/repo/src/app1.py"));
+        assert!(!glob.is_match("/repo/src/app/.py"));
+        assert!(!glob.is_match("/repo/src/app.py"));
+
+        let modules = name_glob_regex("app.v?", "./:");
+        assert!(modules.is_match("app.v2"));
+        assert!(!modules.is_match("app.v.db"));
+        assert!(!name_glob_regex("app?db", "./:").is_match("app.db"));
+    }
+}
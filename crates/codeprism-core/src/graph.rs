@@ -5,13 +5,26 @@
 
 use crate::ast::{Edge, EdgeKind, Node, NodeId, NodeKind};
 use crate::error::Result;
+use crate::patch::AstPatch;
 use dashmap::DashMap;
 use regex;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+/// Edge kinds followed backwards by impact analysis
+///
+/// `RoutesTo` is included so that routes dispatching to an affected handler are
+/// reported as affected entry points.
+const IMPACT_EDGE_KINDS: [EdgeKind; 5] = [
+    EdgeKind::Calls,
+    EdgeKind::Imports,
+    EdgeKind::Extends,
+    EdgeKind::Implements,
+    EdgeKind::RoutesTo,
+];
+
 /// In-memory graph store for code intelligence
 #[derive(Debug)]
 pub struct GraphStore {
@@ -155,9 +168,16 @@ impl GraphStore {
     /// Remove a node and all its edges
     pub fn remove_node(&self, node_id: &NodeId) -> Option<Node> {
         if let Some((_, node)) = self.nodes.remove(node_id) {
-            // Remove from indices
-            if let Some(mut file_nodes) = self.file_index.get_mut(&node.file) {
-                file_nodes.retain(|id| id != node_id);
+            // Remove from indices, dropping files that no longer hold any nodes
+            let file_emptied = match self.file_index.get_mut(&node.file) {
+                Some(mut file_nodes) => {
+                    file_nodes.retain(|id| id != node_id);
+                    file_nodes.is_empty()
+                }
+                None => false,
+            };
+            if file_emptied {
+                self.file_index.remove(&node.file);
             }
 
             if let Some(mut symbol_nodes) = self.symbol_index.get_mut(&node.name) {
@@ -187,6 +207,40 @@ impl GraphStore {
         }
     }
 
+    /// Remove an edge by its ID, returning whether it was present
+    pub fn remove_edge(&self, edge_id: &str) -> bool {
+        let mut removed = false;
+        for mut edges in self.outgoing_edges.iter_mut() {
+            let before = edges.len();
+            edges.retain(|edge| edge.id() != edge_id);
+            removed |= edges.len() != before;
+        }
+        for mut edges in self.incoming_edges.iter_mut() {
+            edges.retain(|edge| edge.id() != edge_id);
+        }
+        removed
+    }
+
+    /// Apply a patch: deletions first, then additions
+    ///
+    /// Node IDs in `nodes_delete` that are not valid hex are ignored.
+    pub fn apply_patch(&self, patch: &AstPatch) {
+        for node_id in &patch.nodes_delete {
+            if let Ok(node_id) = NodeId::from_hex(node_id) {
+                self.remove_node(&node_id);
+            }
+        }
+        for edge_id in &patch.edges_delete {
+            self.remove_edge(edge_id);
+        }
+        for node in &patch.nodes_add {
+            self.add_node(node.clone());
+        }
+        for edge in &patch.edges_add {
+            self.add_edge(edge.clone());
+        }
+    }
+
     /// Get all file paths in the index
     pub fn get_all_files(&self) -> Vec<PathBuf> {
         self.file_index
@@ -786,6 +840,184 @@ impl GraphQuery {
         let mixins = self.get_mixins(node_id)?;
         Ok(mixins.iter().any(|m| m.class_name == mixin_name))
     }
+
+    /// Find the symbols that a set of changes can break
+    ///
+    /// Each change is mapped to the nodes of its file whose spans overlap it,
+    /// then incoming `Calls`, `Imports`, `Extends`, `Implements` and `RoutesTo`
+    /// edges are followed backwards up to `max_depth` hops. Call and import
+    /// nodes only connect two symbols, so passing through them is not a hop.
+    pub fn analyze_impact(
+        &self,
+        changes: &[ChangedRegion],
+        max_depth: Option<usize>,
+    ) -> Result<ImpactAnalysis> {
+        let max_depth = max_depth.unwrap_or(5);
+
+        let mut changed = Vec::new();
+        let mut unmatched = Vec::new();
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+
+        for change in changes {
+            let mut nodes: Vec<Node> = self
+                .graph
+                .get_nodes_in_file(&change.file)
+                .into_iter()
+                .filter(|node| change.overlaps(node))
+                .collect();
+            if nodes.is_empty() {
+                unmatched.push(change.clone());
+                continue;
+            }
+            nodes.sort_by_key(|node| node.span.start_byte);
+            for node in nodes {
+                if distances.insert(node.id, 0).is_none() {
+                    queue.push_back((node.id, 0));
+                    if !is_connector(node.kind) {
+                        changed.push(node);
+                    }
+                }
+            }
+        }
+
+        // 0-1 BFS: connectors are pushed to the front at the same distance
+        let mut reached_by: HashMap<NodeId, (Option<NodeId>, EdgeKind)> = HashMap::new();
+        while let Some((current, distance)) = queue.pop_front() {
+            if distances.get(&current) != Some(&distance) {
+                continue;
+            }
+            let current_is_connector = self
+                .graph
+                .get_node(&current)
+                .is_some_and(|node| is_connector(node.kind));
+            let from = if current_is_connector {
+                reached_by.get(&current).and_then(|(from, _)| *from)
+            } else {
+                Some(current)
+            };
+
+            for edge in self.graph.get_incoming_edges(&current) {
+                if !IMPACT_EDGE_KINDS.contains(&edge.kind) {
+                    continue;
+                }
+                let Some(source) = self.graph.get_node(&edge.source) else {
+                    continue;
+                };
+                let connector = is_connector(source.kind);
+                let next = if connector { distance } else { distance + 1 };
+                if next > max_depth || distances.get(&source.id).is_some_and(|d| *d <= next) {
+                    continue;
+                }
+
+                distances.insert(source.id, next);
+                reached_by.insert(source.id, (from, edge.kind));
+                if connector {
+                    queue.push_front((source.id, next));
+                } else {
+                    queue.push_back((source.id, next));
+                }
+            }
+        }
+
+        let mut affected: Vec<ImpactedSymbol> = reached_by
+            .into_iter()
+            .filter_map(|(node_id, (reached_from, edge_kind))| {
+                let node = self.graph.get_node(&node_id)?;
+                if is_connector(node.kind) {
+                    return None;
+                }
+                Some(ImpactedSymbol {
+                    distance: distances[&node_id],
+                    node,
+                    reached_from,
+                    edge_kind: Some(edge_kind),
+                })
+            })
+            .collect();
+        affected.sort_by(|a, b| {
+            (a.distance, &a.node.file, a.node.span.start_byte).cmp(&(
+                b.distance,
+                &b.node.file,
+                b.node.span.start_byte,
+            ))
+        });
+
+        let entry_points = changed
+            .iter()
+            .map(|node| ImpactedSymbol {
+                node: node.clone(),
+                distance: 0,
+                reached_from: None,
+                edge_kind: None,
+            })
+            .chain(affected.iter().cloned())
+            .filter(|symbol| symbol.node.kind == NodeKind::Route)
+            .collect();
+
+        let mut test_files: Vec<ImpactedFile> = Vec::new();
+        let changed_nodes = changed.iter().map(|node| (node, 0));
+        let affected_nodes = affected
+            .iter()
+            .map(|symbol| (&symbol.node, symbol.distance));
+        for (node, distance) in changed_nodes.chain(affected_nodes) {
+            if !is_test_file(&node.file) {
+                continue;
+            }
+            match test_files.iter_mut().find(|file| file.file == node.file) {
+                Some(file) => {
+                    file.distance = file.distance.min(distance);
+                    file.symbols += 1;
+                }
+                None => test_files.push(ImpactedFile {
+                    file: node.file.clone(),
+                    distance,
+                    symbols: 1,
+                }),
+            }
+        }
+        test_files.sort_by(|a, b| (a.distance, &a.file).cmp(&(b.distance, &b.file)));
+
+        Ok(ImpactAnalysis {
+            changed,
+            affected,
+            entry_points,
+            test_files,
+            unmatched,
+            max_depth,
+        })
+    }
+}
+
+/// Whether a node only connects two symbols (a call site or import statement)
+fn is_connector(kind: NodeKind) -> bool {
+    matches!(kind, NodeKind::Call | NodeKind::Import)
+}
+
+/// Whether a path looks like a test file by directory or file name convention
+pub fn is_test_file(path: &Path) -> bool {
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components().any(|component| {
+            matches!(
+                component.as_os_str().to_str(),
+                Some("test" | "tests" | "__tests__" | "spec")
+            )
+        })
+    });
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let lower = stem.to_lowercase();
+
+    in_test_dir
+        || lower.starts_with("test_")
+        || lower.ends_with("_test")
+        || lower.ends_with(".test")
+        || lower.ends_with(".spec")
+        || lower.ends_with("_spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
 }
 
 /// Result of a path finding operation
@@ -909,6 +1141,88 @@ pub struct InheritanceRelation {
     pub span: crate::ast::Span,
 }
 
+/// A changed file or line range used as the starting point of impact analysis
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedRegion {
+    /// Changed file, as stored in the graph
+    pub file: PathBuf,
+    /// Changed lines (1-indexed, inclusive), or `None` for the whole file
+    pub lines: Option<(usize, usize)>,
+}
+
+impl ChangedRegion {
+    /// A change to a whole file
+    pub fn file(file: impl Into<PathBuf>) -> Self {
+        Self {
+            file: file.into(),
+            lines: None,
+        }
+    }
+
+    /// A change to lines `start..=end` of a file
+    pub fn lines(file: impl Into<PathBuf>, start: usize, end: usize) -> Self {
+        Self {
+            file: file.into(),
+            lines: Some((start.min(end), start.max(end))),
+        }
+    }
+
+    /// Whether the change touches a node
+    ///
+    /// Module nodes span their whole file, so a line range only selects them
+    /// when it covers the module's first line.
+    fn overlaps(&self, node: &Node) -> bool {
+        match self.lines {
+            None => true,
+            Some((start, end)) if node.kind == NodeKind::Module => {
+                start <= node.span.start_line && node.span.start_line <= end
+            }
+            Some((start, end)) => node.span.start_line <= end && start <= node.span.end_line,
+        }
+    }
+}
+
+/// Result of an impact analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactAnalysis {
+    /// Symbols inside the changed regions
+    pub changed: Vec<Node>,
+    /// Symbols that depend on a changed symbol, nearest first
+    pub affected: Vec<ImpactedSymbol>,
+    /// Routes among the changed and affected symbols
+    pub entry_points: Vec<ImpactedSymbol>,
+    /// Test files holding changed or affected symbols, nearest first
+    pub test_files: Vec<ImpactedFile>,
+    /// Changes that matched no indexed node
+    pub unmatched: Vec<ChangedRegion>,
+    /// Maximum distance that was searched
+    pub max_depth: usize,
+}
+
+/// A symbol reached by impact analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactedSymbol {
+    /// The affected symbol
+    pub node: Node,
+    /// Hops from the nearest changed symbol (0 for changed symbols)
+    pub distance: usize,
+    /// The symbol through which this one is affected
+    pub reached_from: Option<NodeId>,
+    /// Kind of the edge that connects this symbol towards the change
+    pub edge_kind: Option<EdgeKind>,
+}
+
+/// A file holding changed or affected symbols
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactedFile {
+    /// File path
+    pub file: PathBuf,
+    /// Distance of its nearest changed or affected symbol
+    pub distance: usize,
+    /// Number of changed or affected symbols in the file
+    pub symbols: usize,
+}
+
 /// Represents a dynamic attribute created by metaclasses or decorators
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DynamicAttribute {
@@ -1065,4 +1379,131 @@ mod tests {
         let results = query.search_symbols("Agent", None, None).unwrap();
         assert_eq!(results.len(), 4); // All nodes containing "Agent"
     }
+
+    #[test]
+    fn test_apply_patch_replaces_file_nodes() {
+        let graph = GraphStore::new();
+
+        let old_fn = create_test_node("old_function", NodeKind::Function, "module.py");
+        let caller = create_test_node("caller", NodeKind::Function, "main.py");
+        let old_edge = Edge::new(caller.id, old_fn.id, EdgeKind::Calls);
+        graph.add_node(old_fn.clone());
+        graph.add_node(caller.clone());
+        graph.add_edge(old_edge.clone());
+
+        let new_fn =
+            create_test_node_with_span("new_function", NodeKind::Function, "module.py", 20, 40);
+        let patch = crate::patch::PatchBuilder::new("test_repo".to_string(), "live".to_string())
+            .delete_node(old_fn.id.to_hex())
+            .add_node(new_fn.clone())
+            .add_edge(Edge::new(caller.id, new_fn.id, EdgeKind::Calls))
+            .build();
+        graph.apply_patch(&patch);
+
+        assert!(graph.get_node(&old_fn.id).is_none());
+        let module_nodes = graph.get_nodes_in_file(&PathBuf::from("module.py"));
+        assert_eq!(module_nodes.len(), 1);
+        assert_eq!(module_nodes[0].name, "new_function");
+
+        let outgoing = graph.get_outgoing_edges(&caller.id);
+        assert_eq!(outgoing.len(), 1);
+        assert_eq!(outgoing[0].target, new_fn.id);
+
+        assert!(graph.remove_edge(&outgoing[0].id()));
+        assert!(graph.get_outgoing_edges(&caller.id).is_empty());
+        assert!(graph.get_incoming_edges(&new_fn.id).is_empty());
+        assert!(!graph.remove_edge(&old_edge.id()));
+    }
+
+    #[test]
+    fn test_analyze_impact_walks_incoming_edges_by_distance() {
+        let graph = Arc::new(GraphStore::new());
+        let query = GraphQuery::new(graph.clone());
+
+        let changed = Node::new(
+            "test_repo",
+            NodeKind::Function,
+            "save".to_string(),
+            Language::Python,
+            PathBuf::from("db.py"),
+            Span::new(0, 50, 10, 14, 1, 1),
+        );
+        let untouched = create_test_node("load", NodeKind::Function, "db.py");
+        let caller = create_test_node("create_user", NodeKind::Function, "service.py");
+        let call = create_test_node("save", NodeKind::Call, "service.py");
+        let route = create_test_node("POST /users", NodeKind::Route, "routes.py");
+        let test = create_test_node(
+            "test_create_user",
+            NodeKind::Function,
+            "tests/test_users.py",
+        );
+        let test_call = create_test_node("create_user", NodeKind::Call, "tests/test_users.py");
+        for node in [
+            &changed, &untouched, &caller, &call, &route, &test, &test_call,
+        ] {
+            graph.add_node(node.clone());
+        }
+        graph.add_edge(Edge::new(caller.id, call.id, EdgeKind::Calls));
+        graph.add_edge(Edge::new(call.id, changed.id, EdgeKind::Calls));
+        graph.add_edge(Edge::new(route.id, caller.id, EdgeKind::RoutesTo));
+        graph.add_edge(Edge::new(test.id, test_call.id, EdgeKind::Calls));
+        graph.add_edge(Edge::new(test_call.id, caller.id, EdgeKind::Calls));
+        graph.add_edge(Edge::new(untouched.id, caller.id, EdgeKind::Reads));
+
+        let changes = [
+            ChangedRegion::lines("db.py", 12, 12),
+            ChangedRegion::file("missing.py"),
+        ];
+        let impact = query.analyze_impact(&changes, None).unwrap();
+
+        assert_eq!(impact.changed.len(), 1);
+        assert_eq!(impact.changed[0].id, changed.id);
+        assert_eq!(impact.unmatched, vec![ChangedRegion::file("missing.py")]);
+
+        let affected: Vec<_> = impact
+            .affected
+            .iter()
+            .map(|symbol| (symbol.node.name.as_str(), symbol.distance))
+            .collect();
+        assert_eq!(affected[0], ("create_user", 1));
+        assert!(affected.contains(&("POST /users", 2)));
+        assert!(affected.contains(&("test_create_user", 2)));
+        assert_eq!(affected.len(), 3);
+        assert_eq!(impact.affected[0].reached_from, Some(changed.id));
+
+        assert_eq!(impact.entry_points.len(), 1);
+        assert_eq!(impact.entry_points[0].node.id, route.id);
+        assert_eq!(impact.test_files.len(), 1);
+        assert_eq!(
+            impact.test_files[0].file,
+            PathBuf::from("tests/test_users.py")
+        );
+        assert_eq!(impact.test_files[0].distance, 2);
+
+        let shallow = query.analyze_impact(&changes, Some(1)).unwrap();
+        assert_eq!(shallow.affected.len(), 1);
+        assert!(shallow.entry_points.is_empty());
+    }
+
+    #[test]
+    fn test_is_test_file() {
+        assert!(is_test_file(Path::new("tests/test_users.py")));
+        assert!(is_test_file(Path::new("pkg/orders_test.go")));
+        assert!(is_test_file(Path::new("src/app.spec.ts")));
+        assert!(is_test_file(Path::new("src/OrderServiceTest.java")));
+        assert!(!is_test_file(Path::new("src/contest.py")));
+        assert!(!is_test_file(Path::new("src/latest.rs")));
+    }
+
+    #[test]
+    fn test_remove_last_node_drops_file() {
+        let graph = GraphStore::new();
+        let node = create_test_node("only", NodeKind::Function, "gone.py");
+        graph.add_node(node.clone());
+        assert_eq!(graph.get_stats().total_files, 1);
+
+        graph.remove_node(&node.id);
+        assert_eq!(graph.get_stats().total_files, 0);
+        assert!(graph.get_all_files().is_empty());
+    }
 }
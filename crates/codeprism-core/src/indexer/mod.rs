@@ -5,14 +5,16 @@
 
 use crate::ast::Edge;
 use crate::error::{Error, Result};
+use crate::git::diff::{self, ParsedFile};
+use crate::git::{ChangedFile, FileDiff, GitRepository, SemanticDiff};
 use crate::graph::GraphStore;
 use crate::linkers::SymbolResolver;
-use crate::parser::{ParseContext, ParserEngine};
+use crate::parser::{ParseContext, ParseResult, ParserEngine};
 use crate::patch::{AstPatch, PatchBuilder};
 use crate::scanner::{DiscoveredFile, ProgressReporter, ScanResult};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -207,29 +209,7 @@ impl BulkIndexer {
         }
 
         // After all files are processed, perform cross-file symbol resolution
-        if self.config.enable_cross_file_linking {
-            tracing::info!("Starting cross-file symbol resolution...");
-            let linking_start = Instant::now();
-
-            let cross_file_edges = self.resolve_cross_file_symbols(&indexing_result)?;
-
-            if !cross_file_edges.is_empty() {
-                // Create a patch with the new cross-file edges
-                let cross_file_patch =
-                    PatchBuilder::new(self.config.repo_id.clone(), self.config.commit_sha.clone())
-                        .add_edges(cross_file_edges.clone())
-                        .build();
-
-                indexing_result.patches.push(cross_file_patch);
-                indexing_result.stats.edges_created += cross_file_edges.len();
-
-                tracing::info!(
-                    "Cross-file symbol resolution completed: {} edges created in {}ms",
-                    cross_file_edges.len(),
-                    linking_start.elapsed().as_millis()
-                );
-            }
-        }
+        self.link_cross_file_symbols(&mut indexing_result, &self.config.commit_sha)?;
 
         // Finalize statistics
         indexing_result.stats.duration_ms = start_time.elapsed().as_millis() as u64;
@@ -419,9 +399,13 @@ impl BulkIndexer {
         // Parse the file
         let parse_result = self.parser_engine.parse_file(context)?;
 
+        Ok(self.build_patch(parse_result, &self.config.commit_sha))
+    }
+
+    /// Create a patch adding everything in a parse result
+    fn build_patch(&self, parse_result: ParseResult, commit: &str) -> Option<AstPatch> {
         // Create patch from parse result
-        let mut patch_builder =
-            PatchBuilder::new(self.config.repo_id.clone(), self.config.commit_sha.clone());
+        let mut patch_builder = PatchBuilder::new(self.config.repo_id.clone(), commit.to_string());
 
         // Add all nodes
         patch_builder = patch_builder.add_nodes(parse_result.nodes);
@@ -433,12 +417,178 @@ impl BulkIndexer {
 
         // Only return patch if it has content
         if patch.is_empty() {
-            Ok(None)
+            None
         } else {
-            Ok(Some(patch))
+            Some(patch)
         }
     }
 
+    /// Index a git revision straight from the object database
+    ///
+    /// Files are read from the commit tree, so the working tree is neither
+    /// read nor checked out. Only files with a registered parser are indexed,
+    /// and patches carry the resolved commit ID.
+    pub fn index_revision(
+        &self,
+        repository: &GitRepository,
+        revision: &str,
+    ) -> Result<IndexingResult> {
+        let start_time = Instant::now();
+        let commit = repository.resolve_commit(revision)?;
+        let files = repository.files_at(&commit, |path| self.parser_engine.supports_file(path))?;
+
+        let parsed: Vec<_> = files
+            .into_par_iter()
+            .filter(|file| !file.content.trim().is_empty())
+            .map(|file| {
+                let result = self.parse_revision_file(&file.path, file.content);
+                (file.path, result)
+            })
+            .collect();
+
+        let mut indexing_result = IndexingResult::new(self.config.repo_id.clone());
+        for (path, result) in parsed {
+            match result {
+                Ok(parse_result) => {
+                    indexing_result.stats.files_processed += 1;
+                    if let Some(patch) = self.build_patch(parse_result, &commit) {
+                        indexing_result.stats.nodes_created += patch.nodes_add.len();
+                        indexing_result.stats.edges_created += patch.edges_add.len();
+                        indexing_result.patches.push(patch);
+                    }
+                }
+                Err(error) if self.config.continue_on_error => {
+                    indexing_result.stats.error_count += 1;
+                    indexing_result.failed_files.push((path, error));
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        self.link_cross_file_symbols(&mut indexing_result, &commit)?;
+
+        indexing_result.stats.duration_ms = start_time.elapsed().as_millis() as u64;
+        indexing_result.stats.throughput = if indexing_result.stats.duration_ms > 0 {
+            (indexing_result.stats.files_processed as f64 * 1000.0)
+                / indexing_result.stats.duration_ms as f64
+        } else {
+            0.0
+        };
+        Ok(indexing_result)
+    }
+
+    /// Compute the semantic diff between two git revisions
+    ///
+    /// Only files that differ between the revisions are parsed. The patches
+    /// of the diff cover the nodes and edges produced by the parsers; edges
+    /// added by cross-file linking are not part of them.
+    pub fn diff_revisions(
+        &self,
+        repository: &GitRepository,
+        from: &str,
+        to: &str,
+    ) -> Result<SemanticDiff> {
+        let from = repository.resolve_commit(from)?;
+        let to = repository.resolve_commit(to)?;
+        let changed =
+            repository.changed_files(&from, &to, |path| self.parser_engine.supports_file(path))?;
+
+        let results: Vec<_> = changed
+            .into_par_iter()
+            .map(|file| {
+                let path = file.path.clone();
+                let result = self.diff_changed_file(file, &to);
+                (path, result)
+            })
+            .collect();
+
+        let mut diff = SemanticDiff {
+            repo_id: self.config.repo_id.clone(),
+            from,
+            to,
+            files: Vec::new(),
+            failed_files: Vec::new(),
+        };
+        for (path, result) in results {
+            match result {
+                Ok(file_diff) => diff.files.push(file_diff),
+                Err(error) if self.config.continue_on_error => {
+                    diff.failed_files.push((path, error.to_string()));
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        diff.files.sort_by(|a, b| a.path.cmp(&b.path));
+        diff.failed_files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(diff)
+    }
+
+    /// Parse both sides of a changed file and compare them
+    fn diff_changed_file(&self, file: ChangedFile, commit: &str) -> Result<FileDiff> {
+        let parse = |content: String| -> Result<ParsedFile> {
+            let parse_result = self.parse_revision_file(&file.path, content.clone())?;
+            Ok(ParsedFile {
+                nodes: parse_result.nodes,
+                edges: parse_result.edges,
+                content,
+            })
+        };
+        let old = file.old.map(|old| parse(old.content)).transpose()?;
+        let new = file.new.map(|new| parse(new.content)).transpose()?;
+
+        Ok(diff::diff_file(
+            &self.config.repo_id,
+            commit,
+            file.path,
+            file.status,
+            old.as_ref(),
+            new.as_ref(),
+        ))
+    }
+
+    /// Parse file content read from a git revision
+    ///
+    /// The tree cache belongs to the working tree, so it is bypassed.
+    fn parse_revision_file(&self, path: &Path, content: String) -> Result<ParseResult> {
+        let context = ParseContext::new(self.config.repo_id.clone(), path.to_path_buf(), content);
+        self.parser_engine.parse_uncached(&context)
+    }
+
+    /// Add edges between symbols of different files, if enabled
+    fn link_cross_file_symbols(
+        &self,
+        indexing_result: &mut IndexingResult,
+        commit: &str,
+    ) -> Result<()> {
+        if !self.config.enable_cross_file_linking {
+            return Ok(());
+        }
+
+        tracing::info!("Starting cross-file symbol resolution...");
+        let linking_start = Instant::now();
+
+        let cross_file_edges = self.resolve_cross_file_symbols(indexing_result)?;
+
+        if !cross_file_edges.is_empty() {
+            // Create a patch with the new cross-file edges
+            let cross_file_patch =
+                PatchBuilder::new(self.config.repo_id.clone(), commit.to_string())
+                    .add_edges(cross_file_edges.clone())
+                    .build();
+
+            indexing_result.patches.push(cross_file_patch);
+            indexing_result.stats.edges_created += cross_file_edges.len();
+
+            tracing::info!(
+                "Cross-file symbol resolution completed: {} edges created in {}ms",
+                cross_file_edges.len(),
+                linking_start.elapsed().as_millis()
+            );
+        }
+
+        Ok(())
+    }
+
     /// Estimate memory usage of the indexing result
     fn estimate_memory_usage(&self, result: &IndexingResult) -> usize {
         let mut total = 0;
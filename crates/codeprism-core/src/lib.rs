@@ -10,6 +10,8 @@
 pub mod ast;
 pub mod content;
 pub mod error;
+pub mod git;
+pub mod glob;
 pub mod graph;
 pub mod indexer;
 pub mod linkers;
@@ -17,6 +19,7 @@ pub mod observability;
 pub mod parser;
 pub mod patch;
 pub mod pipeline;
+pub mod query;
 pub mod repository;
 pub mod resilience;
 pub mod scanner;
@@ -29,9 +32,14 @@ pub use content::{
     DocumentFormat, SearchQuery, SearchResult,
 };
 pub use error::{Error, ErrorContext, ErrorSeverity, RecoveryStrategy, Result};
+pub use git::{
+    CallEdge, ChangedFile, FileDiff, FileStatus, GitRepository, RevisionFile, SemanticDiff,
+    SymbolChange, SymbolChangeKind,
+};
 pub use graph::{
-    DynamicAttribute, GraphQuery, GraphStore, InheritanceFilter, InheritanceInfo,
-    InheritanceRelation, PathResult, SymbolInfo,
+    ChangedRegion, DynamicAttribute, GraphQuery, GraphStore, ImpactAnalysis, ImpactedFile,
+    ImpactedSymbol, InheritanceFilter, InheritanceInfo, InheritanceRelation, PathResult,
+    SymbolInfo,
 };
 pub use indexer::{
     BulkIndexer, IndexingConfig, IndexingProgressReporter, IndexingResult, IndexingStats,
@@ -48,6 +56,7 @@ pub use pipeline::{
     LoggingEventHandler, MonitoringPipeline, NoOpEventHandler, PipelineConfig, PipelineEvent,
     PipelineEventHandler, PipelineStats,
 };
+pub use query::{Query, QueryResult, QueryValue};
 pub use repository::{HealthStatus, RepositoryConfig, RepositoryInfo, RepositoryManager};
 pub use resilience::{CircuitBreaker, CircuitState, ResilienceManager, RetryConfig};
 pub use scanner::{
@@ -64,9 +73,14 @@ pub mod prelude {
         DocumentFormat, SearchQuery, SearchResult,
     };
     pub use crate::error::{Error, ErrorContext, ErrorSeverity, RecoveryStrategy, Result};
+    pub use crate::git::{
+        CallEdge, ChangedFile, FileDiff, FileStatus, GitRepository, RevisionFile, SemanticDiff,
+        SymbolChange, SymbolChangeKind,
+    };
     pub use crate::graph::{
-        DynamicAttribute, GraphQuery, GraphStore, InheritanceFilter, InheritanceInfo,
-        InheritanceRelation, PathResult, SymbolInfo,
+        ChangedRegion, DynamicAttribute, GraphQuery, GraphStore, ImpactAnalysis, ImpactedFile,
+        ImpactedSymbol, InheritanceFilter, InheritanceInfo, InheritanceRelation, PathResult,
+        SymbolInfo,
     };
     pub use crate::indexer::{
         BulkIndexer, IndexingConfig, IndexingProgressReporter, IndexingResult, IndexingStats,
@@ -86,6 +100,7 @@ pub mod prelude {
         LoggingEventHandler, MonitoringPipeline, NoOpEventHandler, PipelineConfig, PipelineEvent,
         PipelineEventHandler, PipelineStats,
     };
+    pub use crate::query::{Query, QueryResult, QueryValue};
     pub use crate::repository::{
         HealthStatus, RepositoryConfig, RepositoryInfo, RepositoryManager,
     };
@@ -0,0 +1,123 @@
+//! Shared glue between the `codeprism-lang-*` crates and the [`LanguageRegistry`]
+//!
+//! Every language crate has its own copy of the node and edge types, so each
+//! bridge module imports its crate as `lang` and provides the kind mappings:
+//!
+//! - `convert_language(lang::Language) -> Language`
+//! - `convert_node_kind(lang::NodeKind) -> NodeKind`
+//! - `specific_kind(lang::NodeKind) -> Option<&'static str>`, the original kind
+//!   of nodes mapped onto a broader universal kind
+//! - `convert_edge_kind(lang::EdgeKind) -> Option<EdgeKind>`, `None` for edges
+//!   without a universal equivalent
+//!
+//! [`language_bridge!`] then defines the parser type and `register`.
+//!
+//! [`LanguageRegistry`]: super::LanguageRegistry
+
+/// Define a [`LanguageParser`](super::LanguageParser) over the `lang` crate in
+/// scope, registered once for each of the listed languages
+///
+/// Language-specific kinds are kept in the node metadata under `$kind_key`.
+macro_rules! language_bridge {
+    ($parser:ident, $inner:ident, $kind_key:literal, [$($language:ident),+]) => {
+        #[doc = concat!("[`LanguageParser`](crate::parser::LanguageParser) backed by `", stringify!($inner), "`")]
+        pub struct $parser {
+            language: $crate::ast::Language,
+            parser: lang::$inner,
+        }
+
+        impl $parser {
+            /// Create a parser for files of `language`
+            pub fn new(language: $crate::ast::Language) -> Self {
+                Self {
+                    language,
+                    parser: lang::create_parser(),
+                }
+            }
+        }
+
+        impl $crate::parser::LanguageParser for $parser {
+            fn language(&self) -> $crate::ast::Language {
+                self.language
+            }
+
+            fn parse(
+                &self,
+                context: &$crate::parser::ParseContext,
+            ) -> $crate::error::Result<$crate::parser::ParseResult> {
+                let (tree, lang_nodes, lang_edges) = lang::parse_file(
+                    &self.parser,
+                    &context.repo_id,
+                    context.file_path.clone(),
+                    context.content.clone(),
+                    context.old_tree.clone(),
+                )
+                .map_err(|e| $crate::error::Error::parse(&context.file_path, e.to_string()))?;
+
+                let mut ids = std::collections::HashMap::with_capacity(lang_nodes.len());
+                let mut nodes = Vec::with_capacity(lang_nodes.len());
+                for lang_node in lang_nodes {
+                    let lang_id = lang_node.id;
+                    let node = convert_node(&context.repo_id, lang_node);
+                    ids.insert(lang_id, node.id);
+                    nodes.push(node);
+                }
+
+                let edges = lang_edges
+                    .into_iter()
+                    .filter_map(|edge| {
+                        Some($crate::ast::Edge::new(
+                            *ids.get(&edge.source)?,
+                            *ids.get(&edge.target)?,
+                            convert_edge_kind(edge.kind)?,
+                        ))
+                    })
+                    .collect();
+
+                Ok($crate::parser::ParseResult { tree, nodes, edges })
+            }
+        }
+
+        #[doc = concat!("Register [`", stringify!($parser), "`] in `registry`")]
+        pub fn register(registry: &$crate::parser::LanguageRegistry) {
+            $(
+                registry.register(std::sync::Arc::new($parser::new(
+                    $crate::ast::Language::$language,
+                )));
+            )+
+        }
+
+        fn convert_node(repo_id: &str, node: lang::Node) -> $crate::ast::Node {
+            let kind = convert_node_kind(node.kind);
+            let span = $crate::ast::Span::new(
+                node.span.start_byte,
+                node.span.end_byte,
+                node.span.start_line,
+                node.span.end_line,
+                node.span.start_column,
+                node.span.end_column,
+            );
+
+            let mut metadata = node.metadata;
+            if let Some(specific) = specific_kind(node.kind) {
+                if !metadata.is_object() {
+                    metadata = serde_json::json!({});
+                }
+                metadata[$kind_key] = serde_json::json!(specific);
+            }
+
+            $crate::ast::Node {
+                id: $crate::ast::NodeId::new(repo_id, &node.file, &span, &kind),
+                kind,
+                name: node.name,
+                lang: convert_language(node.lang),
+                file: node.file,
+                span,
+                signature: node.signature,
+                metadata,
+            }
+        }
+    };
+}
+
+pub(crate) use language_bridge;
@@ -0,0 +1,144 @@
+//! Go parser registration
+//!
+//! Bridges `codeprism-lang-go` into the
+//! [`LanguageRegistry`](super::LanguageRegistry). Go-specific node kinds are
+//! mapped onto the closest universal kind, and the original kind is kept in
+//! the node metadata under `go_kind`.
+
+use super::bridge::language_bridge;
+use crate::ast::{EdgeKind, Language, NodeKind};
+use codeprism_lang_go as lang;
+
+language_bridge!(GoLanguageParser, GoLanguageParser, "go_kind", [Go]);
+
+fn convert_language(_: lang::Language) -> Language {
+    Language::Go
+}
+
+fn convert_node_kind(kind: lang::NodeKind) -> NodeKind {
+    match kind {
+        lang::NodeKind::Module | lang::NodeKind::Package => NodeKind::Module,
+        lang::NodeKind::Class
+        | lang::NodeKind::Struct
+        | lang::NodeKind::Interface
+        | lang::NodeKind::TypeAlias => NodeKind::Class,
+        lang::NodeKind::Function => NodeKind::Function,
+        lang::NodeKind::Method => NodeKind::Method,
+        lang::NodeKind::Parameter => NodeKind::Parameter,
+        lang::NodeKind::Variable
+        | lang::NodeKind::Field
+        | lang::NodeKind::Const
+        | lang::NodeKind::Channel => NodeKind::Variable,
+        lang::NodeKind::Call | lang::NodeKind::Goroutine => NodeKind::Call,
+        lang::NodeKind::Import => NodeKind::Import,
+        lang::NodeKind::Literal => NodeKind::Literal,
+        lang::NodeKind::Route => NodeKind::Route,
+        lang::NodeKind::SqlQuery => NodeKind::SqlQuery,
+        lang::NodeKind::Event => NodeKind::Event,
+        lang::NodeKind::Unknown => NodeKind::Unknown,
+    }
+}
+
+fn specific_kind(kind: lang::NodeKind) -> Option<&'static str> {
+    match kind {
+        lang::NodeKind::Package => Some("package"),
+        lang::NodeKind::Struct => Some("struct"),
+        lang::NodeKind::Interface => Some("interface"),
+        lang::NodeKind::TypeAlias => Some("type_alias"),
+        lang::NodeKind::Field => Some("field"),
+        lang::NodeKind::Const => Some("const"),
+        lang::NodeKind::Goroutine => Some("goroutine"),
+        lang::NodeKind::Channel => Some("channel"),
+        _ => None,
+    }
+}
+
+/// Universal edge kind for a Go edge; containment has no universal equivalent
+fn convert_edge_kind(kind: lang::EdgeKind) -> Option<EdgeKind> {
+    match kind {
+        lang::EdgeKind::Calls | lang::EdgeKind::Spawns => Some(EdgeKind::Calls),
+        lang::EdgeKind::Reads | lang::EdgeKind::Receives => Some(EdgeKind::Reads),
+        lang::EdgeKind::Writes | lang::EdgeKind::Sends => Some(EdgeKind::Writes),
+        lang::EdgeKind::Imports => Some(EdgeKind::Imports),
+        lang::EdgeKind::Emits => Some(EdgeKind::Emits),
+        lang::EdgeKind::RoutesTo => Some(EdgeKind::RoutesTo),
+        lang::EdgeKind::Raises => Some(EdgeKind::Raises),
+        lang::EdgeKind::Extends | lang::EdgeKind::Embeds => Some(EdgeKind::Extends),
+        lang::EdgeKind::Implements => Some(EdgeKind::Implements),
+        lang::EdgeKind::Contains => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::Edge;
+    use crate::parser::{LanguageRegistry, ParseContext, ParserEngine};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_go_files_parse_through_registry() {
+        let registry = Arc::new(LanguageRegistry::new());
+        register(&registry);
+        let engine = ParserEngine::new(registry);
+
+        let context = ParseContext::new(
+            "repo".to_string(),
+            PathBuf::from("server.go"),
+            r#"package main
+
+import "net/http"
+
+type Greeter interface{ Greet() string }
+
+type english struct{}
+
+func (english) Greet() string { return "hello" }
+
+func hello(w http.ResponseWriter, r *http.Request) {}
+
+func main() {
+	http.HandleFunc("/hello", hello)
+	go http.ListenAndServe(":8080", nil)
+}
+"#
+            .to_string(),
+        );
+        let result = engine.parse_file(context).unwrap();
+
+        assert!(result.nodes.iter().all(|n| n.lang == Language::Go));
+        let find = |kind: NodeKind, name: &str| {
+            result
+                .nodes
+                .iter()
+                .find(|n| n.kind == kind && n.name == name)
+                .unwrap()
+        };
+
+        let route = find(NodeKind::Route, "/hello");
+        let handler = find(NodeKind::Function, "hello");
+        assert!(result
+            .edges
+            .contains(&Edge::new(route.id, handler.id, EdgeKind::RoutesTo)));
+
+        let interface = find(NodeKind::Class, "Greeter");
+        let implementor = find(NodeKind::Class, "english");
+        assert_eq!(interface.metadata["go_kind"], "interface");
+        assert!(result.edges.contains(&Edge::new(
+            implementor.id,
+            interface.id,
+            EdgeKind::Implements
+        )));
+
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| n.metadata["go_kind"] == "goroutine"));
+        // Every edge refers to converted nodes
+        assert!(result.edges.iter().all(|edge| {
+            result.nodes.iter().any(|n| n.id == edge.source)
+                && result.nodes.iter().any(|n| n.id == edge.target)
+        }));
+    }
+}
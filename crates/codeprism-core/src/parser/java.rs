@@ -0,0 +1,131 @@
+//! Java parser registration
+//!
+//! Bridges `codeprism-lang-java` into the
+//! [`LanguageRegistry`](super::LanguageRegistry). Java-specific node kinds are
+//! mapped onto the closest universal kind, and the original kind is kept in
+//! the node metadata under `java_kind`.
+
+use super::bridge::language_bridge;
+use crate::ast::{EdgeKind, Language, NodeKind};
+use codeprism_lang_java as lang;
+
+language_bridge!(JavaLanguageParser, JavaLanguageParser, "java_kind", [Java]);
+
+fn convert_language(language: lang::Language) -> Language {
+    match language {
+        lang::Language::Java => Language::Java,
+        lang::Language::Python => Language::Python,
+        lang::Language::Rust => Language::Rust,
+    }
+}
+
+fn convert_node_kind(kind: lang::NodeKind) -> NodeKind {
+    match kind {
+        lang::NodeKind::Module | lang::NodeKind::Package => NodeKind::Module,
+        lang::NodeKind::Class | lang::NodeKind::Interface | lang::NodeKind::Enum => NodeKind::Class,
+        lang::NodeKind::Function | lang::NodeKind::Lambda => NodeKind::Function,
+        lang::NodeKind::Method | lang::NodeKind::Constructor => NodeKind::Method,
+        lang::NodeKind::Parameter | lang::NodeKind::TypeParameter => NodeKind::Parameter,
+        lang::NodeKind::Variable | lang::NodeKind::Field => NodeKind::Variable,
+        lang::NodeKind::Call | lang::NodeKind::MethodReference => NodeKind::Call,
+        lang::NodeKind::Import => NodeKind::Import,
+        lang::NodeKind::Literal => NodeKind::Literal,
+        lang::NodeKind::Route => NodeKind::Route,
+        lang::NodeKind::SqlQuery => NodeKind::SqlQuery,
+        lang::NodeKind::Event => NodeKind::Event,
+        lang::NodeKind::Annotation
+        | lang::NodeKind::StaticBlock
+        | lang::NodeKind::InstanceBlock
+        | lang::NodeKind::TryBlock
+        | lang::NodeKind::CatchClause
+        | lang::NodeKind::FinallyClause
+        | lang::NodeKind::ThrowStatement
+        | lang::NodeKind::WildcardType
+        | lang::NodeKind::ArrayCreation
+        | lang::NodeKind::SynchronizedBlock
+        | lang::NodeKind::AssertStatement
+        | lang::NodeKind::Unknown => NodeKind::Unknown,
+    }
+}
+
+fn specific_kind(kind: lang::NodeKind) -> Option<&'static str> {
+    match kind {
+        lang::NodeKind::Package => Some("package"),
+        lang::NodeKind::Interface => Some("interface"),
+        lang::NodeKind::Enum => Some("enum"),
+        lang::NodeKind::Lambda => Some("lambda"),
+        lang::NodeKind::Constructor => Some("constructor"),
+        lang::NodeKind::TypeParameter => Some("type_parameter"),
+        lang::NodeKind::Field => Some("field"),
+        lang::NodeKind::MethodReference => Some("method_reference"),
+        lang::NodeKind::Annotation => Some("annotation"),
+        lang::NodeKind::StaticBlock => Some("static_block"),
+        lang::NodeKind::InstanceBlock => Some("instance_block"),
+        lang::NodeKind::TryBlock => Some("try_block"),
+        lang::NodeKind::CatchClause => Some("catch_clause"),
+        lang::NodeKind::FinallyClause => Some("finally_clause"),
+        lang::NodeKind::ThrowStatement => Some("throw_statement"),
+        lang::NodeKind::WildcardType => Some("wildcard_type"),
+        lang::NodeKind::ArrayCreation => Some("array_creation"),
+        lang::NodeKind::SynchronizedBlock => Some("synchronized_block"),
+        lang::NodeKind::AssertStatement => Some("assert_statement"),
+        _ => None,
+    }
+}
+
+/// Universal edge kind for a Java edge, if there is one
+fn convert_edge_kind(kind: lang::EdgeKind) -> Option<EdgeKind> {
+    match kind {
+        lang::EdgeKind::Calls | lang::EdgeKind::Instantiates => Some(EdgeKind::Calls),
+        lang::EdgeKind::Reads | lang::EdgeKind::Accesses | lang::EdgeKind::StaticAccess => {
+            Some(EdgeKind::Reads)
+        }
+        lang::EdgeKind::Writes => Some(EdgeKind::Writes),
+        lang::EdgeKind::Imports | lang::EdgeKind::ImportsPackage => Some(EdgeKind::Imports),
+        lang::EdgeKind::Emits => Some(EdgeKind::Emits),
+        lang::EdgeKind::RoutesTo => Some(EdgeKind::RoutesTo),
+        lang::EdgeKind::Raises | lang::EdgeKind::Throws => Some(EdgeKind::Raises),
+        lang::EdgeKind::Extends => Some(EdgeKind::Extends),
+        lang::EdgeKind::Implements | lang::EdgeKind::ImplementsInterface => {
+            Some(EdgeKind::Implements)
+        }
+        lang::EdgeKind::Annotates
+        | lang::EdgeKind::TypeParameterBinds
+        | lang::EdgeKind::Catches
+        | lang::EdgeKind::Overrides
+        | lang::EdgeKind::Casts
+        | lang::EdgeKind::Synchronizes
+        | lang::EdgeKind::Captures
+        | lang::EdgeKind::Contains => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{LanguageRegistry, ParseContext, ParserEngine};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_java_files_parse_through_registry() {
+        let registry = Arc::new(LanguageRegistry::new());
+        register(&registry);
+        let engine = ParserEngine::new(registry);
+
+        let context = ParseContext::new(
+            "repo".to_string(),
+            PathBuf::from("Greeter.java"),
+            "package app;\n\npublic interface Greeter {\n    String greet();\n}\n".to_string(),
+        );
+        let result = engine.parse_file(context).unwrap();
+
+        assert!(result.nodes.iter().all(|n| n.lang == Language::Java));
+        let greeter = result
+            .nodes
+            .iter()
+            .find(|n| n.kind == NodeKind::Class && n.name == "Greeter")
+            .unwrap();
+        assert_eq!(greeter.metadata["java_kind"], "interface");
+    }
+}
@@ -0,0 +1,92 @@
+//! JavaScript and TypeScript parser registration
+//!
+//! Bridges `codeprism-lang-js` into the
+//! [`LanguageRegistry`](super::LanguageRegistry) for both languages. Its node
+//! and edge kinds are the universal ones.
+
+use super::bridge::language_bridge;
+use crate::ast::{EdgeKind, Language, NodeKind};
+use codeprism_lang_js as lang;
+
+language_bridge!(
+    JavaScriptLanguageParser,
+    JavaScriptLanguageParser,
+    "js_kind",
+    [JavaScript, TypeScript]
+);
+
+fn convert_language(language: lang::Language) -> Language {
+    match language {
+        lang::Language::JavaScript => Language::JavaScript,
+        lang::Language::TypeScript => Language::TypeScript,
+    }
+}
+
+fn convert_node_kind(kind: lang::NodeKind) -> NodeKind {
+    match kind {
+        lang::NodeKind::Module => NodeKind::Module,
+        lang::NodeKind::Class => NodeKind::Class,
+        lang::NodeKind::Function => NodeKind::Function,
+        lang::NodeKind::Method => NodeKind::Method,
+        lang::NodeKind::Parameter => NodeKind::Parameter,
+        lang::NodeKind::Variable => NodeKind::Variable,
+        lang::NodeKind::Call => NodeKind::Call,
+        lang::NodeKind::Import => NodeKind::Import,
+        lang::NodeKind::Literal => NodeKind::Literal,
+        lang::NodeKind::Route => NodeKind::Route,
+        lang::NodeKind::SqlQuery => NodeKind::SqlQuery,
+        lang::NodeKind::Event => NodeKind::Event,
+        lang::NodeKind::Unknown => NodeKind::Unknown,
+    }
+}
+
+fn specific_kind(_: lang::NodeKind) -> Option<&'static str> {
+    None
+}
+
+fn convert_edge_kind(kind: lang::EdgeKind) -> Option<EdgeKind> {
+    Some(match kind {
+        lang::EdgeKind::Calls => EdgeKind::Calls,
+        lang::EdgeKind::Reads => EdgeKind::Reads,
+        lang::EdgeKind::Writes => EdgeKind::Writes,
+        lang::EdgeKind::Imports => EdgeKind::Imports,
+        lang::EdgeKind::Emits => EdgeKind::Emits,
+        lang::EdgeKind::RoutesTo => EdgeKind::RoutesTo,
+        lang::EdgeKind::Raises => EdgeKind::Raises,
+        lang::EdgeKind::Extends => EdgeKind::Extends,
+        lang::EdgeKind::Implements => EdgeKind::Implements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{LanguageRegistry, ParseContext, ParserEngine};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_javascript_and_typescript_parse_through_registry() {
+        let registry = Arc::new(LanguageRegistry::new());
+        register(&registry);
+        let engine = ParserEngine::new(registry);
+
+        for (file, language) in [
+            ("app.js", Language::JavaScript),
+            ("app.ts", Language::TypeScript),
+        ] {
+            let context = ParseContext::new(
+                "repo".to_string(),
+                PathBuf::from(file),
+                "function greet(name) { return 'hello ' + name; }\n".to_string(),
+            );
+            let result = engine.parse_file(context).unwrap();
+
+            assert!(result.nodes.iter().all(|n| n.lang == language), "{}", file);
+            assert!(result
+                .nodes
+                .iter()
+                .any(|n| n.kind == NodeKind::Function && n.name == "greet"));
+        }
+    }
+}
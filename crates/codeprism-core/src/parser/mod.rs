@@ -7,6 +7,25 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tree_sitter::Tree;
 
+#[cfg(any(
+    feature = "lang-go",
+    feature = "lang-java",
+    feature = "lang-js",
+    feature = "lang-python",
+    feature = "lang-rust"
+))]
+mod bridge;
+#[cfg(feature = "lang-go")]
+pub mod go;
+#[cfg(feature = "lang-java")]
+pub mod java;
+#[cfg(feature = "lang-js")]
+pub mod javascript;
+#[cfg(feature = "lang-python")]
+pub mod python;
+#[cfg(feature = "lang-rust")]
+pub mod rust;
+
 /// Parser context for incremental parsing
 #[derive(Debug, Clone)]
 pub struct ParseContext {
@@ -114,6 +133,20 @@ impl ParserEngine {
 
     /// Parse a file
     pub fn parse_file(&self, context: ParseContext) -> Result<ParseResult> {
+        let result = self.parse_uncached(&context)?;
+
+        // Cache the tree
+        self.tree_cache
+            .insert(context.file_path.clone(), result.tree.clone());
+
+        Ok(result)
+    }
+
+    /// Parse a file without caching its tree
+    ///
+    /// For content that is not the file's current version, such as a file read
+    /// from another git revision, so that incremental parses never start from it.
+    pub fn parse_uncached(&self, context: &ParseContext) -> Result<ParseResult> {
         // Detect language from file extension
         let ext = context
             .file_path
@@ -127,14 +160,14 @@ impl ParserEngine {
             .get_by_extension(ext)
             .ok_or_else(|| Error::unsupported_language(ext.to_string()))?;
 
-        // Parse the file
-        let result = parser.parse(&context)?;
-
-        // Cache the tree
-        self.tree_cache
-            .insert(context.file_path.clone(), result.tree.clone());
+        parser.parse(context)
+    }
 
-        Ok(result)
+    /// Check whether a parser is registered for a file's extension
+    pub fn supports_file(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| self.registry.get_by_extension(ext).is_some())
     }
 
     /// Parse a file incrementally
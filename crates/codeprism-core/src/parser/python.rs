@@ -0,0 +1,89 @@
+//! Python parser registration
+//!
+//! Bridges `codeprism-lang-python` into the
+//! [`LanguageRegistry`](super::LanguageRegistry). Its node and edge kinds are
+//! the universal ones.
+
+use super::bridge::language_bridge;
+use crate::ast::{EdgeKind, Language, NodeKind};
+use codeprism_lang_python as lang;
+
+language_bridge!(
+    PythonLanguageParser,
+    PythonLanguageParser,
+    "python_kind",
+    [Python]
+);
+
+fn convert_language(_: lang::Language) -> Language {
+    Language::Python
+}
+
+fn convert_node_kind(kind: lang::NodeKind) -> NodeKind {
+    match kind {
+        lang::NodeKind::Module => NodeKind::Module,
+        lang::NodeKind::Class => NodeKind::Class,
+        lang::NodeKind::Function => NodeKind::Function,
+        lang::NodeKind::Method => NodeKind::Method,
+        lang::NodeKind::Parameter => NodeKind::Parameter,
+        lang::NodeKind::Variable => NodeKind::Variable,
+        lang::NodeKind::Call => NodeKind::Call,
+        lang::NodeKind::Import => NodeKind::Import,
+        lang::NodeKind::Literal => NodeKind::Literal,
+        lang::NodeKind::Route => NodeKind::Route,
+        lang::NodeKind::SqlQuery => NodeKind::SqlQuery,
+        lang::NodeKind::Event => NodeKind::Event,
+        lang::NodeKind::Unknown => NodeKind::Unknown,
+    }
+}
+
+fn specific_kind(_: lang::NodeKind) -> Option<&'static str> {
+    None
+}
+
+fn convert_edge_kind(kind: lang::EdgeKind) -> Option<EdgeKind> {
+    Some(match kind {
+        lang::EdgeKind::Calls => EdgeKind::Calls,
+        lang::EdgeKind::Reads => EdgeKind::Reads,
+        lang::EdgeKind::Writes => EdgeKind::Writes,
+        lang::EdgeKind::Imports => EdgeKind::Imports,
+        lang::EdgeKind::Emits => EdgeKind::Emits,
+        lang::EdgeKind::RoutesTo => EdgeKind::RoutesTo,
+        lang::EdgeKind::Raises => EdgeKind::Raises,
+        lang::EdgeKind::Extends => EdgeKind::Extends,
+        lang::EdgeKind::Implements => EdgeKind::Implements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{LanguageRegistry, ParseContext, ParserEngine};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_python_files_parse_through_registry() {
+        let registry = Arc::new(LanguageRegistry::new());
+        register(&registry);
+        let engine = ParserEngine::new(registry);
+
+        let context = ParseContext::new(
+            "repo".to_string(),
+            PathBuf::from("app.py"),
+            "import os\n\nclass Greeter:\n    def greet(self):\n        return os.getcwd()\n"
+                .to_string(),
+        );
+        let result = engine.parse_file(context).unwrap();
+
+        assert!(result.nodes.iter().all(|n| n.lang == Language::Python));
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| n.kind == NodeKind::Class && n.name == "Greeter"));
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| matches!(n.kind, NodeKind::Function | NodeKind::Method) && n.name == "greet"));
+    }
+}
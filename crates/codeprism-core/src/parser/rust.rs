@@ -0,0 +1,136 @@
+//! Rust parser registration
+//!
+//! Bridges `codeprism-lang-rust` into the
+//! [`LanguageRegistry`](super::LanguageRegistry). Rust-specific node kinds are
+//! mapped onto the closest universal kind, and the original kind is kept in
+//! the node metadata under `rust_kind`.
+
+use super::bridge::language_bridge;
+use crate::ast::{EdgeKind, Language, NodeKind};
+use codeprism_lang_rust as lang;
+
+language_bridge!(RustLanguageParser, RustLanguageParser, "rust_kind", [Rust]);
+
+fn convert_language(language: lang::Language) -> Language {
+    match language {
+        lang::Language::Rust => Language::Rust,
+        lang::Language::Python => Language::Python,
+    }
+}
+
+fn convert_node_kind(kind: lang::NodeKind) -> NodeKind {
+    match kind {
+        lang::NodeKind::Module | lang::NodeKind::Mod => NodeKind::Module,
+        lang::NodeKind::Class
+        | lang::NodeKind::Trait
+        | lang::NodeKind::Struct
+        | lang::NodeKind::Enum
+        | lang::NodeKind::Union
+        | lang::NodeKind::TypeAlias
+        | lang::NodeKind::AssociatedType => NodeKind::Class,
+        lang::NodeKind::Function | lang::NodeKind::Macro => NodeKind::Function,
+        lang::NodeKind::Method => NodeKind::Method,
+        lang::NodeKind::Parameter => NodeKind::Parameter,
+        lang::NodeKind::Variable
+        | lang::NodeKind::Const
+        | lang::NodeKind::Static
+        | lang::NodeKind::AssociatedConst
+        | lang::NodeKind::Field
+        | lang::NodeKind::Variant => NodeKind::Variable,
+        lang::NodeKind::Call => NodeKind::Call,
+        lang::NodeKind::Import | lang::NodeKind::Use => NodeKind::Import,
+        lang::NodeKind::Literal => NodeKind::Literal,
+        lang::NodeKind::Route => NodeKind::Route,
+        lang::NodeKind::SqlQuery => NodeKind::SqlQuery,
+        lang::NodeKind::Event => NodeKind::Event,
+        lang::NodeKind::Impl
+        | lang::NodeKind::Lifetime
+        | lang::NodeKind::Pub
+        | lang::NodeKind::Attribute
+        | lang::NodeKind::Unknown => NodeKind::Unknown,
+    }
+}
+
+fn specific_kind(kind: lang::NodeKind) -> Option<&'static str> {
+    match kind {
+        lang::NodeKind::Mod => Some("mod"),
+        lang::NodeKind::Trait => Some("trait"),
+        lang::NodeKind::Struct => Some("struct"),
+        lang::NodeKind::Enum => Some("enum"),
+        lang::NodeKind::Union => Some("union"),
+        lang::NodeKind::TypeAlias => Some("type_alias"),
+        lang::NodeKind::AssociatedType => Some("associated_type"),
+        lang::NodeKind::Macro => Some("macro"),
+        lang::NodeKind::Const => Some("const"),
+        lang::NodeKind::Static => Some("static"),
+        lang::NodeKind::AssociatedConst => Some("associated_const"),
+        lang::NodeKind::Field => Some("field"),
+        lang::NodeKind::Variant => Some("variant"),
+        lang::NodeKind::Use => Some("use"),
+        lang::NodeKind::Impl => Some("impl"),
+        lang::NodeKind::Lifetime => Some("lifetime"),
+        lang::NodeKind::Pub => Some("pub"),
+        lang::NodeKind::Attribute => Some("attribute"),
+        _ => None,
+    }
+}
+
+/// Universal edge kind for a Rust edge, if there is one
+fn convert_edge_kind(kind: lang::EdgeKind) -> Option<EdgeKind> {
+    match kind {
+        lang::EdgeKind::Calls | lang::EdgeKind::Expands => Some(EdgeKind::Calls),
+        lang::EdgeKind::Reads => Some(EdgeKind::Reads),
+        lang::EdgeKind::Writes => Some(EdgeKind::Writes),
+        lang::EdgeKind::Imports | lang::EdgeKind::Uses => Some(EdgeKind::Imports),
+        lang::EdgeKind::Emits => Some(EdgeKind::Emits),
+        lang::EdgeKind::RoutesTo => Some(EdgeKind::RoutesTo),
+        lang::EdgeKind::Raises => Some(EdgeKind::Raises),
+        lang::EdgeKind::Extends => Some(EdgeKind::Extends),
+        lang::EdgeKind::Implements | lang::EdgeKind::ImplementsTrait | lang::EdgeKind::Derives => {
+            Some(EdgeKind::Implements)
+        }
+        lang::EdgeKind::Constrains
+        | lang::EdgeKind::Owns
+        | lang::EdgeKind::Borrows
+        | lang::EdgeKind::BorrowsMut
+        | lang::EdgeKind::Outlives
+        | lang::EdgeKind::Bounds
+        | lang::EdgeKind::Binds
+        | lang::EdgeKind::Contains => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{LanguageRegistry, ParseContext, ParserEngine};
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_rust_files_parse_through_registry() {
+        let registry = Arc::new(LanguageRegistry::new());
+        register(&registry);
+        let engine = ParserEngine::new(registry);
+
+        let context = ParseContext::new(
+            "repo".to_string(),
+            PathBuf::from("lib.rs"),
+            "pub trait Greet { fn greet(&self) -> String; }\n\npub fn hello() -> u32 { 1 }\n"
+                .to_string(),
+        );
+        let result = engine.parse_file(context).unwrap();
+
+        assert!(result.nodes.iter().all(|n| n.lang == Language::Rust));
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| n.kind == NodeKind::Function && n.name == "hello"));
+        let greet = result
+            .nodes
+            .iter()
+            .find(|n| n.kind == NodeKind::Class && n.name == "Greet")
+            .unwrap();
+        assert_eq!(greet.metadata["rust_kind"], "trait");
+    }
+}
@@ -0,0 +1,812 @@
+//! Declarative pattern queries over the code graph
+//!
+//! Queries use a small Cypher-like language evaluated in-process against a
+//! [`GraphStore`]:
+//!
+//! ```text
+//! MATCH (r:Route)-[:RoutesTo|Calls*]->(f:Function)-[:Writes]->(q:SqlQuery)
+//! WHERE f.file GLOB "src/**/*.py" AND f.name =~ "^save" AND r.metadata.method = "POST"
+//! RETURN r, f.name AS handler, q
+//! LIMIT 50
+//! ```
+//!
+//! - Node patterns `(var:Kind|Kind {name: "x"})` match on [`NodeKind`] and
+//!   property values; the variable, kinds and properties are all optional.
+//! - Edge patterns `-[:Kind|Kind]->`, `<-[...]-` and `-[...]-` match on
+//!   [`EdgeKind`] and direction. `*`, `*n`, `*n..`, `*..m` and `*n..m` make
+//!   the path variable-length; `*` alone means one or more edges.
+//! - Several comma-separated patterns may share variables.
+//! - Node properties are `id`, `name`, `kind`, `lang`, `file`, `signature`,
+//!   `line`, `end_line` and `metadata`, and `metadata.a.b` reaches into the
+//!   metadata object.
+//! - `WHERE` combines comparisons (`=`, `<>`, `<`, `<=`, `>`, `>=`), regexes
+//!   (`=~`), file globs (`GLOB`), `CONTAINS` and `IS [NOT] NULL` with `AND`,
+//!   `OR`, `NOT` and parentheses.
+//!
+//! Variable-length paths match when any path of a suitable length connects
+//! the two nodes, and result rows are distinct.
+
+mod parser;
+
+use crate::ast::{Edge, EdgeKind, Node, NodeId, NodeKind};
+use crate::error::{Error, Result};
+use crate::graph::GraphStore;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Number of partial matches after which a query is rejected as too broad
+const MAX_BINDINGS: usize = 100_000;
+
+/// Number of nodes visited by path traversals after which a query is
+/// rejected as too broad
+const MAX_VISITED_STATES: usize = 1_000_000;
+
+/// A parsed graph query
+#[derive(Debug, Clone)]
+pub struct Query {
+    patterns: Vec<PathPattern>,
+    filter: Option<Expr>,
+    returns: Vec<ReturnItem>,
+    limit: Option<usize>,
+    /// Variable names by slot; anonymous nodes get generated names
+    variables: Vec<String>,
+}
+
+/// Rows produced by a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    /// Column names in `RETURN` order
+    pub columns: Vec<String>,
+    /// Distinct rows, each with one value per column
+    pub rows: Vec<Vec<QueryValue>>,
+    /// Whether rows were dropped because of `LIMIT`
+    pub truncated: bool,
+}
+
+/// A value in a result row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum QueryValue {
+    /// A matched node
+    Node(Node),
+    /// A property value
+    Value(serde_json::Value),
+}
+
+#[derive(Debug, Clone)]
+struct PathPattern {
+    start: NodePattern,
+    steps: Vec<(RelPattern, NodePattern)>,
+}
+
+#[derive(Debug, Clone)]
+struct NodePattern {
+    slot: usize,
+    kinds: Vec<NodeKind>,
+    properties: Vec<(Vec<String>, serde_json::Value)>,
+}
+
+#[derive(Debug, Clone)]
+struct RelPattern {
+    kinds: Vec<EdgeKind>,
+    direction: Direction,
+    min: usize,
+    max: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Outgoing,
+    Incoming,
+    Both,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare {
+        left: Operand,
+        op: CompareOp,
+        right: Operand,
+    },
+    Matches {
+        operand: Operand,
+        regex: Regex,
+    },
+    IsNull {
+        operand: Operand,
+        negated: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Property { slot: usize, path: Vec<String> },
+    Literal(serde_json::Value),
+}
+
+#[derive(Debug, Clone)]
+struct ReturnItem {
+    column: String,
+    slot: usize,
+    /// Property path, or empty to return the node
+    path: Vec<String>,
+}
+
+/// Node bound to each variable slot
+type Binding = Vec<Option<NodeId>>;
+
+impl Query {
+    /// Parse a query
+    pub fn parse(text: &str) -> Result<Self> {
+        parser::parse(text)
+    }
+
+    /// Evaluate the query against a graph
+    ///
+    /// `WHERE` conditions joined by `AND` are checked as soon as the
+    /// variables they use are bound, so selective filters prune the search
+    /// before paths are traversed from every candidate.
+    pub fn execute(&self, graph: &GraphStore) -> Result<QueryResult> {
+        let mut execution = Execution {
+            graph,
+            nodes: NodeCache::new(graph),
+            conjuncts: self
+                .filter
+                .iter()
+                .flat_map(conjuncts)
+                .map(|expr| (slots(expr), expr))
+                .collect(),
+            visited_budget: MAX_VISITED_STATES,
+        };
+        let mut bindings: Vec<Binding> = vec![vec![None; self.variables.len()]];
+
+        for pattern in &self.patterns {
+            let mut matched = Vec::new();
+            for binding in &bindings {
+                let starts = match binding[pattern.start.slot] {
+                    Some(id) => vec![id],
+                    None => candidates(graph, &pattern.start),
+                };
+                for start in starts {
+                    if !execution.nodes.matches(&start, &pattern.start) {
+                        continue;
+                    }
+                    let mut binding = binding.clone();
+                    binding[pattern.start.slot] = Some(start);
+                    if !execution.accepts(&binding, pattern.start.slot) {
+                        continue;
+                    }
+                    execution.extend(pattern, 0, binding, &mut matched)?;
+                }
+            }
+            bindings = matched;
+        }
+
+        let nodes = &mut execution.nodes;
+        let mut rows = Vec::new();
+        let mut seen = HashSet::new();
+        for binding in &bindings {
+            if let Some(filter) = &self.filter {
+                if !evaluate(filter, binding, nodes) {
+                    continue;
+                }
+            }
+            let row: Vec<QueryValue> = self
+                .returns
+                .iter()
+                .map(|item| {
+                    let node = nodes.get(binding[item.slot].as_ref());
+                    match (item.path.is_empty(), node) {
+                        (true, Some(node)) => QueryValue::Node(node.clone()),
+                        (false, Some(node)) => QueryValue::Value(property(node, &item.path)),
+                        (_, None) => QueryValue::Value(serde_json::Value::Null),
+                    }
+                })
+                .collect();
+            let key = row_key(&row);
+            if seen.insert(key.clone()) {
+                rows.push((key, row));
+            }
+        }
+
+        rows.sort_by(|a, b| a.0.cmp(&b.0));
+        let truncated = self.limit.is_some_and(|limit| rows.len() > limit);
+        rows.truncate(self.limit.unwrap_or(usize::MAX));
+
+        Ok(QueryResult {
+            columns: self
+                .returns
+                .iter()
+                .map(|item| item.column.clone())
+                .collect(),
+            rows: rows.into_iter().map(|(_, row)| row).collect(),
+            truncated,
+        })
+    }
+}
+
+/// State of one query evaluation
+struct Execution<'a> {
+    graph: &'a GraphStore,
+    nodes: NodeCache<'a>,
+    /// `AND`-joined parts of the `WHERE` clause with the slots each one uses
+    conjuncts: Vec<(Vec<usize>, &'a Expr)>,
+    /// Traversal states left before the query is rejected
+    visited_budget: usize,
+}
+
+impl Execution<'_> {
+    /// Whether the conditions that use `slot` and only bound slots hold
+    fn accepts(&mut self, binding: &Binding, slot: usize) -> bool {
+        self.conjuncts.iter().all(|(slots, expr)| {
+            !slots.contains(&slot)
+                || slots.iter().any(|slot| binding[*slot].is_none())
+                || evaluate(expr, binding, &mut self.nodes)
+        })
+    }
+
+    /// Match the remaining steps of `pattern` from `binding`
+    fn extend(
+        &mut self,
+        pattern: &PathPattern,
+        step: usize,
+        binding: Binding,
+        matched: &mut Vec<Binding>,
+    ) -> Result<()> {
+        let Some((relationship, target)) = pattern.steps.get(step) else {
+            if matched.len() >= MAX_BINDINGS {
+                return Err(Error::resource_exhausted(
+                    "query",
+                    format!(
+                        "more than {} matches; narrow the pattern with node kinds, names or edge kinds",
+                        MAX_BINDINGS
+                    ),
+                ));
+            }
+            matched.push(binding);
+            return Ok(());
+        };
+
+        let from_slot = if step == 0 {
+            pattern.start.slot
+        } else {
+            pattern.steps[step - 1].1.slot
+        };
+        let from = binding[from_slot].expect("previous node is bound");
+
+        for reached in traverse(self.graph, &from, relationship, &mut self.visited_budget)? {
+            if binding[target.slot].is_some_and(|bound| bound != reached)
+                || !self.nodes.matches(&reached, target)
+            {
+                continue;
+            }
+            let mut binding = binding.clone();
+            binding[target.slot] = Some(reached);
+            if !self.accepts(&binding, target.slot) {
+                continue;
+            }
+            self.extend(pattern, step + 1, binding, matched)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self> {
+        Self::parse(text)
+    }
+}
+
+/// Nodes fetched from the graph during a query
+struct NodeCache<'a> {
+    graph: &'a GraphStore,
+    nodes: HashMap<NodeId, Option<Node>>,
+}
+
+impl<'a> NodeCache<'a> {
+    fn new(graph: &'a GraphStore) -> Self {
+        Self {
+            graph,
+            nodes: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, id: Option<&NodeId>) -> Option<&Node> {
+        let id = id?;
+        let graph = self.graph;
+        self.nodes
+            .entry(*id)
+            .or_insert_with(|| graph.get_node(id))
+            .as_ref()
+    }
+
+    fn matches(&mut self, id: &NodeId, pattern: &NodePattern) -> bool {
+        let Some(node) = self.get(Some(id)) else {
+            return false;
+        };
+        (pattern.kinds.is_empty() || pattern.kinds.contains(&node.kind))
+            && pattern
+                .properties
+                .iter()
+                .all(|(path, value)| values_equal(&property(node, path), value))
+    }
+}
+
+/// Nodes that may match the first node of a pattern
+fn candidates(graph: &GraphStore, pattern: &NodePattern) -> Vec<NodeId> {
+    let name = pattern
+        .properties
+        .iter()
+        .find(|(path, _)| path.len() == 1 && path[0] == "name")
+        .and_then(|(_, value)| value.as_str());
+    if let Some(name) = name {
+        return graph.get_node_ids_by_name(name);
+    }
+    if !pattern.kinds.is_empty() {
+        return pattern
+            .kinds
+            .iter()
+            .flat_map(|kind| graph.get_nodes_by_kind(*kind))
+            .map(|node| node.id)
+            .collect();
+    }
+    graph.iter_file_index().flat_map(|(_, ids)| ids).collect()
+}
+
+/// Nodes reachable from `from` over paths matching `relationship`
+///
+/// Every visited state is taken from `budget`, and the query fails once it
+/// runs out.
+fn traverse(
+    graph: &GraphStore,
+    from: &NodeId,
+    relationship: &RelPattern,
+    budget: &mut usize,
+) -> Result<Vec<NodeId>> {
+    let neighbors = |id: &NodeId| -> Vec<NodeId> {
+        let accepts =
+            |edge: &Edge| relationship.kinds.is_empty() || relationship.kinds.contains(&edge.kind);
+        let mut next = Vec::new();
+        if relationship.direction != Direction::Incoming {
+            next.extend(
+                graph
+                    .get_outgoing_edges(id)
+                    .into_iter()
+                    .filter(|edge| accepts(edge))
+                    .map(|edge| edge.target),
+            );
+        }
+        if relationship.direction != Direction::Outgoing {
+            next.extend(
+                graph
+                    .get_incoming_edges(id)
+                    .into_iter()
+                    .filter(|edge| accepts(edge))
+                    .map(|edge| edge.source),
+            );
+        }
+        next
+    };
+
+    // Depths at or beyond `min` behave the same, so they share a state
+    let min = relationship.min;
+    let mut reached = Vec::new();
+    let mut reached_set = HashSet::new();
+    let mut visited = HashSet::from([(*from, 0)]);
+    let mut queue = VecDeque::from([(*from, 0usize)]);
+    while let Some((id, depth)) = queue.pop_front() {
+        if *budget == 0 {
+            return Err(Error::resource_exhausted(
+                "query",
+                format!(
+                    "paths visit more than {} nodes; bound the path length or narrow the start nodes",
+                    MAX_VISITED_STATES
+                ),
+            ));
+        }
+        *budget -= 1;
+        if depth >= min && reached_set.insert(id) {
+            reached.push(id);
+        }
+        if relationship.max.is_some_and(|max| depth >= max) {
+            continue;
+        }
+        for next in neighbors(&id) {
+            if visited.insert((next, (depth + 1).min(min))) {
+                queue.push_back((next, depth + 1));
+            }
+        }
+    }
+    Ok(reached)
+}
+
+/// Parts of an expression joined by `AND`
+fn conjuncts(expr: &Expr) -> Vec<&Expr> {
+    match expr {
+        Expr::And(left, right) => {
+            let mut parts = conjuncts(left);
+            parts.extend(conjuncts(right));
+            parts
+        }
+        _ => vec![expr],
+    }
+}
+
+/// Variable slots an expression reads
+fn slots(expr: &Expr) -> Vec<usize> {
+    let operand_slot = |operand: &Operand| match operand {
+        Operand::Property { slot, .. } => Some(*slot),
+        Operand::Literal(_) => None,
+    };
+    let mut slots = match expr {
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            slots(left).into_iter().chain(slots(right)).collect()
+        }
+        Expr::Not(inner) => slots(inner),
+        Expr::Compare { left, right, .. } => operand_slot(left)
+            .into_iter()
+            .chain(operand_slot(right))
+            .collect(),
+        Expr::Matches { operand, .. } | Expr::IsNull { operand, .. } => {
+            operand_slot(operand).into_iter().collect()
+        }
+    };
+    slots.sort_unstable();
+    slots.dedup();
+    slots
+}
+
+fn evaluate(expr: &Expr, binding: &Binding, nodes: &mut NodeCache) -> bool {
+    match expr {
+        Expr::And(left, right) => evaluate(left, binding, nodes) && evaluate(right, binding, nodes),
+        Expr::Or(left, right) => evaluate(left, binding, nodes) || evaluate(right, binding, nodes),
+        Expr::Not(inner) => !evaluate(inner, binding, nodes),
+        Expr::IsNull { operand, negated } => {
+            operand_value(operand, binding, nodes).is_null() != *negated
+        }
+        Expr::Matches { operand, regex } => match operand_value(operand, binding, nodes) {
+            serde_json::Value::String(s) => regex.is_match(&s),
+            _ => false,
+        },
+        Expr::Compare { left, op, right } => {
+            let left = operand_value(left, binding, nodes);
+            let right = operand_value(right, binding, nodes);
+            compare(&left, *op, &right)
+        }
+    }
+}
+
+fn operand_value(operand: &Operand, binding: &Binding, nodes: &mut NodeCache) -> serde_json::Value {
+    match operand {
+        Operand::Literal(value) => value.clone(),
+        Operand::Property { slot, path } => nodes
+            .get(binding[*slot].as_ref())
+            .map_or(serde_json::Value::Null, |node| property(node, path)),
+    }
+}
+
+fn compare(left: &serde_json::Value, op: CompareOp, right: &serde_json::Value) -> bool {
+    use serde_json::Value;
+    use std::cmp::Ordering;
+
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        CompareOp::Eq => values_equal(left, right),
+        CompareOp::Ne => !left.is_null() && !right.is_null() && !values_equal(left, right),
+        CompareOp::Lt => ordering == Some(Ordering::Less),
+        CompareOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CompareOp::Gt => ordering == Some(Ordering::Greater),
+        CompareOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        CompareOp::Contains => match (left, right) {
+            (Value::String(a), Value::String(b)) => a.contains(b.as_str()),
+            (Value::Array(items), value) => items.iter().any(|item| values_equal(item, value)),
+            _ => false,
+        },
+    }
+}
+
+/// Equality that treats `1` and `1.0` as the same number
+fn values_equal(left: &serde_json::Value, right: &serde_json::Value) -> bool {
+    match (left, right) {
+        (serde_json::Value::Number(a), serde_json::Value::Number(b)) => a.as_f64() == b.as_f64(),
+        _ => left == right,
+    }
+}
+
+/// Value of a property path of a node, or null if it does not exist
+fn property(node: &Node, path: &[String]) -> serde_json::Value {
+    use serde_json::Value;
+
+    let Some((first, rest)) = path.split_first() else {
+        return Value::Null;
+    };
+    let value = match first.as_str() {
+        "id" => Value::String(node.id.to_hex()),
+        "name" => Value::String(node.name.clone()),
+        "kind" => Value::String(format!("{:?}", node.kind)),
+        "lang" => serde_json::to_value(node.lang).unwrap_or(Value::Null),
+        "file" => Value::String(node.file.display().to_string()),
+        "signature" => node.signature.clone().map_or(Value::Null, Value::String),
+        "line" => Value::from(node.span.start_line),
+        "end_line" => Value::from(node.span.end_line),
+        "metadata" => node.metadata.clone(),
+        _ => return Value::Null,
+    };
+    rest.iter()
+        .try_fold(value, |value, key| value.get(key).cloned())
+        .unwrap_or(Value::Null)
+}
+
+/// Sort and deduplication key of a row
+fn row_key(row: &[QueryValue]) -> String {
+    row.iter()
+        .map(|value| match value {
+            QueryValue::Node(node) => format!(
+                "{}\u{0}{:012}\u{0}{}",
+                node.file.display(),
+                node.span.start_byte,
+                node.id
+            ),
+            QueryValue::Value(value) => value.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\u{1}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{NodeBuilder, Span};
+
+    struct Fixture {
+        graph: GraphStore,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self {
+                graph: GraphStore::new(),
+            }
+        }
+
+        fn node(&self, kind: NodeKind, name: &str, file: &str, line: usize) -> NodeId {
+            // `line` and `end_line` are queryable, so nodes get a real line range
+            let node = NodeBuilder::new("repo", kind)
+                .name(name)
+                .file(file)
+                .span(Span::new(line * 100, line * 100 + 50, line, line + 2, 1, 1))
+                .build();
+            let id = node.id;
+            self.graph.add_node(node);
+            id
+        }
+
+        fn edge(&self, source: NodeId, target: NodeId, kind: EdgeKind) {
+            self.graph.add_edge(Edge::new(source, target, kind));
+        }
+
+        fn names(&self, query: &str) -> Vec<Vec<String>> {
+            let result = Query::parse(query).unwrap().execute(&self.graph).unwrap();
+            result
+                .rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|value| match value {
+                            QueryValue::Node(node) => node.name.clone(),
+                            QueryValue::Value(value) => value.to_string(),
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+    }
+
+    /// Routes → handlers → calls → functions → SQL, as the mappers link them
+    fn web_app() -> Fixture {
+        let f = Fixture::new();
+        let create = f.node(NodeKind::Route, "/users", "/repo/src/api/routes.py", 1);
+        let list = f.node(NodeKind::Route, "/users/list", "/repo/src/api/routes.py", 2);
+        let create_user = f.node(
+            NodeKind::Function,
+            "create_user",
+            "/repo/src/api/users.py",
+            3,
+        );
+        let list_users = f.node(
+            NodeKind::Function,
+            "list_users",
+            "/repo/src/api/users.py",
+            6,
+        );
+        let call_save = f.node(NodeKind::Call, "save", "/repo/src/api/users.py", 4);
+        let save = f.node(NodeKind::Function, "save", "/repo/src/db/store.py", 1);
+        let insert = f.node(
+            NodeKind::SqlQuery,
+            "INSERT INTO users",
+            "/repo/src/db/store.py",
+            2,
+        );
+        let select = f.node(
+            NodeKind::SqlQuery,
+            "SELECT * FROM users",
+            "/repo/src/db/store.py",
+            5,
+        );
+
+        f.edge(create, create_user, EdgeKind::RoutesTo);
+        f.edge(list, list_users, EdgeKind::RoutesTo);
+        f.edge(create_user, call_save, EdgeKind::Calls);
+        f.edge(call_save, save, EdgeKind::Calls);
+        f.edge(save, insert, EdgeKind::Writes);
+        f.edge(list_users, select, EdgeKind::Reads);
+
+        let mut node = f.graph.get_node(&create).unwrap();
+        node.metadata = serde_json::json!({"method": "POST", "auth": {"required": true}});
+        f.graph.add_node(node);
+        f
+    }
+
+    #[test]
+    fn test_routes_that_transitively_write_sql() {
+        let f = web_app();
+        assert_eq!(
+            f.names(
+                "MATCH (r:Route)-[:RoutesTo|Calls*]->(fn:Function)-[:Writes]->(q:SqlQuery) \
+                 RETURN r, fn, q.name"
+            ),
+            vec![vec!["/users", "save", "\"INSERT INTO users\""]]
+        );
+        // A single hop does not reach through the call node
+        assert!(f
+            .names("MATCH (r:Route)-[:RoutesTo|Calls*1..2]->(fn:Function)-[:Writes]->() RETURN r")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_where_filters() {
+        let f = web_app();
+        let query = |filter: &str| {
+            f.names(&format!("MATCH (n) WHERE {} RETURN n.name AS name", filter))
+                .into_iter()
+                .map(|row| row[0].trim_matches('"').to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(query("n.name =~ '^list'"), vec!["list_users"]);
+        assert_eq!(
+            query("n.file GLOB 'db/*.py' AND n.kind = 'Function'"),
+            vec!["save"]
+        );
+        assert_eq!(query("n.metadata.method = 'POST'"), vec!["/users"]);
+        assert_eq!(query("n.metadata.auth.required = true"), vec!["/users"]);
+        assert_eq!(
+            query("n.name CONTAINS 'FROM' OR n.line > 5"),
+            vec!["SELECT * FROM users", "list_users"]
+        );
+        assert_eq!(
+            query("n.kind = 'Route' AND NOT (n.metadata IS NOT NULL)"),
+            vec!["/users/list"]
+        );
+    }
+
+    #[test]
+    fn test_shared_variables_directions_and_limit() {
+        let f = web_app();
+        // Handlers that are routed to and whose callee writes SQL, joined on `h`
+        assert_eq!(
+            f.names("MATCH (r:Route)-->(h), (h)-[:Calls*2]->({name: 'save'}) RETURN h"),
+            vec![vec!["create_user"]]
+        );
+        assert_eq!(
+            f.names("MATCH (q:SqlQuery)<-[:Writes|Reads]-(fn) RETURN fn.name, q.name"),
+            vec![
+                vec!["\"list_users\"", "\"SELECT * FROM users\""],
+                vec!["\"save\"", "\"INSERT INTO users\""],
+            ]
+        );
+        assert_eq!(
+            f.names("MATCH (a:Function {name: 'save'})-[:Calls]-(b) RETURN b.kind"),
+            vec![vec!["\"Call\""]]
+        );
+
+        let result = Query::parse("MATCH (n:Function) RETURN n.name LIMIT 2")
+            .unwrap()
+            .execute(&f.graph)
+            .unwrap();
+        assert_eq!(result.columns, vec!["n.name"]);
+        assert_eq!(result.rows.len(), 2);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_variable_length_paths_handle_cycles() {
+        let f = Fixture::new();
+        let a = f.node(NodeKind::Function, "a", "/repo/m.py", 1);
+        let b = f.node(NodeKind::Function, "b", "/repo/m.py", 2);
+        let c = f.node(NodeKind::Function, "c", "/repo/m.py", 3);
+        f.edge(a, b, EdgeKind::Calls);
+        f.edge(b, c, EdgeKind::Calls);
+        f.edge(c, a, EdgeKind::Calls);
+
+        assert_eq!(
+            f.names("MATCH ({name: 'a'})-[*]->(n) RETURN n"),
+            vec![vec!["a"], vec!["b"], vec!["c"]]
+        );
+        assert_eq!(
+            f.names("MATCH ({name: 'a'})-[*0..1]->(n) RETURN n"),
+            vec![vec!["a"], vec!["b"]]
+        );
+        // Mutual recursion: functions that reach themselves
+        assert_eq!(
+            f.names("MATCH (n:Function)-[:Calls*2..]->(n) RETURN n")
+                .len(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_where_prunes_before_traversal() {
+        // A call chain f0 -> f1 -> ... -> f1499 has over a million paths
+        let f = Fixture::new();
+        let ids: Vec<NodeId> = (0..1500)
+            .map(|i| {
+                f.node(
+                    NodeKind::Function,
+                    &format!("f{i}"),
+                    "/repo/chain.py",
+                    i + 1,
+                )
+            })
+            .collect();
+        for pair in ids.windows(2) {
+            f.edge(pair[0], pair[1], EdgeKind::Calls);
+        }
+
+        assert_eq!(
+            f.names("MATCH (a)-[:Calls*]->(b) WHERE a.name = 'f1496' RETURN b"),
+            vec![vec!["f1497"], vec!["f1498"], vec!["f1499"]]
+        );
+        // Conditions on later nodes prune as soon as those nodes are bound
+        assert_eq!(
+            f.names(
+                "MATCH (a {name: 'f0'})-[:Calls]->(b)-[:Calls*]->(c) \
+                 WHERE b.name = 'f1' AND c.line > 1498 AND a.line < b.line RETURN c"
+            ),
+            vec![vec!["f1498"], vec!["f1499"]]
+        );
+
+        // Traversing from every node runs out of budget instead of hanging
+        let error = Query::parse("MATCH (a)-[*]->(b:Route) RETURN b")
+            .unwrap()
+            .execute(&f.graph)
+            .unwrap_err();
+        assert!(error.to_string().contains("paths visit more than"));
+        // Unfiltered, the same chain exceeds the match limit
+        assert!(Query::parse("MATCH (a)-[:Calls*]->(b) RETURN b")
+            .unwrap()
+            .execute(&f.graph)
+            .is_err());
+    }
+}
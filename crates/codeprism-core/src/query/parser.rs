@@ -0,0 +1,647 @@
+//! Tokenizer and recursive-descent parser for graph queries
+
+use super::{
+    CompareOp, Direction, Expr, NodePattern, Operand, PathPattern, Query, RelPattern, ReturnItem,
+};
+use crate::ast::{EdgeKind, NodeKind};
+use crate::error::{Error, Result};
+use crate::glob::glob_regex;
+use regex::Regex;
+
+const NODE_KINDS: [NodeKind; 13] = [
+    NodeKind::Module,
+    NodeKind::Class,
+    NodeKind::Function,
+    NodeKind::Method,
+    NodeKind::Parameter,
+    NodeKind::Variable,
+    NodeKind::Call,
+    NodeKind::Import,
+    NodeKind::Literal,
+    NodeKind::Route,
+    NodeKind::SqlQuery,
+    NodeKind::Event,
+    NodeKind::Unknown,
+];
+
+const EDGE_KINDS: [EdgeKind; 9] = [
+    EdgeKind::Calls,
+    EdgeKind::Reads,
+    EdgeKind::Writes,
+    EdgeKind::Imports,
+    EdgeKind::Emits,
+    EdgeKind::RoutesTo,
+    EdgeKind::Raises,
+    EdgeKind::Extends,
+    EdgeKind::Implements,
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Punct(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "'{}'", ident),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Num(n) => write!(f, "{}", n),
+            Token::Punct(p) => write!(f, "'{}'", p),
+        }
+    }
+}
+
+/// Punctuation, longest first so that `<=` wins over `<`
+const PUNCTUATION: [&str; 21] = [
+    "..", "<=", ">=", "<>", "!=", "=~", "(", ")", "[", "]", "{", "}", ":", ",", ".", "|", "*", "-",
+    ">", "<", "=",
+];
+
+fn syntax_error(position: usize, message: impl std::fmt::Display) -> Error {
+    Error::validation("query", format!("{} at position {}", message, position))
+}
+
+fn tokenize(text: &str) -> Result<Vec<(Token, usize)>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((Token::Ident(chars[start..i].iter().collect()), start));
+        } else if c.is_ascii_digit() {
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            // `1..3` is a range, not a decimal
+            if i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit() {
+                i += 1;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+            }
+            let number: String = chars[start..i].iter().collect();
+            let value = number
+                .parse()
+                .map_err(|_| syntax_error(start, format!("invalid number {}", number)))?;
+            tokens.push((Token::Num(value), start));
+        } else if c == '"' || c == '\'' {
+            let mut value = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => return Err(syntax_error(start, "unterminated string")),
+                    Some('\\') => {
+                        match chars.get(i + 1) {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            // Keep other escapes so that regexes like "\d" work
+                            Some(&escaped) if escaped != c && escaped != '\\' => {
+                                value.push('\\');
+                                value.push(escaped);
+                            }
+                            Some(&escaped) => value.push(escaped),
+                            None => return Err(syntax_error(start, "unterminated string")),
+                        }
+                        i += 2;
+                    }
+                    Some(&quote) if quote == c => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&other) => {
+                        value.push(other);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push((Token::Str(value), start));
+        } else {
+            let punct = PUNCTUATION
+                .iter()
+                .find(|p| {
+                    p.chars()
+                        .enumerate()
+                        .all(|(offset, pc)| chars.get(i + offset) == Some(&pc))
+                })
+                .ok_or_else(|| syntax_error(start, format!("unexpected character '{}'", c)))?;
+            i += punct.len();
+            tokens.push((Token::Punct(punct), start));
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Parse a query
+pub(super) fn parse(text: &str) -> Result<Query> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        position: 0,
+        end: text.chars().count(),
+        variables: Vec::new(),
+    };
+    parser.query()
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+    end: usize,
+    variables: Vec<String>,
+}
+
+impl Parser {
+    fn query(&mut self) -> Result<Query> {
+        self.expect_keyword("MATCH")?;
+        let mut patterns = vec![self.path_pattern()?];
+        while self.eat_punct(",") {
+            patterns.push(self.path_pattern()?);
+        }
+
+        let filter = if self.eat_keyword("WHERE") {
+            Some(self.or_expr()?)
+        } else {
+            None
+        };
+
+        self.expect_keyword("RETURN")?;
+        // Rows are always distinct
+        self.eat_keyword("DISTINCT");
+        let mut returns = vec![self.return_item()?];
+        while self.eat_punct(",") {
+            returns.push(self.return_item()?);
+        }
+
+        let limit = if self.eat_keyword("LIMIT") {
+            match self.next() {
+                Some(Token::Num(n)) if n >= 0.0 && n.fract() == 0.0 => Some(n as usize),
+                _ => return Err(self.error_at_previous("LIMIT expects a whole number")),
+            }
+        } else {
+            None
+        };
+
+        if let Some((token, position)) = self.tokens.get(self.position) {
+            return Err(syntax_error(*position, format!("unexpected {}", token)));
+        }
+
+        Ok(Query {
+            patterns,
+            filter,
+            returns,
+            limit,
+            variables: std::mem::take(&mut self.variables),
+        })
+    }
+
+    fn path_pattern(&mut self) -> Result<PathPattern> {
+        let start = self.node_pattern()?;
+        let mut steps = Vec::new();
+        while matches!(self.peek(), Some(Token::Punct("-" | "<"))) {
+            let relationship = self.rel_pattern()?;
+            steps.push((relationship, self.node_pattern()?));
+        }
+        Ok(PathPattern { start, steps })
+    }
+
+    fn node_pattern(&mut self) -> Result<NodePattern> {
+        self.expect_punct("(")?;
+        let variable = match self.peek() {
+            Some(Token::Ident(name)) => {
+                let name = name.clone();
+                self.position += 1;
+                Some(name)
+            }
+            _ => None,
+        };
+        let slot = self.slot(variable);
+
+        let mut kinds = Vec::new();
+        if self.eat_punct(":") {
+            loop {
+                let label = self.ident("a node kind")?;
+                kinds.push(
+                    parse_kind(&label, &NODE_KINDS)
+                        .ok_or_else(|| self.error_at_previous(unknown_kind("node", &label)))?,
+                );
+                if !self.eat_punct("|") {
+                    break;
+                }
+                self.eat_punct(":");
+            }
+        }
+
+        let mut properties = Vec::new();
+        if self.eat_punct("{") {
+            loop {
+                let key = self.ident("a property name")?;
+                self.expect_punct(":")?;
+                properties.push((vec![key], self.literal()?));
+                if !self.eat_punct(",") {
+                    break;
+                }
+            }
+            self.expect_punct("}")?;
+        }
+
+        self.expect_punct(")")?;
+        Ok(NodePattern {
+            slot,
+            kinds,
+            properties,
+        })
+    }
+
+    fn rel_pattern(&mut self) -> Result<RelPattern> {
+        let incoming = self.eat_punct("<");
+        self.expect_punct("-")?;
+
+        let mut kinds = Vec::new();
+        let (mut min, mut max) = (1, Some(1));
+        if self.eat_punct("[") {
+            if let Some(Token::Ident(_)) = self.peek() {
+                return Err(self.error_here("relationship variables are not supported"));
+            }
+            if self.eat_punct(":") {
+                loop {
+                    let label = self.ident("an edge kind")?;
+                    kinds.push(
+                        parse_kind(&label, &EDGE_KINDS)
+                            .ok_or_else(|| self.error_at_previous(unknown_kind("edge", &label)))?,
+                    );
+                    if !self.eat_punct("|") {
+                        break;
+                    }
+                    self.eat_punct(":");
+                }
+            }
+            if self.eat_punct("*") {
+                (min, max) = self.range()?;
+            }
+            self.expect_punct("]")?;
+        }
+
+        self.expect_punct("-")?;
+        let outgoing = self.eat_punct(">");
+        let direction = match (incoming, outgoing) {
+            (true, true) => return Err(self.error_at_previous("an edge has a single direction")),
+            (true, false) => Direction::Incoming,
+            (false, true) => Direction::Outgoing,
+            (false, false) => Direction::Both,
+        };
+
+        Ok(RelPattern {
+            kinds,
+            direction,
+            min,
+            max,
+        })
+    }
+
+    /// Length range after `*`: none, `n`, `n..`, `n..m` or `..m`
+    fn range(&mut self) -> Result<(usize, Option<usize>)> {
+        let min = self.count()?;
+        if !self.eat_punct("..") {
+            return Ok(match min {
+                Some(exact) => (exact, Some(exact)),
+                None => (1, None),
+            });
+        }
+        let max = self.count()?;
+        let min = min.unwrap_or(1);
+        if max.is_some_and(|max| max < min) {
+            return Err(self.error_at_previous("path length range is empty"));
+        }
+        Ok((min, max))
+    }
+
+    fn count(&mut self) -> Result<Option<usize>> {
+        match self.peek() {
+            Some(Token::Num(n)) if *n >= 0.0 && n.fract() == 0.0 => {
+                let n = *n as usize;
+                self.position += 1;
+                Ok(Some(n))
+            }
+            Some(Token::Num(_)) => Err(self.error_here("path lengths are whole numbers")),
+            _ => Ok(None),
+        }
+    }
+
+    fn return_item(&mut self) -> Result<ReturnItem> {
+        let (slot, path) = self.property_path()?;
+        let mut column = std::iter::once(self.variables[slot].clone())
+            .chain(path.iter().cloned())
+            .collect::<Vec<_>>()
+            .join(".");
+        if self.eat_keyword("AS") {
+            column = self.ident("a column name")?;
+        }
+        Ok(ReturnItem { column, slot, path })
+    }
+
+    /// `variable` or `variable.property.path`, referring to a matched variable
+    fn property_path(&mut self) -> Result<(usize, Vec<String>)> {
+        let variable = self.ident("a variable")?;
+        let slot = self
+            .variables
+            .iter()
+            .position(|known| *known == variable)
+            .ok_or_else(|| self.error_at_previous(format!("unknown variable '{}'", variable)))?;
+        let mut path = Vec::new();
+        while self.eat_punct(".") {
+            path.push(self.ident("a property name")?);
+        }
+        Ok((slot, path))
+    }
+
+    fn or_expr(&mut self) -> Result<Expr> {
+        let mut expr = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr> {
+        let mut expr = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.not_expr()?));
+        }
+        Ok(expr)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not_expr()?)));
+        }
+        if self.eat_punct("(") {
+            let expr = self.or_expr()?;
+            self.expect_punct(")")?;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.operand()?;
+
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull {
+                operand: left,
+                negated,
+            });
+        }
+        if self.eat_punct("=~") {
+            let pattern = self.string("a regular expression")?;
+            let regex = Regex::new(&pattern)
+                .map_err(|e| self.error_at_previous(format!("invalid regex: {}", e)))?;
+            return Ok(Expr::Matches {
+                operand: left,
+                regex,
+            });
+        }
+        if self.eat_keyword("GLOB") {
+            let pattern = self.string("a glob pattern")?;
+            return Ok(Expr::Matches {
+                operand: left,
+                regex: glob_regex(&pattern),
+            });
+        }
+
+        let op = if self.eat_keyword("CONTAINS") {
+            CompareOp::Contains
+        } else {
+            match self.next() {
+                Some(Token::Punct("=")) => CompareOp::Eq,
+                Some(Token::Punct("!=" | "<>")) => CompareOp::Ne,
+                Some(Token::Punct("<")) => CompareOp::Lt,
+                Some(Token::Punct("<=")) => CompareOp::Le,
+                Some(Token::Punct(">")) => CompareOp::Gt,
+                Some(Token::Punct(">=")) => CompareOp::Ge,
+                _ => return Err(self.error_at_previous(
+                    "expected a comparison (=, <>, <, <=, >, >=, =~, GLOB, CONTAINS or IS NULL)",
+                )),
+            }
+        };
+        Ok(Expr::Compare {
+            left,
+            op,
+            right: self.operand()?,
+        })
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        match self.peek() {
+            Some(Token::Ident(ident))
+                if !["true", "false", "null"].contains(&ident.to_lowercase().as_str()) =>
+            {
+                let (slot, path) = self.property_path()?;
+                if path.is_empty() {
+                    return Err(self.error_at_previous(
+                        "compare a property such as n.name rather than a whole node",
+                    ));
+                }
+                Ok(Operand::Property { slot, path })
+            }
+            _ => Ok(Operand::Literal(self.literal()?)),
+        }
+    }
+
+    fn literal(&mut self) -> Result<serde_json::Value> {
+        let negative = self.eat_punct("-");
+        match self.next() {
+            Some(Token::Num(n)) => Ok(serde_json::json!(if negative { -n } else { n })),
+            Some(Token::Str(s)) if !negative => Ok(serde_json::Value::String(s)),
+            Some(Token::Ident(ident)) if !negative => match ident.to_lowercase().as_str() {
+                "true" => Ok(serde_json::Value::Bool(true)),
+                "false" => Ok(serde_json::Value::Bool(false)),
+                "null" => Ok(serde_json::Value::Null),
+                _ => Err(self.error_at_previous("expected a value")),
+            },
+            _ => Err(self.error_at_previous("expected a value")),
+        }
+    }
+
+    fn string(&mut self, what: &str) -> Result<String> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(s),
+            _ => Err(self.error_at_previous(format!("expected {} as a string", what))),
+        }
+    }
+
+    fn ident(&mut self, what: &str) -> Result<String> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            _ => Err(self.error_at_previous(format!("expected {}", what))),
+        }
+    }
+
+    /// Slot of a variable, declaring it on first use
+    fn slot(&mut self, variable: Option<String>) -> usize {
+        let name = match variable {
+            Some(name) => name,
+            None => format!("_{}", self.variables.len()),
+        };
+        match self.variables.iter().position(|known| *known == name) {
+            Some(slot) => slot,
+            None => {
+                self.variables.push(name);
+                self.variables.len() - 1
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek().cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            Err(self.error_here(format!("expected '{}'", punct)))
+        }
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(self.error_here(format!("expected {}", keyword)))
+        }
+    }
+
+    /// Error describing the token at the current position
+    fn error_here(&self, message: impl std::fmt::Display) -> Error {
+        match self.tokens.get(self.position) {
+            Some((token, position)) => {
+                syntax_error(*position, format!("{}, found {}", message, token))
+            }
+            None => syntax_error(self.end, format!("{}, found end of query", message)),
+        }
+    }
+
+    /// Error at the token that was just consumed
+    fn error_at_previous(&self, message: impl std::fmt::Display) -> Error {
+        let position = self
+            .position
+            .checked_sub(1)
+            .and_then(|previous| self.tokens.get(previous))
+            .map_or(self.end, |(_, position)| *position);
+        syntax_error(position, message)
+    }
+}
+
+/// Match a label such as `SqlQuery`, `sql_query` or `ROUTES_TO` to a kind
+fn parse_kind<T: std::fmt::Debug + Copy>(label: &str, kinds: &[T]) -> Option<T> {
+    let normalize = |s: &str| s.replace('_', "").to_lowercase();
+    let label = normalize(label);
+    kinds
+        .iter()
+        .find(|kind| normalize(&format!("{:?}", kind)) == label)
+        .copied()
+}
+
+fn unknown_kind(what: &str, label: &str) -> String {
+    let known: Vec<String> = if what == "node" {
+        NODE_KINDS
+            .iter()
+            .map(|kind| format!("{:?}", kind))
+            .collect()
+    } else {
+        EDGE_KINDS
+            .iter()
+            .map(|kind| format!("{:?}", kind))
+            .collect()
+    };
+    format!(
+        "unknown {} kind '{}' (expected one of {})",
+        what,
+        label,
+        known.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_patterns_and_ranges() {
+        let query = parse(
+            "match (r:Route)-[:ROUTES_TO|calls*1..]->(f:Function|Method {name: 'save'})\
+             <-[*2]-(), (f)--(q:sql_query) return r, f.name AS handler limit 5",
+        )
+        .unwrap();
+        assert_eq!(query.variables, vec!["r", "f", "_2", "q"]);
+        assert_eq!(query.patterns.len(), 2);
+
+        let steps = &query.patterns[0].steps;
+        assert_eq!(steps[0].0.kinds, vec![EdgeKind::RoutesTo, EdgeKind::Calls]);
+        assert_eq!((steps[0].0.min, steps[0].0.max), (1, None));
+        assert_eq!(steps[0].0.direction, Direction::Outgoing);
+        assert_eq!(steps[0].1.kinds, vec![NodeKind::Function, NodeKind::Method]);
+        assert_eq!((steps[1].0.min, steps[1].0.max), (2, Some(2)));
+        assert_eq!(steps[1].0.direction, Direction::Incoming);
+
+        let second = &query.patterns[1];
+        assert_eq!(second.start.slot, 1);
+        assert_eq!(second.steps[0].0.direction, Direction::Both);
+        assert_eq!(second.steps[0].1.kinds, vec![NodeKind::SqlQuery]);
+
+        assert_eq!(query.returns[1].column, "handler");
+        assert_eq!(query.returns[1].path, vec!["name"]);
+        assert_eq!(query.limit, Some(5));
+    }
+
+    #[test]
+    fn test_syntax_errors_name_the_position() {
+        let error = |text: &str| parse(text).unwrap_err().to_string();
+
+        assert!(error("MATCH (f:Lambda) RETURN f").contains("unknown node kind 'Lambda'"));
+        assert!(error("MATCH (f) RETURN g").contains("unknown variable 'g'"));
+        assert!(error("MATCH (f) WHERE f.name =~ '(' RETURN f").contains("invalid regex"));
+        assert!(error("MATCH (f)-[*3..1]->(g) RETURN f").contains("range is empty"));
+        assert!(error("MATCH (f)<-[:Calls]->(g) RETURN f").contains("single direction"));
+        assert!(error("MATCH (f) RETURN f extra").contains("unexpected 'extra' at position 19"));
+        assert!(error("MATCH (f").contains("found end of query at position 8"));
+    }
+}
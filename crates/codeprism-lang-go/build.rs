@@ -0,0 +1,4 @@
+fn main() {
+    // Add build configuration if needed
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,61 @@
+//! Adapter to integrate Go parser with codeprism
+
+use crate::parser::{GoParser, ParseContext as GoParseContext};
+use crate::types as go_types;
+
+/// Adapter that implements codeprism's LanguageParser trait
+pub struct GoLanguageParser {
+    parser: std::sync::Mutex<GoParser>,
+}
+
+impl GoLanguageParser {
+    /// Create a new Go language parser adapter
+    pub fn new() -> Self {
+        Self {
+            parser: std::sync::Mutex::new(GoParser::new()),
+        }
+    }
+}
+
+impl Default for GoLanguageParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Since we can't import codeprism types directly, we'll need to define a conversion
+// trait that the caller can implement
+pub trait ParseResultConverter {
+    type Node;
+    type Edge;
+    type ParseResult;
+
+    fn convert_node(node: go_types::Node) -> Self::Node;
+    fn convert_edge(edge: go_types::Edge) -> Self::Edge;
+    fn create_parse_result(
+        tree: tree_sitter::Tree,
+        nodes: Vec<Self::Node>,
+        edges: Vec<Self::Edge>,
+    ) -> Self::ParseResult;
+}
+
+/// Parse a file and return the result in our internal types
+pub fn parse_file(
+    parser: &GoLanguageParser,
+    repo_id: &str,
+    file_path: std::path::PathBuf,
+    content: String,
+    old_tree: Option<tree_sitter::Tree>,
+) -> Result<(tree_sitter::Tree, Vec<go_types::Node>, Vec<go_types::Edge>), crate::error::Error> {
+    let context = GoParseContext {
+        repo_id: repo_id.to_string(),
+        file_path,
+        old_tree,
+        content,
+    };
+
+    let mut parser = parser.parser.lock().unwrap();
+    let result = parser.parse(&context)?;
+
+    Ok((result.tree, result.nodes, result.edges))
+}
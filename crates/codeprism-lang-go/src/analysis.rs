@@ -0,0 +1,444 @@
+//! Go-specific analysis capabilities
+
+use crate::types::{Edge, EdgeKind, Node, NodeId, NodeKind, Span};
+use std::collections::HashMap;
+
+/// Calls that synchronize with spawned goroutines
+const SYNC_CALLS: &[&str] = &[".Wait", ".Done", ".Go"];
+
+/// Analyzer for Go-specific language features
+pub struct GoAnalyzer {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    /// Map from function/method ID to the nodes it spawns, calls or contains
+    scope_map: HashMap<NodeId, Vec<NodeId>>,
+}
+
+impl GoAnalyzer {
+    /// Create a new Go analyzer
+    pub fn new(nodes: Vec<Node>, edges: Vec<Edge>) -> Self {
+        let mut analyzer = Self {
+            nodes,
+            edges,
+            scope_map: HashMap::new(),
+        };
+        analyzer.build_scope_map();
+        analyzer
+    }
+
+    /// Build a map of functions to the nodes within them
+    fn build_scope_map(&mut self) {
+        for edge in &self.edges {
+            if matches!(
+                edge.kind,
+                EdgeKind::Contains
+                    | EdgeKind::Calls
+                    | EdgeKind::Spawns
+                    | EdgeKind::Sends
+                    | EdgeKind::Receives
+            ) {
+                self.scope_map
+                    .entry(edge.source)
+                    .or_default()
+                    .push(edge.target);
+            }
+        }
+    }
+
+    /// Comprehensive analysis of all Go-specific patterns
+    pub fn analyze_all(&self) -> GoAnalysisResult {
+        GoAnalysisResult {
+            concurrency_patterns: self.analyze_concurrency_patterns(),
+            concurrency_issues: self.analyze_concurrency_issues(),
+            http_routes: self.analyze_http_routes(),
+            interface_implementations: self.analyze_interface_implementations(),
+        }
+    }
+
+    /// Goroutine and channel patterns, in source order
+    pub fn analyze_concurrency_patterns(&self) -> Vec<ConcurrencyPattern> {
+        let mut patterns = Vec::new();
+
+        for function in self.functions() {
+            let scope = self.scope_nodes(function);
+
+            for node in &scope {
+                let pattern_type = match node.kind {
+                    NodeKind::Goroutine => Some(ConcurrencyPatternType::Goroutine),
+                    NodeKind::Channel => match Self::operation(node) {
+                        Some("make") => Some(ConcurrencyPatternType::ChannelCreation),
+                        Some("send") => Some(ConcurrencyPatternType::ChannelSend),
+                        Some("receive") => Some(ConcurrencyPatternType::ChannelReceive),
+                        Some("close") => Some(ConcurrencyPatternType::ChannelClose),
+                        Some("select") => Some(ConcurrencyPatternType::Select),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some(pattern_type) = pattern_type {
+                    patterns.push(ConcurrencyPattern {
+                        pattern_type,
+                        location: node.span.clone(),
+                        function: function.name.clone(),
+                        description: Self::describe(node),
+                    });
+                }
+            }
+
+            // Goroutines started in a loop and fed through a channel
+            let spawns_in_loop = scope
+                .iter()
+                .any(|n| n.kind == NodeKind::Goroutine && n.metadata["in_loop"] == true);
+            let makes_channel = scope
+                .iter()
+                .any(|n| n.kind == NodeKind::Channel && Self::operation(n) == Some("make"));
+            if spawns_in_loop && makes_channel {
+                patterns.push(ConcurrencyPattern {
+                    pattern_type: ConcurrencyPatternType::WorkerPool,
+                    location: function.span.clone(),
+                    function: function.name.clone(),
+                    description: format!(
+                        "{} starts goroutines in a loop and coordinates them with channels",
+                        function.name
+                    ),
+                });
+            }
+        }
+
+        patterns.sort_by_key(|pattern| pattern.location.start_byte);
+        patterns
+    }
+
+    /// Concurrency issues in goroutine and channel usage
+    pub fn analyze_concurrency_issues(&self) -> Vec<ConcurrencyIssue> {
+        let mut issues = Vec::new();
+
+        for function in self.functions() {
+            let scope = self.scope_nodes(function);
+            let goroutines: Vec<_> = scope
+                .iter()
+                .filter(|n| n.kind == NodeKind::Goroutine)
+                .collect();
+            if goroutines.is_empty() {
+                continue;
+            }
+
+            for goroutine in &goroutines {
+                if goroutine.metadata["in_loop"] == true && goroutine.metadata["anonymous"] == true
+                {
+                    issues.push(ConcurrencyIssue {
+                        issue_type: ConcurrencyIssueType::GoroutineInLoop,
+                        location: goroutine.span.clone(),
+                        description: "Anonymous goroutine started inside a loop".to_string(),
+                        suggestion: Some(
+                            "Pass loop variables as arguments (required before Go 1.22) and bound the number of goroutines"
+                                .to_string(),
+                        ),
+                        severity: Severity::Medium,
+                    });
+                }
+            }
+
+            let synchronized = scope.iter().any(|n| match n.kind {
+                NodeKind::Channel => true,
+                NodeKind::Call => SYNC_CALLS.iter().any(|call| n.name.ends_with(call)),
+                _ => false,
+            });
+            let signature_has_sync = function.signature.as_deref().is_some_and(|signature| {
+                signature.contains("chan ")
+                    || signature.contains("context.Context")
+                    || signature.contains("WaitGroup")
+            });
+            if !synchronized && !signature_has_sync {
+                issues.push(ConcurrencyIssue {
+                    issue_type: ConcurrencyIssueType::UnsynchronizedGoroutine,
+                    location: goroutines[0].span.clone(),
+                    description: format!(
+                        "{} starts goroutines without channels, WaitGroup or context",
+                        function.name
+                    ),
+                    suggestion: Some(
+                        "Make sure the goroutine can finish or be cancelled to avoid leaks"
+                            .to_string(),
+                    ),
+                    severity: Severity::Low,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// `net/http` route registrations
+    pub fn analyze_http_routes(&self) -> Vec<HttpRoute> {
+        self.nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Route)
+            .map(|route| HttpRoute {
+                pattern: route.name.clone(),
+                method: route.metadata["method"].as_str().map(str::to_string),
+                path: route.metadata["path"]
+                    .as_str()
+                    .unwrap_or(&route.name)
+                    .to_string(),
+                handler: route.metadata["handler"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                mux: route.metadata["mux"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                location: route.span.clone(),
+            })
+            .collect()
+    }
+
+    /// Types that implicitly satisfy interfaces declared in the same file
+    pub fn analyze_interface_implementations(&self) -> Vec<InterfaceImplementation> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.kind == EdgeKind::Implements)
+            .filter_map(|edge| {
+                let implementor = self.node(edge.source)?;
+                let interface = self.node(edge.target)?;
+                Some(InterfaceImplementation {
+                    type_name: implementor.name.clone(),
+                    interface_name: interface.name.clone(),
+                    location: implementor.span.clone(),
+                })
+            })
+            .collect()
+    }
+
+    fn functions(&self) -> impl Iterator<Item = &Node> {
+        self.nodes
+            .iter()
+            .filter(|n| matches!(n.kind, NodeKind::Function | NodeKind::Method))
+    }
+
+    fn scope_nodes(&self, function: &Node) -> Vec<&Node> {
+        let mut nodes: Vec<&Node> = self
+            .scope_map
+            .get(&function.id)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.node(*id))
+            .collect();
+        nodes.sort_by_key(|node| node.span.start_byte);
+        nodes
+    }
+
+    fn node(&self, id: NodeId) -> Option<&Node> {
+        self.nodes.iter().find(|n| n.id == id)
+    }
+
+    fn operation(node: &Node) -> Option<&str> {
+        node.metadata["operation"].as_str()
+    }
+
+    fn describe(node: &Node) -> String {
+        match (node.kind, Self::operation(node)) {
+            (NodeKind::Goroutine, _) if node.metadata["anonymous"] == true => {
+                "Goroutine running a function literal".to_string()
+            }
+            (NodeKind::Goroutine, _) => format!("Goroutine running {}", node.name),
+            (_, Some("make")) if node.metadata["buffered"] == true => format!(
+                "Buffered {} with capacity {}",
+                node.name,
+                node.metadata["capacity"].as_str().unwrap_or("?")
+            ),
+            (_, Some("make")) => format!("Unbuffered {}", node.name),
+            (_, Some("select")) => format!(
+                "select over {} channel operations",
+                node.metadata["cases"].as_u64().unwrap_or(0)
+            ),
+            (_, Some(operation)) => format!("Channel {} on {}", operation, node.name),
+            _ => node.name.clone(),
+        }
+    }
+}
+
+/// Comprehensive Go analysis result
+#[derive(Debug, Clone)]
+pub struct GoAnalysisResult {
+    pub concurrency_patterns: Vec<ConcurrencyPattern>,
+    pub concurrency_issues: Vec<ConcurrencyIssue>,
+    pub http_routes: Vec<HttpRoute>,
+    pub interface_implementations: Vec<InterfaceImplementation>,
+}
+
+/// A goroutine or channel pattern found in the code
+#[derive(Debug, Clone)]
+pub struct ConcurrencyPattern {
+    pub pattern_type: ConcurrencyPatternType,
+    pub location: Span,
+    /// Function or method the pattern appears in
+    pub function: String,
+    pub description: String,
+}
+
+/// Types of goroutine and channel patterns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConcurrencyPatternType {
+    Goroutine,
+    ChannelCreation,
+    ChannelSend,
+    ChannelReceive,
+    ChannelClose,
+    Select,
+    WorkerPool,
+}
+
+/// Concurrency issue found in the code
+#[derive(Debug, Clone)]
+pub struct ConcurrencyIssue {
+    pub issue_type: ConcurrencyIssueType,
+    pub location: Span,
+    pub description: String,
+    pub suggestion: Option<String>,
+    pub severity: Severity,
+}
+
+/// Types of concurrency issues
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConcurrencyIssueType {
+    GoroutineInLoop,
+    UnsynchronizedGoroutine,
+}
+
+/// General severity levels
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+/// An HTTP route registered with `net/http`
+#[derive(Debug, Clone)]
+pub struct HttpRoute {
+    /// Full `ServeMux` pattern, e.g. `GET /items/{id}`
+    pub pattern: String,
+    pub method: Option<String>,
+    pub path: String,
+    pub handler: String,
+    /// Mux the route is registered on
+    pub mux: String,
+    pub location: Span,
+}
+
+/// A type that satisfies an interface
+#[derive(Debug, Clone)]
+pub struct InterfaceImplementation {
+    pub type_name: String,
+    pub interface_name: String,
+    pub location: Span,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{GoParser, ParseContext};
+    use std::path::PathBuf;
+
+    fn analyze(content: &str) -> GoAnalysisResult {
+        let mut parser = GoParser::new();
+        let result = parser
+            .parse(&ParseContext {
+                repo_id: "test_repo".to_string(),
+                file_path: PathBuf::from("main.go"),
+                old_tree: None,
+                content: content.to_string(),
+            })
+            .unwrap();
+        GoAnalyzer::new(result.nodes, result.edges).analyze_all()
+    }
+
+    #[test]
+    fn test_worker_pool_and_loop_goroutines() {
+        let result = analyze(
+            r#"package main
+
+func process(items []string) {
+	out := make(chan string, len(items))
+	for _, item := range items {
+		go func() { out <- item }()
+	}
+	for range items {
+		<-out
+	}
+}
+
+func fireAndForget() {
+	go cleanup()
+}
+
+func cleanup() {}
+"#,
+        );
+
+        let types: Vec<_> = result
+            .concurrency_patterns
+            .iter()
+            .map(|p| p.pattern_type.clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ConcurrencyPatternType::WorkerPool,
+                ConcurrencyPatternType::ChannelCreation,
+                ConcurrencyPatternType::Goroutine,
+                ConcurrencyPatternType::ChannelSend,
+                ConcurrencyPatternType::ChannelReceive,
+                ConcurrencyPatternType::Goroutine,
+            ]
+        );
+
+        let issues: Vec<_> = result
+            .concurrency_issues
+            .iter()
+            .map(|i| i.issue_type.clone())
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                ConcurrencyIssueType::GoroutineInLoop,
+                ConcurrencyIssueType::UnsynchronizedGoroutine,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_routes_and_interfaces() {
+        let result = analyze(
+            r#"package main
+
+import "net/http"
+
+type Store interface{ Get(id string) string }
+
+type memory struct{}
+
+func (m memory) Get(id string) string { return id }
+
+func main() {
+	http.HandleFunc("POST /orders", createOrder)
+}
+
+func createOrder(w http.ResponseWriter, r *http.Request) {}
+"#,
+        );
+
+        assert_eq!(result.http_routes.len(), 1);
+        let route = &result.http_routes[0];
+        assert_eq!(route.method.as_deref(), Some("POST"));
+        assert_eq!(route.path, "/orders");
+        assert_eq!(route.handler, "createOrder");
+
+        assert_eq!(result.interface_implementations.len(), 1);
+        assert_eq!(result.interface_implementations[0].type_name, "memory");
+        assert_eq!(result.interface_implementations[0].interface_name, "Store");
+    }
+}
@@ -0,0 +1,1154 @@
+//! AST mapper for converting Tree-sitter CST to Universal AST for Go
+
+use crate::error::Result;
+use crate::types::{Edge, EdgeKind, Language, Node, NodeId, NodeKind, Span};
+
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use tree_sitter::{Tree, TreeCursor};
+
+/// Import path of the standard library HTTP package
+const NET_HTTP: &str = "net/http";
+
+/// Route registration methods on `net/http` and `http.ServeMux`
+const ROUTE_METHODS: &[&str] = &["Handle", "HandleFunc"];
+
+/// HTTP methods accepted as a prefix of Go 1.22 `ServeMux` patterns
+const HTTP_METHODS: &[&str] = &[
+    "GET", "HEAD", "POST", "PUT", "PATCH", "DELETE", "CONNECT", "OPTIONS", "TRACE",
+];
+
+/// AST mapper for Go
+pub struct AstMapper {
+    repo_id: String,
+    file_path: PathBuf,
+    language: Language,
+    source: String,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    /// Map from tree-sitter node ID to our NodeId for edge creation
+    node_map: HashMap<usize, NodeId>,
+    /// Import alias to import path
+    imports: HashMap<String, String>,
+    /// Types declared in this file by name
+    type_ids: HashMap<String, NodeId>,
+    /// Method names declared in each interface, in declaration order
+    interface_methods: Vec<(NodeId, Vec<String>)>,
+    /// Methods declared for each receiver type name
+    methods_by_receiver: HashMap<String, Vec<(NodeId, String)>>,
+    /// Top-level functions by name
+    function_ids: HashMap<String, NodeId>,
+    /// Embedded type names per struct or interface
+    embedded_types: Vec<(NodeId, String)>,
+    /// Handler names of registered routes
+    route_handlers: Vec<(NodeId, String)>,
+}
+
+impl AstMapper {
+    /// Create a new AST mapper
+    pub fn new(repo_id: &str, file_path: PathBuf, language: Language, source: &str) -> Self {
+        Self {
+            repo_id: repo_id.to_string(),
+            file_path,
+            language,
+            source: source.to_string(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            node_map: HashMap::new(),
+            imports: HashMap::new(),
+            type_ids: HashMap::new(),
+            interface_methods: Vec::new(),
+            methods_by_receiver: HashMap::new(),
+            function_ids: HashMap::new(),
+            embedded_types: Vec::new(),
+            route_handlers: Vec::new(),
+        }
+    }
+
+    /// Extract nodes and edges from the tree
+    pub fn extract(mut self, tree: &Tree) -> Result<(Vec<Node>, Vec<Edge>)> {
+        let mut cursor = tree.walk();
+
+        // Create module node for the file
+        let module_node = self.create_module_node(&cursor);
+        self.nodes.push(module_node);
+
+        // Walk the tree and extract nodes
+        self.walk_tree(&mut cursor)?;
+
+        // Relationships that need every declaration in the file
+        self.link_methods_to_receivers();
+        self.link_embedded_types();
+        self.link_interface_implementations();
+        self.link_route_handlers();
+
+        Ok((self.nodes, self.edges))
+    }
+
+    /// Create a module node for the file
+    fn create_module_node(&mut self, cursor: &TreeCursor) -> Node {
+        let root = cursor.node();
+        let span = Span::from_node(&root);
+
+        let module_name = self
+            .file_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("module")
+            .to_string();
+
+        let node = Node::new(
+            &self.repo_id,
+            NodeKind::Module,
+            module_name,
+            self.language,
+            self.file_path.clone(),
+            span,
+        );
+
+        self.node_map.insert(root.id(), node.id);
+        node
+    }
+
+    /// Walk the tree and extract nodes
+    fn walk_tree(&mut self, cursor: &mut TreeCursor) -> Result<()> {
+        self.visit_node(cursor)?;
+
+        if cursor.goto_first_child() {
+            loop {
+                self.walk_tree(cursor)?;
+                if !cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+            cursor.goto_parent();
+        }
+
+        Ok(())
+    }
+
+    /// Visit a single node and extract information
+    fn visit_node(&mut self, cursor: &TreeCursor) -> Result<()> {
+        let node = cursor.node();
+
+        match node.kind() {
+            "package_clause" => self.handle_package(&node),
+            "import_spec" => self.handle_import(&node),
+            "type_spec" | "type_alias" => self.handle_type_spec(&node),
+            "field_declaration" => self.handle_field(&node),
+            "method_elem" => self.handle_interface_method(&node),
+            "type_elem" => self.handle_embedded_interface(&node),
+            "function_declaration" => self.handle_function(&node),
+            "method_declaration" => self.handle_method(&node),
+            "const_spec" => self.handle_value_spec(&node, NodeKind::Const),
+            "var_spec" => self.handle_value_spec(&node, NodeKind::Variable),
+            "short_var_declaration" => self.handle_short_var_declaration(&node),
+            "call_expression" => self.handle_call(&node),
+            "go_statement" => self.handle_go_statement(&node),
+            "send_statement" => self.handle_send(&node),
+            "unary_expression" => self.handle_receive(&node),
+            "select_statement" => self.handle_select(&node),
+            _ => {} // Skip other node types in this implementation
+        }
+
+        Ok(())
+    }
+
+    /// Handle the package clause
+    fn handle_package(&mut self, node: &tree_sitter::Node) {
+        let Some(name_node) = node.named_child(0) else {
+            return;
+        };
+        let name = self.get_node_text(&name_node);
+
+        if let Some(module) = self.nodes.first_mut() {
+            module.metadata = json!({ "package": name });
+        }
+
+        let package_node = self.create_node(NodeKind::Package, name, node);
+        self.add_edge_from_module(package_node.id, EdgeKind::Contains);
+        self.nodes.push(package_node);
+    }
+
+    /// Handle a single import spec
+    fn handle_import(&mut self, node: &tree_sitter::Node) {
+        let Some(path_node) = node.child_by_field_name("path") else {
+            return;
+        };
+        let path = Self::unquote(&self.get_node_text(&path_node));
+
+        let (alias, import_kind) = match node.child_by_field_name("name") {
+            Some(name) if name.kind() == "dot" => (".".to_string(), "dot"),
+            Some(name) if name.kind() == "blank_identifier" => ("_".to_string(), "blank"),
+            Some(name) => (self.get_node_text(&name), "named"),
+            None => (Self::default_import_alias(&path), "default"),
+        };
+        self.imports.insert(alias.clone(), path.clone());
+
+        let import_node = self
+            .create_node(NodeKind::Import, path, node)
+            .with_metadata(json!({ "alias": alias, "import_kind": import_kind }));
+        self.add_edge_from_module(import_node.id, EdgeKind::Imports);
+        self.nodes.push(import_node);
+    }
+
+    /// Handle type declarations (structs, interfaces, named types and aliases)
+    fn handle_type_spec(&mut self, node: &tree_sitter::Node) {
+        let (Some(name_node), Some(type_node)) = (
+            node.child_by_field_name("name"),
+            node.child_by_field_name("type"),
+        ) else {
+            return;
+        };
+        let name = self.get_node_text(&name_node);
+
+        let kind = match type_node.kind() {
+            "struct_type" => NodeKind::Struct,
+            "interface_type" => NodeKind::Interface,
+            _ => NodeKind::TypeAlias,
+        };
+        let signature = match kind {
+            NodeKind::Struct => format!("type {} struct", name),
+            NodeKind::Interface => format!("type {} interface", name),
+            _ => format!("type {}", self.get_node_text(node)),
+        };
+
+        let mut metadata = json!({
+            "exported": Self::is_exported(&name),
+        });
+        if let Some(type_parameters) = node.child_by_field_name("type_parameters") {
+            metadata["type_parameters"] = json!(self.get_node_text(&type_parameters));
+        }
+        if kind == NodeKind::TypeAlias {
+            metadata["underlying_type"] = json!(self.get_node_text(&type_node));
+            metadata["alias"] = json!(node.kind() == "type_alias");
+        }
+
+        let type_decl = self
+            .create_node(kind, name.clone(), node)
+            .with_signature(signature)
+            .with_metadata(metadata);
+
+        if kind == NodeKind::Interface {
+            self.interface_methods.push((type_decl.id, Vec::new()));
+        }
+        self.type_ids.insert(name, type_decl.id);
+        self.add_edge_from_scope(node, type_decl.id, EdgeKind::Contains);
+        self.nodes.push(type_decl);
+    }
+
+    /// Handle struct fields, including embedded types
+    fn handle_field(&mut self, node: &tree_sitter::Node) {
+        let Some(struct_id) = self.owning_type_id(node, "struct_type") else {
+            return;
+        };
+        let Some(type_node) = node.child_by_field_name("type") else {
+            return;
+        };
+        let field_type = self.get_node_text(&type_node);
+        let tag = node
+            .child_by_field_name("tag")
+            .map(|tag| self.get_node_text(&tag));
+
+        let mut cursor = node.walk();
+        let names: Vec<_> = node.children_by_field_name("name", &mut cursor).collect();
+
+        if names.is_empty() {
+            // Embedded field: the type name doubles as the field name
+            let type_name = Self::base_type_name(&field_type);
+            let field_node = self
+                .create_node(NodeKind::Field, type_name.clone(), node)
+                .with_signature(field_type.clone())
+                .with_metadata(json!({
+                    "type": field_type,
+                    "embedded": true,
+                    "tag": tag,
+                    "exported": Self::is_exported(&type_name),
+                }));
+            self.edges
+                .push(Edge::new(struct_id, field_node.id, EdgeKind::Contains));
+            self.embedded_types.push((struct_id, type_name));
+            self.nodes.push(field_node);
+            return;
+        }
+
+        for name_node in names {
+            let name = self.get_node_text(&name_node);
+            let field_node = self
+                .create_node(NodeKind::Field, name.clone(), &name_node)
+                .with_signature(format!("{} {}", name, field_type))
+                .with_metadata(json!({
+                    "type": field_type,
+                    "embedded": false,
+                    "tag": tag,
+                    "exported": Self::is_exported(&name),
+                }));
+            self.edges
+                .push(Edge::new(struct_id, field_node.id, EdgeKind::Contains));
+            self.nodes.push(field_node);
+        }
+    }
+
+    /// Handle method declarations inside interfaces
+    fn handle_interface_method(&mut self, node: &tree_sitter::Node) {
+        let Some(interface_id) = self.owning_type_id(node, "interface_type") else {
+            return;
+        };
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = self.get_node_text(&name_node);
+
+        let method_node = self
+            .create_node(NodeKind::Method, name.clone(), node)
+            .with_signature(self.get_node_text(node))
+            .with_metadata(json!({
+                "abstract": true,
+                "exported": Self::is_exported(&name),
+            }));
+        self.edges
+            .push(Edge::new(interface_id, method_node.id, EdgeKind::Contains));
+        if let Some((_, methods)) = self
+            .interface_methods
+            .iter_mut()
+            .find(|(id, _)| *id == interface_id)
+        {
+            methods.push(name);
+        }
+        self.nodes.push(method_node);
+    }
+
+    /// Handle interfaces embedded in other interfaces
+    fn handle_embedded_interface(&mut self, node: &tree_sitter::Node) {
+        let Some(interface_id) = self.owning_type_id(node, "interface_type") else {
+            return;
+        };
+        // Union and approximation constraints (`~int | ~string`) are not embeddings
+        if node.named_child_count() != 1 {
+            return;
+        }
+        let type_text = self.get_node_text(node);
+        if type_text.starts_with('~') {
+            return;
+        }
+        self.embedded_types
+            .push((interface_id, Self::base_type_name(&type_text)));
+    }
+
+    /// Handle top-level function declarations
+    fn handle_function(&mut self, node: &tree_sitter::Node) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = self.get_node_text(&name_node);
+
+        let mut metadata = json!({ "exported": Self::is_exported(&name) });
+        if let Some(type_parameters) = node.child_by_field_name("type_parameters") {
+            metadata["type_parameters"] = json!(self.get_node_text(&type_parameters));
+        }
+        if let Some(result) = node.child_by_field_name("result") {
+            metadata["result"] = json!(self.get_node_text(&result));
+        }
+
+        let func_node = self
+            .create_node(NodeKind::Function, name.clone(), node)
+            .with_signature(self.extract_signature(node))
+            .with_metadata(metadata);
+
+        self.function_ids.insert(name, func_node.id);
+        self.add_edge_from_module(func_node.id, EdgeKind::Contains);
+        let func_id = func_node.id;
+        self.nodes.push(func_node);
+
+        self.extract_parameters(node, func_id);
+    }
+
+    /// Handle method declarations with receivers
+    fn handle_method(&mut self, node: &tree_sitter::Node) {
+        let Some(name_node) = node.child_by_field_name("name") else {
+            return;
+        };
+        let name = self.get_node_text(&name_node);
+
+        let receiver = node
+            .child_by_field_name("receiver")
+            .and_then(|receiver| receiver.named_child(0))
+            .filter(|param| param.kind() == "parameter_declaration");
+        let receiver_name = receiver
+            .and_then(|param| param.child_by_field_name("name"))
+            .map(|name| self.get_node_text(&name));
+        let receiver_type = receiver
+            .and_then(|param| param.child_by_field_name("type"))
+            .map(|ty| self.get_node_text(&ty))
+            .unwrap_or_default();
+        let receiver_base = Self::base_type_name(&receiver_type);
+
+        let mut metadata = json!({
+            "exported": Self::is_exported(&name),
+            "receiver": {
+                "name": receiver_name,
+                "type": receiver_base,
+                "pointer": receiver_type.starts_with('*'),
+            },
+        });
+        if let Some(result) = node.child_by_field_name("result") {
+            metadata["result"] = json!(self.get_node_text(&result));
+        }
+
+        let method_node = self
+            .create_node(NodeKind::Method, name.clone(), node)
+            .with_signature(self.extract_signature(node))
+            .with_metadata(metadata);
+
+        self.methods_by_receiver
+            .entry(receiver_base)
+            .or_default()
+            .push((method_node.id, name));
+        let method_id = method_node.id;
+        self.nodes.push(method_node);
+
+        self.extract_parameters(node, method_id);
+    }
+
+    /// Extract named parameters of a function or method
+    fn extract_parameters(&mut self, node: &tree_sitter::Node, owner_id: NodeId) {
+        let Some(parameters) = node.child_by_field_name("parameters") else {
+            return;
+        };
+
+        let mut cursor = parameters.walk();
+        let declarations: Vec<_> = parameters.named_children(&mut cursor).collect();
+        for declaration in declarations {
+            let variadic = declaration.kind() == "variadic_parameter_declaration";
+            let param_type = declaration
+                .child_by_field_name("type")
+                .map(|ty| self.get_node_text(&ty))
+                .unwrap_or_default();
+
+            let mut name_cursor = declaration.walk();
+            let names: Vec<_> = declaration
+                .children_by_field_name("name", &mut name_cursor)
+                .collect();
+            for name_node in names {
+                let name = self.get_node_text(&name_node);
+                let param_node = self
+                    .create_node(NodeKind::Parameter, name, &name_node)
+                    .with_signature(param_type.clone())
+                    .with_metadata(json!({ "type": param_type, "variadic": variadic }));
+                self.edges
+                    .push(Edge::new(owner_id, param_node.id, EdgeKind::Contains));
+                self.nodes.push(param_node);
+            }
+        }
+    }
+
+    /// Handle `const` and `var` specs
+    fn handle_value_spec(&mut self, node: &tree_sitter::Node, kind: NodeKind) {
+        let value_type = node
+            .child_by_field_name("type")
+            .map(|ty| self.get_node_text(&ty));
+
+        let mut cursor = node.walk();
+        let names: Vec<_> = node.children_by_field_name("name", &mut cursor).collect();
+        for name_node in names {
+            let name = self.get_node_text(&name_node);
+            if name == "_" {
+                continue;
+            }
+            let value_node = self
+                .create_node(kind, name.clone(), &name_node)
+                .with_metadata(json!({
+                    "type": value_type,
+                    "exported": Self::is_exported(&name),
+                }));
+            self.add_edge_from_scope(node, value_node.id, EdgeKind::Contains);
+            self.nodes.push(value_node);
+        }
+    }
+
+    /// Handle `:=` declarations inside functions
+    fn handle_short_var_declaration(&mut self, node: &tree_sitter::Node) {
+        let Some(left) = node.child_by_field_name("left") else {
+            return;
+        };
+
+        let mut cursor = left.walk();
+        let names: Vec<_> = left
+            .named_children(&mut cursor)
+            .filter(|name| name.kind() == "identifier")
+            .collect();
+        for name_node in names {
+            let name = self.get_node_text(&name_node);
+            if name == "_" {
+                continue;
+            }
+            let var_node = self.create_node(NodeKind::Variable, name, &name_node);
+            self.add_edge_from_scope(node, var_node.id, EdgeKind::Contains);
+            self.nodes.push(var_node);
+        }
+    }
+
+    /// Handle calls, including channel builtins and `net/http` route registrations
+    fn handle_call(&mut self, node: &tree_sitter::Node) {
+        let Some(function) = node.child_by_field_name("function") else {
+            return;
+        };
+        let target = self.get_node_text(&function);
+        let arguments = node
+            .child_by_field_name("arguments")
+            .map(|args| {
+                let mut cursor = args.walk();
+                args.named_children(&mut cursor).collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        match target.as_str() {
+            "make"
+                if arguments
+                    .first()
+                    .is_some_and(|a| a.kind() == "channel_type") =>
+            {
+                let channel_type = self.get_node_text(&arguments[0]);
+                let capacity = arguments.get(1).map(|arg| self.get_node_text(arg));
+                let channel_node = self
+                    .create_node(NodeKind::Channel, channel_type.clone(), node)
+                    .with_metadata(json!({
+                        "operation": "make",
+                        "channel_type": channel_type,
+                        "buffered": capacity.as_deref().is_some_and(|c| c != "0"),
+                        "capacity": capacity,
+                    }));
+                self.add_edge_from_scope(node, channel_node.id, EdgeKind::Contains);
+                self.nodes.push(channel_node);
+                return;
+            }
+            "close" if arguments.len() == 1 => {
+                let channel = self.get_node_text(&arguments[0]);
+                let channel_node = self
+                    .create_node(NodeKind::Channel, channel, node)
+                    .with_metadata(json!({ "operation": "close" }));
+                self.add_edge_from_scope(node, channel_node.id, EdgeKind::Sends);
+                self.nodes.push(channel_node);
+                return;
+            }
+            _ => {}
+        }
+
+        let mut metadata = json!({});
+        if function.kind() == "selector_expression" {
+            if let Some(operand) = function.child_by_field_name("operand") {
+                let operand = self.get_node_text(&operand);
+                if let Some(package) = self.imports.get(&operand) {
+                    metadata["package"] = json!(package);
+                } else {
+                    metadata["receiver"] = json!(operand);
+                }
+            }
+        }
+
+        let call_node = self
+            .create_node(NodeKind::Call, target, node)
+            .with_metadata(metadata);
+        self.add_edge_from_scope(node, call_node.id, EdgeKind::Calls);
+        self.nodes.push(call_node);
+
+        self.detect_http_route(node, &function, &arguments);
+    }
+
+    /// Detect `http.HandleFunc("/path", handler)` and `mux.Handle("GET /path", h)`
+    fn detect_http_route(
+        &mut self,
+        node: &tree_sitter::Node,
+        function: &tree_sitter::Node,
+        arguments: &[tree_sitter::Node],
+    ) {
+        if function.kind() != "selector_expression"
+            || !self.imports.values().any(|path| path == NET_HTTP)
+        {
+            return;
+        }
+        let (Some(operand), Some(field)) = (
+            function.child_by_field_name("operand"),
+            function.child_by_field_name("field"),
+        ) else {
+            return;
+        };
+        let registration = self.get_node_text(&field);
+        if !ROUTE_METHODS.contains(&registration.as_str()) || arguments.len() != 2 {
+            return;
+        }
+        if !matches!(
+            arguments[0].kind(),
+            "interpreted_string_literal" | "raw_string_literal"
+        ) {
+            return;
+        }
+
+        let pattern = Self::unquote(&self.get_node_text(&arguments[0]));
+        let (method, path) = match pattern.split_once(' ') {
+            Some((method, path)) if HTTP_METHODS.contains(&method) => {
+                (Some(method.to_string()), path.trim().to_string())
+            }
+            _ => (None, pattern.clone()),
+        };
+        // ServeMux patterns always contain a path
+        if !path.contains('/') {
+            return;
+        }
+
+        let operand = self.get_node_text(&operand);
+        let receiver = if self.imports.get(&operand).map(String::as_str) == Some(NET_HTTP) {
+            "DefaultServeMux".to_string()
+        } else {
+            operand
+        };
+        let handler = self.get_node_text(&arguments[1]);
+
+        let route_node = self
+            .create_node(NodeKind::Route, pattern, node)
+            .with_metadata(json!({
+                "framework": NET_HTTP,
+                "method": method,
+                "path": path,
+                "handler": handler,
+                "registration": registration,
+                "mux": receiver,
+            }));
+        self.add_edge_from_scope(node, route_node.id, EdgeKind::Contains);
+
+        let handler_name = match arguments[1].kind() {
+            "identifier" => Some(handler),
+            "selector_expression" => arguments[1]
+                .child_by_field_name("field")
+                .map(|field| self.get_node_text(&field)),
+            _ => None,
+        };
+        if let Some(handler_name) = handler_name {
+            self.route_handlers.push((route_node.id, handler_name));
+        }
+        self.nodes.push(route_node);
+    }
+
+    /// Handle `go` statements
+    fn handle_go_statement(&mut self, node: &tree_sitter::Node) {
+        let Some(call) = node.named_child(0) else {
+            return;
+        };
+        let function = call.child_by_field_name("function");
+        let anonymous = function.is_some_and(|f| f.kind() == "func_literal");
+        let name = match function {
+            Some(_) if anonymous => "func literal".to_string(),
+            Some(function) => self.get_node_text(&function),
+            None => self.get_node_text(&call),
+        };
+
+        let goroutine_node = self
+            .create_node(NodeKind::Goroutine, name, node)
+            .with_metadata(json!({
+                "anonymous": anonymous,
+                "in_loop": self.is_inside_loop(node),
+            }));
+        self.add_edge_from_scope(node, goroutine_node.id, EdgeKind::Spawns);
+        self.nodes.push(goroutine_node);
+    }
+
+    /// Handle `ch <- value`
+    fn handle_send(&mut self, node: &tree_sitter::Node) {
+        let Some(channel) = node.child_by_field_name("channel") else {
+            return;
+        };
+        let channel_node = self
+            .create_node(NodeKind::Channel, self.get_node_text(&channel), node)
+            .with_metadata(json!({
+                "operation": "send",
+                "in_select": Self::is_select_case(node),
+            }));
+        self.add_edge_from_scope(node, channel_node.id, EdgeKind::Sends);
+        self.nodes.push(channel_node);
+    }
+
+    /// Handle `<-ch`
+    fn handle_receive(&mut self, node: &tree_sitter::Node) {
+        let is_receive = node
+            .child_by_field_name("operator")
+            .is_some_and(|op| op.kind() == "<-");
+        let Some(operand) = node.child_by_field_name("operand").filter(|_| is_receive) else {
+            return;
+        };
+
+        let channel_node = self
+            .create_node(NodeKind::Channel, self.get_node_text(&operand), node)
+            .with_metadata(json!({
+                "operation": "receive",
+                "in_select": Self::is_select_case(node),
+            }));
+        self.add_edge_from_scope(node, channel_node.id, EdgeKind::Receives);
+        self.nodes.push(channel_node);
+    }
+
+    /// Handle `select` statements
+    fn handle_select(&mut self, node: &tree_sitter::Node) {
+        let mut cursor = node.walk();
+        let cases: Vec<_> = node.named_children(&mut cursor).collect();
+        let communication_cases = cases
+            .iter()
+            .filter(|case| case.kind() == "communication_case")
+            .count();
+        let has_default = cases.iter().any(|case| case.kind() == "default_case");
+
+        let select_node = self
+            .create_node(NodeKind::Channel, "select".to_string(), node)
+            .with_metadata(json!({
+                "operation": "select",
+                "cases": communication_cases,
+                "has_default": has_default,
+                "in_loop": self.is_inside_loop(node),
+            }));
+        self.add_edge_from_scope(node, select_node.id, EdgeKind::Contains);
+        self.nodes.push(select_node);
+    }
+
+    /// Attach methods to the type named by their receiver
+    fn link_methods_to_receivers(&mut self) {
+        for node in &self.nodes {
+            if !matches!(node.kind, NodeKind::Struct | NodeKind::TypeAlias) {
+                continue;
+            }
+            if let Some(methods) = self.methods_by_receiver.get(&node.name) {
+                for (method_id, _) in methods {
+                    self.edges
+                        .push(Edge::new(node.id, *method_id, EdgeKind::Contains));
+                }
+            }
+        }
+        // Methods on types declared in other files belong to the module
+        let module_id = self.find_module_node_id();
+        for (receiver, methods) in &self.methods_by_receiver {
+            if self.type_ids.contains_key(receiver) {
+                continue;
+            }
+            if let Some(module_id) = module_id {
+                for (method_id, _) in methods {
+                    self.edges
+                        .push(Edge::new(module_id, *method_id, EdgeKind::Contains));
+                }
+            }
+        }
+    }
+
+    /// Link embedding structs and interfaces to embedded types in this file
+    fn link_embedded_types(&mut self) {
+        for (owner_id, type_name) in &self.embedded_types {
+            if let Some(&target_id) = self.type_ids.get(type_name) {
+                self.edges
+                    .push(Edge::new(*owner_id, target_id, EdgeKind::Embeds));
+            }
+        }
+    }
+
+    /// Link types to the interfaces whose method sets they satisfy
+    ///
+    /// Go interfaces are implemented implicitly; a type is treated as
+    /// implementing an interface when it declares every interface method,
+    /// counting both value and pointer receivers.
+    fn link_interface_implementations(&mut self) {
+        for node in &self.nodes {
+            if !matches!(node.kind, NodeKind::Struct | NodeKind::TypeAlias) {
+                continue;
+            }
+            let Some(methods) = self.methods_by_receiver.get(&node.name) else {
+                continue;
+            };
+            let method_names: HashSet<&str> =
+                methods.iter().map(|(_, name)| name.as_str()).collect();
+
+            for (interface_id, interface_methods) in &self.interface_methods {
+                if !interface_methods.is_empty()
+                    && interface_methods
+                        .iter()
+                        .all(|name| method_names.contains(name.as_str()))
+                {
+                    self.edges
+                        .push(Edge::new(node.id, *interface_id, EdgeKind::Implements));
+                }
+            }
+        }
+    }
+
+    /// Link routes to handler functions or methods declared in this file
+    fn link_route_handlers(&mut self) {
+        for (route_id, handler) in &self.route_handlers {
+            let handler_id = self.function_ids.get(handler).copied().or_else(|| {
+                self.methods_by_receiver
+                    .values()
+                    .flatten()
+                    .find(|(_, name)| name == handler)
+                    .map(|(id, _)| *id)
+            });
+            if let Some(handler_id) = handler_id {
+                self.edges
+                    .push(Edge::new(*route_id, handler_id, EdgeKind::RoutesTo));
+            }
+        }
+    }
+
+    /// Create a node spanning `node` and register it in the node map
+    fn create_node(&mut self, kind: NodeKind, name: String, node: &tree_sitter::Node) -> Node {
+        let created = Node::new(
+            &self.repo_id,
+            kind,
+            name,
+            self.language,
+            self.file_path.clone(),
+            Span::from_node(node),
+        );
+        self.node_map.insert(node.id(), created.id);
+        created
+    }
+
+    /// Add an edge from the module node
+    fn add_edge_from_module(&mut self, target: NodeId, kind: EdgeKind) {
+        if let Some(module_id) = self.find_module_node_id() {
+            self.edges.push(Edge::new(module_id, target, kind));
+        }
+    }
+
+    /// Add an edge from the enclosing function, method or module
+    fn add_edge_from_scope(&mut self, node: &tree_sitter::Node, target: NodeId, kind: EdgeKind) {
+        if let Some(scope_id) = self.find_containing_function_id(node) {
+            self.edges.push(Edge::new(scope_id, target, kind));
+        }
+    }
+
+    /// Find the struct or interface declaration that owns a member node
+    fn owning_type_id(&self, node: &tree_sitter::Node, type_kind: &str) -> Option<NodeId> {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if parent.kind() == type_kind {
+                // Only members of named type declarations, not anonymous types
+                let spec = parent.parent().filter(|p| p.kind() == "type_spec")?;
+                return self.node_map.get(&spec.id()).copied();
+            }
+            current = parent.parent();
+        }
+        None
+    }
+
+    /// Get text content of a node
+    fn get_node_text(&self, node: &tree_sitter::Node) -> String {
+        node.utf8_text(self.source.as_bytes())
+            .unwrap_or("ERROR")
+            .to_string()
+    }
+
+    /// Signature of a function or method, without its body
+    fn extract_signature(&self, node: &tree_sitter::Node) -> String {
+        let end = node
+            .child_by_field_name("body")
+            .map(|body| body.start_byte())
+            .unwrap_or_else(|| node.end_byte());
+        self.source
+            .get(node.start_byte()..end)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    /// Find module node ID (the root module)
+    fn find_module_node_id(&self) -> Option<NodeId> {
+        self.nodes
+            .first()
+            .filter(|n| matches!(n.kind, NodeKind::Module))
+            .map(|n| n.id)
+    }
+
+    /// Find containing function or method ID, falling back to the module
+    fn find_containing_function_id(&self, node: &tree_sitter::Node) -> Option<NodeId> {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if matches!(parent.kind(), "function_declaration" | "method_declaration") {
+                if let Some(&node_id) = self.node_map.get(&parent.id()) {
+                    return Some(node_id);
+                }
+            }
+            current = parent.parent();
+        }
+        self.find_module_node_id()
+    }
+
+    /// Check if a node is inside a `for` loop of its function
+    fn is_inside_loop(&self, node: &tree_sitter::Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            match parent.kind() {
+                "for_statement" => return true,
+                "function_declaration" | "method_declaration" | "func_literal" => return false,
+                _ => current = parent.parent(),
+            }
+        }
+        false
+    }
+
+    /// Check if a channel operation is the communication of a `select` case
+    fn is_select_case(node: &tree_sitter::Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            match parent.kind() {
+                "communication_case" => return true,
+                "receive_statement" | "expression_list" => current = parent.parent(),
+                _ => return false,
+            }
+        }
+        false
+    }
+
+    /// Strip the quotes of a Go string literal
+    fn unquote(literal: &str) -> String {
+        literal.trim_matches(|c| c == '"' || c == '`').to_string()
+    }
+
+    /// Package name used for an import without an explicit alias
+    ///
+    /// Major version suffixes (`github.com/org/lib/v2`) are skipped.
+    fn default_import_alias(path: &str) -> String {
+        let mut segments = path.rsplit('/');
+        let last = segments.next().unwrap_or(path);
+        let is_version = last.len() > 1
+            && last.starts_with('v')
+            && last[1..].chars().all(|c| c.is_ascii_digit());
+        match segments.next() {
+            Some(previous) if is_version => previous.to_string(),
+            _ => last.to_string(),
+        }
+    }
+
+    /// Type name without pointer, package qualifier or type arguments
+    fn base_type_name(type_text: &str) -> String {
+        let without_pointer = type_text.trim_start_matches('*');
+        let without_args = without_pointer.split('[').next().unwrap_or(without_pointer);
+        without_args
+            .rsplit('.')
+            .next()
+            .unwrap_or(without_args)
+            .trim()
+            .to_string()
+    }
+
+    /// Whether a Go identifier is exported from its package
+    fn is_exported(name: &str) -> bool {
+        name.chars().next().is_some_and(|c| c.is_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(source: &str) -> (Vec<Node>, Vec<Edge>) {
+        let mut parser = tree_sitter::Parser::new();
+        parser
+            .set_language(&tree_sitter_go::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source, None).unwrap();
+        AstMapper::new(
+            "test_repo",
+            PathBuf::from("server.go"),
+            Language::Go,
+            source,
+        )
+        .extract(&tree)
+        .unwrap()
+    }
+
+    fn find<'a>(nodes: &'a [Node], kind: NodeKind, name: &str) -> &'a Node {
+        nodes
+            .iter()
+            .find(|n| n.kind == kind && n.name == name)
+            .unwrap_or_else(|| panic!("missing {:?} {}", kind, name))
+    }
+
+    fn has_edge(edges: &[Edge], source: &Node, target: &Node, kind: EdgeKind) -> bool {
+        edges.contains(&Edge::new(source.id, target.id, kind))
+    }
+
+    #[test]
+    fn test_default_import_alias() {
+        assert_eq!(AstMapper::default_import_alias("net/http"), "http");
+        assert_eq!(AstMapper::default_import_alias("fmt"), "fmt");
+        assert_eq!(
+            AstMapper::default_import_alias("github.com/go-chi/chi/v5"),
+            "chi"
+        );
+    }
+
+    #[test]
+    fn test_base_type_name() {
+        assert_eq!(AstMapper::base_type_name("*Server"), "Server");
+        assert_eq!(AstMapper::base_type_name("sync.Mutex"), "Mutex");
+        assert_eq!(AstMapper::base_type_name("*List[T]"), "List");
+    }
+
+    #[test]
+    fn test_methods_embedding_and_implicit_interfaces() {
+        let (nodes, edges) = extract(
+            r#"package shapes
+
+type Shape interface {
+	Area() float64
+	Perimeter() float64
+}
+
+type Named interface {
+	Shape
+	Name() string
+}
+
+type Base struct{ id int }
+
+type Square struct {
+	Base
+	side float64
+}
+
+func (s Square) Area() float64 { return s.side * s.side }
+func (s *Square) Perimeter() float64 { return 4 * s.side }
+"#,
+        );
+
+        let shape = find(&nodes, NodeKind::Interface, "Shape");
+        let named = find(&nodes, NodeKind::Interface, "Named");
+        let base = find(&nodes, NodeKind::Struct, "Base");
+        let square = find(&nodes, NodeKind::Struct, "Square");
+        let area = nodes
+            .iter()
+            .find(|n| n.name == "Area" && !n.metadata["receiver"].is_null())
+            .unwrap();
+        let perimeter = nodes
+            .iter()
+            .find(|n| n.name == "Perimeter" && n.metadata["receiver"]["pointer"] == true)
+            .unwrap();
+
+        assert_eq!(area.metadata["receiver"]["type"], "Square");
+        assert!(has_edge(&edges, square, area, EdgeKind::Contains));
+        assert!(has_edge(&edges, square, perimeter, EdgeKind::Contains));
+        assert!(has_edge(&edges, square, base, EdgeKind::Embeds));
+        assert!(has_edge(&edges, named, shape, EdgeKind::Embeds));
+        assert!(has_edge(&edges, square, shape, EdgeKind::Implements));
+        // Named also needs Name(), which Square lacks
+        assert!(!has_edge(&edges, square, named, EdgeKind::Implements));
+    }
+
+    #[test]
+    fn test_concurrency_nodes() {
+        let (nodes, edges) = extract(
+            r#"package main
+
+func run(jobs []int) {
+	results := make(chan int, len(jobs))
+	done := make(chan struct{})
+	for _, j := range jobs {
+		go func() { results <- j }()
+	}
+	go worker(done)
+	select {
+	case r := <-results:
+		_ = r
+	case <-done:
+	}
+	close(results)
+}
+
+func worker(done chan struct{}) {}
+"#,
+        );
+
+        let run = find(&nodes, NodeKind::Function, "run");
+        let goroutines: Vec<_> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Goroutine)
+            .collect();
+        assert_eq!(goroutines.len(), 2);
+        assert_eq!(goroutines[0].metadata["in_loop"], true);
+        assert_eq!(goroutines[1].name, "worker");
+        assert!(has_edge(&edges, run, goroutines[1], EdgeKind::Spawns));
+
+        let operations: Vec<_> = nodes
+            .iter()
+            .filter(|n| n.kind == NodeKind::Channel)
+            .map(|n| n.metadata["operation"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            operations,
+            vec!["make", "make", "send", "select", "receive", "receive", "close"]
+        );
+
+        let buffered = find(&nodes, NodeKind::Channel, "chan int");
+        assert_eq!(buffered.metadata["buffered"], true);
+        let receive = nodes
+            .iter()
+            .find(|n| n.metadata["operation"] == "receive")
+            .unwrap();
+        assert_eq!(receive.metadata["in_select"], true);
+        assert!(has_edge(&edges, run, receive, EdgeKind::Receives));
+    }
+
+    #[test]
+    fn test_net_http_routes() {
+        let (nodes, edges) = extract(
+            r#"package main
+
+import (
+	"fmt"
+	"net/http"
+)
+
+func users(w http.ResponseWriter, r *http.Request) { fmt.Fprintln(w, "ok") }
+
+type API struct{}
+
+func (a *API) item(w http.ResponseWriter, r *http.Request) {}
+
+func main() {
+	api := &API{}
+	http.HandleFunc("/users", users)
+	mux := http.NewServeMux()
+	mux.HandleFunc("GET /items/{id}", api.item)
+	mux.Handle("/static/", http.FileServer(http.Dir(".")))
+	fmt.Println("not a route", "x")
+}
+"#,
+        );
+
+        let routes: Vec<_> = nodes.iter().filter(|n| n.kind == NodeKind::Route).collect();
+        assert_eq!(routes.len(), 3);
+
+        assert_eq!(routes[0].name, "/users");
+        assert_eq!(routes[0].metadata["mux"], "DefaultServeMux");
+        assert!(routes[0].metadata["method"].is_null());
+        assert_eq!(routes[1].metadata["method"], "GET");
+        assert_eq!(routes[1].metadata["path"], "/items/{id}");
+        assert_eq!(routes[1].metadata["mux"], "mux");
+
+        let users = find(&nodes, NodeKind::Function, "users");
+        let item = find(&nodes, NodeKind::Method, "item");
+        assert!(has_edge(&edges, routes[0], users, EdgeKind::RoutesTo));
+        assert!(has_edge(&edges, routes[1], item, EdgeKind::RoutesTo));
+
+        let println = find(&nodes, NodeKind::Call, "fmt.Println");
+        assert_eq!(println.metadata["package"], "fmt");
+    }
+
+    #[test]
+    fn test_routes_require_net_http_import() {
+        let (nodes, _) = extract(
+            r#"package main
+
+func main() {
+	router.HandleFunc("/users", users)
+}
+"#,
+        );
+        assert!(!nodes.iter().any(|n| n.kind == NodeKind::Route));
+    }
+}
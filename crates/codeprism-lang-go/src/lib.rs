@@ -0,0 +1,22 @@
+//! Go language support for codeprism
+
+mod adapter;
+mod analysis;
+mod ast_mapper;
+mod error;
+mod parser;
+mod types;
+
+pub use adapter::{parse_file, GoLanguageParser, ParseResultConverter};
+pub use analysis::{
+    ConcurrencyIssue, ConcurrencyIssueType, ConcurrencyPattern, ConcurrencyPatternType,
+    GoAnalysisResult, GoAnalyzer, HttpRoute, InterfaceImplementation, Severity,
+};
+pub use error::{Error, Result};
+pub use parser::{GoParser, ParseContext, ParseResult};
+pub use types::{Edge, EdgeKind, Language, Node, NodeId, NodeKind, Span};
+
+// Re-export the parser for registration
+pub fn create_parser() -> GoLanguageParser {
+    GoLanguageParser::new()
+}
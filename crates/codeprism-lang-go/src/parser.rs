@@ -0,0 +1,155 @@
+//! Go parser implementation
+
+use crate::ast_mapper::AstMapper;
+use crate::error::{Error, Result};
+use crate::types::{Edge, Language, Node};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Parser, Tree};
+
+/// Parse context for Go files
+#[derive(Debug, Clone)]
+pub struct ParseContext {
+    /// Repository ID
+    pub repo_id: String,
+    /// File path being parsed
+    pub file_path: PathBuf,
+    /// Previous tree for incremental parsing
+    pub old_tree: Option<Tree>,
+    /// File content
+    pub content: String,
+}
+
+/// Parse result containing nodes and edges
+#[derive(Debug)]
+pub struct ParseResult {
+    /// The parsed tree
+    pub tree: Tree,
+    /// Extracted nodes
+    pub nodes: Vec<Node>,
+    /// Extracted edges
+    pub edges: Vec<Edge>,
+}
+
+/// Go parser
+pub struct GoParser {
+    /// Tree-sitter parser for Go
+    parser: Parser,
+}
+
+impl GoParser {
+    /// Create a new Go parser
+    pub fn new() -> Self {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_go::LANGUAGE.into())
+            .expect("Failed to load Go grammar");
+
+        Self { parser }
+    }
+
+    /// Get the language for a file based on its extension
+    pub fn detect_language(_path: &Path) -> Language {
+        // All Go files are Go language
+        Language::Go
+    }
+
+    /// Parse a Go file
+    pub fn parse(&mut self, context: &ParseContext) -> Result<ParseResult> {
+        let language = Self::detect_language(&context.file_path);
+
+        // Parse the file
+        let tree = self
+            .parser
+            .parse(&context.content, context.old_tree.as_ref())
+            .ok_or_else(|| Error::parse(&context.file_path, "Failed to parse file"))?;
+
+        // Extract nodes and edges
+        let mapper = AstMapper::new(
+            &context.repo_id,
+            context.file_path.clone(),
+            language,
+            &context.content,
+        );
+
+        let (nodes, edges) = mapper.extract(&tree)?;
+
+        Ok(ParseResult { tree, nodes, edges })
+    }
+}
+
+impl Default for GoParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::NodeKind;
+
+    fn parse(content: &str) -> ParseResult {
+        let mut parser = GoParser::new();
+        let context = ParseContext {
+            repo_id: "test_repo".to_string(),
+            file_path: PathBuf::from("main.go"),
+            old_tree: None,
+            content: content.to_string(),
+        };
+        parser.parse(&context).unwrap()
+    }
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            GoParser::detect_language(Path::new("main.go")),
+            Language::Go
+        );
+    }
+
+    #[test]
+    fn test_parse_simple_go() {
+        let result = parse("package main\n\nfunc hello() string {\n\treturn \"world\"\n}\n");
+
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| matches!(n.kind, NodeKind::Module)));
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| matches!(n.kind, NodeKind::Package) && n.name == "main"));
+        assert!(result
+            .nodes
+            .iter()
+            .any(|n| matches!(n.kind, NodeKind::Function) && n.name == "hello"));
+    }
+
+    #[test]
+    fn test_incremental_parsing() {
+        let mut parser = GoParser::new();
+        let context1 = ParseContext {
+            repo_id: "test_repo".to_string(),
+            file_path: PathBuf::from("main.go"),
+            old_tree: None,
+            content: "package main\n\nfunc foo() int {\n\treturn 1\n}\n".to_string(),
+        };
+        let result1 = parser.parse(&context1).unwrap();
+
+        let context2 = ParseContext {
+            repo_id: "test_repo".to_string(),
+            file_path: PathBuf::from("main.go"),
+            old_tree: Some(result1.tree),
+            content: "package main\n\nfunc foo() int {\n\treturn 2\n}\n".to_string(),
+        };
+        let result2 = parser.parse(&context2).unwrap();
+
+        assert_eq!(result1.nodes.len(), result2.nodes.len());
+        let func = result2
+            .nodes
+            .iter()
+            .find(|n| matches!(n.kind, NodeKind::Function))
+            .unwrap();
+        assert_eq!(func.name, "foo");
+    }
+}
@@ -0,0 +1,183 @@
+//! Integration tests for Go parser
+
+use codeprism_lang_go::*;
+use std::path::PathBuf;
+
+fn create_test_context(content: &str) -> ParseContext {
+    ParseContext {
+        repo_id: "test_repo".to_string(),
+        file_path: PathBuf::from("main.go"),
+        old_tree: None,
+        content: content.to_string(),
+    }
+}
+
+const SERVICE: &str = r#"package orders
+
+import (
+	"context"
+	"encoding/json"
+	"net/http"
+	"sync"
+)
+
+type Order struct {
+	ID    string `json:"id"`
+	Total int    `json:"total"`
+}
+
+type Repository interface {
+	Save(ctx context.Context, order Order) error
+	Find(ctx context.Context, id string) (Order, error)
+}
+
+type memoryRepository struct {
+	sync.Mutex
+	orders map[string]Order
+}
+
+func (r *memoryRepository) Save(ctx context.Context, order Order) error {
+	r.Lock()
+	defer r.Unlock()
+	r.orders[order.ID] = order
+	return nil
+}
+
+func (r *memoryRepository) Find(ctx context.Context, id string) (Order, error) {
+	return r.orders[id], nil
+}
+
+type Handler struct {
+	repo Repository
+}
+
+func (h *Handler) create(w http.ResponseWriter, req *http.Request) {
+	var order Order
+	if err := json.NewDecoder(req.Body).Decode(&order); err != nil {
+		http.Error(w, err.Error(), http.StatusBadRequest)
+		return
+	}
+	h.repo.Save(req.Context(), order)
+}
+
+func Routes(h *Handler) *http.ServeMux {
+	mux := http.NewServeMux()
+	mux.HandleFunc("POST /orders", h.create)
+	return mux
+}
+
+func Notify(orders []Order) {
+	var wg sync.WaitGroup
+	for _, o := range orders {
+		wg.Add(1)
+		go send(o, &wg)
+	}
+	wg.Wait()
+}
+
+func send(o Order, wg *sync.WaitGroup) { defer wg.Done() }
+"#;
+
+#[test]
+fn test_service_declarations() {
+    let mut parser = GoParser::new();
+    let result = parser.parse(&create_test_context(SERVICE)).unwrap();
+
+    let names = |kind: NodeKind| -> Vec<String> {
+        result
+            .nodes
+            .iter()
+            .filter(|n| n.kind == kind)
+            .map(|n| n.name.clone())
+            .collect()
+    };
+
+    assert_eq!(names(NodeKind::Package), vec!["orders"]);
+    assert_eq!(
+        names(NodeKind::Import),
+        vec!["context", "encoding/json", "net/http", "sync"]
+    );
+    assert_eq!(
+        names(NodeKind::Struct),
+        vec!["Order", "memoryRepository", "Handler"]
+    );
+    assert_eq!(names(NodeKind::Interface), vec!["Repository"]);
+    assert_eq!(names(NodeKind::Function), vec!["Routes", "Notify", "send"]);
+    assert!(names(NodeKind::Field).contains(&"Mutex".to_string()));
+
+    let order_id = result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Field && n.name == "ID")
+        .unwrap();
+    assert_eq!(order_id.metadata["tag"], "`json:\"id\"`");
+    assert_eq!(order_id.metadata["exported"], true);
+}
+
+#[test]
+fn test_service_relationships() {
+    let mut parser = GoParser::new();
+    let result = parser.parse(&create_test_context(SERVICE)).unwrap();
+    let node = |kind: NodeKind, name: &str| {
+        result
+            .nodes
+            .iter()
+            .find(|n| n.kind == kind && n.name == name)
+            .unwrap()
+    };
+
+    let repository = node(NodeKind::Interface, "Repository");
+    let memory = node(NodeKind::Struct, "memoryRepository");
+    assert!(result
+        .edges
+        .contains(&Edge::new(memory.id, repository.id, EdgeKind::Implements)));
+
+    let route = node(NodeKind::Route, "POST /orders");
+    let create = result
+        .nodes
+        .iter()
+        .find(|n| n.kind == NodeKind::Method && n.name == "create")
+        .unwrap();
+    assert_eq!(create.metadata["receiver"]["type"], "Handler");
+    assert!(result
+        .edges
+        .contains(&Edge::new(route.id, create.id, EdgeKind::RoutesTo)));
+
+    let analysis = GoAnalyzer::new(result.nodes.clone(), result.edges.clone()).analyze_all();
+    assert_eq!(analysis.http_routes.len(), 1);
+    // Goroutines in Notify are synchronized with a WaitGroup
+    assert!(analysis.concurrency_issues.is_empty());
+    assert!(analysis
+        .concurrency_patterns
+        .iter()
+        .any(|p| p.pattern_type == ConcurrencyPatternType::Goroutine && p.function == "Notify"));
+}
+
+#[test]
+fn test_adapter_parse_file() {
+    let parser = create_parser();
+    let (tree, nodes, _) = parse_file(
+        &parser,
+        "test_repo",
+        PathBuf::from("main.go"),
+        "package main\n\nfunc main() {}\n".to_string(),
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(tree.root_node().kind(), "source_file");
+    assert!(nodes.iter().all(|n| n.lang == Language::Go));
+}
+
+#[test]
+fn test_syntax_errors_still_produce_nodes() {
+    let mut parser = GoParser::new();
+    let result = parser
+        .parse(&create_test_context(
+            "package main\n\nfunc broken( {\n\nfunc ok() {}\n",
+        ))
+        .unwrap();
+
+    assert!(result.tree.root_node().has_error());
+    assert!(result.nodes.iter().any(|n| n.kind == NodeKind::Module));
+}
@@ -5,6 +5,7 @@
 //! Ported from legacy codeprism-mcp and adapted for rust-sdk architecture.
 
 use crate::Result;
+use codeprism_storage::StorageBackend;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -59,6 +60,95 @@ pub struct ServerSettings {
     pub enable_streaming: bool,
     /// Maximum response size in bytes
     pub max_response_size: usize,
+    /// Transport used to serve MCP clients
+    #[serde(default)]
+    pub transport: TransportConfig,
+}
+
+/// Transport used to serve MCP clients
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// Single client over stdin/stdout
+    #[default]
+    Stdio,
+    /// Streamable HTTP, shared by many clients
+    Http,
+    /// Server-sent events with a POST message endpoint, shared by many clients
+    Sse,
+}
+
+impl std::str::FromStr for TransportKind {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "stdio" => Ok(Self::Stdio),
+            "http" => Ok(Self::Http),
+            "sse" => Ok(Self::Sse),
+            other => Err(crate::Error::server_init(format!(
+                "Unknown transport: {other}. Expected stdio, http or sse"
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for TransportKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Stdio => write!(f, "stdio"),
+            Self::Http => write!(f, "http"),
+            Self::Sse => write!(f, "sse"),
+        }
+    }
+}
+
+/// Transport configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransportConfig {
+    /// Transport type
+    pub kind: TransportKind,
+    /// Address to bind for network transports
+    pub bind_address: String,
+    /// Port to listen on for network transports
+    pub port: u16,
+    /// Endpoint path for the streamable HTTP transport
+    pub http_path: String,
+    /// Endpoint path for the SSE event stream
+    pub sse_path: String,
+    /// Endpoint path for SSE client messages
+    pub sse_message_path: String,
+    /// Keep-alive interval for SSE streams
+    pub keep_alive: Duration,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            kind: TransportKind::Stdio,
+            bind_address: "127.0.0.1".to_string(),
+            port: 8080,
+            http_path: "/mcp".to_string(),
+            sse_path: "/sse".to_string(),
+            sse_message_path: "/message".to_string(),
+            keep_alive: Duration::from_secs(15),
+        }
+    }
+}
+
+impl TransportConfig {
+    /// Socket address to bind for network transports
+    pub fn socket_addr(&self) -> Result<std::net::SocketAddr> {
+        format!("{}:{}", self.bind_address, self.port)
+            .parse()
+            .map_err(|e| {
+                crate::Error::server_init(format!(
+                    "Invalid bind address {}:{}: {}",
+                    self.bind_address, self.port, e
+                ))
+            })
+    }
 }
 
 /// Dependency scanning modes
@@ -207,6 +297,9 @@ pub struct SecurityConfig {
     pub validate_paths: bool,
     /// Rate limiting configuration
     pub rate_limiting: RateLimitConfig,
+    /// Bearer tokens accepted by network transports (empty disables authentication)
+    #[serde(default)]
+    pub api_keys: Vec<String>,
 }
 
 /// Rate limiting configuration
@@ -239,6 +332,30 @@ pub struct CachingConfig {
     pub enable_compression: bool,
     /// Cache cleanup interval
     pub cleanup_interval: Duration,
+    /// Backend for persisted graph snapshots used to warm-start indexing
+    /// (`None` disables snapshots)
+    #[serde(default)]
+    pub graph_storage: Option<StorageBackend>,
+}
+
+impl CachingConfig {
+    /// Directory holding persisted graph snapshots
+    pub fn graph_storage_dir(&self) -> PathBuf {
+        self.cache_dir.join("graphs")
+    }
+}
+
+/// Parse a graph snapshot backend name, where `none` disables snapshots
+pub fn parse_graph_storage(s: &str) -> Result<Option<StorageBackend>> {
+    match s.to_lowercase().as_str() {
+        "none" | "off" => Ok(None),
+        "sqlite" => Ok(Some(StorageBackend::Sqlite)),
+        "file" => Ok(Some(StorageBackend::File)),
+        "neo4j" => Ok(Some(StorageBackend::Neo4j)),
+        other => Err(crate::Error::server_init(format!(
+            "Unknown graph storage: {other}. Expected sqlite, file, neo4j or none"
+        ))),
+    }
 }
 
 /// Main configuration structure for the CodePrism MCP Server
@@ -302,6 +419,7 @@ impl ConfigProfileManager {
                     max_concurrent_operations: 4,
                     enable_streaming: true,
                     max_response_size: 50_000,
+                    transport: TransportConfig::default(),
                 },
                 tools: ToolsConfig {
                     enabled_categories: vec![
@@ -340,6 +458,7 @@ impl ConfigProfileManager {
                         max_concurrent: 10,
                         burst_size: 20,
                     },
+                    api_keys: vec![],
                 },
                 caching: CachingConfig {
                     enabled: true,
@@ -349,6 +468,7 @@ impl ConfigProfileManager {
                     content_ttl: Duration::from_secs(1800),
                     enable_compression: false,
                     cleanup_interval: Duration::from_secs(3600),
+                    graph_storage: None,
                 },
             },
         );
@@ -381,6 +501,7 @@ impl ConfigProfileManager {
                     max_concurrent_operations: 12,
                     enable_streaming: true,
                     max_response_size: 150_000,
+                    transport: TransportConfig::default(),
                 },
                 tools: ToolsConfig {
                     enabled_categories: vec![
@@ -431,6 +552,7 @@ impl ConfigProfileManager {
                         max_concurrent: 15,
                         burst_size: 50,
                     },
+                    api_keys: vec![],
                 },
                 caching: CachingConfig {
                     enabled: true,
@@ -440,6 +562,7 @@ impl ConfigProfileManager {
                     content_ttl: Duration::from_secs(3600),
                     enable_compression: true,
                     cleanup_interval: Duration::from_secs(1800),
+                    graph_storage: Some(StorageBackend::Sqlite),
                 },
             },
         );
@@ -473,6 +596,7 @@ impl ConfigProfileManager {
                     max_concurrent_operations: 24,
                     enable_streaming: true,
                     max_response_size: 500_000,
+                    transport: TransportConfig::default(),
                 },
                 tools: ToolsConfig {
                     enabled_categories: vec![
@@ -518,6 +642,7 @@ impl ConfigProfileManager {
                         max_concurrent: 30,
                         burst_size: 100,
                     },
+                    api_keys: vec![],
                 },
                 caching: CachingConfig {
                     enabled: true,
@@ -527,6 +652,7 @@ impl ConfigProfileManager {
                     content_ttl: Duration::from_secs(7200),
                     enable_compression: true,
                     cleanup_interval: Duration::from_secs(900),
+                    graph_storage: Some(StorageBackend::Sqlite),
                 },
             },
         );
@@ -621,6 +747,22 @@ impl ConfigProfileManager {
             warnings.push("Rate limit is very high, may not prevent abuse effectively".to_string());
         }
 
+        // Validate network exposure
+        let transport = &profile.settings.transport;
+        if transport.kind != TransportKind::Stdio {
+            let loopback = transport
+                .bind_address
+                .parse::<std::net::IpAddr>()
+                .map(|ip| ip.is_loopback())
+                .unwrap_or(transport.bind_address == "localhost");
+            if !loopback && profile.security.api_keys.is_empty() {
+                warnings.push(format!(
+                    "{} transport is bound to {} without API keys, any client on the network can connect",
+                    transport.kind, transport.bind_address
+                ));
+            }
+        }
+
         Ok(warnings)
     }
 
@@ -672,6 +814,32 @@ impl ConfigProfileManager {
             profile.caching.cache_dir = PathBuf::from(cache_dir);
         }
 
+        if let Ok(graph_storage) = std::env::var("CODEPRISM_GRAPH_STORAGE") {
+            profile.caching.graph_storage = parse_graph_storage(&graph_storage)?;
+        }
+
+        if let Ok(transport) = std::env::var("CODEPRISM_TRANSPORT") {
+            profile.settings.transport.kind = transport.parse()?;
+        }
+
+        if let Ok(bind_address) = std::env::var("CODEPRISM_BIND_ADDRESS") {
+            profile.settings.transport.bind_address = bind_address;
+        }
+
+        if let Ok(port) = std::env::var("CODEPRISM_PORT") {
+            if let Ok(port) = port.parse::<u16>() {
+                profile.settings.transport.port = port;
+            }
+        }
+
+        if let Ok(api_keys) = std::env::var("CODEPRISM_API_KEYS") {
+            profile.security.api_keys = api_keys
+                .split(',')
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty())
+                .collect();
+        }
+
         profile.name = format!("{}_env", profile_name);
         profile.description = format!("Environment-configured {} profile", profile_name);
 
@@ -740,6 +908,10 @@ impl Config {
             ));
         }
 
+        if self.profile.settings.transport.kind != TransportKind::Stdio {
+            self.profile.settings.transport.socket_addr()?;
+        }
+
         // Return validation warnings
         self.manager.validate_profile(&self.profile)
     }
@@ -759,13 +931,18 @@ impl Config {
         // Check if tool category is enabled
         let tool_category = match tool_name {
             "trace_path" | "find_dependencies" | "find_references" | "explain_symbol"
-            | "search_symbols" => Some(ToolCategory::CoreNavigation),
+            | "search_symbols" | "analyze_impact" | "graph_query" => {
+                Some(ToolCategory::CoreNavigation)
+            }
             "search_content" | "find_patterns" | "semantic_search" | "search_by_type"
             | "advanced_search" => Some(ToolCategory::SearchDiscovery),
             "analyze_complexity"
             | "analyze_control_flow"
             | "analyze_code_quality"
-            | "analyze_performance" => Some(ToolCategory::Analysis),
+            | "analyze_performance"
+            | "find_unused_code"
+            | "analyze_call_graph"
+            | "check_architecture" => Some(ToolCategory::Analysis),
             "provide_guidance" | "optimize_code" | "batch_process" | "workflow_automation" => {
                 Some(ToolCategory::Workflow)
             }
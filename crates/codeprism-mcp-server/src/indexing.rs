@@ -0,0 +1,566 @@
+//! Repository indexing and progress tracking
+//!
+//! Indexing populates the shared graph store and content search index from a
+//! repository on disk. It can run inline through
+//! [`CodePrismMcpServer::initialize_repository`](crate::CodePrismMcpServer::initialize_repository)
+//! or in the background at startup, in which case [`IndexingStatus`] lets
+//! tools report progress instead of answering from a half-built index.
+//! When graph snapshots are enabled, indexing warm-starts from the last
+//! snapshot and only re-parses files that changed.
+
+use crate::snapshot::{
+    content_hash, current_commit, restore_unchanged, to_snapshot, MAX_SNAPSHOTS_PER_REPO,
+};
+use codeprism_core::{
+    BulkIndexer, ContentSearchManager, GraphStore, IndexingConfig, LanguageRegistry, ParserEngine,
+    ProgressReporter, RepositoryScanner, ScanResult,
+};
+use codeprism_storage::{GraphStorage, SerializableGraph};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Lifecycle phase of repository indexing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexingPhase {
+    /// No repository has been indexed
+    Idle,
+    /// Indexing is running
+    Indexing,
+    /// The index is complete and tools answer from it
+    Ready,
+    /// The last indexing run failed
+    Failed,
+}
+
+/// Point-in-time view of indexing progress, as reported to MCP clients
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexingProgress {
+    pub phase: IndexingPhase,
+    pub files_processed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_total: Option<usize>,
+    pub elapsed_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+struct IndexingState {
+    phase: IndexingPhase,
+    started_at: Option<Instant>,
+    duration: Option<Duration>,
+    error: Option<String>,
+}
+
+/// Shared indexing status, updated by the indexer and read by tool handlers
+#[derive(Debug)]
+pub struct IndexingStatus {
+    state: Mutex<IndexingState>,
+    files_processed: AtomicUsize,
+    files_total: AtomicUsize,
+}
+
+impl Default for IndexingStatus {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(IndexingState {
+                phase: IndexingPhase::Idle,
+                started_at: None,
+                duration: None,
+                error: None,
+            }),
+            files_processed: AtomicUsize::new(0),
+            files_total: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl IndexingStatus {
+    /// Create a status in the idle phase
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current indexing phase
+    pub fn phase(&self) -> IndexingPhase {
+        self.lock().phase
+    }
+
+    /// Whether indexing is currently running
+    pub fn is_indexing(&self) -> bool {
+        self.phase() == IndexingPhase::Indexing
+    }
+
+    /// Snapshot of the current progress
+    pub fn progress(&self) -> IndexingProgress {
+        let state = self.lock();
+        let elapsed = match (state.duration, state.started_at) {
+            (Some(duration), _) => duration,
+            (None, Some(started_at)) => started_at.elapsed(),
+            (None, None) => Duration::ZERO,
+        };
+        let files_total = self.files_total.load(Ordering::Relaxed);
+
+        IndexingProgress {
+            phase: state.phase,
+            files_processed: self.files_processed.load(Ordering::Relaxed),
+            files_total: (files_total > 0).then_some(files_total),
+            elapsed_ms: elapsed.as_millis() as u64,
+            error: state.error.clone(),
+        }
+    }
+
+    /// Mark the start of an indexing run
+    pub fn begin(&self) {
+        self.files_processed.store(0, Ordering::Relaxed);
+        self.files_total.store(0, Ordering::Relaxed);
+        let mut state = self.lock();
+        state.phase = IndexingPhase::Indexing;
+        state.started_at = Some(Instant::now());
+        state.duration = None;
+        state.error = None;
+    }
+
+    /// Mark the current indexing run as complete
+    pub fn finish(&self) {
+        let mut state = self.lock();
+        state.phase = IndexingPhase::Ready;
+        state.duration = state.started_at.map(|started_at| started_at.elapsed());
+    }
+
+    /// Mark the current indexing run as failed
+    pub fn fail(&self, error: impl Into<String>) {
+        let mut state = self.lock();
+        state.phase = IndexingPhase::Failed;
+        state.duration = state.started_at.map(|started_at| started_at.elapsed());
+        state.error = Some(error.into());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, IndexingState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ProgressReporter for IndexingStatus {
+    fn report_progress(&self, current: usize, total: Option<usize>) {
+        if let Some(total) = total {
+            self.files_total.store(total, Ordering::Relaxed);
+        }
+        self.files_processed.store(current, Ordering::Relaxed);
+
+        if current % 100 == 0 || total == Some(current) {
+            info!(
+                "Repository indexing progress: {}/{}",
+                current,
+                total
+                    .map(|t| t.to_string())
+                    .unwrap_or_else(|| "?".to_string())
+            );
+        }
+    }
+
+    fn report_complete(&self, result: &ScanResult) {
+        info!(
+            "Repository scan completed: {} files discovered in {}ms",
+            result.total_files, result.duration_ms
+        );
+    }
+
+    fn report_error(&self, error: &codeprism_core::Error) {
+        warn!("Repository scanning error: {}", error);
+    }
+}
+
+/// Outcome of indexing a repository
+#[derive(Debug, Clone)]
+pub struct IndexingSummary {
+    pub repo_id: String,
+    pub commit: String,
+    pub files_processed: usize,
+    /// Files restored unchanged from a persisted snapshot
+    pub files_reused: usize,
+    pub nodes_added: usize,
+    pub edges_added: usize,
+    pub content_files_indexed: usize,
+    pub failed_files: Vec<(PathBuf, String)>,
+    pub duration: Duration,
+}
+
+/// Check that `repo_path` is an existing directory
+pub fn validate_repository_path(repo_path: &Path) -> crate::Result<()> {
+    if !repo_path.exists() {
+        return Err(crate::Error::server_init(format!(
+            "Repository path does not exist: {}",
+            repo_path.display()
+        )));
+    }
+
+    if !repo_path.is_dir() {
+        return Err(crate::Error::server_init(format!(
+            "Repository path is not a directory: {}",
+            repo_path.display()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Repository identifier derived from the directory name
+pub fn repository_id(repo_path: &Path) -> String {
+    repo_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("default")
+        .to_string()
+}
+
+/// Parsers for the languages the server indexes
+pub fn language_registry() -> Arc<LanguageRegistry> {
+    let registry = LanguageRegistry::new();
+    codeprism_core::parser::go::register(&registry);
+    codeprism_core::parser::java::register(&registry);
+    codeprism_core::parser::javascript::register(&registry);
+    codeprism_core::parser::python::register(&registry);
+    codeprism_core::parser::rust::register(&registry);
+    Arc::new(registry)
+}
+
+/// Index `repo_path` into the given graph store and content search index
+///
+/// With `snapshots`, the most recent graph snapshot of the repository is
+/// loaded first and only files whose content hash changed are parsed; the
+/// resulting graph is saved back as the snapshot of the current commit.
+/// Progress is reported through `status`; the caller is responsible for
+/// marking the run as started, finished or failed.
+pub async fn index_repository(
+    repo_path: &Path,
+    graph_store: &GraphStore,
+    content_search: &ContentSearchManager,
+    status: Arc<IndexingStatus>,
+    snapshots: Option<&dyn GraphStorage>,
+) -> crate::Result<IndexingSummary> {
+    let repo_id = repository_id(repo_path);
+    let commit = current_commit(repo_path);
+    let start_time = Instant::now();
+
+    info!("Scanning repository {} at {}...", repo_id, commit);
+    let progress: Arc<dyn ProgressReporter> = status;
+    let scan_result = RepositoryScanner::new()
+        .scan_repository(repo_path, Arc::clone(&progress))
+        .await
+        .map_err(|e| crate::Error::server_init(format!("Failed to scan repository: {}", e)))?;
+
+    let mut file_hashes = HashMap::new();
+    for file in scan_result.all_files() {
+        if let Ok(content) = tokio::fs::read(&file.path).await {
+            file_hashes.insert(file.path.clone(), content_hash(&content));
+        }
+    }
+
+    // Restore unchanged files from the last snapshot
+    let snapshot = match snapshots {
+        Some(storage) => match storage.load_snapshot(&repo_id, &commit).await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!(
+                    "Failed to load graph snapshot, indexing from scratch: {}",
+                    e
+                );
+                None
+            }
+        },
+        None => None,
+    };
+    let (reused_files, snapshot_edges) = match &snapshot {
+        Some(snapshot) => restore_unchanged(graph_store, snapshot, &file_hashes),
+        None => Default::default(),
+    };
+    if snapshot.is_some() {
+        info!(
+            "Restored {} unchanged files from graph snapshot",
+            reused_files.len()
+        );
+    }
+
+    let mut changed = ScanResult::new();
+    for (language, files) in &scan_result.files_by_language {
+        for file in files {
+            if !reused_files.contains(&file.path) {
+                changed
+                    .files_by_language
+                    .entry(*language)
+                    .or_default()
+                    .push(file.clone());
+                changed.total_files += 1;
+            }
+        }
+    }
+
+    info!("Indexing {} changed files...", changed.total_files);
+    let parser_engine = Arc::new(ParserEngine::new(language_registry()));
+    let indexer = BulkIndexer::new(
+        IndexingConfig::new(repo_id.clone(), commit.clone()),
+        parser_engine,
+    );
+    let indexing_result = indexer
+        .index_scan_result(&changed, progress)
+        .await
+        .map_err(|e| crate::Error::server_init(format!("Failed to index repository: {}", e)))?;
+
+    // Apply patches to populate the graph store
+    info!(
+        "Applying {} patches to graph store...",
+        indexing_result.patches.len()
+    );
+
+    let mut nodes_added = 0;
+    let mut edges_added = 0;
+    for patch in &indexing_result.patches {
+        for node in &patch.nodes_add {
+            graph_store.add_node(node.clone());
+            nodes_added += 1;
+        }
+
+        for edge in &patch.edges_add {
+            graph_store.add_edge(edge.clone());
+            edges_added += 1;
+        }
+    }
+
+    // Snapshot edges may point into files that were re-indexed
+    for edge in snapshot_edges {
+        if graph_store.get_node(&edge.target).is_some() {
+            graph_store.add_edge(edge);
+        }
+    }
+
+    let graph_stats = graph_store.get_stats();
+    info!(
+        "Graph store populated: {} nodes, {} edges",
+        graph_stats.total_nodes, graph_stats.total_edges
+    );
+
+    // Index content for all files in the graph
+    info!("Updating content search index...");
+    let mut content_files_indexed = 0;
+    for file_path in graph_store.get_all_files() {
+        if let Ok(content) = tokio::fs::read_to_string(&file_path).await {
+            if let Err(e) = content_search.index_file(&file_path, &content) {
+                warn!("Failed to index content for {}: {}", file_path.display(), e);
+            } else {
+                content_files_indexed += 1;
+            }
+        }
+    }
+
+    info!(
+        "Content search index updated: {} files indexed",
+        content_files_indexed
+    );
+
+    if let Some(storage) = snapshots {
+        // Files that failed to parse are left out so they are retried next time
+        for (path, _) in &indexing_result.failed_files {
+            file_hashes.remove(path);
+        }
+        let snapshot = to_snapshot(graph_store, &repo_id, &commit, file_hashes);
+        if let Err(e) = save_snapshot(storage, &repo_id, &snapshot).await {
+            warn!("Failed to save graph snapshot: {}", e);
+        }
+    }
+
+    Ok(IndexingSummary {
+        repo_id,
+        commit,
+        files_processed: indexing_result.stats.files_processed,
+        files_reused: reused_files.len(),
+        nodes_added,
+        edges_added,
+        content_files_indexed,
+        failed_files: indexing_result
+            .failed_files
+            .into_iter()
+            .map(|(path, error)| (path, error.to_string()))
+            .collect(),
+        duration: start_time.elapsed(),
+    })
+}
+
+async fn save_snapshot(
+    storage: &dyn GraphStorage,
+    repo_id: &str,
+    snapshot: &SerializableGraph,
+) -> anyhow::Result<()> {
+    storage.store_graph(snapshot).await?;
+    storage
+        .prune_snapshots(repo_id, MAX_SNAPSHOTS_PER_REPO)
+        .await?;
+    info!(
+        "Saved graph snapshot {} ({} nodes, {} edges)",
+        snapshot.repo_id, snapshot.metadata.total_nodes, snapshot.metadata.total_edges
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_lifecycle() {
+        let status = IndexingStatus::new();
+        assert_eq!(status.phase(), IndexingPhase::Idle);
+        assert!(!status.is_indexing());
+
+        status.begin();
+        assert!(status.is_indexing());
+        status.report_progress(3, Some(10));
+        let progress = status.progress();
+        assert_eq!(progress.phase, IndexingPhase::Indexing);
+        assert_eq!(progress.files_processed, 3);
+        assert_eq!(progress.files_total, Some(10));
+
+        status.finish();
+        assert_eq!(status.phase(), IndexingPhase::Ready);
+        assert!(status.progress().error.is_none());
+
+        status.begin();
+        assert_eq!(status.progress().files_processed, 0);
+        status.fail("disk on fire");
+        let progress = status.progress();
+        assert_eq!(progress.phase, IndexingPhase::Failed);
+        assert_eq!(progress.error.as_deref(), Some("disk on fire"));
+    }
+
+    #[test]
+    fn test_progress_serialization() {
+        let status = IndexingStatus::new();
+        status.begin();
+        let json = serde_json::to_value(status.progress()).unwrap();
+        assert_eq!(json["phase"], "indexing");
+        assert!(json.get("files_total").is_none());
+        assert!(json.get("error").is_none());
+    }
+
+    #[test]
+    fn test_validate_repository_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(validate_repository_path(temp_dir.path()).is_ok());
+
+        let file = temp_dir.path().join("file.txt");
+        std::fs::write(&file, "x").unwrap();
+        assert!(validate_repository_path(&file).is_err());
+        assert!(validate_repository_path(&temp_dir.path().join("missing")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_index_repository_marks_progress() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("main.py"), "def main():\n    pass\n").unwrap();
+
+        let graph_store = GraphStore::new();
+        let content_search = ContentSearchManager::new();
+        let status = Arc::new(IndexingStatus::new());
+
+        status.begin();
+        let summary = index_repository(
+            temp_dir.path(),
+            &graph_store,
+            &content_search,
+            Arc::clone(&status),
+            None,
+        )
+        .await
+        .unwrap();
+        status.finish();
+
+        assert_eq!(summary.repo_id, repository_id(temp_dir.path()));
+        assert_eq!(status.phase(), IndexingPhase::Ready);
+    }
+
+    #[tokio::test]
+    async fn test_index_repository_parses_go() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("main.go");
+        std::fs::write(&file, "package main\n\nfunc main() {}\n").unwrap();
+
+        let graph_store = GraphStore::new();
+        let content_search = ContentSearchManager::new();
+        let status = Arc::new(IndexingStatus::new());
+        index_repository(temp_dir.path(), &graph_store, &content_search, status, None)
+            .await
+            .unwrap();
+
+        let nodes = graph_store.get_nodes_in_file(&file);
+        assert!(nodes
+            .iter()
+            .any(|n| n.name == "main" && n.kind == codeprism_core::NodeKind::Function));
+    }
+
+    #[tokio::test]
+    async fn test_index_repository_warm_starts_from_snapshot() {
+        use codeprism_core::{Language, Node, NodeKind, Span};
+        use codeprism_storage::InMemoryGraphStorage;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let unchanged = temp_dir.path().join("unchanged.py");
+        let edited = temp_dir.path().join("edited.py");
+        std::fs::write(&unchanged, "def kept():\n    pass\n").unwrap();
+        std::fs::write(&edited, "def before():\n    pass\n").unwrap();
+
+        // Snapshot from an earlier run, taken before `edited.py` changed
+        let repo_id = repository_id(temp_dir.path());
+        let previous = GraphStore::new();
+        for (file, name) in [(&unchanged, "kept"), (&edited, "before")] {
+            previous.add_node(Node::new(
+                &repo_id,
+                NodeKind::Function,
+                name.to_string(),
+                Language::Python,
+                file.clone(),
+                Span::new(0, 10, 1, 2, 1, 1),
+            ));
+        }
+        let hashes = HashMap::from([
+            (unchanged.clone(), content_hash(b"def kept():\n    pass\n")),
+            (edited.clone(), content_hash(b"def before():\n    pass\n")),
+        ]);
+        let storage = InMemoryGraphStorage::new();
+        storage
+            .store_graph(&to_snapshot(&previous, &repo_id, "old-commit", hashes))
+            .await
+            .unwrap();
+        std::fs::write(&edited, "def after():\n    pass\n").unwrap();
+
+        let graph_store = GraphStore::new();
+        let summary = index_repository(
+            temp_dir.path(),
+            &graph_store,
+            &ContentSearchManager::new(),
+            Arc::new(IndexingStatus::new()),
+            Some(&storage),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(summary.files_reused, 1);
+        assert_eq!(graph_store.get_nodes_in_file(&unchanged)[0].name, "kept");
+        assert!(graph_store.get_nodes_by_name("before").is_empty());
+
+        // The new run is saved as the snapshot of the current commit
+        let saved = storage
+            .load_graph(&SerializableGraph::snapshot_key(&repo_id, &summary.commit))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(saved.metadata.file_hashes.contains_key(&unchanged));
+    }
+}
@@ -4,6 +4,38 @@
 mod tests {
     use crate::{CodePrismMcpServer, Config};
 
+    /// Write `files` into a fresh repository and wait until it is indexed
+    ///
+    /// The directory is returned so that it lives as long as the server.
+    async fn indexed_server(
+        prefix: &str,
+        files: &[(&str, &str)],
+    ) -> (CodePrismMcpServer, tempfile::TempDir) {
+        // Hidden directories are skipped by the scanner, so avoid `.tmp` names
+        let temp_dir = tempfile::Builder::new().prefix(prefix).tempdir().unwrap();
+        for (path, source) in files {
+            let path = temp_dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+
+        let mut server = CodePrismMcpServer::new(Config::default()).await.unwrap();
+        server
+            .start_background_indexing(temp_dir.path())
+            .unwrap()
+            .await
+            .unwrap();
+        (server, temp_dir)
+    }
+
+    /// JSON body of a tool result
+    fn result_json(result: &rmcp::model::CallToolResult) -> serde_json::Value {
+        match &result.content[0].raw {
+            rmcp::model::RawContent::Text(text) => serde_json::from_str(&text.text).unwrap(),
+            other => panic!("expected text content, got {:?}", other),
+        }
+    }
+
     #[tokio::test]
     async fn test_server_with_tools_creation() {
         // Test that we can create a server with tool router
@@ -233,4 +265,431 @@ fn inefficient_search(data: &[Vec<String>], target: &str) -> Option<(usize, usiz
             "Test file should contain security and concurrency patterns"
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_background_indexing_sets_repository_and_completes() {
+        use crate::indexing::IndexingPhase;
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp_dir.path().join("lib.py"),
+            "def helper():\n    return 1\n",
+        )
+        .unwrap();
+
+        let mut server = CodePrismMcpServer::new(Config::default()).await.unwrap();
+        assert_eq!(server.indexing_progress().phase, IndexingPhase::Idle);
+
+        let handle = server.start_background_indexing(temp_dir.path()).unwrap();
+        assert_eq!(server.repository_path(), Some(temp_dir.path()));
+
+        handle.await.unwrap();
+        let progress = server.indexing_progress();
+        assert_eq!(progress.phase, IndexingPhase::Ready);
+        assert!(server
+            .indexing_in_progress_result("search_symbols")
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_background_indexing_rejects_missing_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut server = CodePrismMcpServer::new(Config::default()).await.unwrap();
+
+        let result = server.start_background_indexing(temp_dir.path().join("missing"));
+        assert!(result.is_err());
+        assert!(server.repository_path().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_tools_report_indexing_in_progress() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut server = CodePrismMcpServer::new(Config::default()).await.unwrap();
+
+        // On the current-thread runtime the indexing task cannot make progress
+        // until this test yields, so the index is observed mid-build
+        let handle = server.start_background_indexing(temp_dir.path()).unwrap();
+
+        let result = server
+            .indexing_in_progress_result("search_symbols")
+            .expect("index-dependent tools should report indexing progress");
+        let text = match &result.content[0].raw {
+            rmcp::model::RawContent::Text(text) => text.text.clone(),
+            other => panic!("expected text content, got {:?}", other),
+        };
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["status"], "indexing_in_progress");
+        assert_eq!(json["tool"], "search_symbols");
+        assert_eq!(json["indexing"]["phase"], "indexing");
+
+        // Tools that do not read the index keep answering normally
+        assert!(server.indexing_in_progress_result("ping").is_none());
+        assert!(server.indexing_in_progress_result("health_check").is_none());
+
+        handle.await.unwrap();
+        assert!(server.indexing_in_progress_result("search_symbols").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_background_indexing_parses_non_go_languages() {
+        use rmcp::handler::server::tool::Parameters;
+
+        let (server, _repo) = indexed_server(
+            "langs",
+            &[
+                ("billing.py", "def charge_card():\n    return 1\n"),
+                ("cart.js", "function addToCart(item) {\n  return item;\n}\n"),
+                ("ledger.rs", "pub fn post_entry() -> u32 {\n    1\n}\n"),
+                (
+                    "Invoice.java",
+                    "public class Invoice {\n    public void issueInvoice() {}\n}\n",
+                ),
+            ],
+        )
+        .await;
+
+        for (pattern, language) in [
+            ("charge_card", "Python"),
+            ("addToCart", "JavaScript"),
+            ("post_entry", "Rust"),
+            ("issueInvoice", "Java"),
+        ] {
+            let json = result_json(
+                &server
+                    .search_symbols(Parameters(crate::server::SearchSymbolsParams {
+                        pattern: pattern.to_string(),
+                        symbol_types: None,
+                        inheritance_filters: None,
+                        limit: None,
+                        context_lines: None,
+                    }))
+                    .unwrap(),
+            );
+            let symbols = json["symbols"].as_array().unwrap();
+            assert!(
+                symbols
+                    .iter()
+                    .any(|s| s["name"] == pattern && s["language"] == language),
+                "{} symbol {} was not indexed: {}",
+                language,
+                pattern,
+                json
+            );
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_analyze_impact_tool() {
+        use crate::server::AnalyzeImpactParams;
+        use rmcp::handler::server::tool::Parameters;
+        use rmcp::model::ErrorCode;
+
+        // Impact propagation is covered in codeprism-core; this checks how
+        // changes are parsed and the tool's errors
+        let (server, repo) = indexed_server(
+            "impact",
+            &[(
+                "service.go",
+                r#"package app
+
+func Save(name string) string {
+	return name
+}
+
+func CreateUser() {
+	Save("a")
+}
+"#,
+            )],
+        )
+        .await;
+        let analyze = |changes: &[&str], limit| {
+            server.analyze_impact(Parameters(AnalyzeImpactParams {
+                changes: changes.iter().map(|change| change.to_string()).collect(),
+                max_depth: None,
+                limit,
+            }))
+        };
+
+        let region = server.parse_changed_region("service.go:3-4").unwrap();
+        assert_eq!(region.file, repo.path().join("service.go"));
+        assert_eq!(region.lines, Some((3, 4)));
+        assert!(server
+            .parse_changed_region("service.go")
+            .unwrap()
+            .lines
+            .is_none());
+        for change in ["service.go:0", "service.go:5-0", "service.go:3-1"] {
+            let error = server.parse_changed_region(change).unwrap_err();
+            assert_eq!(error.code, ErrorCode::INVALID_PARAMS, "{change}");
+        }
+
+        let json = result_json(&analyze(&["service.go:3", "missing.go"], Some(0)).unwrap());
+        assert_eq!(json["status"], "success");
+        assert_eq!(json["changed_symbols"][0]["name"], "Save");
+        assert_eq!(json["total_affected"], 1);
+        assert!(json["affected_symbols"].as_array().unwrap().is_empty());
+        assert_eq!(json["unmatched_changes"].as_array().unwrap().len(), 1);
+        assert_eq!(json["query"]["limit"], 0);
+
+        let error = analyze(&["service.go:3", "service.go:5-0"], None).unwrap_err();
+        assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+        assert!(error.message.contains("service.go:5-0"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_find_unused_code_tool() {
+        use crate::server::FindUnusedCodeParams;
+        use rmcp::handler::server::tool::Parameters;
+
+        let (server, _temp_dir) = indexed_server(
+            "unused",
+            &[(
+                "unused_code.py",
+                include_str!("../../../test-projects/unused-code-test.py"),
+            )],
+        )
+        .await;
+        let params = |entry_point_patterns: Option<Vec<&str>>, limit| {
+            Parameters(FindUnusedCodeParams {
+                include_main: None,
+                include_routes: None,
+                include_tests: None,
+                include_module_code: None,
+                entry_point_patterns: entry_point_patterns
+                    .map(|patterns| patterns.into_iter().map(String::from).collect()),
+                limit,
+            })
+        };
+        let names = |json: &serde_json::Value| -> Vec<String> {
+            json["unused_symbols"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|symbol| symbol["name"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        let json = result_json(&server.find_unused_code(params(None, Some(500))).unwrap());
+        assert_eq!(json["status"], "success");
+        let unused = names(&json);
+        assert!(unused.iter().any(|name| name == "unused_calculate_hash"));
+        assert!(!unused.iter().any(|name| name == "main"));
+
+        // Extra entry points and the limit reach the analyzer
+        let json = result_json(
+            &server
+                .find_unused_code(params(Some(vec!["^unused_calc"]), Some(1)))
+                .unwrap(),
+        );
+        assert_eq!(json["query"]["limit"], 1);
+        assert_eq!(json["unused_symbols"].as_array().unwrap().len(), 1);
+        assert!(json["total_unused"].as_u64().unwrap() > 1);
+        assert!(!names(&json)
+            .iter()
+            .any(|name| name == "unused_calculate_hash"));
+
+        let json = result_json(
+            &server
+                .find_unused_code(params(Some(vec!["("]), None))
+                .unwrap(),
+        );
+        assert_eq!(json["status"], "error");
+        assert!(json["message"]
+            .as_str()
+            .unwrap()
+            .contains("Invalid entry point pattern: ("));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_analyze_call_graph_tool() {
+        use crate::server::AnalyzeCallGraphParams;
+        use rmcp::handler::server::tool::Parameters;
+
+        let (server, _temp_dir) = indexed_server(
+            "callgraph",
+            &[(
+                "main.go",
+                r#"package main
+
+func Countdown(n int) int {
+	if n == 0 {
+		return 0
+	}
+	return Countdown(n - 1)
+}
+
+func main() {
+	Countdown(3)
+}
+"#,
+            )],
+        )
+        .await;
+
+        let json = result_json(
+            &server
+                .analyze_call_graph(Parameters(AnalyzeCallGraphParams {
+                    fan_out_threshold: Some(3),
+                    betweenness_threshold: Some(0.5),
+                    limit: Some(0),
+                }))
+                .unwrap(),
+        );
+        assert_eq!(json["status"], "success");
+        assert_eq!(json["query"]["fan_out_threshold"], 3);
+        assert_eq!(json["query"]["betweenness_threshold"], 0.5);
+        assert_eq!(json["query"]["limit"], 0);
+        assert_eq!(json["total_call_cycles"], 1);
+        assert!(json["call_cycles"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_check_architecture_tool() {
+        use crate::server::CheckArchitectureParams;
+        use rmcp::handler::server::tool::Parameters;
+
+        // Layering rules are covered in codeprism-analysis; this checks how
+        // the config is located and the tool's errors
+        let (server, _repo) = indexed_server(
+            "layers",
+            &[
+                (
+                    "domain/user.go",
+                    r#"package domain
+
+import "example.com/app/infra"
+"#,
+                ),
+                ("infra/db.go", "package infra\n"),
+                (
+                    "config/layers.toml",
+                    r#"
+[[layers]]
+name = "domain"
+files = ["domain/**"]
+must_not_depend_on = ["infra"]
+
+[[layers]]
+name = "infra"
+files = ["infra/**"]
+modules = ["example.com/app/infra"]
+"#,
+                ),
+            ],
+        )
+        .await;
+        let check = |config_path: &str, limit| {
+            server
+                .check_architecture(Parameters(CheckArchitectureParams {
+                    config_path: Some(config_path.to_string()),
+                    limit,
+                }))
+                .unwrap()
+        };
+
+        let json = result_json(&check("config/layers.toml", Some(0)));
+        assert_eq!(json["status"], "success");
+        assert!(json["config"].as_str().unwrap().ends_with("layers.toml"));
+        assert_eq!(json["total_violations"], 1);
+        assert!(json["violations"].as_array().unwrap().is_empty());
+        assert_eq!(json["query"]["config_path"], "config/layers.toml");
+        assert_eq!(json["query"]["limit"], 0);
+
+        // Without a config path only the repository root is searched
+        let json = result_json(
+            &server
+                .check_architecture(Parameters(CheckArchitectureParams {
+                    config_path: None,
+                    limit: None,
+                }))
+                .unwrap(),
+        );
+        assert_eq!(json["status"], "error");
+
+        let json = result_json(&check("missing.toml", None));
+        assert_eq!(json["status"], "error");
+        assert_eq!(json["query"]["config_path"], "missing.toml");
+
+        // Config files outside the repository are never read
+        let outside = tempfile::Builder::new()
+            .prefix("outside")
+            .tempdir()
+            .unwrap();
+        let secret = outside.path().join("secret.toml");
+        std::fs::write(&secret, "api_key = \"hunter2\"\n").unwrap();
+        let relative = format!(
+            "../{}/secret.toml",
+            outside.path().file_name().unwrap().to_str().unwrap()
+        );
+        for config_path in [secret.display().to_string(), relative] {
+            let json = result_json(&check(&config_path, None));
+            assert_eq!(json["status"], "error");
+            assert!(!json.to_string().contains("hunter2"));
+            assert!(json["message"]
+                .as_str()
+                .unwrap()
+                .contains("outside the repository"));
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_graph_query_tool() {
+        use rmcp::handler::server::tool::Parameters;
+
+        // Query semantics are covered in codeprism-core; this checks the
+        // tool's arguments, row encoding and errors
+        let (server, _repo) = indexed_server(
+            "query",
+            &[(
+                "service.go",
+                r#"package app
+
+import "net/http"
+
+func CreateUser(w http.ResponseWriter, r *http.Request) {}
+
+func Health(w http.ResponseWriter, r *http.Request) {}
+
+func Routes() {
+	http.HandleFunc("/users", CreateUser)
+	http.HandleFunc("/health", Health)
+}
+"#,
+            )],
+        )
+        .await;
+        let run = |query: &str, limit: Option<u32>| {
+            result_json(
+                &server
+                    .graph_query(Parameters(crate::server::GraphQueryParams {
+                        query: query.to_string(),
+                        limit,
+                    }))
+                    .unwrap(),
+            )
+        };
+
+        let json = run(
+            "MATCH (r:Route {name: '/users'})-[:RoutesTo]->(f) RETURN r, f.name AS handler",
+            None,
+        );
+        assert_eq!(json["status"], "success");
+        assert_eq!(json["columns"], serde_json::json!(["r", "handler"]));
+        assert_eq!(json["rows"][0]["r"]["name"], "/users");
+        assert_eq!(json["rows"][0]["r"]["kind"], "Route");
+        assert_eq!(json["rows"][0]["handler"], "CreateUser");
+
+        let json = run("MATCH (r:Route) RETURN r.name", Some(1));
+        assert_eq!(json["rows"].as_array().unwrap().len(), 1);
+        assert_eq!(json["total_rows"], 2);
+        assert_eq!(json["truncated"], true);
+
+        let json = run("MATCH (r:Handler) RETURN r", None);
+        assert_eq!(json["status"], "error");
+        assert!(json["message"]
+            .as_str()
+            .unwrap()
+            .contains("unknown node kind 'Handler'"));
+    }
 }
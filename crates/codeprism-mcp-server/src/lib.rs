@@ -9,18 +9,33 @@
 //! The server is organized into several modules:
 //! - `server`: Core MCP server implementation
 //! - `tools`: MCP tool implementations (core, search, analysis, workflow)
+//! - `indexing`: Repository indexing with progress tracking
+//! - `live_index`: Incremental re-indexing of files as they change on disk
+//! - `snapshot`: Persisted graph snapshots for warm starts
+//! - `resources`: MCP resources for indexed files, symbols and graph statistics
+//! - `subscriptions`: Per-client resource subscriptions and update notifications
+//! - `prompts`: Built-in MCP prompts for common code-intelligence workflows
+//! - `transport`: Streamable HTTP and SSE transports for sharing one server
 //! - `config`: Configuration management
 //! - `error`: Error types and handling
 //!
 //! # Usage
 //!
 //! The server can be run as a standalone binary or embedded in other applications.
-//! It supports stdio transport for communication with MCP clients.
+//! It supports stdio transport for a single MCP client, and streamable HTTP or SSE
+//! transports for sharing one indexed repository between several clients.
 
 pub mod config;
 pub mod error;
+pub mod indexing;
+pub mod live_index;
+pub mod prompts;
+pub mod resources;
 pub mod server;
+pub mod snapshot;
+pub mod subscriptions;
 pub mod tools;
+pub mod transport;
 
 #[cfg(test)]
 mod integration_test;
@@ -76,6 +91,8 @@ mod tests {
         assert_eq!(info.server_info.name, "codeprism-mcp-server");
         assert!(info.instructions.is_some());
         assert!(info.capabilities.tools.is_some());
+        assert!(info.capabilities.resources.is_some());
+        assert!(info.capabilities.prompts.is_some());
     }
 
     // Additional tests moved to integration_test.rs module
@@ -0,0 +1,401 @@
+//! Live incremental re-indexing
+//!
+//! After the initial index is built, a [`MonitoringPipeline`] watches the
+//! repository and re-parses changed files. [`LiveIndexHandler`] replaces the
+//! graph nodes of each changed file with the freshly parsed ones, refreshes
+//! the content search index and notifies subscribed clients through
+//! `notifications/resources/updated`.
+
+use crate::resources::{ResourceUri, GRAPH_STATS_URI};
+use crate::subscriptions::ResourceSubscriptions;
+use codeprism_core::{
+    AstPatch, ChangeEvent, ChangeKind, ContentSearchManager, Edge, GraphStore, MonitoringPipeline,
+    Node, NodeId, NodeKind, ParserEngine, PatchBuilder, PipelineConfig, PipelineEvent,
+    PipelineEventHandler,
+};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Directories whose changes never reach the index
+const EXCLUDED_DIRS: &[&str] = &["node_modules", "target", "build", "dist", "__pycache__"];
+
+/// Pipeline event handler that keeps the graph and content index current
+pub struct LiveIndexHandler {
+    graph_store: Arc<GraphStore>,
+    content_search: Arc<ContentSearchManager>,
+    subscriptions: Arc<ResourceSubscriptions>,
+    repository_path: PathBuf,
+}
+
+impl LiveIndexHandler {
+    /// Create a handler for the repository at `repository_path`
+    pub fn new(
+        graph_store: Arc<GraphStore>,
+        content_search: Arc<ContentSearchManager>,
+        subscriptions: Arc<ResourceSubscriptions>,
+        repository_path: PathBuf,
+    ) -> Self {
+        Self {
+            graph_store,
+            content_search,
+            subscriptions,
+            repository_path,
+        }
+    }
+
+    /// Whether a change to `path` should be reflected in the index
+    pub fn is_tracked(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.repository_path).unwrap_or(path);
+        let excluded = relative.components().any(|component| {
+            let name = component.as_os_str().to_string_lossy();
+            (name.starts_with('.') && name != "." && name != "..")
+                || EXCLUDED_DIRS.contains(&name.as_ref())
+        });
+        !excluded && !path.is_dir()
+    }
+
+    /// Replace the indexed state of `path` with the nodes and edges in `patch`
+    ///
+    /// A missing file, or a `None` patch, removes the file from the index.
+    /// Returns the resource URIs affected by the change.
+    pub fn apply_file_change(&self, path: &Path, patch: Option<&AstPatch>) -> HashSet<String> {
+        let existing = self.graph_store.get_nodes_in_file(&path.to_path_buf());
+        let file_exists = path.is_file();
+        let was_indexed = !existing.is_empty();
+
+        let mut builder = PatchBuilder::new(
+            patch.map(|p| p.repo.clone()).unwrap_or_default(),
+            patch.map(|p| p.commit.clone()).unwrap_or_default(),
+        );
+        for node in &existing {
+            builder = builder.delete_node(node.id.to_hex());
+        }
+        if let (true, Some(patch)) = (file_exists, patch) {
+            let relinked = self.relink_cross_file_edges(path, &existing, &patch.nodes_add);
+            builder = builder
+                .add_nodes(patch.nodes_add.clone())
+                .add_edges(patch.edges_add.clone())
+                .add_edges(relinked);
+        }
+        let replacement = builder.build();
+        self.graph_store.apply_patch(&replacement);
+
+        self.refresh_content(path, file_exists);
+
+        let mut uris = HashSet::from([
+            ResourceUri::file_uri(path, Some(&self.repository_path)),
+            GRAPH_STATS_URI.to_string(),
+        ]);
+        uris.extend(
+            existing
+                .iter()
+                .map(|node| ResourceUri::symbol_uri(&node.id)),
+        );
+        uris.extend(
+            replacement
+                .nodes_add
+                .iter()
+                .map(|node| ResourceUri::symbol_uri(&node.id)),
+        );
+
+        let is_indexed = !replacement.nodes_add.is_empty();
+        if was_indexed != is_indexed {
+            self.notify_list_changed();
+        }
+
+        debug!(
+            "Live index updated {}: -{} +{} nodes",
+            path.display(),
+            existing.len(),
+            replacement.nodes_add.len()
+        );
+        uris
+    }
+
+    /// Edges between the old nodes of `path` and other files, moved to the
+    /// new nodes with the same kind and name
+    ///
+    /// Cross-file edges come from symbol resolution over the whole graph,
+    /// which re-parsing a single file does not repeat. Edges of symbols that
+    /// no longer exist are dropped.
+    fn relink_cross_file_edges(
+        &self,
+        path: &Path,
+        existing: &[Node],
+        nodes_add: &[Node],
+    ) -> Vec<Edge> {
+        let mut replacements: HashMap<(NodeKind, &str), NodeId> = HashMap::new();
+        for node in nodes_add {
+            replacements
+                .entry((node.kind, node.name.as_str()))
+                .or_insert(node.id);
+        }
+        let in_other_file = |id: &NodeId| {
+            self.graph_store
+                .get_node(id)
+                .is_some_and(|node| node.file != path)
+        };
+
+        let mut edges = Vec::new();
+        for node in existing {
+            let Some(&new_id) = replacements.get(&(node.kind, node.name.as_str())) else {
+                continue;
+            };
+            for edge in self.graph_store.get_incoming_edges(&node.id) {
+                if in_other_file(&edge.source) {
+                    edges.push(Edge::new(edge.source, new_id, edge.kind));
+                }
+            }
+            for edge in self.graph_store.get_outgoing_edges(&node.id) {
+                if in_other_file(&edge.target) {
+                    edges.push(Edge::new(new_id, edge.target, edge.kind));
+                }
+            }
+        }
+        edges
+    }
+
+    fn refresh_content(&self, path: &Path, file_exists: bool) {
+        let result = match std::fs::read_to_string(path) {
+            Ok(content) if file_exists => self.content_search.index_file(path, &content),
+            _ => self.content_search.remove_file(path),
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to refresh content index for {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+
+    fn notify_updated(&self, uris: HashSet<String>) {
+        let subscriptions = Arc::clone(&self.subscriptions);
+        spawn_notification(async move {
+            subscriptions.notify_updated(&uris).await;
+        });
+    }
+
+    fn notify_list_changed(&self) {
+        let subscriptions = Arc::clone(&self.subscriptions);
+        spawn_notification(async move {
+            subscriptions.notify_list_changed().await;
+        });
+    }
+}
+
+impl PipelineEventHandler for LiveIndexHandler {
+    fn handle_event(&self, event: &PipelineEvent) -> codeprism_core::Result<()> {
+        let change = &event.change_event;
+        let mut uris = HashSet::new();
+
+        if let ChangeKind::Renamed { old, .. } = &change.kind {
+            if self.is_tracked(old) {
+                uris.extend(self.apply_file_change(old, None));
+            }
+        }
+        if self.is_tracked(&change.path) {
+            uris.extend(self.apply_file_change(&change.path, event.patch.as_ref()));
+        }
+
+        if !uris.is_empty() {
+            self.notify_updated(uris);
+        }
+        Ok(())
+    }
+
+    fn handle_error(&self, error: &codeprism_core::Error, change_event: &ChangeEvent) {
+        if !self.is_tracked(&change_event.path) {
+            return;
+        }
+
+        // The file could not be parsed, but its text is still searchable
+        debug!(
+            "Live re-parse of {} failed: {}",
+            change_event.path.display(),
+            error
+        );
+        self.refresh_content(&change_event.path, change_event.path.is_file());
+        self.notify_updated(HashSet::from([ResourceUri::file_uri(
+            &change_event.path,
+            Some(&self.repository_path),
+        )]));
+    }
+}
+
+/// Watch `repository_path` and apply changes until the pipeline stops
+pub async fn run_live_updates(
+    repository_path: PathBuf,
+    repo_id: String,
+    handler: Arc<LiveIndexHandler>,
+) -> crate::Result<()> {
+    let mut config = PipelineConfig::new(repo_id, "live".to_string());
+    // Apply every change as soon as the watcher has debounced it
+    config.enable_batching = false;
+
+    let parser_engine = Arc::new(ParserEngine::new(crate::indexing::language_registry()));
+    let mut pipeline = MonitoringPipeline::new(config, parser_engine, handler)
+        .map_err(|e| crate::Error::server_init(format!("Failed to start file watcher: {}", e)))?;
+
+    pipeline
+        .start_monitoring(&repository_path)
+        .await
+        .map_err(|e| crate::Error::server_init(format!("Live re-indexing stopped: {}", e)))
+}
+
+fn spawn_notification<F>(future: F)
+where
+    F: std::future::Future<Output = ()> + Send + 'static,
+{
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(future);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeprism_core::{EdgeKind, Language, Span};
+
+    fn handler(root: &Path) -> LiveIndexHandler {
+        let graph_store = Arc::new(GraphStore::new());
+        let content_search = Arc::new(ContentSearchManager::with_graph_store(Arc::clone(
+            &graph_store,
+        )));
+        LiveIndexHandler::new(
+            graph_store,
+            content_search,
+            Arc::new(ResourceSubscriptions::new()),
+            root.to_path_buf(),
+        )
+    }
+
+    fn function_node(file: &Path, name: &str, line: usize) -> Node {
+        let span = Span::new(line * 10, line * 10 + 5, line, line, 1, 6);
+        Node::new(
+            "repo",
+            NodeKind::Function,
+            name.to_string(),
+            Language::Python,
+            file.to_path_buf(),
+            span,
+        )
+    }
+
+    fn patch_with(nodes: Vec<Node>) -> AstPatch {
+        PatchBuilder::new("repo".to_string(), "live".to_string())
+            .add_nodes(nodes)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_file_change_replaces_nodes_and_content() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("app.py");
+        std::fs::write(&file, "def old_name():\n    pass\n").unwrap();
+        let handler = handler(temp_dir.path());
+
+        let old = function_node(&file, "old_name", 1);
+        handler.apply_file_change(&file, Some(&patch_with(vec![old.clone()])));
+        assert_eq!(handler.graph_store.get_nodes_in_file(&file).len(), 1);
+
+        std::fs::write(&file, "def new_name():\n    pass\n").unwrap();
+        let new = function_node(&file, "new_name", 2);
+        let uris = handler.apply_file_change(&file, Some(&patch_with(vec![new.clone()])));
+
+        let nodes = handler.graph_store.get_nodes_in_file(&file);
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(nodes[0].name, "new_name");
+        assert!(uris.contains(&ResourceUri::symbol_uri(&old.id)));
+        assert!(uris.contains(&ResourceUri::symbol_uri(&new.id)));
+        assert!(uris.contains(GRAPH_STATS_URI));
+        assert!(uris.contains(&ResourceUri::file_uri(&file, Some(temp_dir.path()))));
+
+        let stats = handler.content_search.get_stats();
+        assert_eq!(stats.total_files, 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_change_keeps_edges_from_other_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("lib.py");
+        let caller_file = temp_dir.path().join("main.py");
+        std::fs::write(&file, "def helper():\n    pass\n").unwrap();
+        let handler = handler(temp_dir.path());
+
+        let helper = function_node(&file, "helper", 1);
+        let unused = function_node(&file, "unused", 2);
+        handler.apply_file_change(
+            &file,
+            Some(&patch_with(vec![helper.clone(), unused.clone()])),
+        );
+        let main = function_node(&caller_file, "main", 1);
+        let call = Node::new(
+            "repo",
+            NodeKind::Call,
+            "helper".to_string(),
+            Language::Python,
+            caller_file.clone(),
+            Span::new(12, 20, 2, 2, 5, 13),
+        );
+        handler.graph_store.add_node(main.clone());
+        handler.graph_store.add_node(call.clone());
+        handler
+            .graph_store
+            .add_edge(Edge::new(main.id, call.id, EdgeKind::Calls));
+        // Cross-file edges from symbol resolution
+        handler
+            .graph_store
+            .add_edge(Edge::new(call.id, helper.id, EdgeKind::Calls));
+        handler
+            .graph_store
+            .add_edge(Edge::new(main.id, unused.id, EdgeKind::Calls));
+
+        // `helper` moves down a line, so its node ID changes, and `unused` is removed
+        std::fs::write(&file, "\ndef helper():\n    pass\n").unwrap();
+        let moved = function_node(&file, "helper", 2);
+        assert_ne!(moved.id, helper.id);
+        handler.apply_file_change(&file, Some(&patch_with(vec![moved.clone()])));
+
+        let incoming = handler.graph_store.get_incoming_edges(&moved.id);
+        assert_eq!(incoming.len(), 1);
+        assert_eq!(incoming[0].source, call.id);
+        assert_eq!(incoming[0].kind, EdgeKind::Calls);
+        let outgoing: Vec<_> = handler
+            .graph_store
+            .get_outgoing_edges(&main.id)
+            .into_iter()
+            .map(|edge| edge.target)
+            .collect();
+        assert_eq!(outgoing, vec![call.id]);
+    }
+
+    #[tokio::test]
+    async fn test_deleted_file_is_removed_from_index() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("gone.py");
+        std::fs::write(&file, "def f():\n    pass\n").unwrap();
+        let handler = handler(temp_dir.path());
+        handler.apply_file_change(&file, Some(&patch_with(vec![function_node(&file, "f", 1)])));
+
+        std::fs::remove_file(&file).unwrap();
+        handler.apply_file_change(&file, Some(&patch_with(Vec::new())));
+
+        assert!(handler.graph_store.get_nodes_in_file(&file).is_empty());
+        assert!(!handler.graph_store.get_all_files().contains(&file));
+        assert_eq!(handler.content_search.get_stats().total_files, 0);
+    }
+
+    #[test]
+    fn test_is_tracked_skips_excluded_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let handler = handler(temp_dir.path());
+
+        assert!(handler.is_tracked(&temp_dir.path().join("src/main.py")));
+        assert!(!handler.is_tracked(&temp_dir.path().join(".git/HEAD")));
+        assert!(!handler.is_tracked(&temp_dir.path().join("target/debug/out.rs")));
+        assert!(!handler.is_tracked(temp_dir.path()));
+    }
+}
@@ -5,8 +5,9 @@
 
 use anyhow::Result;
 use clap::Parser;
-use codeprism_mcp_server::{CodePrismMcpServer, Config};
-use tracing::{info, level_filters::LevelFilter};
+use codeprism_mcp_server::{config::TransportKind, CodePrismMcpServer, Config};
+use std::path::PathBuf;
+use tracing::{info, level_filters::LevelFilter, warn};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 /// CodePrism MCP Server - Expose code analysis capabilities via MCP protocol
@@ -32,6 +33,22 @@ struct Cli {
     /// Validate configuration and exit
     #[arg(long)]
     validate_config: bool,
+
+    /// Transport to serve clients over (stdio, http, sse)
+    #[arg(long, value_name = "TRANSPORT")]
+    transport: Option<TransportKind>,
+
+    /// Bind address for the http and sse transports
+    #[arg(long, value_name = "ADDRESS")]
+    bind: Option<String>,
+
+    /// Port for the http and sse transports
+    #[arg(long, value_name = "PORT")]
+    port: Option<u16>,
+
+    /// Repository to index in the background at startup
+    #[arg(long, value_name = "PATH", env = "CODEPRISM_REPO")]
+    repo: Option<PathBuf>,
 }
 
 #[tokio::main]
@@ -48,7 +65,11 @@ async fn main() -> Result<()> {
     );
 
     // Load configuration
-    let config = load_config(cli.config.as_deref(), cli.profile.as_deref()).await?;
+    let mut config = load_config(cli.config.as_deref(), cli.profile.as_deref()).await?;
+    apply_transport_overrides(&mut config, &cli);
+    for warning in config.validate()? {
+        warn!("Configuration warning: {}", warning);
+    }
 
     // Validate config and exit if requested
     if cli.validate_config {
@@ -57,7 +78,10 @@ async fn main() -> Result<()> {
     }
 
     // Create and run the MCP server
-    let server = CodePrismMcpServer::new(config).await?;
+    let mut server = CodePrismMcpServer::new(config).await?;
+    if let Some(repo) = &cli.repo {
+        server.start_background_indexing(repo)?;
+    }
     server.run().await?;
 
     Ok(())
@@ -86,6 +110,20 @@ fn init_logging(log_level: &str) -> Result<()> {
     Ok(())
 }
 
+/// Apply transport options given on the command line
+fn apply_transport_overrides(config: &mut Config, cli: &Cli) {
+    let transport = &mut config.profile.settings.transport;
+    if let Some(kind) = cli.transport {
+        transport.kind = kind;
+    }
+    if let Some(bind) = &cli.bind {
+        transport.bind_address = bind.clone();
+    }
+    if let Some(port) = cli.port {
+        transport.port = port;
+    }
+}
+
 /// Load configuration from file, environment, or use defaults
 async fn load_config(config_path: Option<&str>, profile: Option<&str>) -> Result<Config> {
     match config_path {
@@ -104,6 +142,7 @@ async fn load_config(config_path: Option<&str>, profile: Option<&str>) -> Result
             if std::env::var("CODEPRISM_PROFILE").is_ok()
                 || std::env::var("CODEPRISM_MEMORY_LIMIT_MB").is_ok()
                 || std::env::var("CODEPRISM_BATCH_SIZE").is_ok()
+                || std::env::var("CODEPRISM_TRANSPORT").is_ok()
             {
                 info!("Loading configuration from environment variables");
                 Config::from_env().await.map_err(Into::into)
@@ -0,0 +1,805 @@
+//! Built-in MCP prompts for common code-intelligence workflows
+//!
+//! Each prompt is parameterised by its arguments and filled in from live index
+//! data (graph, content search and analyzers) at the time `prompts/get` is called,
+//! so clients receive ready-to-send messages grounded in the current repository.
+
+use crate::resources::{read_span_source, resolve_file, resolve_in_repository, ResourceUri};
+use codeprism_analysis::CodeAnalyzer;
+use codeprism_core::graph::DependencyType;
+use codeprism_core::{ContentSearchManager, GraphQuery, GraphStore, Node, NodeId, NodeKind};
+use rmcp::{model::*, Error as McpError};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+/// Maximum number of related items (references, findings, ...) listed in a prompt
+const MAX_LISTED_ITEMS: usize = 20;
+
+/// Maximum number of source bytes embedded in a prompt
+const MAX_SOURCE_BYTES: usize = 16 * 1024;
+
+/// Severity levels in ascending order, as used by the security analyzer
+const SEVERITY_LEVELS: [&str; 4] = ["low", "medium", "high", "critical"];
+
+/// Index data used to fill in prompt templates
+pub struct PromptContext<'a> {
+    /// Code graph
+    pub graph_store: &'a GraphStore,
+    /// Graph query engine
+    pub graph_query: &'a GraphQuery,
+    /// Content search index
+    pub content_search: &'a ContentSearchManager,
+    /// Code analyzers
+    pub code_analyzer: &'a CodeAnalyzer,
+    /// Repository root, if one is configured
+    pub repository_path: Option<&'a Path>,
+}
+
+/// Prompts advertised through `prompts/list`
+pub fn list_prompts() -> Vec<Prompt> {
+    vec![
+        Prompt::new(
+            "review_file",
+            Some(
+                "Review a file using its indexed symbols, complexity metrics and security findings",
+            ),
+            Some(vec![
+                argument("path", "File path, relative to the repository root", true),
+                argument(
+                    "focus",
+                    "Optional review focus, e.g. readability, performance or security",
+                    false,
+                ),
+            ]),
+        ),
+        Prompt::new(
+            "explain_symbol",
+            Some("Explain a symbol using its source, dependencies, usages and related content"),
+            Some(vec![argument(
+                "symbol",
+                "Symbol name or node ID (hexadecimal)",
+                true,
+            )]),
+        ),
+        Prompt::new(
+            "plan_refactor",
+            Some("Plan a refactor of a symbol, taking its callers and dependencies into account"),
+            Some(vec![
+                argument("symbol", "Symbol name or node ID (hexadecimal)", true),
+                argument("goal", "Optional goal of the refactor", false),
+            ]),
+        ),
+        Prompt::new(
+            "triage_security",
+            Some("Triage security findings for a file, directory or the whole repository"),
+            Some(vec![
+                argument(
+                    "target",
+                    "Optional file or directory; defaults to the whole repository",
+                    false,
+                ),
+                argument(
+                    "severity_threshold",
+                    "Minimum severity to include: low, medium, high or critical (default: medium)",
+                    false,
+                ),
+            ]),
+        ),
+    ]
+}
+
+/// Render a prompt with the given arguments
+pub fn get_prompt(
+    context: &PromptContext<'_>,
+    name: &str,
+    arguments: Option<&JsonObject>,
+) -> std::result::Result<GetPromptResult, McpError> {
+    let args = PromptArguments(arguments);
+    match name {
+        "review_file" => review_file(context, &args),
+        "explain_symbol" => explain_symbol(context, &args),
+        "plan_refactor" => plan_refactor(context, &args),
+        "triage_security" => triage_security(context, &args),
+        _ => Err(McpError::invalid_params(
+            format!("Unknown prompt: {name}"),
+            None,
+        )),
+    }
+}
+
+fn argument(name: &str, description: &str, required: bool) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        required: Some(required),
+    }
+}
+
+/// Typed access to prompt arguments
+struct PromptArguments<'a>(Option<&'a JsonObject>);
+
+impl PromptArguments<'_> {
+    fn optional(&self, name: &str) -> Option<String> {
+        self.0
+            .and_then(|args| args.get(name))
+            .and_then(|value| match value {
+                serde_json::Value::String(s) => Some(s.clone()),
+                serde_json::Value::Null => None,
+                other => Some(other.to_string()),
+            })
+            .filter(|s| !s.trim().is_empty())
+    }
+
+    fn required(&self, name: &str) -> std::result::Result<String, McpError> {
+        self.optional(name).ok_or_else(|| {
+            McpError::invalid_params(format!("Missing required argument: {name}"), None)
+        })
+    }
+}
+
+fn user_prompt(description: String, text: String) -> GetPromptResult {
+    GetPromptResult {
+        description: Some(description),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    }
+}
+
+/// Resolve a path argument to a path under the repository root
+fn resolve_path(context: &PromptContext<'_>, path: &str) -> std::result::Result<PathBuf, McpError> {
+    match context.repository_path {
+        Some(root) => resolve_in_repository(root, Path::new(path)).ok_or_else(|| {
+            McpError::invalid_params(format!("Path is outside the repository: {path}"), None)
+        }),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// Resolve a symbol argument by node ID or by exact name
+fn resolve_symbol(
+    context: &PromptContext<'_>,
+    symbol: &str,
+) -> std::result::Result<Node, McpError> {
+    if let Some(node) = NodeId::from_hex(symbol)
+        .ok()
+        .and_then(|id| context.graph_store.get_node(&id))
+    {
+        return Ok(node);
+    }
+
+    let mut candidates = context.graph_store.get_nodes_by_name(symbol);
+    candidates.retain(|node| !matches!(node.kind, NodeKind::Call | NodeKind::Import));
+    // Prefer definitions over parameters and variables sharing the same name
+    candidates.sort_by_key(|node| match node.kind {
+        NodeKind::Class | NodeKind::Function | NodeKind::Method => 0,
+        NodeKind::Module => 1,
+        _ => 2,
+    });
+
+    candidates.into_iter().next().ok_or_else(|| {
+        McpError::invalid_params(format!("Symbol not found in index: {symbol}"), None)
+    })
+}
+
+fn truncate_source(source: &str) -> &str {
+    if source.len() <= MAX_SOURCE_BYTES {
+        return source;
+    }
+    let mut end = MAX_SOURCE_BYTES;
+    while !source.is_char_boundary(end) {
+        end -= 1;
+    }
+    &source[..end]
+}
+
+fn describe_node(node: &Node) -> String {
+    format!(
+        "{} `{}` ({}:{})",
+        node.kind,
+        node.name,
+        node.file.display(),
+        node.span.start_line
+    )
+}
+
+fn review_file(
+    context: &PromptContext<'_>,
+    args: &PromptArguments<'_>,
+) -> std::result::Result<GetPromptResult, McpError> {
+    let path_arg = args.required("path")?;
+    let focus = args.optional("focus");
+    let path = resolve_file(
+        context.graph_store,
+        context.repository_path,
+        Path::new(&path_arg),
+    )
+    .ok_or_else(|| {
+        McpError::invalid_params(
+            format!("Not an indexed file in the repository: {path_arg}"),
+            None,
+        )
+    })?;
+
+    let content = std::fs::read_to_string(&path).map_err(|e| {
+        McpError::invalid_params(format!("Cannot read file {}: {e}", path.display()), None)
+    })?;
+
+    let mut symbols = context.graph_store.get_nodes_in_file(&path);
+    symbols.retain(|node| {
+        matches!(
+            node.kind,
+            NodeKind::Class | NodeKind::Function | NodeKind::Method | NodeKind::Route
+        )
+    });
+    symbols.sort_by_key(|node| node.span.start_byte);
+
+    let metrics = context
+        .code_analyzer
+        .complexity
+        .calculate_all_metrics(&content, content.lines().count());
+    let findings = context
+        .code_analyzer
+        .security
+        .analyze_content_with_location(
+            &content,
+            Some(&path.display().to_string()),
+            &["all".to_string()],
+            "medium",
+        )
+        .unwrap_or_default();
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Please review the file `{}`{}.",
+        path_arg,
+        focus
+            .as_deref()
+            .map(|f| format!(" with a focus on {f}"))
+            .unwrap_or_default()
+    );
+    let _ = writeln!(
+        text,
+        "Point out bugs, design problems and risky code, and suggest concrete improvements.\n"
+    );
+
+    let _ = writeln!(text, "## Indexed symbols");
+    if symbols.is_empty() {
+        let _ = writeln!(text, "No symbols are indexed for this file.");
+    }
+    for node in symbols.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(
+            text,
+            "- {} `{}` (lines {}-{}, {} references)",
+            node.kind,
+            node.name,
+            node.span.start_line,
+            node.span.end_line,
+            context.graph_store.get_incoming_edges(&node.id).len()
+        );
+    }
+
+    let _ = writeln!(text, "\n## Complexity metrics");
+    let _ = writeln!(text, "- Lines: {}", metrics.lines_of_code);
+    let _ = writeln!(text, "- Cyclomatic complexity: {}", metrics.cyclomatic);
+    let _ = writeln!(text, "- Cognitive complexity: {}", metrics.cognitive);
+    let _ = writeln!(
+        text,
+        "- Maintainability index: {:.1}",
+        metrics.maintainability_index
+    );
+
+    let _ = writeln!(text, "\n## Security findings (medium and above)");
+    if findings.is_empty() {
+        let _ = writeln!(text, "None detected.");
+    }
+    for finding in findings.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(
+            text,
+            "- [{}] {} at line {}: {}",
+            finding.severity,
+            finding.vulnerability_type,
+            finding.line_number.unwrap_or_default(),
+            finding.description
+        );
+    }
+
+    let _ = writeln!(text, "\n## Source\n```\n{}\n```", truncate_source(&content));
+
+    Ok(user_prompt(format!("Review of {path_arg}"), text))
+}
+
+fn explain_symbol(
+    context: &PromptContext<'_>,
+    args: &PromptArguments<'_>,
+) -> std::result::Result<GetPromptResult, McpError> {
+    let node = resolve_symbol(context, &args.required("symbol")?)?;
+
+    let dependencies = context
+        .graph_query
+        .find_dependencies(&node.id, DependencyType::Direct)
+        .unwrap_or_default();
+    let references = context
+        .graph_query
+        .find_references(&node.id)
+        .unwrap_or_default();
+    let related_content = context
+        .content_search
+        .simple_search(&node.name, Some(5))
+        .unwrap_or_default();
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Explain what the {} `{}` does, how it is used and anything surprising about it.\n",
+        node.kind, node.name
+    );
+    let _ = writeln!(text, "## Symbol");
+    let _ = writeln!(text, "- Resource: {}", ResourceUri::symbol_uri(&node.id));
+    let _ = writeln!(text, "- Language: {}", node.lang);
+    let _ = writeln!(
+        text,
+        "- Location: {}:{}-{}",
+        node.file.display(),
+        node.span.start_line,
+        node.span.end_line
+    );
+    if let Some(signature) = &node.signature {
+        let _ = writeln!(text, "- Signature: `{signature}`");
+    }
+
+    if let Some(source) = read_span_source(&node) {
+        let _ = writeln!(text, "\n## Source\n```\n{}\n```", truncate_source(&source));
+    }
+
+    let _ = writeln!(text, "\n## Dependencies ({})", dependencies.len());
+    for dep in dependencies.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(
+            text,
+            "- {} {}",
+            dep.edge_kind,
+            describe_node(&dep.target_node)
+        );
+    }
+
+    let _ = writeln!(text, "\n## Usages ({})", references.len());
+    for reference in references.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(
+            text,
+            "- {} from {}",
+            reference.edge_kind,
+            describe_node(&reference.source_node)
+        );
+    }
+
+    if !related_content.is_empty() {
+        let _ = writeln!(text, "\n## Related content");
+        for result in &related_content {
+            let _ = writeln!(
+                text,
+                "- {}:{} ({:?})",
+                result.chunk.file_path.display(),
+                result.chunk.span.start_line,
+                result.chunk.content_type
+            );
+        }
+    }
+
+    Ok(user_prompt(format!("Explanation of {}", node.name), text))
+}
+
+fn plan_refactor(
+    context: &PromptContext<'_>,
+    args: &PromptArguments<'_>,
+) -> std::result::Result<GetPromptResult, McpError> {
+    let node = resolve_symbol(context, &args.required("symbol")?)?;
+    let goal = args
+        .optional("goal")
+        .unwrap_or_else(|| "improve readability and maintainability".to_string());
+
+    let references = context
+        .graph_query
+        .find_references(&node.id)
+        .unwrap_or_default();
+    let dependencies = context
+        .graph_query
+        .find_dependencies(&node.id, DependencyType::Direct)
+        .unwrap_or_default();
+    let source = read_span_source(&node);
+
+    let mut affected_files: Vec<_> = references
+        .iter()
+        .map(|reference| reference.location.file.clone())
+        .collect();
+    affected_files.sort();
+    affected_files.dedup();
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Plan a refactor of the {} `{}` in {} with the goal to {}.",
+        node.kind,
+        node.name,
+        node.file.display(),
+        goal
+    );
+    let _ = writeln!(
+        text,
+        "Produce a step-by-step plan that keeps every caller working, lists the files to change \
+         and calls out risks and tests to add.\n"
+    );
+
+    if let Some(source) = &source {
+        let metrics = context
+            .code_analyzer
+            .complexity
+            .calculate_all_metrics(source, source.lines().count());
+        let _ = writeln!(text, "## Current complexity");
+        let _ = writeln!(text, "- Lines: {}", metrics.lines_of_code);
+        let _ = writeln!(text, "- Cyclomatic complexity: {}", metrics.cyclomatic);
+        let _ = writeln!(text, "- Cognitive complexity: {}", metrics.cognitive);
+        let _ = writeln!(text, "\n## Source\n```\n{}\n```", truncate_source(source));
+    }
+
+    let _ = writeln!(
+        text,
+        "\n## Callers and references ({} in {} files)",
+        references.len(),
+        affected_files.len()
+    );
+    for reference in references.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(
+            text,
+            "- {} from {}",
+            reference.edge_kind,
+            describe_node(&reference.source_node)
+        );
+    }
+
+    let _ = writeln!(text, "\n## Dependencies ({})", dependencies.len());
+    for dep in dependencies.iter().take(MAX_LISTED_ITEMS) {
+        let _ = writeln!(
+            text,
+            "- {} {}",
+            dep.edge_kind,
+            describe_node(&dep.target_node)
+        );
+    }
+
+    Ok(user_prompt(
+        format!("Refactor plan for {}", node.name),
+        text,
+    ))
+}
+
+fn triage_security(
+    context: &PromptContext<'_>,
+    args: &PromptArguments<'_>,
+) -> std::result::Result<GetPromptResult, McpError> {
+    let threshold = args
+        .optional("severity_threshold")
+        .unwrap_or_else(|| "medium".to_string())
+        .to_lowercase();
+    if !SEVERITY_LEVELS.contains(&threshold.as_str()) {
+        return Err(McpError::invalid_params(
+            format!(
+                "Invalid severity_threshold: {threshold}. Expected low, medium, high or critical."
+            ),
+            None,
+        ));
+    }
+
+    let target = match args.optional("target") {
+        Some(target) => Some(resolve_path(context, &target)?),
+        None => context.repository_path.map(Path::to_path_buf),
+    };
+
+    // Only indexed files are read, so a target cannot reach other files
+    let mut files: Vec<PathBuf> = context
+        .graph_store
+        .get_all_files()
+        .into_iter()
+        .filter(|file| {
+            target
+                .as_ref()
+                .is_none_or(|target| file.starts_with(target))
+        })
+        .collect();
+    files.sort();
+
+    if files.is_empty() {
+        return Err(McpError::invalid_params(
+            "No indexed files to triage. Configure a repository or pass a target.",
+            None,
+        ));
+    }
+
+    let mut findings = Vec::new();
+    for file in &files {
+        let Ok(content) = std::fs::read_to_string(file) else {
+            continue;
+        };
+        if let Ok(mut file_findings) = context
+            .code_analyzer
+            .security
+            .analyze_content_with_location(
+                &content,
+                Some(&file.display().to_string()),
+                &["all".to_string()],
+                &threshold,
+            )
+        {
+            findings.append(&mut file_findings);
+        }
+    }
+
+    // Most severe first, then by confidence
+    findings.sort_by(|a, b| {
+        let rank = |s: &str| SEVERITY_LEVELS.iter().position(|l| *l == s).unwrap_or(0);
+        rank(&b.severity).cmp(&rank(&a.severity)).then(
+            b.confidence
+                .partial_cmp(&a.confidence)
+                .unwrap_or(std::cmp::Ordering::Equal),
+        )
+    });
+
+    let scope = target
+        .as_ref()
+        .map(|t| t.display().to_string())
+        .unwrap_or_else(|| "the indexed repository".to_string());
+
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "Triage the following security findings for {scope}. For each finding decide whether it \
+         is a true positive, rate its real-world impact and propose a fix. Group duplicates.\n"
+    );
+    let _ = writeln!(
+        text,
+        "Scanned {} files; {} findings at severity {} or above.\n",
+        files.len(),
+        findings.len(),
+        threshold
+    );
+
+    for finding in findings.iter().take(MAX_LISTED_ITEMS * 2) {
+        let _ = writeln!(
+            text,
+            "- [{}] {} in {}:{} (confidence {:.2}{}): {}. Suggested: {}",
+            finding.severity,
+            finding.vulnerability_type,
+            finding.file_path.as_deref().unwrap_or("unknown"),
+            finding.line_number.unwrap_or_default(),
+            finding.confidence,
+            finding
+                .owasp_category
+                .as_deref()
+                .map(|c| format!(", {c}"))
+                .unwrap_or_default(),
+            finding.description,
+            finding.recommendation
+        );
+    }
+    if findings.len() > MAX_LISTED_ITEMS * 2 {
+        let _ = writeln!(
+            text,
+            "- ... and {} more findings",
+            findings.len() - MAX_LISTED_ITEMS * 2
+        );
+    }
+
+    Ok(user_prompt(format!("Security triage for {scope}"), text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeprism_core::{Edge, EdgeKind, Language, Span};
+    use std::sync::Arc;
+
+    struct Fixture {
+        _temp_dir: tempfile::TempDir,
+        root: PathBuf,
+        graph_store: Arc<GraphStore>,
+        graph_query: GraphQuery,
+        content_search: ContentSearchManager,
+        code_analyzer: CodeAnalyzer,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let root = temp_dir.path().to_path_buf();
+            let source = "def helper():\n    return 1\n\ndef main():\n    password = \"hunter2\"\n    return helper()\n";
+            let file = root.join("app.py");
+            std::fs::write(&file, source).unwrap();
+
+            let graph_store = Arc::new(GraphStore::new());
+            let helper = Node::new(
+                "test",
+                NodeKind::Function,
+                "helper".to_string(),
+                Language::Python,
+                file.clone(),
+                Span::new(0, 26, 1, 2, 1, 13),
+            );
+            let main = Node::new(
+                "test",
+                NodeKind::Function,
+                "main".to_string(),
+                Language::Python,
+                file,
+                Span::new(28, source.len() - 1, 4, 6, 1, 20),
+            );
+            graph_store.add_node(helper.clone());
+            graph_store.add_node(main.clone());
+            graph_store.add_edge(Edge::new(main.id, helper.id, EdgeKind::Calls));
+
+            Self {
+                _temp_dir: temp_dir,
+                root,
+                graph_query: GraphQuery::new(Arc::clone(&graph_store)),
+                graph_store,
+                content_search: ContentSearchManager::new(),
+                code_analyzer: CodeAnalyzer::new(),
+            }
+        }
+
+        fn context(&self) -> PromptContext<'_> {
+            PromptContext {
+                graph_store: &self.graph_store,
+                graph_query: &self.graph_query,
+                content_search: &self.content_search,
+                code_analyzer: &self.code_analyzer,
+                repository_path: Some(&self.root),
+            }
+        }
+    }
+
+    fn args(pairs: &[(&str, &str)]) -> JsonObject {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), serde_json::Value::String(v.to_string())))
+            .collect()
+    }
+
+    fn prompt_text(result: &GetPromptResult) -> &str {
+        match &result.messages[0].content {
+            PromptMessageContent::Text { text } => text,
+            _ => panic!("expected text prompt message"),
+        }
+    }
+
+    #[test]
+    fn test_list_prompts_declares_arguments() {
+        let prompts = list_prompts();
+        let names: Vec<_> = prompts.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "review_file",
+                "explain_symbol",
+                "plan_refactor",
+                "triage_security"
+            ]
+        );
+
+        let review = &prompts[0];
+        let path_arg = &review.arguments.as_ref().unwrap()[0];
+        assert_eq!(path_arg.name, "path");
+        assert_eq!(path_arg.required, Some(true));
+    }
+
+    #[test]
+    fn test_explain_symbol_uses_graph_data() {
+        let fixture = Fixture::new();
+        let result = get_prompt(
+            &fixture.context(),
+            "explain_symbol",
+            Some(&args(&[("symbol", "helper")])),
+        )
+        .unwrap();
+
+        let text = prompt_text(&result);
+        assert!(text.contains("`helper`"));
+        assert!(text.contains("return 1"));
+        assert!(text.contains("## Usages (1)"));
+        assert!(text.contains("`main`"));
+    }
+
+    #[test]
+    fn test_plan_refactor_lists_callers() {
+        let fixture = Fixture::new();
+        let result = get_prompt(
+            &fixture.context(),
+            "plan_refactor",
+            Some(&args(&[("symbol", "helper"), ("goal", "inline it")])),
+        )
+        .unwrap();
+
+        let text = prompt_text(&result);
+        assert!(text.contains("inline it"));
+        assert!(text.contains("Callers and references (1 in 1 files)"));
+    }
+
+    #[test]
+    fn test_review_file_and_triage_security() {
+        let fixture = Fixture::new();
+        let review = get_prompt(
+            &fixture.context(),
+            "review_file",
+            Some(&args(&[("path", "app.py"), ("focus", "security")])),
+        )
+        .unwrap();
+        let text = prompt_text(&review);
+        assert!(text.contains("with a focus on security"));
+        assert!(text.contains("Function `main`"));
+        assert!(text.contains("Cyclomatic complexity"));
+
+        let triage = get_prompt(
+            &fixture.context(),
+            "triage_security",
+            Some(&args(&[("severity_threshold", "low")])),
+        )
+        .unwrap();
+        assert!(prompt_text(&triage).contains("Scanned 1 files"));
+    }
+
+    #[test]
+    fn test_invalid_prompt_requests() {
+        let fixture = Fixture::new();
+        let context = fixture.context();
+        assert!(get_prompt(&context, "unknown", None).is_err());
+        assert!(get_prompt(&context, "explain_symbol", None).is_err());
+        assert!(get_prompt(
+            &context,
+            "explain_symbol",
+            Some(&args(&[("symbol", "does_not_exist")]))
+        )
+        .is_err());
+        assert!(get_prompt(
+            &context,
+            "triage_security",
+            Some(&args(&[("severity_threshold", "extreme")]))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_prompts_reject_paths_outside_repository() {
+        let fixture = Fixture::new();
+        let context = fixture.context();
+        let outside = tempfile::tempdir().unwrap();
+        let secret = outside.path().join("secret.py");
+        std::fs::write(&secret, "password = 'hunter2'\n").unwrap();
+        // A file inside the repository that was not indexed
+        std::fs::write(fixture.root.join("notes.txt"), "private").unwrap();
+
+        let relative = format!(
+            "../{}/secret.py",
+            outside.path().file_name().unwrap().to_str().unwrap()
+        );
+        for path in [
+            relative.as_str(),
+            secret.to_str().unwrap(),
+            "/etc/passwd",
+            "notes.txt",
+        ] {
+            assert!(
+                get_prompt(&context, "review_file", Some(&args(&[("path", path)]))).is_err(),
+                "review_file accepted {path}"
+            );
+        }
+
+        for target in ["..", outside.path().to_str().unwrap(), "/etc"] {
+            assert!(
+                get_prompt(
+                    &context,
+                    "triage_security",
+                    Some(&args(&[("target", target)]))
+                )
+                .is_err(),
+                "triage_security accepted {target}"
+            );
+        }
+    }
+}
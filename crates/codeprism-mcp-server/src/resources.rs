@@ -0,0 +1,475 @@
+//! MCP resources exposing the indexed repository
+//!
+//! Resources are addressed with the `codeprism://` scheme:
+//! - `codeprism://graph/stats` - statistics for the whole code graph
+//! - `codeprism://file/{path}` - symbols indexed for a single file
+//! - `codeprism://symbol/{node_id}` - metadata and source span for a single symbol
+//!
+//! File paths in URIs are relative to the repository root when a repository
+//! is configured, otherwise they are the paths stored in the graph.
+
+use codeprism_core::{GraphStore, Node, NodeId};
+use rmcp::{model::*, Error as McpError};
+use std::path::{Component, Path, PathBuf};
+
+/// URI scheme used for all CodePrism resources
+pub const RESOURCE_SCHEME: &str = "codeprism://";
+
+/// URI of the graph statistics resource
+pub const GRAPH_STATS_URI: &str = "codeprism://graph/stats";
+
+/// Number of resources returned per `resources/list` page
+pub const RESOURCE_PAGE_SIZE: usize = 100;
+
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// A parsed CodePrism resource URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResourceUri {
+    /// Graph-wide statistics
+    GraphStats,
+    /// Symbols indexed for a file
+    File(PathBuf),
+    /// A single symbol by node ID
+    Symbol(NodeId),
+}
+
+impl ResourceUri {
+    /// Parse a `codeprism://` URI
+    pub fn parse(uri: &str) -> std::result::Result<Self, McpError> {
+        let rest = uri.strip_prefix(RESOURCE_SCHEME).ok_or_else(|| {
+            McpError::invalid_params(
+                format!("Unsupported resource URI: {uri}. Expected {RESOURCE_SCHEME} scheme."),
+                None,
+            )
+        })?;
+
+        if rest == "graph/stats" {
+            return Ok(Self::GraphStats);
+        }
+
+        if let Some(path) = rest.strip_prefix("file/") {
+            if path.is_empty() {
+                return Err(McpError::invalid_params(
+                    "File resource URI is missing a path",
+                    None,
+                ));
+            }
+            return Ok(Self::File(PathBuf::from(path)));
+        }
+
+        if let Some(id) = rest.strip_prefix("symbol/") {
+            return NodeId::from_hex(id).map(Self::Symbol).map_err(|_| {
+                McpError::invalid_params(
+                    format!("Invalid symbol ID format: {id}. Expected hexadecimal string."),
+                    None,
+                )
+            });
+        }
+
+        Err(McpError::resource_not_found(
+            format!("Unknown resource: {uri}"),
+            None,
+        ))
+    }
+
+    /// Build a file resource URI, relative to the repository root when possible
+    pub fn file_uri(path: &Path, repository_path: Option<&Path>) -> String {
+        let display_path = repository_path
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        format!("{RESOURCE_SCHEME}file/{}", display_path.display())
+    }
+
+    /// Build a symbol resource URI
+    pub fn symbol_uri(node_id: &NodeId) -> String {
+        format!("{RESOURCE_SCHEME}symbol/{}", node_id.to_hex())
+    }
+}
+
+/// Resource templates advertised through `resources/templates/list`
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        RawResourceTemplate {
+            uri_template: format!("{RESOURCE_SCHEME}file/{{path}}"),
+            name: "Indexed file".to_string(),
+            description: Some("Symbols indexed for a repository file".to_string()),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+        }
+        .no_annotation(),
+        RawResourceTemplate {
+            uri_template: format!("{RESOURCE_SCHEME}symbol/{{node_id}}"),
+            name: "Symbol".to_string(),
+            description: Some(
+                "Metadata and source span of a symbol, addressed by node ID".to_string(),
+            ),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+        }
+        .no_annotation(),
+    ]
+}
+
+/// List resources for the graph statistics and every indexed file
+///
+/// The cursor is the offset of the first resource to return.
+pub fn list_resources(
+    graph_store: &GraphStore,
+    repository_path: Option<&Path>,
+    cursor: Option<&str>,
+) -> std::result::Result<ListResourcesResult, McpError> {
+    let offset = match cursor {
+        Some(cursor) => cursor.parse::<usize>().map_err(|_| {
+            McpError::invalid_params(format!("Invalid pagination cursor: {cursor}"), None)
+        })?,
+        None => 0,
+    };
+
+    let mut files = graph_store.get_all_files();
+    files.sort();
+
+    let mut resources = Vec::with_capacity(files.len() + 1);
+    let mut stats = RawResource::new(GRAPH_STATS_URI, "Code graph statistics");
+    stats.description = Some("Node, edge and file counts for the indexed repository".to_string());
+    stats.mime_type = Some(JSON_MIME_TYPE.to_string());
+    resources.push(stats.no_annotation());
+
+    for file in files {
+        let mut resource = RawResource::new(
+            ResourceUri::file_uri(&file, repository_path),
+            file.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string(),
+        );
+        resource.description = Some(format!("Indexed symbols in {}", file.display()));
+        resource.mime_type = Some(JSON_MIME_TYPE.to_string());
+        resources.push(resource.no_annotation());
+    }
+
+    let total = resources.len();
+    let page: Vec<_> = resources
+        .into_iter()
+        .skip(offset)
+        .take(RESOURCE_PAGE_SIZE)
+        .collect();
+    let next_offset = offset + page.len();
+
+    Ok(ListResourcesResult {
+        resources: page,
+        next_cursor: (next_offset < total).then(|| next_offset.to_string()),
+    })
+}
+
+/// Read the contents of a resource
+pub fn read_resource(
+    graph_store: &GraphStore,
+    repository_path: Option<&Path>,
+    uri: &str,
+) -> std::result::Result<ReadResourceResult, McpError> {
+    let body = match ResourceUri::parse(uri)? {
+        ResourceUri::GraphStats => graph_stats_json(graph_store),
+        ResourceUri::File(path) => {
+            file_json(graph_store, repository_path, &path).ok_or_else(|| {
+                McpError::resource_not_found(format!("File not indexed: {}", path.display()), None)
+            })?
+        }
+        ResourceUri::Symbol(node_id) => {
+            let node = graph_store.get_node(&node_id).ok_or_else(|| {
+                McpError::resource_not_found(
+                    format!("Symbol with ID {} not found in graph", node_id.to_hex()),
+                    None,
+                )
+            })?;
+            symbol_json(graph_store, repository_path, &node)
+        }
+    };
+
+    let text = serde_json::to_string_pretty(&body)
+        .map_err(|e| McpError::internal_error(format!("Failed to format resource: {e}"), None))?;
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(JSON_MIME_TYPE.to_string()),
+            text,
+        }],
+    })
+}
+
+fn graph_stats_json(graph_store: &GraphStore) -> serde_json::Value {
+    let stats = graph_store.get_stats();
+    serde_json::json!({
+        "total_nodes": stats.total_nodes,
+        "total_edges": stats.total_edges,
+        "total_files": stats.total_files,
+        "nodes_by_kind": stats.nodes_by_kind.iter()
+            .map(|(kind, count)| (format!("{:?}", kind), *count))
+            .collect::<std::collections::HashMap<String, usize>>()
+    })
+}
+
+/// Resolve a client-supplied path to an indexed file in the repository
+///
+/// Without a repository root, only paths stored in the graph are accepted.
+pub(crate) fn resolve_file(
+    graph_store: &GraphStore,
+    repository_path: Option<&Path>,
+    path: &Path,
+) -> Option<PathBuf> {
+    let file = match repository_path {
+        Some(root) => resolve_in_repository(root, path)?,
+        None => path.to_path_buf(),
+    };
+    (!graph_store.get_nodes_by_file(&file).is_empty()).then_some(file)
+}
+
+/// Resolve a client-supplied path to a path under the repository root
+///
+/// Paths with `..` segments or outside the root are rejected, and a path that
+/// exists must still be inside the root once symlinks are resolved. The
+/// result is joined to the root as configured, so that it matches the paths
+/// in the file index.
+pub(crate) fn resolve_in_repository(root: &Path, path: &Path) -> Option<PathBuf> {
+    let relative = if path.is_absolute() {
+        path.strip_prefix(root).ok()?
+    } else {
+        path
+    };
+    let mut resolved = root.to_path_buf();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+
+    if let Ok(canonical) = resolved.canonicalize() {
+        let canonical_root = root.canonicalize().ok()?;
+        if !canonical.starts_with(canonical_root) {
+            return None;
+        }
+    }
+    Some(resolved)
+}
+
+fn file_json(
+    graph_store: &GraphStore,
+    repository_path: Option<&Path>,
+    path: &Path,
+) -> Option<serde_json::Value> {
+    let file = resolve_file(graph_store, repository_path, path)?;
+    let mut nodes = graph_store.get_nodes_in_file(&file);
+    nodes.sort_by_key(|node| (node.span.start_byte, node.span.end_byte));
+
+    Some(serde_json::json!({
+        "file": file.display().to_string(),
+        "language": nodes.first().map(|node| format!("{:?}", node.lang)),
+        "symbol_count": nodes.len(),
+        "symbols": nodes.iter().map(|node| {
+            serde_json::json!({
+                "id": node.id.to_hex(),
+                "uri": ResourceUri::symbol_uri(&node.id),
+                "name": node.name,
+                "kind": format!("{:?}", node.kind),
+                "start_line": node.span.start_line,
+                "end_line": node.span.end_line,
+                "signature": node.signature
+            })
+        }).collect::<Vec<_>>()
+    }))
+}
+
+fn symbol_json(
+    graph_store: &GraphStore,
+    repository_path: Option<&Path>,
+    node: &Node,
+) -> serde_json::Value {
+    serde_json::json!({
+        "id": node.id.to_hex(),
+        "name": node.name,
+        "kind": format!("{:?}", node.kind),
+        "language": format!("{:?}", node.lang),
+        "file": node.file.display().to_string(),
+        "file_uri": ResourceUri::file_uri(&node.file, repository_path),
+        "span": {
+            "start_byte": node.span.start_byte,
+            "end_byte": node.span.end_byte,
+            "start_line": node.span.start_line,
+            "start_column": node.span.start_column,
+            "end_line": node.span.end_line,
+            "end_column": node.span.end_column,
+        },
+        "signature": node.signature,
+        "metadata": node.metadata,
+        "source": read_span_source(node),
+        "outgoing_edges": graph_store.get_outgoing_edges(&node.id).len(),
+        "incoming_edges": graph_store.get_incoming_edges(&node.id).len()
+    })
+}
+
+/// Read the source text covered by a node's span, if the file is readable
+pub(crate) fn read_span_source(node: &Node) -> Option<String> {
+    let content = std::fs::read(&node.file).ok()?;
+    let bytes = content.get(node.span.start_byte..node.span.end_byte)?;
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codeprism_core::{Language, NodeKind, Span};
+
+    fn add_function(graph_store: &GraphStore, file: &Path, name: &str, span: Span) -> Node {
+        let node = Node::new(
+            "test",
+            NodeKind::Function,
+            name.to_string(),
+            Language::Python,
+            file.to_path_buf(),
+            span,
+        );
+        graph_store.add_node(node.clone());
+        node
+    }
+
+    #[test]
+    fn test_parse_resource_uris() {
+        assert_eq!(
+            ResourceUri::parse("codeprism://graph/stats").unwrap(),
+            ResourceUri::GraphStats
+        );
+        assert_eq!(
+            ResourceUri::parse("codeprism://file/src/main.py").unwrap(),
+            ResourceUri::File(PathBuf::from("src/main.py"))
+        );
+
+        let node_id = NodeId::new(
+            "test",
+            Path::new("a.py"),
+            &Span::new(0, 1, 1, 1, 1, 2),
+            &NodeKind::Function,
+        );
+        assert_eq!(
+            ResourceUri::parse(&ResourceUri::symbol_uri(&node_id)).unwrap(),
+            ResourceUri::Symbol(node_id)
+        );
+
+        assert!(ResourceUri::parse("file:///etc/passwd").is_err());
+        assert!(ResourceUri::parse("codeprism://symbol/not-hex").is_err());
+        assert!(ResourceUri::parse("codeprism://file/").is_err());
+        assert!(ResourceUri::parse("codeprism://unknown").is_err());
+    }
+
+    #[test]
+    fn test_list_resources_uses_relative_paths_and_paginates() {
+        let graph_store = GraphStore::new();
+        let root = Path::new("/repo");
+        for i in 0..RESOURCE_PAGE_SIZE + 5 {
+            let file = root.join(format!("module_{i:03}.py"));
+            add_function(&graph_store, &file, "f", Span::new(0, 1, 1, 1, 1, 2));
+        }
+
+        let first = list_resources(&graph_store, Some(root), None).unwrap();
+        assert_eq!(first.resources.len(), RESOURCE_PAGE_SIZE);
+        assert_eq!(first.resources[0].uri, GRAPH_STATS_URI);
+        assert_eq!(first.resources[1].uri, "codeprism://file/module_000.py");
+
+        let cursor = first.next_cursor.expect("expected a second page");
+        let second = list_resources(&graph_store, Some(root), Some(&cursor)).unwrap();
+        assert_eq!(second.resources.len(), 6);
+        assert!(second.next_cursor.is_none());
+
+        assert!(list_resources(&graph_store, Some(root), Some("abc")).is_err());
+    }
+
+    #[test]
+    fn test_read_symbol_resource_includes_source() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file = temp_dir.path().join("example.py");
+        let source = "def greet():\n    return 'hi'\n";
+        std::fs::write(&file, source).unwrap();
+
+        let graph_store = GraphStore::new();
+        let node = add_function(
+            &graph_store,
+            &file,
+            "greet",
+            Span::new(0, source.len() - 1, 1, 2, 1, 16),
+        );
+
+        let result = read_resource(
+            &graph_store,
+            Some(temp_dir.path()),
+            &ResourceUri::symbol_uri(&node.id),
+        )
+        .unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        let body: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(body["name"], "greet");
+        assert_eq!(body["kind"], "Function");
+        assert_eq!(body["file_uri"], "codeprism://file/example.py");
+        assert_eq!(body["source"], source.trim_end());
+    }
+
+    #[test]
+    fn test_read_file_and_stats_resources() {
+        let graph_store = GraphStore::new();
+        let root = Path::new("/repo");
+        let file = root.join("pkg/util.py");
+        add_function(&graph_store, &file, "b", Span::new(20, 30, 3, 4, 1, 5));
+        add_function(&graph_store, &file, "a", Span::new(0, 10, 1, 2, 1, 5));
+
+        let result =
+            read_resource(&graph_store, Some(root), "codeprism://file/pkg/util.py").unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        let body: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(body["symbol_count"], 2);
+        assert_eq!(body["symbols"][0]["name"], "a");
+
+        let result = read_resource(&graph_store, Some(root), GRAPH_STATS_URI).unwrap();
+        let ResourceContents::TextResourceContents { text, .. } = &result.contents[0] else {
+            panic!("expected text contents");
+        };
+        let body: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(body["total_nodes"], 2);
+        assert_eq!(body["total_files"], 1);
+
+        assert!(read_resource(&graph_store, Some(root), "codeprism://file/missing.py").is_err());
+    }
+
+    #[test]
+    fn test_resolve_in_repository_rejects_paths_outside_root() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path().join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/app.py"), "").unwrap();
+        std::fs::write(temp_dir.path().join("secret.txt"), "").unwrap();
+
+        let app = root.join("src/app.py");
+        assert_eq!(
+            resolve_in_repository(&root, Path::new("src/app.py")),
+            Some(app.clone())
+        );
+        assert_eq!(
+            resolve_in_repository(&root, Path::new("./src/app.py")),
+            Some(app.clone())
+        );
+        assert_eq!(resolve_in_repository(&root, &app), Some(app));
+        assert!(resolve_in_repository(&root, Path::new("../secret.txt")).is_none());
+        assert!(resolve_in_repository(&root, Path::new("src/../../secret.txt")).is_none());
+        assert!(resolve_in_repository(&root, &temp_dir.path().join("secret.txt")).is_none());
+        assert!(resolve_in_repository(&root, Path::new("/etc/passwd")).is_none());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(temp_dir.path().join("secret.txt"), root.join("link.txt"))
+                .unwrap();
+            assert!(resolve_in_repository(&root, Path::new("link.txt")).is_none());
+        }
+    }
+}
@@ -1,23 +1,35 @@
 //! Core MCP server implementation using rust-sdk
 
+use crate::config::TransportKind;
+use crate::indexing::{repository_id, validate_repository_path, IndexingProgress, IndexingStatus};
+use crate::live_index::{run_live_updates, LiveIndexHandler};
+use crate::resources::resolve_in_repository;
+use crate::subscriptions::{ClientSession, ResourceSubscriptions};
 use crate::Config;
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, tool::Parameters},
+    handler::server::{
+        router::tool::ToolRouter,
+        tool::{Parameters, ToolCallContext},
+    },
     model::*,
     service::RequestContext,
-    tool, tool_handler, tool_router, Error as McpError, RoleServer, ServerHandler, ServiceExt,
+    tool, tool_router, Error as McpError, RoleServer, ServerHandler, ServiceExt,
 };
 use serde::Deserialize;
 use tracing::{debug, info, warn};
 
 // CodePrism core components
-use codeprism_analysis::CodeAnalyzer;
+use codeprism_analysis::architecture::{ArchitectureReport, CONFIG_FILE_NAMES};
+use codeprism_analysis::call_graph::{EntryPoints, SymbolCentrality};
+use codeprism_analysis::{
+    ArchitectureAnalyzer, ArchitectureConfig, CallGraphAnalyzer, CodeAnalyzer,
+};
 use codeprism_core::graph::DependencyType;
 use codeprism_core::{
-    ContentSearchManager, GraphQuery, GraphStore, InheritanceFilter, LanguageRegistry,
-    NoOpProgressReporter, NodeKind, RepositoryConfig, RepositoryManager, RepositoryScanner,
-    SearchQueryBuilder,
+    ChangedRegion, ContentSearchManager, GraphQuery, GraphStore, ImpactedSymbol, InheritanceFilter,
+    NoOpProgressReporter, Node, NodeKind, Query, QueryValue, RepositoryScanner, SearchQueryBuilder,
 };
+use codeprism_storage::{StorageConfig, StorageManager};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -42,6 +54,19 @@ pub struct FindReferencesParams {
     pub context_lines: Option<u32>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AnalyzeImpactParams {
+    pub changes: Vec<String>,
+    pub max_depth: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GraphQueryParams {
+    pub query: String,
+    pub limit: Option<u32>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ExplainSymbolParams {
     pub symbol_id: String,
@@ -179,6 +204,29 @@ pub struct AnalyzeControlFlowParams {
     pub include_paths: Option<bool>,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct FindUnusedCodeParams {
+    pub include_main: Option<bool>,
+    pub include_routes: Option<bool>,
+    pub include_tests: Option<bool>,
+    pub include_module_code: Option<bool>,
+    pub entry_point_patterns: Option<Vec<String>>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AnalyzeCallGraphParams {
+    pub fan_out_threshold: Option<u32>,
+    pub betweenness_threshold: Option<f64>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct CheckArchitectureParams {
+    pub config_path: Option<String>,
+    pub limit: Option<u32>,
+}
+
 #[derive(Debug, Clone, Deserialize, schemars::JsonSchema)]
 pub struct AnalyzeCodeQualityParams {
     pub target: String,
@@ -225,12 +273,18 @@ pub struct CodePrismMcpServer {
     repository_scanner: Arc<RepositoryScanner>,
     /// Content search manager for text search
     content_search: Arc<ContentSearchManager>,
-    /// Repository manager for metadata and configuration
-    repository_manager: Arc<RepositoryManager>,
     /// Current repository path
     repository_path: Option<PathBuf>,
+    /// Progress of the current or last repository indexing run
+    indexing: Arc<IndexingStatus>,
     /// Code analyzer for complexity, performance, and security analysis
     code_analyzer: Arc<CodeAnalyzer>,
+    /// Resource subscriptions of all connected clients
+    subscriptions: Arc<ResourceSubscriptions>,
+    /// Subscription state of the client served by this instance
+    session: Arc<ClientSession>,
+    /// Persistent storage for graph snapshots, when enabled
+    storage: Option<Arc<StorageManager>>,
 }
 
 #[tool_router]
@@ -248,15 +302,17 @@ impl CodePrismMcpServer {
         let graph_store = Arc::new(GraphStore::new());
         let graph_query = Arc::new(GraphQuery::new(Arc::clone(&graph_store)));
         let repository_scanner = Arc::new(RepositoryScanner::new());
-        let content_search = Arc::new(ContentSearchManager::new());
-
-        // Initialize repository manager with language registry
-        let language_registry = Arc::new(LanguageRegistry::new());
-        let repository_manager = Arc::new(RepositoryManager::new(language_registry));
+        let content_search = Arc::new(ContentSearchManager::with_graph_store(Arc::clone(
+            &graph_store,
+        )));
 
         // Initialize code analyzer
         let code_analyzer = Arc::new(CodeAnalyzer::new());
 
+        let subscriptions = Arc::new(ResourceSubscriptions::new());
+        let session = subscriptions.new_session();
+        let storage = Self::open_storage(&config).await;
+
         Ok(Self {
             config,
             tool_router: Self::tool_router(),
@@ -264,12 +320,51 @@ impl CodePrismMcpServer {
             graph_query,
             repository_scanner,
             content_search,
-            repository_manager,
             repository_path: None,
+            indexing: Arc::new(IndexingStatus::new()),
             code_analyzer,
+            subscriptions,
+            session,
+            storage,
         })
     }
 
+    /// Open the graph snapshot storage configured in the caching settings
+    ///
+    /// Storage problems only disable warm starts; the server still starts.
+    async fn open_storage(config: &Config) -> Option<Arc<StorageManager>> {
+        let caching = &config.profile.caching;
+        let backend = caching.graph_storage.filter(|_| caching.enabled)?;
+        let storage_config = StorageConfig::new(backend, caching.graph_storage_dir())
+            .with_compression(caching.enable_compression);
+
+        match StorageManager::new(storage_config).await {
+            Ok(storage) => {
+                info!(
+                    "Graph snapshots enabled ({:?} at {})",
+                    backend,
+                    caching.graph_storage_dir().display()
+                );
+                Some(Arc::new(storage))
+            }
+            Err(e) => {
+                warn!("Graph snapshots disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Clone of this server for a newly connected client
+    ///
+    /// The clone shares the index with every other client but tracks its own
+    /// resource subscriptions.
+    pub fn new_session(&self) -> Self {
+        Self {
+            session: self.subscriptions.new_session(),
+            ..self.clone()
+        }
+    }
+
     /// Simple ping tool for testing MCP functionality
     #[tool(description = "Simple ping tool that responds with pong")]
     fn ping(&self) -> std::result::Result<CallToolResult, McpError> {
@@ -336,6 +431,7 @@ impl CodePrismMcpServer {
                 "tools": "available",
                 "config": "valid"
             },
+            "indexing": self.indexing.progress(),
             "uptime_seconds": std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -661,6 +757,156 @@ impl CodePrismMcpServer {
         )]))
     }
 
+    /// Find the symbols, routes and tests a change can break
+    #[tool(
+        description = "Analyze the blast radius of changed files or line ranges (e.g. 'src/app.py', 'src/app.py:10-24', 'src/app.py:42'): affected symbols, entry points and test files ranked by distance"
+    )]
+    pub(crate) fn analyze_impact(
+        &self,
+        Parameters(params): Parameters<AnalyzeImpactParams>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        info!("Analyze impact tool called for: {:?}", params.changes);
+
+        let max_depth = params.max_depth.unwrap_or(5) as usize;
+        let limit = params.limit.unwrap_or(100) as usize;
+
+        let changes = params
+            .changes
+            .iter()
+            .map(|change| self.parse_changed_region(change))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let symbol_json = |symbol: &ImpactedSymbol| {
+            serde_json::json!({
+                "id": symbol.node.id.to_hex(),
+                "name": symbol.node.name,
+                "kind": format!("{:?}", symbol.node.kind),
+                "file": symbol.node.file.display().to_string(),
+                "line": symbol.node.span.start_line,
+                "distance": symbol.distance,
+                "reached_from": symbol.reached_from.map(|id| id.to_hex()),
+                "edge_type": symbol.edge_kind.map(|kind| format!("{:?}", kind)),
+            })
+        };
+
+        let result = match self.graph_query.analyze_impact(&changes, Some(max_depth)) {
+            Ok(impact) => {
+                serde_json::json!({
+                    "status": "success",
+                    "changed_symbols": impact.changed.iter().map(|node| {
+                        serde_json::json!({
+                            "id": node.id.to_hex(),
+                            "name": node.name,
+                            "kind": format!("{:?}", node.kind),
+                            "file": node.file.display().to_string(),
+                            "line": node.span.start_line,
+                        })
+                    }).collect::<Vec<_>>(),
+                    "affected_symbols": impact.affected.iter().take(limit).map(symbol_json).collect::<Vec<_>>(),
+                    "total_affected": impact.affected.len(),
+                    "entry_points": impact.entry_points.iter().map(symbol_json).collect::<Vec<_>>(),
+                    "test_files": impact.test_files.iter().map(|file| {
+                        serde_json::json!({
+                            "file": file.file.display().to_string(),
+                            "distance": file.distance,
+                            "symbols": file.symbols,
+                        })
+                    }).collect::<Vec<_>>(),
+                    "unmatched_changes": impact.unmatched.iter()
+                        .map(|region| region.file.display().to_string())
+                        .collect::<Vec<_>>(),
+                    "query": {
+                        "changes": params.changes,
+                        "max_depth": max_depth,
+                        "limit": limit
+                    }
+                })
+            }
+            Err(e) => {
+                serde_json::json!({
+                    "status": "error",
+                    "message": format!("Impact analysis failed: {}", e),
+                    "query": {
+                        "changes": params.changes,
+                        "max_depth": max_depth
+                    }
+                })
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "Error formatting response".to_string()),
+        )]))
+    }
+
+    /// Answer a declarative pattern query over the code graph
+    #[tool(
+        description = "Query the code graph with a Cypher-like pattern, e.g. MATCH (r:Route)-[:RoutesTo|Calls*]->(f:Function)-[:Writes]->(q:SqlQuery) WHERE f.file GLOB 'src/**' AND f.name =~ '^save' RETURN r, f.name. Supports node kinds, edge kinds and directions, variable-length paths (*, *1..3), name regexes, file globs and metadata fields (n.metadata.key)"
+    )]
+    pub(crate) fn graph_query(
+        &self,
+        Parameters(params): Parameters<GraphQueryParams>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        info!("Graph query tool called: {}", params.query);
+
+        let limit = params.limit.unwrap_or(100) as usize;
+
+        let result =
+            match Query::parse(&params.query).and_then(|query| query.execute(&self.graph_store)) {
+                Ok(result) => {
+                    let total_rows = result.rows.len();
+                    let rows: Vec<serde_json::Value> = result
+                        .rows
+                        .iter()
+                        .take(limit)
+                        .map(|row| {
+                            let cells = result.columns.iter().zip(row).map(|(column, value)| {
+                                let value = match value {
+                                    QueryValue::Node(node) => serde_json::json!({
+                                        "id": node.id.to_hex(),
+                                        "name": node.name,
+                                        "kind": format!("{:?}", node.kind),
+                                        "file": node.file.display().to_string(),
+                                        "line": node.span.start_line,
+                                    }),
+                                    QueryValue::Value(value) => value.clone(),
+                                };
+                                (column.clone(), value)
+                            });
+                            serde_json::Value::Object(cells.collect())
+                        })
+                        .collect();
+
+                    serde_json::json!({
+                        "status": "success",
+                        "columns": result.columns,
+                        "rows": rows,
+                        "total_rows": total_rows,
+                        "truncated": result.truncated || total_rows > limit,
+                        "query": {
+                            "query": params.query,
+                            "limit": limit
+                        }
+                    })
+                }
+                Err(e) => {
+                    serde_json::json!({
+                        "status": "error",
+                        "message": format!("Graph query failed: {}", e),
+                        "query": {
+                            "query": params.query
+                        }
+                    })
+                }
+            };
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "Error formatting response".to_string()),
+        )]))
+    }
+
     // Core Symbol Tools - Real implementations migrated from legacy codeprism-mcp
 
     /// Provide detailed explanation of a code symbol with context
@@ -820,7 +1066,7 @@ impl CodePrismMcpServer {
 
     /// Search for symbols by name pattern with advanced filtering
     #[tool(description = "Search for symbols by name pattern with advanced inheritance filtering")]
-    fn search_symbols(
+    pub(crate) fn search_symbols(
         &self,
         Parameters(params): Parameters<SearchSymbolsParams>,
     ) -> std::result::Result<CallToolResult, McpError> {
@@ -1005,7 +1251,7 @@ impl CodePrismMcpServer {
         } else {
             serde_json::json!({
                 "status": "error",
-                "message": "No repository configured. Call initialize_repository first.",
+                "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.",
                 "note": "Use the server initialization to set up a repository path"
             })
         };
@@ -1086,7 +1332,7 @@ impl CodePrismMcpServer {
         let _repo_path = match &self.repository_path {
             Some(path) => path.clone(),
             None => {
-                let error_msg = "No repository configured. Call initialize_repository first.";
+                let error_msg = "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.";
                 return Ok(CallToolResult::error(vec![Content::text(
                     error_msg.to_string(),
                 )]));
@@ -1809,7 +2055,7 @@ impl CodePrismMcpServer {
                 None => {
                     serde_json::json!({
                         "status": "error",
-                        "message": "No repository configured. Call initialize_repository first.",
+                        "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.",
                         "target": params.target
                     })
                 }
@@ -2063,7 +2309,7 @@ impl CodePrismMcpServer {
                 None => {
                     serde_json::json!({
                         "status": "error",
-                        "message": "No repository configured. Call initialize_repository first.",
+                        "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.",
                         "target": params.target
                     })
                 }
@@ -2083,6 +2329,200 @@ impl CodePrismMcpServer {
         )]))
     }
 
+    /// Find functions and classes that no entry point reaches
+    #[tool(
+        description = "Find unused functions, methods and classes: symbols not reachable through calls, imports or routes from entry points (main functions, routes, tests, module-level code and name regexes)"
+    )]
+    pub(crate) fn find_unused_code(
+        &self,
+        Parameters(params): Parameters<FindUnusedCodeParams>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        info!("Find unused code tool called");
+
+        let limit = params.limit.unwrap_or(100) as usize;
+        let defaults = EntryPoints::default();
+        let entry_points = EntryPoints {
+            main_functions: params.include_main.unwrap_or(defaults.main_functions),
+            routes: params.include_routes.unwrap_or(defaults.routes),
+            tests: params.include_tests.unwrap_or(defaults.tests),
+            module_code: params.include_module_code.unwrap_or(defaults.module_code),
+            names: params.entry_point_patterns.clone().unwrap_or_default(),
+        };
+        let analyzer = CallGraphAnalyzer::new().with_entry_points(entry_points.clone());
+        let node_json = |node: &Node| {
+            serde_json::json!({
+                "id": node.id.to_hex(),
+                "name": node.name,
+                "kind": format!("{:?}", node.kind),
+                "file": node.file.display().to_string(),
+                "line": node.span.start_line,
+            })
+        };
+
+        let result = match analyzer.reachability(&self.graph_store) {
+            Ok(report) => {
+                serde_json::json!({
+                    "status": "success",
+                    "unused_symbols": report.unreferenced.iter().take(limit).map(node_json).collect::<Vec<_>>(),
+                    "total_unused": report.unreferenced.len(),
+                    "reachable_symbols": report.reachable,
+                    "total_symbols": report.total_symbols,
+                    "entry_points": report.entry_points.len(),
+                    "query": {
+                        "entry_points": entry_points,
+                        "limit": limit
+                    }
+                })
+            }
+            Err(e) => {
+                serde_json::json!({
+                    "status": "error",
+                    "message": format!("Unused code analysis failed: {}", e),
+                    "query": {
+                        "entry_points": entry_points
+                    }
+                })
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "Error formatting response".to_string()),
+        )]))
+    }
+
+    /// Report cycles and central functions in the call graph
+    #[tool(
+        description = "Analyze the call and import graph: recursive and mutually recursive functions, import cycles between files, and god functions ranked by fan-in, fan-out and betweenness centrality"
+    )]
+    pub(crate) fn analyze_call_graph(
+        &self,
+        Parameters(params): Parameters<AnalyzeCallGraphParams>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        info!("Analyze call graph tool called");
+
+        let limit = params.limit.unwrap_or(20) as usize;
+        let mut analyzer = CallGraphAnalyzer::new();
+        if let Some(fan_out) = params.fan_out_threshold {
+            analyzer.god_function_fan_out = fan_out as usize;
+        }
+        if let Some(betweenness) = params.betweenness_threshold {
+            analyzer.god_function_betweenness = betweenness;
+        }
+
+        let node_json = |node: &Node| {
+            serde_json::json!({
+                "id": node.id.to_hex(),
+                "name": node.name,
+                "kind": format!("{:?}", node.kind),
+                "file": node.file.display().to_string(),
+                "line": node.span.start_line,
+            })
+        };
+        let centrality_json = |symbol: &SymbolCentrality| {
+            let mut summary = node_json(&symbol.node);
+            summary["fan_in"] = symbol.fan_in.into();
+            summary["fan_out"] = symbol.fan_out.into();
+            summary["betweenness"] = symbol.betweenness.into();
+            summary
+        };
+
+        let call_cycles = analyzer.call_cycles(&self.graph_store);
+        let import_cycles = analyzer.import_cycles(&self.graph_store);
+        let centrality = analyzer.centrality(&self.graph_store);
+        let hotspots: Vec<_> = centrality.iter().take(limit).map(centrality_json).collect();
+        let god_functions = analyzer.god_functions(centrality);
+
+        let result = serde_json::json!({
+            "status": "success",
+            "call_cycles": call_cycles.iter().take(limit).map(|cycle| {
+                serde_json::json!({
+                    "recursive": cycle.symbols.len() == 1,
+                    "symbols": cycle.symbols.iter().map(node_json).collect::<Vec<_>>(),
+                })
+            }).collect::<Vec<_>>(),
+            "total_call_cycles": call_cycles.len(),
+            "import_cycles": import_cycles.iter().take(limit).map(|cycle| {
+                cycle.files.iter().map(|file| file.display().to_string()).collect::<Vec<_>>()
+            }).collect::<Vec<_>>(),
+            "total_import_cycles": import_cycles.len(),
+            "god_functions": god_functions.iter().take(limit).map(centrality_json).collect::<Vec<_>>(),
+            "hotspots": hotspots,
+            "query": {
+                "fan_out_threshold": analyzer.god_function_fan_out,
+                "betweenness_threshold": analyzer.god_function_betweenness,
+                "limit": limit
+            }
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "Error formatting response".to_string()),
+        )]))
+    }
+
+    /// Check architecture layering rules from the repository config
+    #[tool(
+        description = "Check architecture layering rules: layers declared as file globs or module patterns in architecture.toml/.yaml/.json with may_depend_on and must_not_depend_on rules. Reports every import or call that crosses into a forbidden layer with its location, and a layer dependency matrix"
+    )]
+    pub(crate) fn check_architecture(
+        &self,
+        Parameters(params): Parameters<CheckArchitectureParams>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        info!("Check architecture tool called");
+
+        let limit = params.limit.unwrap_or(100) as usize;
+
+        let result = match self.check_layering(params.config_path.as_deref()) {
+            Ok((config_path, report)) => {
+                let node_json = |node: &Node| {
+                    serde_json::json!({
+                        "name": node.name,
+                        "kind": format!("{:?}", node.kind),
+                        "file": node.file.display().to_string(),
+                        "line": node.span.start_line,
+                    })
+                };
+                serde_json::json!({
+                    "status": "success",
+                    "config": config_path.display().to_string(),
+                    "violations": report.violations.iter().take(limit).map(|violation| {
+                        serde_json::json!({
+                            "from_layer": violation.from_layer,
+                            "to_layer": violation.to_layer,
+                            "edge_type": format!("{:?}", violation.edge_kind),
+                            "source": node_json(&violation.source),
+                            "target": node_json(&violation.target),
+                        })
+                    }).collect::<Vec<_>>(),
+                    "total_violations": report.violations.len(),
+                    "dependency_matrix": report.matrix,
+                    "unassigned_files": report.unassigned_files.iter()
+                        .map(|file| file.display().to_string())
+                        .collect::<Vec<_>>(),
+                    "query": {
+                        "config_path": params.config_path,
+                        "limit": limit
+                    }
+                })
+            }
+            Err(e) => {
+                serde_json::json!({
+                    "status": "error",
+                    "message": format!("Architecture check failed: {}", e),
+                    "query": {
+                        "config_path": params.config_path
+                    }
+                })
+            }
+        };
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "Error formatting response".to_string()),
+        )]))
+    }
+
     /// Analyze JavaScript-specific patterns and best practices
     #[tool(
         description = "Comprehensive JavaScript/TypeScript analysis with framework detection and ES compatibility"
@@ -2286,7 +2726,7 @@ impl CodePrismMcpServer {
                 None => {
                     serde_json::json!({
                         "status": "error",
-                        "message": "No repository configured. Call initialize_repository first.",
+                        "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.",
                         "target": params.target
                     })
                 }
@@ -2708,7 +3148,7 @@ impl CodePrismMcpServer {
         } else {
             serde_json::json!({
                 "status": "error",
-                "message": "No repository configured. Call initialize_repository first.",
+                "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.",
                 "workflow_type": params.workflow_type
             })
         };
@@ -3058,7 +3498,7 @@ impl CodePrismMcpServer {
         } else {
             serde_json::json!({
                 "status": "error",
-                "message": "No repository configured. Call initialize_repository first.",
+                "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO.",
                 "operation": params.operation
             })
         };
@@ -3077,245 +3517,146 @@ impl CodePrismMcpServer {
         let repo_path = repo_path.as_ref().to_path_buf();
 
         info!("Initializing repository: {}", repo_path.display());
+        validate_repository_path(&repo_path)?;
 
-        // Validate repository path
-        if !repo_path.exists() {
-            return Err(crate::Error::server_init(format!(
-                "Repository path does not exist: {}",
-                repo_path.display()
-            )));
-        }
-
-        if !repo_path.is_dir() {
-            return Err(crate::Error::server_init(format!(
-                "Repository path is not a directory: {}",
-                repo_path.display()
-            )));
-        }
-
-        // Create repository configuration
-        let repo_id = repo_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("default")
-            .to_string();
-
-        let repo_config = RepositoryConfig::new(repo_id.clone(), &repo_path)
-            .with_name(format!("Repository: {}", repo_id))
-            .with_description(format!(
-                "CodePrism MCP Server repository at {}",
-                repo_path.display()
-            ));
-
-        // Clear existing graph data
-        self.graph_store.clear();
-        info!("Cleared existing graph data");
-
-        // Register repository with the repository manager
-        match Arc::get_mut(&mut self.repository_manager) {
-            Some(manager) => {
-                manager
-                    .register_repository(repo_config.clone())
-                    .map_err(|e| {
-                        crate::Error::server_init(format!("Failed to register repository: {}", e))
-                    })?;
-                info!("Registered repository with manager: {}", repo_id);
-            }
-            None => {
-                // If we can't get mutable access, create a new manager and replace it
-                let language_registry = Arc::new(codeprism_core::LanguageRegistry::new());
-                let mut new_manager = codeprism_core::RepositoryManager::new(language_registry);
-                new_manager
-                    .register_repository(repo_config.clone())
-                    .map_err(|e| {
-                        crate::Error::server_init(format!("Failed to register repository: {}", e))
-                    })?;
-                self.repository_manager = Arc::new(new_manager);
-                info!(
-                    "Created new repository manager and registered repository: {}",
-                    repo_id
-                );
-            }
-        }
-
-        // Create a progress reporter for indexing
-        struct IndexingProgressReporter {
-            total_files: std::sync::atomic::AtomicUsize,
-            processed_files: std::sync::atomic::AtomicUsize,
-        }
-
-        impl IndexingProgressReporter {
-            fn new() -> Self {
-                Self {
-                    total_files: std::sync::atomic::AtomicUsize::new(0),
-                    processed_files: std::sync::atomic::AtomicUsize::new(0),
-                }
-            }
-        }
-
-        impl codeprism_core::ProgressReporter for IndexingProgressReporter {
-            fn report_progress(&self, current: usize, total: Option<usize>) {
-                if let Some(total) = total {
-                    self.total_files
-                        .store(total, std::sync::atomic::Ordering::Relaxed);
-                }
-                self.processed_files
-                    .store(current, std::sync::atomic::Ordering::Relaxed);
-
-                if current % 100 == 0 || (total.is_some() && current == total.unwrap()) {
-                    info!(
-                        "Repository indexing progress: {}/{}",
-                        current,
-                        total
-                            .map(|t| t.to_string())
-                            .unwrap_or_else(|| "?".to_string())
-                    );
-                }
-            }
-
-            fn report_complete(&self, result: &codeprism_core::ScanResult) {
-                info!(
-                    "Repository scan completed: {} files discovered in {}ms",
-                    result.total_files, result.duration_ms
-                );
-            }
-
-            fn report_error(&self, error: &codeprism_core::Error) {
-                warn!("Repository scanning error: {}", error);
-            }
-        }
-
-        let progress_reporter = Arc::new(IndexingProgressReporter::new());
-
-        // Index the repository to populate the graph store
-        info!("Starting repository indexing...");
-        let start_time = std::time::Instant::now();
-
-        // Get mutable access to repository manager for indexing
-        let indexing_result = match Arc::try_unwrap(self.repository_manager.clone()) {
-            Ok(mut manager) => {
-                let result = manager
-                    .index_repository(&repo_id, Some(progress_reporter.clone()))
-                    .await
-                    .map_err(|e| {
-                        crate::Error::server_init(format!("Failed to index repository: {}", e))
-                    })?;
-
-                // Replace the repository manager
-                self.repository_manager = Arc::new(manager);
-                result
-            }
-            Err(shared_manager) => {
-                // If we can't get exclusive access, this means the manager is being used elsewhere
-                // This is a concurrency safety measure - we defer indexing to avoid conflicts
-                warn!("Repository manager is in use, deferring graph population");
-                warn!("Repository will be indexed on next initialization or when manager becomes available");
-
-                // Keep the existing manager
-                self.repository_manager = shared_manager;
+        self.repository_path = Some(repo_path.clone());
+        self.index_repository_into_store(&repo_path).await
+    }
 
-                // Set repository path and return early
-                self.repository_path = Some(repo_path);
-                return Ok(());
-            }
-        };
+    /// Set the repository path and index it on a background task
+    ///
+    /// Tools that depend on the index answer with an "indexing in progress"
+    /// status until the returned task completes. Once the index is ready, a
+    /// separate task keeps it current by re-indexing files as they change.
+    pub fn start_background_indexing<P: AsRef<std::path::Path>>(
+        &mut self,
+        repo_path: P,
+    ) -> Result<tokio::task::JoinHandle<()>, crate::Error> {
+        let repo_path = repo_path.as_ref().to_path_buf();
 
-        let duration = start_time.elapsed();
         info!(
-            "Repository indexing completed in {:.2}s",
-            duration.as_secs_f64()
+            "Indexing repository in the background: {}",
+            repo_path.display()
         );
+        validate_repository_path(&repo_path)?;
+
+        self.repository_path = Some(repo_path.clone());
+        // Mark the run as started before spawning so that no request can
+        // observe an empty index without the in-progress status
+        self.indexing.begin();
+
+        let server = self.clone();
+        Ok(tokio::spawn(async move {
+            if let Err(e) = server.index_repository_into_store(&repo_path).await {
+                warn!("Background repository indexing failed: {}", e);
+                return;
+            }
+            tokio::spawn(async move {
+                if let Err(e) = server.watch_repository(repo_path).await {
+                    warn!("{}", e);
+                }
+            });
+        }))
+    }
 
-        // Apply patches to populate the graph store
-        info!(
-            "Applying {} patches to graph store...",
-            indexing_result.patches.len()
-        );
+    /// Keep the index current by re-indexing files as they change on disk
+    ///
+    /// Runs until the file watcher stops. Subscribed clients receive
+    /// `notifications/resources/updated` for every changed file.
+    pub async fn watch_repository(&self, repo_path: PathBuf) -> Result<(), crate::Error> {
+        let handler = Arc::new(LiveIndexHandler::new(
+            Arc::clone(&self.graph_store),
+            Arc::clone(&self.content_search),
+            Arc::clone(&self.subscriptions),
+            repo_path.clone(),
+        ));
 
-        let mut nodes_added = 0;
-        let mut edges_added = 0;
+        info!("Watching repository for changes: {}", repo_path.display());
+        let repo_id = repository_id(&repo_path);
+        run_live_updates(repo_path, repo_id, handler).await
+    }
 
-        for patch in &indexing_result.patches {
-            // Add nodes from the patch
-            for node in &patch.nodes_add {
-                self.graph_store.add_node(node.clone());
-                nodes_added += 1;
-            }
+    /// Current repository indexing progress
+    pub fn indexing_progress(&self) -> IndexingProgress {
+        self.indexing.progress()
+    }
 
-            // Add edges from the patch
-            for edge in &patch.edges_add {
-                self.graph_store.add_edge(edge.clone());
-                edges_added += 1;
-            }
+    /// Response for index-dependent tools while indexing is still running
+    pub(crate) fn indexing_in_progress_result(&self, tool_name: &str) -> Option<CallToolResult> {
+        if !self.indexing.is_indexing() || INDEX_INDEPENDENT_TOOLS.contains(&tool_name) {
+            return None;
         }
 
-        info!(
-            "Graph store populated: {} nodes, {} edges",
-            nodes_added, edges_added
-        );
+        let result = serde_json::json!({
+            "status": "indexing_in_progress",
+            "message": "Repository indexing in progress. Results will be available once indexing completes.",
+            "tool": tool_name,
+            "indexing": self.indexing.progress()
+        });
+        Some(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result)
+                .unwrap_or_else(|_| "Error formatting response".to_string()),
+        )]))
+    }
 
-        // Update content search manager with repository data
-        info!("Updating content search index...");
-        let content_search_manager =
-            ContentSearchManager::with_graph_store(Arc::clone(&self.graph_store));
+    /// Rebuild the graph store and content index from `repo_path`, tracking progress
+    async fn index_repository_into_store(
+        &self,
+        repo_path: &std::path::Path,
+    ) -> Result<(), crate::Error> {
+        self.indexing.begin();
 
-        // Extract unique file paths from all nodes in patches
-        let mut file_paths = std::collections::HashSet::new();
-        for patch in &indexing_result.patches {
-            for node in &patch.nodes_add {
-                file_paths.insert(&node.file);
-            }
-        }
+        // Clear existing graph data
+        self.graph_store.clear();
+        self.content_search.clear();
+        info!("Cleared existing graph data");
 
-        // Index content for all discovered files
-        let mut content_files_indexed = 0;
-        for file_path in file_paths {
-            if let Ok(content) = std::fs::read_to_string(file_path) {
-                if let Err(e) = content_search_manager.index_file(file_path, &content) {
-                    warn!("Failed to index content for {}: {}", file_path.display(), e);
-                } else {
-                    content_files_indexed += 1;
-                }
+        let summary = match crate::indexing::index_repository(
+            repo_path,
+            &self.graph_store,
+            &self.content_search,
+            Arc::clone(&self.indexing),
+            self.storage.as_deref().map(StorageManager::graph),
+        )
+        .await
+        {
+            Ok(summary) => summary,
+            Err(e) => {
+                self.indexing.fail(e.to_string());
+                return Err(e);
             }
-        }
-
-        // Replace the content search manager
-        self.content_search = Arc::new(content_search_manager);
-        info!(
-            "Content search index updated: {} files indexed",
-            content_files_indexed
-        );
-
-        // Set repository path
-        self.repository_path = Some(repo_path);
+        };
+        self.indexing.finish();
 
         // Log final statistics
         let graph_stats = self.graph_store.get_stats();
         info!("Repository initialization completed:");
-        info!("  - Repository ID: {}", repo_id);
-        info!(
-            "  - Files processed: {}",
-            indexing_result.stats.files_processed
-        );
+        info!("  - Repository ID: {}", summary.repo_id);
+        info!("  - Commit: {}", summary.commit);
+        info!("  - Files processed: {}", summary.files_processed);
+        info!("  - Files reused from snapshot: {}", summary.files_reused);
         info!("  - Nodes in graph: {}", graph_stats.total_nodes);
         info!("  - Edges in graph: {}", graph_stats.total_edges);
         info!("  - Files indexed: {}", graph_stats.total_files);
-        info!("  - Content files indexed: {}", content_files_indexed);
-        info!("  - Processing time: {:.2}s", duration.as_secs_f64());
+        info!(
+            "  - Content files indexed: {}",
+            summary.content_files_indexed
+        );
+        info!(
+            "  - Processing time: {:.2}s",
+            summary.duration.as_secs_f64()
+        );
 
-        if !indexing_result.failed_files.is_empty() {
+        if !summary.failed_files.is_empty() {
             warn!(
                 "  - Failed files: {} (check logs for details)",
-                indexing_result.failed_files.len()
+                summary.failed_files.len()
             );
-            for (file_path, error) in indexing_result.failed_files.iter().take(5) {
+            for (file_path, error) in summary.failed_files.iter().take(5) {
                 warn!("    • {}: {}", file_path.display(), error);
             }
-            if indexing_result.failed_files.len() > 5 {
-                warn!(
-                    "    ... and {} more",
-                    indexing_result.failed_files.len() - 5
-                );
+            if summary.failed_files.len() > 5 {
+                warn!("    ... and {} more", summary.failed_files.len() - 5);
             }
         }
 
@@ -3346,10 +3687,20 @@ impl CodePrismMcpServer {
         .to_string()
     }
 
-    /// Run the MCP server with stdio transport
+    /// Run the MCP server with the configured transport
     pub async fn run(self) -> std::result::Result<(), crate::Error> {
-        info!("Starting CodePrism MCP Server");
+        let transport = self.config.profile.settings.transport.kind;
+        info!("Starting CodePrism MCP Server ({} transport)", transport);
+
+        match transport {
+            TransportKind::Stdio => self.run_stdio().await,
+            TransportKind::Http => crate::transport::serve_http(self).await,
+            TransportKind::Sse => crate::transport::serve_sse(self).await,
+        }
+    }
 
+    /// Run the MCP server with stdio transport
+    async fn run_stdio(self) -> std::result::Result<(), crate::Error> {
         use rmcp::transport::stdio;
 
         // Start the MCP server with stdio transport
@@ -3375,6 +3726,11 @@ impl CodePrismMcpServer {
         &self.config
     }
 
+    /// Get the configured repository path, if any
+    pub fn repository_path(&self) -> Option<&std::path::Path> {
+        self.repository_path.as_deref()
+    }
+
     /// Extract semantic keywords from a concept for search
     fn extract_semantic_keywords(&self, concept: &str) -> Vec<String> {
         let mut keywords = Vec::new();
@@ -3609,6 +3965,71 @@ impl CodePrismMcpServer {
         variations
     }
 
+    /// Parse `path`, `path:line` or `path:start-end`, resolving relative paths
+    /// against the repository root
+    pub(crate) fn parse_changed_region(
+        &self,
+        change: &str,
+    ) -> std::result::Result<ChangedRegion, McpError> {
+        let resolve = |path: &str| {
+            let path = PathBuf::from(path);
+            match &self.repository_path {
+                Some(root) if path.is_relative() => root.join(path),
+                _ => path,
+            }
+        };
+
+        let Some((path, lines)) = change.rsplit_once(':') else {
+            return Ok(ChangedRegion::file(resolve(change)));
+        };
+        let (start, end) = lines.split_once('-').unwrap_or((lines, lines));
+        match (start.trim().parse::<usize>(), end.trim().parse::<usize>()) {
+            (Ok(start), Ok(end)) if start > 0 && !path.is_empty() => {
+                if end < start {
+                    return Err(McpError::invalid_params(
+                        format!("Invalid change: {change}. Line {end} is before line {start}."),
+                        None,
+                    ));
+                }
+                Ok(ChangedRegion::lines(resolve(path), start, end))
+            }
+            // A colon that is not followed by a line range belongs to the path
+            _ if !lines.chars().any(|c| c.is_ascii_digit()) => {
+                Ok(ChangedRegion::file(resolve(change)))
+            }
+            _ => Err(McpError::invalid_params(
+                format!(
+                    "Invalid change: {change}. Expected 'path', 'path:line' or 'path:start-end'."
+                ),
+                None,
+            )),
+        }
+    }
+
+    /// Check the layering rules in `config_path`, or in the config file in the
+    /// repository root
+    fn check_layering(
+        &self,
+        config_path: Option<&str>,
+    ) -> anyhow::Result<(PathBuf, ArchitectureReport)> {
+        let root = self
+            .repository_path
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("No repository is configured"))?;
+        let path = match config_path {
+            Some(path) => resolve_in_repository(root, std::path::Path::new(path))
+                .ok_or_else(|| anyhow::anyhow!("Config path is outside the repository: {path}"))?,
+            None => ArchitectureConfig::find(root).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "No architecture config found. Add one of {} to the repository root.",
+                    CONFIG_FILE_NAMES.join(", ")
+                )
+            })?,
+        };
+        let analyzer = ArchitectureAnalyzer::new(ArchitectureConfig::from_file(&path)?)?;
+        Ok((path, analyzer.analyze(&self.graph_store)?))
+    }
+
     /// Check if a file is a test file based on path patterns
     fn is_test_file(&self, file_path: &str) -> bool {
         let path_lower = file_path.to_lowercase();
@@ -4229,7 +4650,7 @@ impl CodePrismMcpServer {
         });
 
         // Architecture Domain Analysis
-        let architecture_analysis = serde_json::json!({
+        let mut architecture_analysis = serde_json::json!({
             "design_patterns": {
                 "detected": [],
                 "anti_patterns": ["god_object"],
@@ -4254,6 +4675,37 @@ impl CodePrismMcpServer {
             }
         });
 
+        // Layering rules apply when the repository declares them
+        let config_path = domain_options
+            .and_then(|options| options.get("architecture"))
+            .and_then(|options| options.get("config_path"))
+            .and_then(|path| path.as_str());
+        if config_path.is_some()
+            || self
+                .repository_path
+                .as_ref()
+                .and_then(ArchitectureConfig::find)
+                .is_some()
+        {
+            architecture_analysis["layering"] = match self.check_layering(config_path) {
+                Ok((path, report)) => serde_json::json!({
+                    "config": path.display().to_string(),
+                    "total_violations": report.violations.len(),
+                    "violations": report.violations.iter().take(10).map(|violation| {
+                        serde_json::json!({
+                            "from_layer": violation.from_layer,
+                            "to_layer": violation.to_layer,
+                            "file": violation.source.file.display().to_string(),
+                            "line": violation.source.span.start_line,
+                            "target": violation.target.name,
+                        })
+                    }).collect::<Vec<_>>(),
+                    "dependency_matrix": report.matrix,
+                }),
+                Err(e) => serde_json::json!({ "error": e.to_string() }),
+            };
+        }
+
         // Performance Domain Analysis
         let performance_analysis = serde_json::json!({
             "hotspots": [
@@ -5301,7 +5753,7 @@ impl CodePrismMcpServer {
             }
             None => Ok(serde_json::json!({
                 "status": "error",
-                "message": "No repository configured. Call initialize_repository first."
+                "message": "No repository configured. Start the server with --repo <path> or set CODEPRISM_REPO."
             })),
         }
     }
@@ -5537,12 +5989,42 @@ impl CodePrismMcpServer {
     }
 }
 
-#[tool_handler]
+/// Tools that answer without consulting the repository index
+const INDEX_INDEPENDENT_TOOLS: &[&str] = &["ping", "version", "system_info", "health_check"];
+
 impl ServerHandler for CodePrismMcpServer {
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<CallToolResult, McpError> {
+        if let Some(result) = self.indexing_in_progress_result(&request.name) {
+            debug!("Tool {} called while indexing is in progress", request.name);
+            return Ok(result);
+        }
+
+        let tcc = ToolCallContext::new(self, request, context);
+        self.tool_router.call(tcc).await
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult::with_all_items(self.tool_router.list_all()))
+    }
+
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
             protocol_version: ProtocolVersion::V_2024_11_05,
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_resources_list_changed()
+                .enable_prompts()
+                .build(),
             server_info: Implementation {
                 name: self.config.server().name.clone(),
                 version: self.config.server().version.clone(),
@@ -5564,26 +6046,50 @@ impl ServerHandler for CodePrismMcpServer {
 
     async fn list_resources(
         &self,
-        _request: Option<PaginatedRequestParam>,
+        request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> std::result::Result<ListResourcesResult, McpError> {
-        warn!("Resources not implemented");
-        Ok(ListResourcesResult {
-            resources: vec![],
-            next_cursor: None,
-        })
+        debug!("Listing resources");
+        crate::resources::list_resources(
+            &self.graph_store,
+            self.repository_path.as_deref(),
+            request.as_ref().and_then(|r| r.cursor.as_deref()),
+        )
     }
 
     async fn read_resource(
         &self,
-        _request: ReadResourceRequestParam,
+        request: ReadResourceRequestParam,
         _context: RequestContext<RoleServer>,
     ) -> std::result::Result<ReadResourceResult, McpError> {
-        warn!("Resource reading not implemented");
-        Err(McpError::invalid_params(
-            "Resource reading not implemented",
-            None,
-        ))
+        info!("Reading resource: {}", request.uri);
+        crate::resources::read_resource(
+            &self.graph_store,
+            self.repository_path.as_deref(),
+            &request.uri,
+        )
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> std::result::Result<(), McpError> {
+        crate::resources::ResourceUri::parse(&request.uri)?;
+        debug!("Subscribing to resource: {}", request.uri);
+        self.subscriptions
+            .subscribe(&self.session, context.peer, request.uri);
+        Ok(())
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> std::result::Result<(), McpError> {
+        debug!("Unsubscribing from resource: {}", request.uri);
+        self.subscriptions.unsubscribe(&self.session, &request.uri);
+        Ok(())
     }
 
     async fn list_prompts(
@@ -5591,23 +6097,26 @@ impl ServerHandler for CodePrismMcpServer {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> std::result::Result<ListPromptsResult, McpError> {
-        warn!("Prompts not implemented");
         Ok(ListPromptsResult {
-            prompts: vec![],
+            prompts: crate::prompts::list_prompts(),
             next_cursor: None,
         })
     }
 
     async fn get_prompt(
         &self,
-        _request: GetPromptRequestParam,
+        request: GetPromptRequestParam,
         _context: RequestContext<RoleServer>,
     ) -> std::result::Result<GetPromptResult, McpError> {
-        warn!("Prompt retrieval not implemented");
-        Err(McpError::invalid_params(
-            "Prompt retrieval not implemented",
-            None,
-        ))
+        info!("Get prompt called: {}", request.name);
+        let context = crate::prompts::PromptContext {
+            graph_store: &self.graph_store,
+            graph_query: &self.graph_query,
+            content_search: &self.content_search,
+            code_analyzer: &self.code_analyzer,
+            repository_path: self.repository_path.as_deref(),
+        };
+        crate::prompts::get_prompt(&context, &request.name, request.arguments.as_ref())
     }
 
     async fn list_resource_templates(
@@ -5615,9 +6124,8 @@ impl ServerHandler for CodePrismMcpServer {
         _request: Option<PaginatedRequestParam>,
         _context: RequestContext<RoleServer>,
     ) -> std::result::Result<ListResourceTemplatesResult, McpError> {
-        warn!("Resource templates not implemented");
         Ok(ListResourceTemplatesResult {
-            resource_templates: vec![],
+            resource_templates: crate::resources::resource_templates(),
             next_cursor: None,
         })
     }
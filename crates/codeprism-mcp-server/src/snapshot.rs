@@ -0,0 +1,291 @@
+//! Persisted graph snapshots for warm starts
+//!
+//! After indexing, the graph is saved through `codeprism-storage` as a
+//! [`SerializableGraph`] keyed by repository id and commit, together with the
+//! content hash of every indexed file. On the next start the snapshot is
+//! loaded and only files whose hash changed are parsed again.
+
+use codeprism_core::{Edge, EdgeKind, GraphStore, Language, Node, NodeId, NodeKind, Span};
+use codeprism_storage::{SerializableEdge, SerializableGraph, SerializableNode, SerializableSpan};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Number of snapshots kept per repository
+pub const MAX_SNAPSHOTS_PER_REPO: usize = 3;
+
+/// Commit key used when the repository is not a git checkout
+pub const WORKING_TREE_COMMIT: &str = "working-tree";
+
+const LANGUAGE_ATTRIBUTE: &str = "lang";
+const SIGNATURE_ATTRIBUTE: &str = "signature";
+const METADATA_ATTRIBUTE: &str = "metadata";
+
+/// Content hash used to detect changed files
+pub fn content_hash(content: &[u8]) -> String {
+    blake3::hash(content).to_hex().to_string()
+}
+
+/// Commit currently checked out in `repo_path`
+///
+/// Falls back to [`WORKING_TREE_COMMIT`] when `git` is unavailable or the
+/// directory is not a repository.
+pub fn current_commit(repo_path: &Path) -> String {
+    std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("HEAD")
+        .current_dir(repo_path)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| WORKING_TREE_COMMIT.to_string())
+}
+
+/// Build a snapshot of everything in `graph_store`
+///
+/// `file_hashes` should only contain files that were indexed successfully,
+/// so that files that failed are parsed again on the next start.
+pub fn to_snapshot(
+    graph_store: &GraphStore,
+    repo_id: &str,
+    commit: &str,
+    file_hashes: HashMap<PathBuf, String>,
+) -> SerializableGraph {
+    let mut snapshot = SerializableGraph::snapshot(repo_id, commit);
+
+    for (_, node_ids) in graph_store.iter_file_index() {
+        for node_id in node_ids {
+            let Some(node) = graph_store.get_node(&node_id) else {
+                continue;
+            };
+            for edge in graph_store.get_outgoing_edges(&node_id) {
+                snapshot.edges.push(edge_to_snapshot(&edge));
+            }
+            snapshot.nodes.push(node_to_snapshot(&node));
+        }
+    }
+
+    snapshot.metadata.file_hashes = file_hashes;
+    snapshot.metadata.total_nodes = snapshot.nodes.len();
+    snapshot.metadata.total_edges = snapshot.edges.len();
+    snapshot
+}
+
+/// Load the nodes and edges of unchanged files from `snapshot`
+///
+/// A file is unchanged when its hash in `file_hashes` matches the snapshot.
+/// Nodes are added to `graph_store` right away; edges are returned so that the
+/// caller can add them once re-indexed files are in place. Returns the set of
+/// restored files and the pending edges.
+pub fn restore_unchanged(
+    graph_store: &GraphStore,
+    snapshot: &SerializableGraph,
+    file_hashes: &HashMap<PathBuf, String>,
+) -> (HashSet<PathBuf>, Vec<Edge>) {
+    let mut unchanged: HashSet<PathBuf> = snapshot
+        .metadata
+        .file_hashes
+        .iter()
+        .filter(|(path, hash)| file_hashes.get(*path) == Some(*hash))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let mut nodes = Vec::new();
+    for stored in &snapshot.nodes {
+        if !unchanged.contains(&stored.file) {
+            continue;
+        }
+        match node_from_snapshot(stored) {
+            Some(node) => nodes.push(node),
+            // Re-index files whose stored nodes no longer deserialize
+            None => {
+                unchanged.remove(&stored.file);
+            }
+        }
+    }
+
+    let mut restored_ids = HashSet::new();
+    for node in nodes {
+        if unchanged.contains(&node.file) {
+            restored_ids.insert(node.id);
+            graph_store.add_node(node);
+        }
+    }
+
+    let edges = snapshot
+        .edges
+        .iter()
+        .filter_map(edge_from_snapshot)
+        .filter(|edge| restored_ids.contains(&edge.source))
+        .collect();
+
+    (unchanged, edges)
+}
+
+/// Convert a graph node into its storage representation
+pub fn node_to_snapshot(node: &Node) -> SerializableNode {
+    let span = &node.span;
+    let mut stored = SerializableNode::new(
+        node.id.to_hex(),
+        node.name.clone(),
+        enum_to_string(&node.kind),
+        node.file.clone(),
+        SerializableSpan {
+            start_byte: span.start_byte,
+            end_byte: span.end_byte,
+            start_line: span.start_line,
+            end_line: span.end_line,
+            start_column: span.start_column,
+            end_column: span.end_column,
+        },
+    );
+    stored.add_attribute(LANGUAGE_ATTRIBUTE.to_string(), enum_to_string(&node.lang));
+    if let Some(signature) = &node.signature {
+        stored.add_attribute(SIGNATURE_ATTRIBUTE.to_string(), signature.clone());
+    }
+    if !node.metadata.is_null() {
+        stored.add_attribute(METADATA_ATTRIBUTE.to_string(), node.metadata.to_string());
+    }
+    stored
+}
+
+/// Convert a stored node back into a graph node
+pub fn node_from_snapshot(stored: &SerializableNode) -> Option<Node> {
+    let span = &stored.span;
+    let lang = stored
+        .attributes
+        .get(LANGUAGE_ATTRIBUTE)
+        .and_then(|lang| enum_from_str(lang))
+        .unwrap_or(Language::Unknown);
+    let metadata = match stored.attributes.get(METADATA_ATTRIBUTE) {
+        Some(metadata) => serde_json::from_str(metadata).ok()?,
+        None => serde_json::Value::Null,
+    };
+
+    Some(Node {
+        id: NodeId::from_hex(&stored.id).ok()?,
+        kind: enum_from_str::<NodeKind>(&stored.kind)?,
+        name: stored.name.clone(),
+        lang,
+        file: stored.file.clone(),
+        span: Span::new(
+            span.start_byte,
+            span.end_byte,
+            span.start_line,
+            span.end_line,
+            span.start_column,
+            span.end_column,
+        ),
+        signature: stored.attributes.get(SIGNATURE_ATTRIBUTE).cloned(),
+        metadata,
+    })
+}
+
+/// Convert a graph edge into its storage representation
+pub fn edge_to_snapshot(edge: &Edge) -> SerializableEdge {
+    SerializableEdge::new(
+        edge.source.to_hex(),
+        edge.target.to_hex(),
+        enum_to_string(&edge.kind),
+    )
+}
+
+/// Convert a stored edge back into a graph edge
+pub fn edge_from_snapshot(stored: &SerializableEdge) -> Option<Edge> {
+    Some(Edge::new(
+        NodeId::from_hex(&stored.source).ok()?,
+        NodeId::from_hex(&stored.target).ok()?,
+        enum_from_str::<EdgeKind>(&stored.kind)?,
+    ))
+}
+
+fn enum_to_string<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+fn enum_from_str<T: DeserializeOwned>(s: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(s.to_string())).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(file: &Path, name: &str, start: usize) -> Node {
+        Node::new(
+            "repo",
+            NodeKind::Function,
+            name.to_string(),
+            Language::Python,
+            file.to_path_buf(),
+            Span::new(start, start + 10, 1, 2, 1, 4),
+        )
+        .with_signature(format!("def {name}()"))
+        .with_metadata(serde_json::json!({"async": true}))
+    }
+
+    #[test]
+    fn test_node_and_edge_round_trip() {
+        let caller = node(Path::new("/repo/a.py"), "caller", 0);
+        let callee = node(Path::new("/repo/a.py"), "callee", 20);
+        let edge = Edge::new(caller.id, callee.id, EdgeKind::Calls);
+
+        let restored = node_from_snapshot(&node_to_snapshot(&caller)).unwrap();
+        assert_eq!(restored.id, caller.id);
+        assert_eq!(restored.kind, caller.kind);
+        assert_eq!(restored.lang, caller.lang);
+        assert_eq!(restored.span, caller.span);
+        assert_eq!(restored.signature, caller.signature);
+        assert_eq!(restored.metadata, caller.metadata);
+        assert_eq!(edge_from_snapshot(&edge_to_snapshot(&edge)), Some(edge));
+    }
+
+    #[test]
+    fn test_restore_only_unchanged_files() {
+        let kept_file = PathBuf::from("/repo/kept.py");
+        let changed_file = PathBuf::from("/repo/changed.py");
+        let kept = node(&kept_file, "kept", 0);
+        let changed = node(&changed_file, "changed", 0);
+
+        let source = GraphStore::new();
+        source.add_node(kept.clone());
+        source.add_node(changed.clone());
+        source.add_edge(Edge::new(kept.id, changed.id, EdgeKind::Calls));
+        source.add_edge(Edge::new(changed.id, kept.id, EdgeKind::Calls));
+
+        let hashes = HashMap::from([
+            (kept_file.clone(), content_hash(b"kept")),
+            (changed_file.clone(), content_hash(b"old")),
+        ]);
+        let snapshot = to_snapshot(&source, "repo", "abc", hashes);
+        assert_eq!(snapshot.repo_id, "repo@abc");
+        assert_eq!(snapshot.metadata.total_edges, 2);
+
+        let current = HashMap::from([
+            (kept_file.clone(), content_hash(b"kept")),
+            (changed_file.clone(), content_hash(b"new")),
+        ]);
+        let target = GraphStore::new();
+        let (unchanged, edges) = restore_unchanged(&target, &snapshot, &current);
+
+        assert_eq!(unchanged, HashSet::from([kept_file.clone()]));
+        assert_eq!(target.get_nodes_in_file(&kept_file).len(), 1);
+        assert!(target.get_nodes_in_file(&changed_file).is_empty());
+        // Only edges leaving restored nodes are carried over
+        assert_eq!(edges, vec![Edge::new(kept.id, changed.id, EdgeKind::Calls)]);
+    }
+
+    #[test]
+    fn test_current_commit_outside_git() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert_eq!(current_commit(temp_dir.path()), WORKING_TREE_COMMIT);
+    }
+}
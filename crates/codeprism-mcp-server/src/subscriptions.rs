@@ -0,0 +1,141 @@
+//! Resource subscriptions and change notifications
+//!
+//! Every connected client gets its own [`ClientSession`]. Clients subscribe to
+//! resource URIs through `resources/subscribe`, and the live index uses
+//! [`ResourceSubscriptions`] to send `notifications/resources/updated` to the
+//! sessions that asked for them.
+
+use rmcp::model::ResourceUpdatedNotificationParam;
+use rmcp::{Peer, RoleServer};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use tracing::debug;
+
+/// Subscription state for a single connected client
+#[derive(Debug, Default)]
+pub struct ClientSession {
+    peer: Mutex<Option<Peer<RoleServer>>>,
+    uris: Mutex<HashSet<String>>,
+}
+
+impl ClientSession {
+    /// URIs this client is subscribed to
+    pub fn subscribed_uris(&self) -> HashSet<String> {
+        lock(&self.uris).clone()
+    }
+
+    fn peer(&self) -> Option<Peer<RoleServer>> {
+        lock(&self.peer).clone()
+    }
+}
+
+/// Registry of client sessions shared by all clones of the server
+#[derive(Debug, Default)]
+pub struct ResourceSubscriptions {
+    sessions: Mutex<Vec<Weak<ClientSession>>>,
+}
+
+impl ResourceSubscriptions {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new client session
+    ///
+    /// Sessions are held weakly and disappear once the server instance
+    /// serving the client is dropped.
+    pub fn new_session(&self) -> Arc<ClientSession> {
+        let session = Arc::new(ClientSession::default());
+        let mut sessions = lock(&self.sessions);
+        sessions.retain(|session| session.strong_count() > 0);
+        sessions.push(Arc::downgrade(&session));
+        session
+    }
+
+    /// Subscribe `session` to updates of `uri`, delivered through `peer`
+    pub fn subscribe(&self, session: &ClientSession, peer: Peer<RoleServer>, uri: String) {
+        *lock(&session.peer) = Some(peer);
+        lock(&session.uris).insert(uri);
+    }
+
+    /// Remove the subscription of `session` to `uri`
+    pub fn unsubscribe(&self, session: &ClientSession, uri: &str) -> bool {
+        lock(&session.uris).remove(uri)
+    }
+
+    /// Notify every subscribed client that resources in `uris` changed
+    ///
+    /// Returns the number of notifications delivered.
+    pub async fn notify_updated(&self, uris: &HashSet<String>) -> usize {
+        let mut delivered = 0;
+        for session in self.live_sessions() {
+            let Some(peer) = session.peer() else {
+                continue;
+            };
+            let subscribed: Vec<String> = lock(&session.uris).intersection(uris).cloned().collect();
+            for uri in subscribed {
+                match peer
+                    .notify_resource_updated(ResourceUpdatedNotificationParam { uri: uri.clone() })
+                    .await
+                {
+                    Ok(()) => delivered += 1,
+                    Err(e) => debug!("Failed to notify resource update for {}: {}", uri, e),
+                }
+            }
+        }
+        delivered
+    }
+
+    /// Tell subscribed clients that the set of listed resources changed
+    pub async fn notify_list_changed(&self) {
+        for session in self.live_sessions() {
+            if let Some(peer) = session.peer() {
+                if let Err(e) = peer.notify_resource_list_changed().await {
+                    debug!("Failed to notify resource list change: {}", e);
+                }
+            }
+        }
+    }
+
+    fn live_sessions(&self) -> Vec<Arc<ClientSession>> {
+        let mut sessions = lock(&self.sessions);
+        sessions.retain(|session| session.strong_count() > 0);
+        sessions.iter().filter_map(Weak::upgrade).collect()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sessions_are_dropped_with_their_server() {
+        let subscriptions = ResourceSubscriptions::new();
+        let first = subscriptions.new_session();
+        let second = subscriptions.new_session();
+        assert_eq!(subscriptions.live_sessions().len(), 2);
+
+        drop(first);
+        let live = subscriptions.live_sessions();
+        assert_eq!(live.len(), 1);
+        assert!(Arc::ptr_eq(&live[0], &second));
+    }
+
+    #[tokio::test]
+    async fn test_notify_without_subscribers_is_noop() {
+        let subscriptions = ResourceSubscriptions::new();
+        let session = subscriptions.new_session();
+        let uris = HashSet::from(["codeprism://graph/stats".to_string()]);
+
+        assert_eq!(subscriptions.notify_updated(&uris).await, 0);
+        assert!(session.subscribed_uris().is_empty());
+        assert!(!subscriptions.unsubscribe(&session, "codeprism://graph/stats"));
+    }
+}
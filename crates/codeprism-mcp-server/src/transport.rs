@@ -0,0 +1,470 @@
+//! Network transports for the CodePrism MCP Server
+//!
+//! Besides stdio, the server can be shared by several MCP clients over
+//! streamable HTTP or server-sent events. Every client session gets a clone of
+//! the server, so all sessions query the same indexed repository.
+//!
+//! Network endpoints are guarded by the profile's `SecurityConfig`: bearer
+//! token authentication through `api_keys`, and per-client throttling through
+//! `rate_limiting`.
+
+use crate::config::{RateLimitConfig, SecurityConfig, TransportConfig};
+use crate::{CodePrismMcpServer, Result};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use rmcp::transport::sse_server::{SseServer, SseServerConfig};
+use rmcp::transport::streamable_http_server::{
+    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Serve MCP clients over streamable HTTP until interrupted
+pub async fn serve_http(server: CodePrismMcpServer) -> Result<()> {
+    let transport = server.config().profile.settings.transport.clone();
+    let security = server.config().profile.security.clone();
+    let addr = transport.socket_addr()?;
+
+    let service = StreamableHttpService::new(
+        move || Ok(server.new_session()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig {
+            sse_keep_alive: Some(transport.keep_alive),
+            stateful_mode: true,
+        },
+    );
+    let router = with_request_guard(
+        Router::new().nest_service(&transport.http_path, service),
+        &security,
+    );
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(
+        "MCP server listening on http://{}{}",
+        listener.local_addr()?,
+        transport.http_path
+    );
+
+    let ct = CancellationToken::new();
+    serve_router(listener, router, ct).await
+}
+
+/// Serve MCP clients over server-sent events until interrupted
+pub async fn serve_sse(server: CodePrismMcpServer) -> Result<()> {
+    let transport = server.config().profile.settings.transport.clone();
+    let security = server.config().profile.security.clone();
+    let addr = transport.socket_addr()?;
+
+    let ct = CancellationToken::new();
+    let (sse_server, router) = SseServer::new(sse_server_config(&transport, addr, ct.clone()));
+    let router = with_request_guard(router, &security);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(
+        "MCP server listening on http://{}{} (messages on {})",
+        listener.local_addr()?,
+        transport.sse_path,
+        transport.sse_message_path
+    );
+
+    sse_server.with_service(move || server.new_session());
+    serve_router(listener, router, ct).await
+}
+
+fn sse_server_config(
+    transport: &TransportConfig,
+    bind: SocketAddr,
+    ct: CancellationToken,
+) -> SseServerConfig {
+    SseServerConfig {
+        bind,
+        sse_path: transport.sse_path.clone(),
+        post_path: transport.sse_message_path.clone(),
+        ct,
+        sse_keep_alive: Some(transport.keep_alive),
+    }
+}
+
+/// Serve a router until Ctrl-C, then cancel all sessions
+async fn serve_router(
+    listener: tokio::net::TcpListener,
+    router: Router,
+    ct: CancellationToken,
+) -> Result<()> {
+    let shutdown = ct.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!("Received shutdown signal");
+        }
+        shutdown.cancel();
+    });
+
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { ct.cancelled().await })
+    .await?;
+
+    info!("MCP server shut down successfully");
+    Ok(())
+}
+
+/// Wrap a router with authentication, rate limiting and concurrency limits
+pub fn with_request_guard(router: Router, security: &SecurityConfig) -> Router {
+    let guard = Arc::new(RequestGuard::new(security));
+    router.layer(middleware::from_fn_with_state(guard, guard_request))
+}
+
+/// Shared state of the request guard middleware
+struct RequestGuard {
+    api_keys: Vec<String>,
+    rate_limiter: Option<RateLimiter>,
+    concurrency: Option<Arc<Semaphore>>,
+}
+
+impl RequestGuard {
+    fn new(security: &SecurityConfig) -> Self {
+        let rate_limiting = &security.rate_limiting;
+        if security.api_keys.is_empty() {
+            warn!("No API keys configured, network transport accepts unauthenticated clients");
+        }
+
+        Self {
+            api_keys: security.api_keys.clone(),
+            rate_limiter: rate_limiting
+                .enabled
+                .then(|| RateLimiter::new(rate_limiting)),
+            concurrency: (rate_limiting.enabled && rate_limiting.max_concurrent > 0)
+                .then(|| Arc::new(Semaphore::new(rate_limiting.max_concurrent))),
+        }
+    }
+
+    fn is_authorized(&self, request: &Request) -> bool {
+        if self.api_keys.is_empty() {
+            return true;
+        }
+
+        request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| self.api_keys.iter().any(|key| key == token.trim()))
+    }
+}
+
+async fn guard_request(
+    State(guard): State<Arc<RequestGuard>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !guard.is_authorized(&request) {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            "Missing or invalid API key",
+        )
+            .into_response();
+    }
+
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+
+    if let Some(limiter) = &guard.rate_limiter {
+        if !limiter.try_acquire(client) {
+            warn!("Rate limit exceeded for client {}", client);
+            return (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded").into_response();
+        }
+    }
+
+    // Event streams stay open for the whole session, so only message posts
+    // count against the concurrency limit.
+    let _permit = match &guard.concurrency {
+        Some(semaphore) if request.method() == Method::POST => {
+            match Arc::clone(semaphore).acquire_owned().await {
+                Ok(permit) => Some(permit),
+                Err(_) => {
+                    return (StatusCode::SERVICE_UNAVAILABLE, "Server is shutting down")
+                        .into_response()
+                }
+            }
+        }
+        _ => None,
+    };
+
+    next.run(request).await
+}
+
+/// Clients tracked by the rate limiter before idle buckets are evicted
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Token bucket rate limiter keyed by client address
+///
+/// A bucket that has refilled completely is equivalent to a new one, so once
+/// `max_clients` buckets exist, full buckets are dropped before a new client
+/// is added. If every bucket is still in use the least recently used one is
+/// dropped, which only lets that client burst again early.
+struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    max_clients: usize,
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            capacity: config.burst_size.max(1) as f64,
+            refill_per_sec: config.requests_per_minute as f64 / 60.0,
+            max_clients: MAX_TRACKED_CLIENTS,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn try_acquire(&self, client: IpAddr) -> bool {
+        self.try_acquire_at(client, Instant::now())
+    }
+
+    fn try_acquire_at(&self, client: IpAddr, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= self.max_clients && !buckets.contains_key(&client) {
+            self.evict(&mut buckets, now);
+        }
+        let bucket = buckets.entry(client).or_insert(TokenBucket {
+            tokens: self.capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * self.refill_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn evict(&self, buckets: &mut HashMap<IpAddr, TokenBucket>, now: Instant) {
+        buckets.retain(|_, bucket| {
+            let elapsed = now.saturating_duration_since(bucket.last_refill);
+            bucket.tokens + elapsed.as_secs_f64() * self.refill_per_sec < self.capacity
+        });
+        if buckets.len() >= self.max_clients {
+            let oldest = buckets
+                .iter()
+                .min_by_key(|(_, bucket)| bucket.last_refill)
+                .map(|(client, _)| *client);
+            if let Some(client) = oldest {
+                buckets.remove(&client);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+    use axum::{body::Body, routing::post};
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    fn security(api_keys: &[&str], rate_limiting: RateLimitConfig) -> SecurityConfig {
+        let mut security = Config::default().profile.security;
+        security.api_keys = api_keys.iter().map(|k| k.to_string()).collect();
+        security.rate_limiting = rate_limiting;
+        security
+    }
+
+    fn no_rate_limit() -> RateLimitConfig {
+        RateLimitConfig {
+            enabled: false,
+            requests_per_minute: 60,
+            max_concurrent: 1,
+            burst_size: 1,
+        }
+    }
+
+    fn request(token: Option<&str>) -> Request {
+        let mut builder = Request::builder().method(Method::POST).uri("/mcp");
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    fn guarded_router(security: &SecurityConfig) -> Router {
+        with_request_guard(
+            Router::new().route("/mcp", post(|| async { "ok" })),
+            security,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_request_guard_requires_api_key() {
+        let router = guarded_router(&security(&["secret"], no_rate_limit()));
+
+        let response = router.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router
+            .clone()
+            .oneshot(request(Some("wrong")))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router.oneshot(request(Some("secret"))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_request_guard_without_api_keys_is_open() {
+        let router = guarded_router(&security(&[], no_rate_limit()));
+        let response = router.oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_request_guard_rate_limits() {
+        let router = guarded_router(&security(
+            &[],
+            RateLimitConfig {
+                enabled: true,
+                requests_per_minute: 1,
+                max_concurrent: 4,
+                burst_size: 2,
+            },
+        ));
+
+        for _ in 0..2 {
+            let response = router.clone().oneshot(request(None)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let response = router.oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_rate_limiter_refills_per_client() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            enabled: true,
+            requests_per_minute: 60,
+            max_concurrent: 1,
+            burst_size: 1,
+        });
+        let start = Instant::now();
+        let a = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let b = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+        assert!(limiter.try_acquire_at(a, start));
+        assert!(!limiter.try_acquire_at(a, start));
+        assert!(limiter.try_acquire_at(b, start));
+        assert!(limiter.try_acquire_at(a, start + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_rate_limiter_evicts_idle_clients() {
+        let mut limiter = RateLimiter::new(&RateLimitConfig {
+            enabled: true,
+            requests_per_minute: 60,
+            max_concurrent: 1,
+            burst_size: 1,
+        });
+        limiter.max_clients = 2;
+        let start = Instant::now();
+        let client = |n| IpAddr::V4(Ipv4Addr::new(10, 0, 0, n));
+        let tracked = |limiter: &RateLimiter| limiter.buckets.lock().unwrap().len();
+
+        assert!(limiter.try_acquire_at(client(1), start));
+        assert!(limiter.try_acquire_at(client(2), start));
+        // Both buckets are still empty, so the oldest one makes room
+        assert!(limiter.try_acquire_at(client(3), start + Duration::from_millis(10)));
+        assert_eq!(tracked(&limiter), 2);
+        assert!(!limiter.try_acquire_at(client(3), start + Duration::from_millis(10)));
+
+        // Once refilled, buckets are dropped before a new client is added
+        let later = start + Duration::from_secs(5);
+        assert!(limiter.try_acquire_at(client(4), later));
+        assert_eq!(tracked(&limiter), 1);
+    }
+
+    #[test]
+    fn test_transport_kind_parsing() {
+        use crate::config::TransportKind;
+        assert_eq!(
+            "HTTP".parse::<TransportKind>().unwrap(),
+            TransportKind::Http
+        );
+        assert_eq!("sse".parse::<TransportKind>().unwrap(), TransportKind::Sse);
+        assert_eq!(
+            "stdio".parse::<TransportKind>().unwrap(),
+            TransportKind::Stdio
+        );
+        assert!("websocket".parse::<TransportKind>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_transport_serves_initialize() {
+        let mut config = Config::default();
+        config.profile.settings.transport.port = 0;
+        let server = CodePrismMcpServer::new(config).await.unwrap();
+        let http_path = server.config().profile.settings.transport.http_path.clone();
+
+        let service = StreamableHttpService::new(
+            move || Ok(server.new_session()),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        let router = with_request_guard(
+            Router::new().nest_service(&http_path, service),
+            &security(&["secret"], no_rate_limit()),
+        );
+
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "0.0.0"}
+            }
+        });
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri(&http_path)
+            .header(header::AUTHORIZATION, "Bearer secret")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(header::ACCEPT, "application/json, text/event-stream")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().contains_key("mcp-session-id"));
+    }
+}
@@ -45,6 +45,13 @@ pub struct SerializableSpan {
     pub end_column: usize,
 }
 
+/// Layout version of [`GraphMetadata`] written by this crate
+///
+/// 1.0 predates [`GraphMetadata::commit`]. Bincode encodes structs as a plain
+/// sequence of fields and ignores `#[serde(default)]`, so 1.0 records have to
+/// be decoded with [`GraphMetadataV1`] and migrated.
+pub const SCHEMA_VERSION: &str = "1.1";
+
 /// Graph metadata for version tracking and incremental updates
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphMetadata {
@@ -55,6 +62,51 @@ pub struct GraphMetadata {
     pub total_nodes: usize,
     pub total_edges: usize,
     pub schema_version: String,
+    /// Commit the graph was built from, for snapshots
+    #[serde(default)]
+    pub commit: Option<String>,
+}
+
+/// [`GraphMetadata`] as written by schema version 1.0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphMetadataV1 {
+    pub repo_id: String,
+    pub last_updated: SystemTime,
+    pub version: u64,
+    pub file_hashes: HashMap<PathBuf, String>,
+    pub total_nodes: usize,
+    pub total_edges: usize,
+    pub schema_version: String,
+}
+
+impl From<GraphMetadataV1> for GraphMetadata {
+    fn from(metadata: GraphMetadataV1) -> Self {
+        Self {
+            repo_id: metadata.repo_id,
+            last_updated: metadata.last_updated,
+            version: metadata.version,
+            file_hashes: metadata.file_hashes,
+            total_nodes: metadata.total_nodes,
+            total_edges: metadata.total_edges,
+            schema_version: SCHEMA_VERSION.to_string(),
+            commit: None,
+        }
+    }
+}
+
+impl GraphMetadata {
+    /// Decode bincode-encoded metadata of any known schema version
+    ///
+    /// Returns the metadata and whether it was migrated from an older layout.
+    pub fn from_bincode(data: &[u8]) -> bincode::Result<(Self, bool)> {
+        match bincode::deserialize::<Self>(data) {
+            Ok(metadata) => Ok((metadata, false)),
+            Err(error) => match bincode::deserialize::<GraphMetadataV1>(data) {
+                Ok(legacy) if legacy.schema_version == "1.0" => Ok((legacy.into(), true)),
+                _ => Err(error),
+            },
+        }
+    }
 }
 
 impl SerializableGraph {
@@ -71,11 +123,27 @@ impl SerializableGraph {
                 file_hashes: HashMap::new(),
                 total_nodes: 0,
                 total_edges: 0,
-                schema_version: "1.0".to_string(),
+                schema_version: SCHEMA_VERSION.to_string(),
+                commit: None,
             },
         }
     }
 
+    /// Create an empty snapshot of `repo_id` at `commit`
+    ///
+    /// The graph is stored under [`SerializableGraph::snapshot_key`] so that
+    /// snapshots of several commits of one repository can coexist.
+    pub fn snapshot(repo_id: &str, commit: &str) -> Self {
+        let mut graph = Self::new(Self::snapshot_key(repo_id, commit));
+        graph.metadata.commit = Some(commit.to_string());
+        graph
+    }
+
+    /// Storage key of the snapshot of `repo_id` at `commit`
+    pub fn snapshot_key(repo_id: &str, commit: &str) -> String {
+        format!("{repo_id}@{commit}")
+    }
+
     /// Add a node to the graph
     pub fn add_node(&mut self, node: SerializableNode) {
         self.nodes.push(node);
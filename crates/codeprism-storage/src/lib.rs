@@ -55,6 +55,56 @@ pub trait GraphStorage: Send + Sync {
 
     /// Check if a graph exists
     async fn graph_exists(&self, repo_id: &str) -> Result<bool>;
+
+    /// List the snapshot keys and metadata of `repo_id`, newest first
+    async fn list_snapshots(&self, repo_id: &str) -> Result<Vec<(String, GraphMetadata)>> {
+        let prefix = SerializableGraph::snapshot_key(repo_id, "");
+        let mut snapshots = Vec::new();
+        for key in self.list_repositories().await? {
+            if !key.starts_with(&prefix) {
+                continue;
+            }
+            if let Some(metadata) = self.get_graph_metadata(&key).await? {
+                snapshots.push((key, metadata));
+            }
+        }
+        snapshots.sort_by_key(|(_, metadata)| std::cmp::Reverse(metadata.last_updated));
+        Ok(snapshots)
+    }
+
+    /// Load the snapshot of `repo_id` at `commit`
+    ///
+    /// Falls back to the most recent snapshot of the repository when there is
+    /// none for `commit`, so callers can re-index only what changed since.
+    async fn load_snapshot(
+        &self,
+        repo_id: &str,
+        commit: &str,
+    ) -> Result<Option<SerializableGraph>> {
+        let key = SerializableGraph::snapshot_key(repo_id, commit);
+        if let Some(graph) = self.load_graph(&key).await? {
+            return Ok(Some(graph));
+        }
+        match self.list_snapshots(repo_id).await?.into_iter().next() {
+            Some((latest, _)) => self.load_graph(&latest).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Delete all but the `keep` most recent snapshots of `repo_id`
+    async fn prune_snapshots(&self, repo_id: &str, keep: usize) -> Result<usize> {
+        let stale: Vec<String> = self
+            .list_snapshots(repo_id)
+            .await?
+            .into_iter()
+            .skip(keep)
+            .map(|(key, _)| key)
+            .collect();
+        for key in &stale {
+            self.delete_graph(key).await?;
+        }
+        Ok(stale.len())
+    }
 }
 
 /// Cache storage trait for temporary data
@@ -248,6 +298,36 @@ mod tests {
         assert_eq!(storage.config().backend, StorageBackend::InMemory);
     }
 
+    #[tokio::test]
+    async fn test_maintenance_applies_retention_period() {
+        let temp_dir = tempdir().unwrap();
+        let config = StorageConfig::file_based(temp_dir.path().to_path_buf())
+            .with_retention_period(Duration::from_secs(86400));
+        let storage = StorageManager::new(config).await.unwrap();
+
+        for (id, age) in [("expired", 86400 * 2), ("fresh", 60)] {
+            let result = AnalysisResult {
+                id: id.to_string(),
+                repo_id: "repo".to_string(),
+                analysis_type: "duplicates".to_string(),
+                timestamp: SystemTime::now() - Duration::from_secs(age),
+                data: serde_json::Value::Null,
+                metadata: HashMap::new(),
+            };
+            storage.analysis().store_analysis(&result).await.unwrap();
+        }
+
+        storage.maintenance().await.unwrap();
+
+        let remaining = storage
+            .analysis()
+            .find_analysis("repo", None, None)
+            .await
+            .unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "fresh");
+    }
+
     #[tokio::test]
     async fn test_cache_operations() {
         let temp_dir = tempdir().unwrap();
@@ -0,0 +1,378 @@
+//! Draft test specifications from recorded sessions
+//!
+//! Every recorded `tools/call`, `resources/read` and `prompts/get` becomes a
+//! test case whose input is the recorded request and whose `expected.fields`
+//! pin the recorded result. The draft is a starting point: volatile values
+//! such as timestamps still need to be relaxed by hand.
+
+use super::{Cassette, Exchange};
+use crate::generator::identifier;
+use crate::spec::{
+    ExpectedOutput, FieldValidation, PromptSpec, ResourceSpec, ServerCapabilities, ServerConfig,
+    TestCase, TestSpecification, ToolSpec,
+};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// Maximum number of field checks generated for one response
+const MAX_FIELDS: usize = 25;
+
+/// Strings longer than this are checked by type instead of value
+const MAX_PINNED_STRING_LEN: usize = 200;
+
+/// Build a draft specification from the exchanges in `cassette`
+pub fn draft_specification(cassette: &Cassette) -> TestSpecification {
+    let mut tools: Vec<ToolSpec> = Vec::new();
+    let mut resources: Vec<ResourceSpec> = Vec::new();
+    let mut prompts: Vec<PromptSpec> = Vec::new();
+    let mut names = TestNames::default();
+
+    for exchange in &cassette.exchanges {
+        let name = |key| exchange.params.get(key).and_then(Value::as_str);
+        match exchange.method.as_str() {
+            "tools/call" => {
+                let Some(tool_name) = name("name") else {
+                    continue;
+                };
+                let input = exchange
+                    .params
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or(json!({}));
+                let test = test_case(names.next(tool_name), input, exchange);
+                match tools.iter_mut().find(|tool| tool.name == tool_name) {
+                    Some(tool) => tool.tests.push(test),
+                    None => tools.push(ToolSpec {
+                        name: tool_name.to_string(),
+                        description: listed(cassette, "tools/list", "tools", "name", tool_name)
+                            .and_then(|tool| description(tool, "description")),
+                        input_schema: None,
+                        output_schema: None,
+                        parameters: None,
+                        matrix: None,
+                        tests: vec![test],
+                    }),
+                }
+            }
+            "resources/read" => {
+                let Some(uri) = name("uri") else { continue };
+                let listing = listed(cassette, "resources/list", "resources", "uri", uri);
+                let resource_name = listing
+                    .and_then(|resource| description(resource, "name"))
+                    .unwrap_or_else(|| uri.to_string());
+                let test = test_case(names.next(&resource_name), json!({}), exchange);
+                match resources
+                    .iter_mut()
+                    .find(|resource| resource.uri_template == uri)
+                {
+                    Some(resource) => resource.tests.push(test),
+                    None => resources.push(ResourceSpec {
+                        uri_template: uri.to_string(),
+                        name: resource_name,
+                        mime_type: listing.and_then(|resource| description(resource, "mimeType")),
+                        tests: vec![test],
+                    }),
+                }
+            }
+            "prompts/get" => {
+                let Some(prompt_name) = name("name") else {
+                    continue;
+                };
+                let input = exchange
+                    .params
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or(json!({}));
+                let test = test_case(names.next(prompt_name), input, exchange);
+                match prompts.iter_mut().find(|prompt| prompt.name == prompt_name) {
+                    Some(prompt) => prompt.tests.push(test),
+                    None => prompts.push(PromptSpec {
+                        name: prompt_name.to_string(),
+                        description: listed(
+                            cassette,
+                            "prompts/list",
+                            "prompts",
+                            "name",
+                            prompt_name,
+                        )
+                        .and_then(|prompt| description(prompt, "description")),
+                        arguments: Vec::new(),
+                        tests: vec![test],
+                    }),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let server = &cassette.server;
+    TestSpecification {
+        name: server
+            .name
+            .clone()
+            .unwrap_or_else(|| "Recorded MCP Server".to_string()),
+        version: server
+            .version
+            .clone()
+            .unwrap_or_else(|| "1.0.0".to_string()),
+        description: Some(format!(
+            "Draft generated from a session recorded at {}",
+            cassette.recorded_at.to_rfc3339()
+        )),
+        capabilities: capabilities(
+            cassette,
+            !tools.is_empty(),
+            !resources.is_empty(),
+            !prompts.is_empty(),
+        ),
+        server: ServerConfig {
+            transport: server.transport.clone(),
+            ..ServerConfig::stdio(server.command.clone(), server.args.clone())
+        },
+        tools: (!tools.is_empty()).then_some(tools),
+        resources: (!resources.is_empty()).then_some(resources),
+        prompts: (!prompts.is_empty()).then_some(prompts),
+        test_config: None,
+        metadata: None,
+        validation_scripts: None,
+    }
+}
+
+fn test_case(name: String, input: Value, exchange: &Exchange) -> TestCase {
+    let expected = match &exchange.error {
+        Some(error) => ExpectedOutput {
+            error: true,
+            error_code: i32::try_from(error.code).ok(),
+            error_message_contains: Some(error.message.clone()),
+            ..ExpectedOutput::default()
+        },
+        None => ExpectedOutput {
+            fields: exchange
+                .result
+                .as_ref()
+                .map(response_fields)
+                .unwrap_or_default(),
+            ..ExpectedOutput::default()
+        },
+    };
+
+    TestCase {
+        name,
+        description: Some(format!(
+            "Recorded {} ({}ms)",
+            exchange.method, exchange.duration_ms
+        )),
+        input,
+        expected,
+        tags: vec!["recorded".to_string()],
+        ..TestCase::default()
+    }
+}
+
+/// Field checks pinning the leaves of a recorded result
+pub fn response_fields(result: &Value) -> Vec<FieldValidation> {
+    let mut fields = Vec::new();
+    collect_fields(result, "$".to_string(), &mut fields);
+    fields
+}
+
+fn collect_fields(value: &Value, path: String, fields: &mut Vec<FieldValidation>) {
+    if fields.len() >= MAX_FIELDS {
+        return;
+    }
+
+    let check = |value: Option<Value>, field_type: Option<&str>| FieldValidation {
+        path: path.clone(),
+        value,
+        field_type: field_type.map(str::to_string),
+        required: true,
+        pattern: None,
+        min: None,
+        max: None,
+    };
+
+    match value {
+        Value::Object(object) if !object.is_empty() => {
+            for (key, child) in object {
+                collect_fields(child, child_path(&path, key), fields);
+            }
+        }
+        Value::Array(items) if !items.is_empty() => {
+            for (index, item) in items.iter().enumerate() {
+                collect_fields(item, format!("{}[{}]", path, index), fields);
+            }
+        }
+        Value::Object(_) => fields.push(check(None, Some("object"))),
+        Value::Array(_) => fields.push(check(None, Some("array"))),
+        Value::Null => fields.push(check(None, Some("null"))),
+        Value::String(text) if text.chars().count() > MAX_PINNED_STRING_LEN => {
+            fields.push(check(None, Some("string")))
+        }
+        scalar => fields.push(check(Some(scalar.clone()), None)),
+    }
+}
+
+/// JSONPath of `key` in the object at `path`
+pub(crate) fn child_path(path: &str, key: &str) -> String {
+    let is_identifier = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        format!("{}.{}", path, key)
+    } else {
+        format!("{}['{}']", path, key.replace('\'', "\\'"))
+    }
+}
+
+/// Entry of a recorded list response whose `key` equals `value`
+fn listed<'a>(
+    cassette: &'a Cassette,
+    method: &str,
+    list: &str,
+    key: &str,
+    value: &str,
+) -> Option<&'a Value> {
+    cassette
+        .exchanges_for(method)
+        .filter_map(|exchange| exchange.result.as_ref()?.get(list)?.as_array())
+        .flatten()
+        .find(|entry| entry.get(key).and_then(Value::as_str) == Some(value))
+}
+
+fn description(entry: &Value, key: &str) -> Option<String> {
+    entry.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+/// Capabilities the server announced, or those the session exercised
+fn capabilities(
+    cassette: &Cassette,
+    tools: bool,
+    resources: bool,
+    prompts: bool,
+) -> ServerCapabilities {
+    let announced = cassette
+        .first_result("initialize")
+        .and_then(|result| result.get("capabilities"));
+    let has = |name: &str| announced.is_some_and(|capabilities| capabilities.get(name).is_some());
+
+    ServerCapabilities {
+        tools: tools || has("tools"),
+        resources: resources || has("resources"),
+        prompts: prompts || has("prompts"),
+        sampling: has("sampling"),
+        logging: has("logging"),
+        experimental: None,
+    }
+}
+
+/// Unique, identifier-like test names derived from the target name
+#[derive(Default)]
+struct TestNames {
+    counts: HashMap<String, usize>,
+}
+
+impl TestNames {
+    fn next(&mut self, target: &str) -> String {
+        let base = identifier(target);
+        let count = self.counts.entry(base.clone()).or_default();
+        *count += 1;
+        format!("{}_{}", base, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::SpecificationLoader;
+
+    #[test]
+    fn test_draft_specification_from_cassette() {
+        let spec = draft_specification(&super::super::tests::sample_cassette());
+
+        assert_eq!(spec.name, "echo-server");
+        assert_eq!(spec.version, "1.2.0");
+        assert_eq!(spec.server.command, "node");
+        assert!(spec.capabilities.tools && spec.capabilities.prompts);
+        assert!(!spec.capabilities.resources);
+        assert!(spec.resources.is_none());
+
+        let tools = spec.tools.as_ref().unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].description.as_deref(), Some("Echo text"));
+        let names: Vec<_> = tools[0]
+            .tests
+            .iter()
+            .map(|test| test.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["echo_1", "echo_2", "echo_3"]);
+
+        let first = &tools[0].tests[0];
+        assert_eq!(first.input, json!({"text": "hi"}));
+        let mut fields: Vec<_> = first
+            .expected
+            .fields
+            .iter()
+            .map(|field| (field.path.as_str(), field.value.clone()))
+            .collect();
+        fields.sort_by_key(|(path, _)| *path);
+        assert_eq!(
+            fields,
+            vec![
+                ("$.content[0].text", Some(json!("hi"))),
+                ("$.content[0].type", Some(json!("text"))),
+                ("$.isError", Some(json!(false))),
+            ]
+        );
+
+        let failed = &tools[0].tests[2].expected;
+        assert!(failed.error);
+        assert_eq!(failed.error_code, Some(-32602));
+        assert_eq!(
+            failed.error_message_contains.as_deref(),
+            Some("missing text")
+        );
+
+        // The draft is a loadable specification
+        let yaml = serde_yml::to_string(&spec).unwrap();
+        let loader = SpecificationLoader::new().unwrap();
+        assert_eq!(loader.parse_yaml(&yaml).unwrap(), spec);
+    }
+
+    #[test]
+    fn test_response_fields_paths_and_limits() {
+        let long_text = "x".repeat(MAX_PINNED_STRING_LEN + 1);
+        let fields = response_fields(&json!({
+            "mime-type": "text/plain",
+            "empty": [],
+            "missing": null,
+            "body": long_text,
+        }));
+        let mut checks: Vec<_> = fields
+            .iter()
+            .map(|field| (field.path.as_str(), field.field_type.as_deref()))
+            .collect();
+        checks.sort();
+        assert_eq!(
+            checks,
+            vec![
+                ("$.body", Some("string")),
+                ("$.empty", Some("array")),
+                ("$.missing", Some("null")),
+                ("$['mime-type']", None),
+            ]
+        );
+
+        let many: Vec<_> = (0..100).collect();
+        assert_eq!(response_fields(&json!(many)).len(), MAX_FIELDS);
+    }
+
+    #[test]
+    fn test_test_names_are_unique_identifiers() {
+        let mut names = TestNames::default();
+        assert_eq!(names.next("Read File"), "read_file_1");
+        assert_eq!(names.next("read-file"), "read_file_2");
+        assert_eq!(names.next("file:///a.txt"), "file____a_txt_1");
+    }
+}
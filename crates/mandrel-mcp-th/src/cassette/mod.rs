@@ -0,0 +1,255 @@
+//! Record-and-replay of MCP sessions
+//!
+//! A cassette is the list of JSON-RPC exchanges of one client↔server session.
+//! `moth record` sits between an MCP client and a real server as a stdio
+//! proxy, forwarding every request through [`crate::client::McpClient`] and
+//! writing each exchange to the cassette. `moth replay` serves the recorded
+//! responses as a fake server, or drafts a [`crate::spec::TestSpecification`]
+//! from them.
+//!
+//! Cassettes are YAML, or JSON when the file name ends in `.json`.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub mod draft;
+pub mod recorder;
+pub mod replayer;
+pub mod session;
+
+pub use draft::draft_specification;
+pub use recorder::{McpForwarder, Recording};
+pub use replayer::Replayer;
+pub use session::{serve_session, RpcError, SessionHandler};
+
+/// Current cassette format version
+pub const CASSETTE_VERSION: u32 = 1;
+
+/// Recorded MCP session
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Cassette {
+    /// Cassette format version
+    pub version: u32,
+    /// When recording started
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    /// Server the session was recorded against
+    pub server: CassetteServer,
+    /// Exchanges in the order the client issued them
+    #[serde(default)]
+    pub exchanges: Vec<Exchange>,
+}
+
+/// Server a cassette was recorded against
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CassetteServer {
+    /// Command used to launch the server
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Transport the server was reached over
+    #[serde(default = "default_transport")]
+    pub transport: String,
+    /// Name the server reported during initialization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Version the server reported during initialization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+/// One JSON-RPC request and the server's answer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Exchange {
+    pub method: String,
+    #[serde(default)]
+    pub params: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    /// Time the server took to answer
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl Exchange {
+    /// The recorded answer as the handler outcome it was produced from
+    pub fn outcome(&self) -> std::result::Result<serde_json::Value, RpcError> {
+        match &self.error {
+            Some(error) => Err(error.clone()),
+            None => Ok(self.result.clone().unwrap_or(serde_json::Value::Null)),
+        }
+    }
+}
+
+impl Default for CassetteServer {
+    fn default() -> Self {
+        Self {
+            command: String::new(),
+            args: Vec::new(),
+            transport: default_transport(),
+            name: None,
+            version: None,
+        }
+    }
+}
+
+impl Cassette {
+    /// Create an empty cassette for `server`
+    pub fn new(server: CassetteServer) -> Self {
+        Self {
+            version: CASSETTE_VERSION,
+            recorded_at: chrono::Utc::now(),
+            server,
+            exchanges: Vec::new(),
+        }
+    }
+
+    /// Exchanges recorded for `method`
+    pub fn exchanges_for(&self, method: &str) -> impl Iterator<Item = &Exchange> {
+        let method = method.to_string();
+        self.exchanges
+            .iter()
+            .filter(move |exchange| exchange.method == method)
+    }
+
+    /// Result of the first successful exchange recorded for `method`
+    pub fn first_result(&self, method: &str) -> Option<&serde_json::Value> {
+        self.exchanges_for(method)
+            .find_map(|exchange| exchange.result.as_ref())
+    }
+
+    /// Load a cassette from a YAML or JSON file
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await?;
+        let cassette: Cassette = if is_json(path) {
+            serde_json::from_str(&content)?
+        } else {
+            serde_yml::from_str(&content)?
+        };
+
+        if cassette.version > CASSETTE_VERSION {
+            return Err(Error::config(format!(
+                "Cassette {} has format version {}, this moth supports up to {}",
+                path.display(),
+                cassette.version,
+                CASSETTE_VERSION
+            )));
+        }
+        Ok(cassette)
+    }
+
+    /// Write the cassette as YAML, or JSON for `.json` paths
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        let content = if is_json(path) {
+            serde_json::to_string_pretty(self)?
+        } else {
+            serde_yml::to_string(self)?
+        };
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+fn default_transport() -> String {
+    "stdio".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    pub(crate) fn sample_cassette() -> Cassette {
+        let mut cassette = Cassette::new(CassetteServer {
+            command: "node".to_string(),
+            args: vec!["server.js".to_string()],
+            transport: "stdio".to_string(),
+            name: Some("echo-server".to_string()),
+            version: Some("1.2.0".to_string()),
+        });
+        cassette.exchanges = vec![
+            Exchange {
+                method: "initialize".to_string(),
+                params: json!({"protocolVersion": "2025-06-18", "clientInfo": {"name": "a"}}),
+                result: Some(json!({
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {"tools": {}, "prompts": {}},
+                    "serverInfo": {"name": "echo-server", "version": "1.2.0"}
+                })),
+                error: None,
+                duration_ms: 3,
+            },
+            Exchange {
+                method: "tools/list".to_string(),
+                params: json!({}),
+                result: Some(json!({"tools": [
+                    {"name": "echo", "description": "Echo text", "inputSchema": {"type": "object"}}
+                ]})),
+                error: None,
+                duration_ms: 1,
+            },
+            Exchange {
+                method: "tools/call".to_string(),
+                params: json!({"name": "echo", "arguments": {"text": "hi"}}),
+                result: Some(
+                    json!({"content": [{"type": "text", "text": "hi"}], "isError": false}),
+                ),
+                error: None,
+                duration_ms: 2,
+            },
+            Exchange {
+                method: "tools/call".to_string(),
+                params: json!({"name": "echo", "arguments": {"text": "bye"}}),
+                result: Some(
+                    json!({"content": [{"type": "text", "text": "bye"}], "isError": false}),
+                ),
+                error: None,
+                duration_ms: 2,
+            },
+            Exchange {
+                method: "tools/call".to_string(),
+                params: json!({"name": "echo", "arguments": {}}),
+                result: None,
+                error: Some(RpcError::new(-32602, "missing text")),
+                duration_ms: 1,
+            },
+        ];
+        cassette
+    }
+
+    #[tokio::test]
+    async fn test_cassette_round_trips_as_yaml_and_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let cassette = sample_cassette();
+
+        for file_name in ["session.yaml", "session.json"] {
+            let path = dir.path().join(file_name);
+            cassette.save(&path).await.unwrap();
+            assert_eq!(Cassette::load(&path).await.unwrap(), cassette);
+        }
+
+        let content = tokio::fs::read_to_string(dir.path().join("session.json"))
+            .await
+            .unwrap();
+        assert!(content.trim_start().starts_with('{'));
+    }
+
+    #[tokio::test]
+    async fn test_newer_cassette_versions_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("future.yaml");
+        let mut cassette = sample_cassette();
+        cassette.version = CASSETTE_VERSION + 1;
+        cassette.save(&path).await.unwrap();
+
+        assert!(matches!(Cassette::load(&path).await, Err(Error::Config(_))));
+    }
+}
@@ -0,0 +1,374 @@
+//! Recording proxy for `moth record`
+//!
+//! [`McpForwarder`] answers a client's requests by issuing them to a real
+//! server through [`McpClient`]; [`Recording`] wraps any handler and appends
+//! every exchange to a cassette.
+
+use super::session::{codes, RpcError, SessionHandler};
+use super::{is_json, Cassette, Exchange};
+use crate::client::McpClient;
+use crate::error::{Error, Result};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::Instant;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tracing::warn;
+
+/// Forwards session requests to a connected server
+pub struct McpForwarder<'a> {
+    client: &'a McpClient,
+}
+
+impl<'a> McpForwarder<'a> {
+    pub fn new(client: &'a McpClient) -> Self {
+        Self { client }
+    }
+
+    fn initialize_result(&self) -> std::result::Result<Value, RpcError> {
+        let info = self.client.server_info().ok_or_else(|| {
+            RpcError::new(
+                codes::INTERNAL_ERROR,
+                "Server did not complete initialization",
+            )
+        })?;
+        Ok(json!({
+            "protocolVersion": info.protocol_version,
+            "capabilities": info.capabilities,
+            "serverInfo": {"name": info.name, "version": info.version},
+        }))
+    }
+}
+
+impl SessionHandler for McpForwarder<'_> {
+    async fn handle(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<Value, RpcError> {
+        let client = self.client;
+        // The client already initialized the server when it connected, so the
+        // handshake is answered from what the server reported then
+        match method {
+            "initialize" => self.initialize_result(),
+            "ping" => Ok(json!({})),
+            "tools/list" => to_result(
+                client
+                    .list_tools()
+                    .await
+                    .map(|tools| json!({"tools": tools})),
+            ),
+            "tools/call" => {
+                let name = required_str(&params, "name")?;
+                to_result(
+                    client
+                        .call_tool(name, params.get("arguments").cloned())
+                        .await,
+                )
+            }
+            "resources/list" => to_result(
+                client
+                    .list_resources()
+                    .await
+                    .map(|resources| json!({"resources": resources})),
+            ),
+            "resources/read" => {
+                to_result(client.read_resource(required_str(&params, "uri")?).await)
+            }
+            "prompts/list" => to_result(
+                client
+                    .list_prompts()
+                    .await
+                    .map(|prompts| json!({"prompts": prompts})),
+            ),
+            "prompts/get" => {
+                let name = required_str(&params, "name")?;
+                to_result(
+                    client
+                        .get_prompt(name, params.get("arguments").cloned())
+                        .await,
+                )
+            }
+            other => Err(RpcError::new(
+                codes::METHOD_NOT_FOUND,
+                format!("Method '{}' cannot be proxied", other),
+            )),
+        }
+    }
+}
+
+/// Appends every exchange answered by the wrapped handler to a cassette
+pub struct Recording<H> {
+    inner: H,
+    cassette: Cassette,
+    file: Option<CassetteFile>,
+}
+
+impl<H: SessionHandler> Recording<H> {
+    pub fn new(inner: H, cassette: Cassette) -> Self {
+        Self {
+            inner,
+            cassette,
+            file: None,
+        }
+    }
+
+    /// Write the cassette to `path` now and append each exchange to it as it
+    /// is recorded, so an interrupted session keeps what was recorded so far
+    pub async fn appending_to(mut self, path: &Path) -> Result<Self> {
+        self.file = Some(CassetteFile::create(path, &self.cassette).await?);
+        Ok(self)
+    }
+
+    pub fn cassette(&self) -> &Cassette {
+        &self.cassette
+    }
+
+    pub fn into_cassette(self) -> Cassette {
+        self.cassette
+    }
+}
+
+impl<H: SessionHandler> SessionHandler for Recording<H> {
+    async fn handle(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> std::result::Result<Value, RpcError> {
+        let start = Instant::now();
+        let outcome = self.inner.handle(method, params.clone()).await;
+
+        let (result, error) = match &outcome {
+            Ok(result) => (Some(result.clone()), None),
+            Err(error) => (None, Some(error.clone())),
+        };
+        let exchange = Exchange {
+            method: method.to_string(),
+            params,
+            result,
+            error,
+            duration_ms: start.elapsed().as_millis() as u64,
+        };
+        if let Some(file) = &mut self.file {
+            if let Err(e) = file.append(&exchange).await {
+                warn!("Failed to save exchange to cassette: {}", e);
+            }
+        }
+        self.cassette.exchanges.push(exchange);
+
+        outcome
+    }
+}
+
+/// Closes the `exchanges` array of a JSON cassette and the cassette object
+const JSON_TAIL: &[u8] = b"\n]}\n";
+
+/// Cassette file that stays complete while exchanges are appended to it
+///
+/// YAML exchanges are written as further items of the trailing `exchanges`
+/// sequence. JSON exchanges are written over the closing [`JSON_TAIL`], which
+/// is then written again. Every append is synced to disk, so a crash loses at
+/// most the exchange being written.
+struct CassetteFile {
+    file: tokio::fs::File,
+    json: bool,
+    /// Exchanges in the file
+    exchanges: usize,
+    /// Where the next exchange goes
+    end: u64,
+}
+
+impl CassetteFile {
+    async fn create(path: &Path, cassette: &Cassette) -> Result<Self> {
+        let json = is_json(path);
+        let header = Cassette {
+            exchanges: Vec::new(),
+            ..cassette.clone()
+        };
+        let mut content = if json {
+            serde_json::to_string(&header)?
+        } else {
+            serde_yml::to_string(&header)?
+        };
+        // Exchanges are the last field; drop the empty list so that they can
+        // be written after it
+        let empty = if json { "]}" } else { "exchanges: []\n" };
+        debug_assert!(content.ends_with(empty));
+        content.truncate(content.len() - empty.len());
+        if json {
+            content.push('\n');
+        }
+
+        let mut file = tokio::fs::File::create(path).await?;
+        file.write_all(content.as_bytes()).await?;
+        let mut cassette_file = Self {
+            file,
+            json,
+            exchanges: 0,
+            end: content.len() as u64,
+        };
+        cassette_file.write_tail().await?;
+        for exchange in &cassette.exchanges {
+            cassette_file.append(exchange).await?;
+        }
+        Ok(cassette_file)
+    }
+
+    async fn append(&mut self, exchange: &Exchange) -> Result<()> {
+        let mut content = String::new();
+        if self.json {
+            if self.exchanges > 0 {
+                content.push_str(",\n");
+            }
+            content.push_str(&serde_json::to_string(exchange)?);
+        } else {
+            if self.exchanges == 0 {
+                content.push_str("exchanges:\n");
+            }
+            content.push_str(&serde_yml::to_string(std::slice::from_ref(exchange))?);
+        }
+
+        self.file.seek(SeekFrom::Start(self.end)).await?;
+        self.file.write_all(content.as_bytes()).await?;
+        self.end += content.len() as u64;
+        self.exchanges += 1;
+        self.write_tail().await
+    }
+
+    async fn write_tail(&mut self) -> Result<()> {
+        if self.json {
+            self.file.write_all(JSON_TAIL).await?;
+        }
+        self.file.sync_data().await?;
+        Ok(())
+    }
+}
+
+fn required_str<'p>(params: &'p Value, key: &str) -> std::result::Result<&'p str, RpcError> {
+    params.get(key).and_then(Value::as_str).ok_or_else(|| {
+        RpcError::new(
+            codes::INVALID_PARAMS,
+            format!("Missing string parameter '{}'", key),
+        )
+    })
+}
+
+fn to_result<T: Serialize>(result: Result<T>) -> std::result::Result<Value, RpcError> {
+    result.map_err(rpc_error).and_then(|value| {
+        serde_json::to_value(value).map_err(|e| RpcError::new(codes::INTERNAL_ERROR, e.to_string()))
+    })
+}
+
+/// The server's error as it answered, or an internal error when the request
+/// never got an answer
+fn rpc_error(error: Error) -> RpcError {
+    match error {
+        Error::Mcp(error) => RpcError {
+            code: error.code.0.into(),
+            message: error.message.into_owned(),
+            data: error.data,
+        },
+        other => RpcError::new(codes::INTERNAL_ERROR, other.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{serve_session, CassetteServer, Replayer};
+    use super::*;
+    use crate::client::ServerConfig;
+    use tokio::io::BufReader;
+
+    #[tokio::test]
+    async fn test_recording_captures_results_and_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.yaml");
+        let source = super::super::tests::sample_cassette();
+        let mut recording = Recording::new(
+            Replayer::new(source.clone()),
+            Cassette::new(CassetteServer::default()),
+        )
+        .appending_to(&path)
+        .await
+        .unwrap();
+
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"tools/call","params":{"name":"echo","arguments":{"text":"hi"}}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"tools/call","params":{"name":"echo","arguments":{}}}"#,
+        ]
+        .join("\n");
+        serve_session(BufReader::new(input.as_bytes()), Vec::new(), &mut recording)
+            .await
+            .unwrap();
+
+        let recorded = Cassette::load(&path).await.unwrap();
+        assert_eq!(&recorded, recording.cassette());
+        assert_eq!(recorded.exchanges.len(), 2);
+        assert_eq!(recorded.exchanges[0].result, source.exchanges[2].result);
+        assert_eq!(recorded.exchanges[1].error, source.exchanges[4].error);
+    }
+
+    #[tokio::test]
+    async fn test_cassette_file_is_complete_after_every_exchange() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = super::super::tests::sample_cassette();
+        for name in ["session.yaml", "session.json"] {
+            let path = dir.path().join(name);
+            let mut cassette = source.clone();
+            let exchanges = cassette.exchanges.split_off(1);
+
+            let mut file = CassetteFile::create(&path, &cassette).await.unwrap();
+            assert_eq!(Cassette::load(&path).await.unwrap(), cassette);
+            for exchange in exchanges {
+                file.append(&exchange).await.unwrap();
+                cassette.exchanges.push(exchange);
+                assert_eq!(Cassette::load(&path).await.unwrap(), cassette);
+            }
+        }
+
+        let path = dir.path().join("empty.json");
+        let empty = Cassette::new(CassetteServer::default());
+        CassetteFile::create(&path, &empty).await.unwrap();
+        assert_eq!(Cassette::load(&path).await.unwrap(), empty);
+    }
+
+    #[tokio::test]
+    async fn test_forwarder_requires_a_connected_client() {
+        let client = McpClient::new(ServerConfig::default()).await.unwrap();
+        let mut forwarder = McpForwarder::new(&client);
+
+        let error = forwarder.handle("tools/list", json!({})).await.unwrap_err();
+        assert_eq!(error.code, codes::INTERNAL_ERROR);
+
+        let error = forwarder.handle("tools/call", json!({})).await.unwrap_err();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+
+        let error = forwarder
+            .handle("sampling/createMessage", json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, codes::METHOD_NOT_FOUND);
+
+        assert_eq!(forwarder.handle("ping", json!({})).await, Ok(json!({})));
+    }
+
+    #[test]
+    fn test_upstream_errors_are_recorded_unchanged() {
+        let upstream =
+            rmcp::Error::invalid_params("'text' is required", Some(json!({"field": "text"})));
+        assert_eq!(
+            rpc_error(Error::Mcp(upstream)),
+            RpcError {
+                code: codes::INVALID_PARAMS,
+                message: "'text' is required".to_string(),
+                data: Some(json!({"field": "text"})),
+            }
+        );
+
+        let error = rpc_error(Error::connection("Tool call timeout"));
+        assert_eq!(error.code, codes::INTERNAL_ERROR);
+        assert!(error.data.is_none());
+    }
+}
@@ -0,0 +1,137 @@
+//! Fake server for `moth replay`
+//!
+//! Requests are matched to recorded exchanges by method and params, ignoring
+//! `_meta`. Requests whose params only describe the client, such as
+//! `initialize` and the list methods, fall back to matching on the method.
+//! A request recorded several times is answered with the recorded responses
+//! in order, repeating the last one once they run out.
+
+use super::session::{codes, RpcError, SessionHandler};
+use super::Cassette;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Serves the responses recorded in a cassette
+pub struct Replayer {
+    cassette: Cassette,
+    /// How often each distinct request has been answered
+    served: HashMap<String, usize>,
+}
+
+impl Replayer {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            cassette,
+            served: HashMap::new(),
+        }
+    }
+
+    /// Recorded answer for a request, advancing through repeated recordings
+    pub fn respond(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        let params = without_meta(params);
+        let mut candidates: Vec<_> = self
+            .cassette
+            .exchanges_for(method)
+            .filter(|exchange| without_meta(&exchange.params) == params)
+            .collect();
+        if candidates.is_empty() && matches_on_method(method) {
+            candidates = self.cassette.exchanges_for(method).collect();
+        }
+
+        if candidates.is_empty() {
+            let code = if self.cassette.exchanges_for(method).next().is_some() {
+                codes::INVALID_PARAMS
+            } else {
+                codes::METHOD_NOT_FOUND
+            };
+            return Err(RpcError::new(
+                code,
+                format!(
+                    "No recorded response for '{}' with params {}",
+                    method, params
+                ),
+            ));
+        }
+
+        let served = self
+            .served
+            .entry(format!("{} {}", method, params))
+            .or_default();
+        let exchange = candidates[(*served).min(candidates.len() - 1)];
+        *served += 1;
+        exchange.outcome()
+    }
+}
+
+impl SessionHandler for Replayer {
+    async fn handle(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        self.respond(method, &params)
+    }
+}
+
+fn matches_on_method(method: &str) -> bool {
+    matches!(method, "initialize" | "ping") || method.ends_with("/list")
+}
+
+fn without_meta(params: &Value) -> Value {
+    let mut params = params.clone();
+    if let Some(object) = params.as_object_mut() {
+        object.remove("_meta");
+    }
+    params
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_replayer_matches_recorded_requests() {
+        let mut replayer = Replayer::new(super::super::tests::sample_cassette());
+
+        let result = replayer
+            .respond(
+                "tools/call",
+                &json!({"name": "echo", "arguments": {"text": "bye"}, "_meta": {"progressToken": 1}}),
+            )
+            .unwrap();
+        assert_eq!(result["content"][0]["text"], json!("bye"));
+
+        let error = replayer
+            .respond("tools/call", &json!({"name": "echo", "arguments": {}}))
+            .unwrap_err();
+        assert_eq!(error.message, "missing text");
+
+        // Client details differ between sessions; the handshake still replays
+        let result = replayer
+            .respond("initialize", &json!({"clientInfo": {"name": "other"}}))
+            .unwrap();
+        assert_eq!(result["serverInfo"]["name"], json!("echo-server"));
+
+        let error = replayer
+            .respond(
+                "tools/call",
+                &json!({"name": "echo", "arguments": {"text": "new"}}),
+            )
+            .unwrap_err();
+        assert_eq!(error.code, codes::INVALID_PARAMS);
+        let error = replayer.respond("resources/read", &json!({})).unwrap_err();
+        assert_eq!(error.code, codes::METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_repeated_requests_replay_in_order() {
+        let mut cassette = super::super::tests::sample_cassette();
+        let mut second = cassette.exchanges[2].clone();
+        second.result = Some(json!({"content": [{"type": "text", "text": "again"}]}));
+        cassette.exchanges.push(second);
+        let mut replayer = Replayer::new(cassette);
+        let params = json!({"name": "echo", "arguments": {"text": "hi"}});
+
+        let texts: Vec<_> = (0..3)
+            .map(|_| replayer.respond("tools/call", &params).unwrap()["content"][0]["text"].clone())
+            .collect();
+        assert_eq!(texts, vec![json!("hi"), json!("again"), json!("again")]);
+    }
+}
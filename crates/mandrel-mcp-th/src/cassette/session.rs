@@ -0,0 +1,176 @@
+//! Server side of a stdio MCP session
+//!
+//! MCP's stdio transport carries one JSON-RPC message per line. The session
+//! loop answers every request from the client through a [`SessionHandler`];
+//! notifications and responses to server-initiated requests need no answer
+//! and are dropped.
+
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::future::Future;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+use tracing::debug;
+
+/// JSON-RPC error codes used when answering requests
+pub mod codes {
+    pub const PARSE_ERROR: i64 = -32700;
+    pub const INVALID_REQUEST: i64 = -32600;
+    pub const METHOD_NOT_FOUND: i64 = -32601;
+    pub const INVALID_PARAMS: i64 = -32602;
+    pub const INTERNAL_ERROR: i64 = -32603;
+}
+
+/// JSON-RPC error object
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+/// Answers the requests of a session
+pub trait SessionHandler {
+    /// Produce the result or error for a `method` request with `params`
+    fn handle(
+        &mut self,
+        method: &str,
+        params: Value,
+    ) -> impl Future<Output = std::result::Result<Value, RpcError>>;
+}
+
+/// Serve the session on `input`/`output` until the client closes `input`
+///
+/// Returns the number of requests answered.
+pub async fn serve_session<R, W, H>(input: R, mut output: W, handler: &mut H) -> Result<usize>
+where
+    R: AsyncBufRead + Unpin,
+    W: AsyncWrite + Unpin,
+    H: SessionHandler,
+{
+    let mut lines = input.lines();
+    let mut answered = 0;
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<Value>(&line) {
+            Ok(message) => match answer(message, handler).await {
+                Some(response) => response,
+                None => continue,
+            },
+            Err(e) => error_response(
+                Value::Null,
+                RpcError::new(codes::PARSE_ERROR, format!("Parse error: {}", e)),
+            ),
+        };
+
+        let mut encoded = serde_json::to_vec(&response)?;
+        encoded.push(b'\n');
+        output.write_all(&encoded).await?;
+        output.flush().await?;
+        answered += 1;
+    }
+
+    Ok(answered)
+}
+
+/// Response to `message`, or `None` for messages that are not requests
+async fn answer<H: SessionHandler>(message: Value, handler: &mut H) -> Option<Value> {
+    let id = message.get("id").cloned();
+    let Some(method) = message.get("method").and_then(Value::as_str) else {
+        // A response to a server-initiated request; only invalid messages get an answer
+        return match id {
+            Some(_) if message.get("result").is_some() || message.get("error").is_some() => None,
+            _ => Some(error_response(
+                id.unwrap_or(Value::Null),
+                RpcError::new(codes::INVALID_REQUEST, "Invalid request"),
+            )),
+        };
+    };
+
+    let Some(id) = id else {
+        debug!("Ignoring notification {}", method);
+        return None;
+    };
+
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+    Some(match handler.handle(method, params).await {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(error) => error_response(id, error),
+    })
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": error})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::BufReader;
+
+    struct Echo;
+
+    impl SessionHandler for Echo {
+        async fn handle(
+            &mut self,
+            method: &str,
+            params: Value,
+        ) -> std::result::Result<Value, RpcError> {
+            match method {
+                "echo" => Ok(params),
+                other => Err(RpcError::new(codes::METHOD_NOT_FOUND, other)),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_answers_requests_only() {
+        let input = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":{"a":1}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#,
+            "",
+            r#"{"jsonrpc":"2.0","id":"x","result":{}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"nope"}"#,
+            "not json",
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+
+        let answered = serve_session(BufReader::new(input.as_bytes()), &mut output, &mut Echo)
+            .await
+            .unwrap();
+        assert_eq!(answered, 3);
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            responses[0],
+            json!({"jsonrpc": "2.0", "id": 1, "result": {"a": 1}})
+        );
+        assert_eq!(responses[1]["id"], json!(2));
+        assert_eq!(
+            responses[1]["error"]["code"],
+            json!(codes::METHOD_NOT_FOUND)
+        );
+        assert_eq!(responses[2]["id"], Value::Null);
+        assert_eq!(responses[2]["error"]["code"], json!(codes::PARSE_ERROR));
+    }
+}
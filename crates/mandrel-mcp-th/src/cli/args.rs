@@ -50,6 +50,21 @@ pub enum Commands {
 
     /// Watch files and auto-generate reports
     Watch(WatchArgs),
+
+    /// Proxy a stdio MCP session to a real server and record it to a cassette
+    Record(RecordArgs),
+
+    /// Serve a recorded cassette as a fake server, or draft a spec from it
+    Replay(ReplayArgs),
+
+    /// Generate a skeleton test specification from a server's tool schemas
+    Generate(GenerateArgs),
+
+    /// Call tools with random arguments and save reproducers for failures
+    Fuzz(FuzzArgs),
+
+    /// Replay a spec's test cases at a target concurrency or rate and report latency
+    Load(LoadArgs),
 }
 
 #[derive(Args, Debug)]
@@ -116,6 +131,26 @@ pub struct RunArgs {
     /// Stop execution on first test failure
     #[arg(long)]
     pub fail_fast: bool,
+
+    /// Write snapshot files instead of failing tests whose snapshot differs
+    #[arg(long)]
+    pub update_snapshots: bool,
+
+    /// JSON report of an earlier run to compare status and latency with
+    #[arg(long, value_name = "REPORT")]
+    pub baseline: Option<PathBuf>,
+
+    /// Run each test this many times to collect latency samples
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub repeat: u32,
+
+    /// Minimum growth of a test's median latency, in percent, reported as a slowdown
+    #[arg(long, default_value_t = 20.0)]
+    pub slowdown_threshold: f64,
+
+    /// Exit with a non-zero code when tests are slower or newly failing since the baseline
+    #[arg(long, requires = "baseline")]
+    pub fail_on_regression: bool,
 }
 
 #[derive(Args, Debug)]
@@ -287,6 +322,119 @@ pub struct ProfileShowArgs {
     pub detailed: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct RecordArgs {
+    /// Cassette file to write (YAML, or JSON with a .json extension)
+    #[arg(short = 'o', long)]
+    pub output: PathBuf,
+
+    /// Test specification whose server configuration is used
+    #[arg(short = 'c', long, conflicts_with = "server_command")]
+    pub config: Option<PathBuf>,
+
+    /// Server command and arguments, given after `--`
+    #[arg(last = true, required_unless_present = "config")]
+    pub server_command: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ReplayArgs {
+    /// Cassette file recorded with `moth record`
+    #[arg()]
+    pub cassette: PathBuf,
+
+    /// Write a draft test specification here instead of serving the cassette
+    #[arg(long)]
+    pub to_spec: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    /// Specification file to write
+    #[arg(short = 'o', long)]
+    pub output: PathBuf,
+
+    /// Test specification whose server configuration is used
+    #[arg(short = 'c', long, conflicts_with = "server_command")]
+    pub config: Option<PathBuf>,
+
+    /// Only generate valid-input cases
+    #[arg(long)]
+    pub no_negative: bool,
+
+    /// Server command and arguments, given after `--`
+    #[arg(last = true, required_unless_present = "config")]
+    pub server_command: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct FuzzArgs {
+    /// Directory for reproducer specifications
+    #[arg(short = 'o', long, default_value = "./fuzz-findings")]
+    pub output_dir: PathBuf,
+
+    /// Test specification whose server configuration is used
+    #[arg(short = 'c', long, conflicts_with = "server_command")]
+    pub config: Option<PathBuf>,
+
+    /// Random seed; a run with the same seed makes the same calls
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Calls per tool
+    #[arg(short = 'n', long, default_value = "100")]
+    pub iterations: usize,
+
+    /// Milliseconds to wait for each response before reporting a hang
+    #[arg(long, default_value = "5000")]
+    pub timeout_ms: u64,
+
+    /// Only fuzz these tools (repeatable)
+    #[arg(short = 't', long = "tool")]
+    pub tools: Vec<String>,
+
+    /// Server command and arguments, given after `--`
+    #[arg(last = true, required_unless_present = "config")]
+    pub server_command: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct LoadArgs {
+    /// Test specification whose test cases are replayed
+    #[arg()]
+    pub config: PathBuf,
+
+    /// Requests kept in flight at once
+    #[arg(short = 'c', long, default_value = "4", conflicts_with = "rate",
+          value_parser = clap::value_parser!(u32).range(1..))]
+    pub concurrency: u32,
+
+    /// Requests started per second, however fast the server answers
+    #[arg(short = 'r', long)]
+    pub rate: Option<f64>,
+
+    /// Seconds to apply load for
+    #[arg(short = 'd', long, default_value = "30")]
+    pub duration: u64,
+
+    /// Client sessions to spread requests over
+    #[arg(short = 's', long, default_value = "1",
+          value_parser = clap::value_parser!(u32).range(1..))]
+    pub sessions: u32,
+
+    /// Milliseconds to wait for each response before counting a timeout
+    #[arg(long, default_value = "30000")]
+    pub timeout_ms: u64,
+
+    /// Write the load report as JSON to this file
+    #[arg(short = 'o', long)]
+    pub output: Option<PathBuf>,
+
+    /// Exit with a non-zero code when more than this percentage of requests fail
+    #[arg(long)]
+    pub max_error_rate: Option<f64>,
+}
+
 #[derive(Args, Debug)]
 pub struct WatchArgs {
     #[command(subcommand)]
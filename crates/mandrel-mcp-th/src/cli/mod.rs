@@ -1,12 +1,20 @@
 //! Command-line interface for Mandrel MCP Test Harness
 
-use crate::client::McpClient;
+use crate::cassette::{
+    draft_specification, serve_session, Cassette, CassetteServer, McpForwarder, Recording, Replayer,
+};
+use crate::client::{McpClient, ServerConfig as ClientServerConfig};
 use crate::executor::SuiteResult;
 use crate::executor::{ExecutorConfig, TestCaseExecutor};
+use crate::fuzz::{save_reproducers, FuzzConfig, Fuzzer, StdioTarget};
+use crate::generator::SpecGenerator;
 use crate::reporting::{
-    BrandingInfo, BuiltInTemplate, ReportConfig, ReportGenerator, TemplateSource,
+    Baseline, BaselineComparison, BaselineConfig, BrandingInfo, BuiltInTemplate, ReportConfig,
+    ReportGenerator, TemplateSource,
+};
+use crate::runner::{
+    LoadConfig, LoadPacing, LoadReport, LoadRunner, RunnerConfig, TestSuiteResult, TestSuiteRunner,
 };
-use crate::runner::{RunnerConfig, TestSuiteResult, TestSuiteRunner};
 use crate::spec::SpecificationLoader;
 use clap::Parser;
 use codeprism_utils::{ChangeEvent, FileWatcher};
@@ -27,6 +35,25 @@ pub use file_manager::*;
 
 use crate::error::Result;
 
+/// Server configuration from a spec file, or for a stdio server command
+async fn resolve_server(
+    config: Option<&Path>,
+    server_command: &[String],
+) -> Result<crate::spec::ServerConfig> {
+    if let Some(config) = config {
+        let spec = SpecificationLoader::new()?.load_from_file(config).await?;
+        return Ok(spec.server);
+    }
+
+    let (command, args) = server_command
+        .split_first()
+        .ok_or_else(|| crate::error::Error::config("No server command given"))?;
+    Ok(crate::spec::ServerConfig::stdio(
+        command.clone(),
+        args.to_vec(),
+    ))
+}
+
 /// Main CLI application struct
 pub struct CliApp {
     args: Cli,
@@ -45,6 +72,11 @@ impl CliApp {
             Commands::Validate(validate_args) => self.handle_validate_command(validate_args).await,
             Commands::Profile(profile_args) => self.handle_profile_command(profile_args).await,
             Commands::Watch(watch_args) => self.handle_watch_command(watch_args).await,
+            Commands::Record(record_args) => self.handle_record_command(record_args).await,
+            Commands::Replay(replay_args) => self.handle_replay_command(replay_args).await,
+            Commands::Generate(generate_args) => self.handle_generate_command(generate_args).await,
+            Commands::Fuzz(fuzz_args) => self.handle_fuzz_command(fuzz_args).await,
+            Commands::Load(load_args) => self.handle_load_command(load_args).await,
         }
     }
 
@@ -183,7 +215,7 @@ impl CliApp {
         // 2. Initialize the client and executor
         // NOTE: This uses the ServerConfig from the loaded YAML spec
         let client_config = spec.server.clone();
-        let mut client = McpClient::new(client_config.into()).await?;
+        let mut client = McpClient::new(client_config.try_into()?).await?;
 
         // 3. Connect to the MCP server
         client.connect().await?;
@@ -194,11 +226,25 @@ impl CliApp {
         // 4. Initialize the TestSuiteRunner
         let runner_config = RunnerConfig::new()
             .with_parallel_execution(args.parallel)
-            .with_fail_fast(args.fail_fast);
+            .with_fail_fast(args.fail_fast)
+            .with_update_snapshots(args.update_snapshots)
+            .with_repetitions(args.repeat as usize);
         let mut runner = TestSuiteRunner::new(executor, runner_config);
 
-        // 5. Execute the test suite
+        // 5. Execute the test suite and compare it with the baseline run
         let suite_result = runner.run_test_suite(&args.config).await?;
+        let suite_result_converted = self.convert_to_suite_result(&suite_result);
+        let comparison = match &args.baseline {
+            Some(baseline_path) => {
+                let baseline = Baseline::load(baseline_path).await?;
+                let config = BaselineConfig {
+                    min_slowdown_ratio: args.slowdown_threshold / 100.0,
+                    ..BaselineConfig::default()
+                };
+                Some(baseline.compare(&suite_result_converted, &config))
+            }
+            None => None,
+        };
 
         // 6. Generate comprehensive reports using the advanced reporting system
         if let Some(output_dir) = &args.output {
@@ -219,13 +265,16 @@ impl CliApp {
             };
 
             // Create ReportGenerator and generate default reports (RunArgs uses default formats)
-            let report_generator = ReportGenerator::new(report_config)?;
-
-            // Convert TestSuiteResult to SuiteResult for ReportGenerator compatibility
-            let suite_result_converted = self.convert_to_suite_result(&suite_result);
+            let report_generator =
+                ReportGenerator::new(report_config)?.with_baseline(comparison.clone());
 
-            // Generate default reports: JSON, HTML, and JUnit
-            let default_formats = vec![ReportFormat::Json, ReportFormat::Html, ReportFormat::Junit];
+            // Generate default reports: JSON, HTML, and JUnit, plus Markdown
+            // for pasting baseline comparisons into reviews
+            let mut default_formats =
+                vec![ReportFormat::Json, ReportFormat::Html, ReportFormat::Junit];
+            if comparison.is_some() {
+                default_formats.push(ReportFormat::Markdown);
+            }
 
             for format in &default_formats {
                 let report_content = match format {
@@ -257,7 +306,270 @@ impl CliApp {
 
         // 7. Display summary and return exit code
         self.display_summary(&suite_result);
-        Ok(if suite_result.failed == 0 { 0 } else { 1 })
+        let regressed = match &comparison {
+            Some(comparison) => {
+                self.display_baseline_changes(comparison);
+                comparison.has_regressions()
+            }
+            None => false,
+        };
+        Ok(
+            if suite_result.failed == 0 && !(args.fail_on_regression && regressed) {
+                0
+            } else {
+                1
+            },
+        )
+    }
+
+    async fn handle_record_command(&self, args: &RecordArgs) -> Result<i32> {
+        // stdout carries the proxied session, so progress goes to stderr
+        let server_config = resolve_server(args.config.as_deref(), &args.server_command).await?;
+        let server = CassetteServer {
+            command: server_config.command.clone(),
+            args: server_config.args.clone(),
+            transport: server_config.transport.clone(),
+            ..CassetteServer::default()
+        };
+        let client_config: ClientServerConfig = server_config.try_into()?;
+
+        let mut client = McpClient::new(client_config).await?;
+        client.connect().await?;
+
+        let mut cassette = Cassette::new(server);
+        if let Some(info) = client.server_info() {
+            cassette.server.name = Some(info.name.clone());
+            cassette.server.version = Some(info.version.clone());
+        }
+
+        let mut recording = Recording::new(McpForwarder::new(&client), cassette)
+            .appending_to(&args.output)
+            .await?;
+        eprintln!("🎙️  Recording MCP session to {}", args.output.display());
+
+        let session = serve_session(
+            tokio::io::BufReader::new(tokio::io::stdin()),
+            tokio::io::stdout(),
+            &mut recording,
+        )
+        .await;
+        let recorded = recording.into_cassette().exchanges.len();
+        client.disconnect().await?;
+        session?;
+
+        eprintln!(
+            "✅ Recorded {} exchanges to {}",
+            recorded,
+            args.output.display()
+        );
+        Ok(0)
+    }
+
+    async fn handle_replay_command(&self, args: &ReplayArgs) -> Result<i32> {
+        let cassette = Cassette::load(&args.cassette).await?;
+
+        match &args.to_spec {
+            Some(spec_path) => {
+                let spec = draft_specification(&cassette);
+                tokio::fs::write(spec_path, serde_yml::to_string(&spec)?).await?;
+
+                let tests = spec.tools.iter().flatten().map(|tool| tool.tests.len());
+                let tests = tests
+                    .chain(spec.resources.iter().flatten().map(|r| r.tests.len()))
+                    .chain(spec.prompts.iter().flatten().map(|p| p.tests.len()))
+                    .sum::<usize>();
+                println!(
+                    "📝 Drafted {} test cases from {} to {}",
+                    tests,
+                    args.cassette.display(),
+                    spec_path.display()
+                );
+            }
+            None => {
+                let mut replayer = Replayer::new(cassette);
+                serve_session(
+                    tokio::io::BufReader::new(tokio::io::stdin()),
+                    tokio::io::stdout(),
+                    &mut replayer,
+                )
+                .await?;
+            }
+        }
+
+        Ok(0)
+    }
+
+    async fn handle_generate_command(&self, args: &GenerateArgs) -> Result<i32> {
+        let server_config = resolve_server(args.config.as_deref(), &args.server_command).await?;
+        let mut client = McpClient::new(server_config.clone().try_into()?).await?;
+        client.connect().await?;
+
+        let tools = client.list_tools().await;
+        let mut generator =
+            SpecGenerator::new(server_config).with_negative_cases(!args.no_negative);
+        if let Some(info) = client.server_info() {
+            generator = generator.with_server_info(info.name.clone(), info.version.clone());
+        }
+        client.disconnect().await?;
+
+        let spec = generator.generate(&tools?);
+        tokio::fs::write(&args.output, serde_yml::to_string(&spec)?).await?;
+
+        let tests: usize = spec
+            .tools
+            .iter()
+            .flatten()
+            .map(|tool| tool.tests.len())
+            .sum();
+        println!(
+            "📝 Generated {} test cases for {} tools to {}",
+            tests,
+            spec.tools.as_ref().map_or(0, Vec::len),
+            args.output.display()
+        );
+        Ok(0)
+    }
+
+    async fn handle_fuzz_command(&self, args: &FuzzArgs) -> Result<i32> {
+        let server_config = resolve_server(args.config.as_deref(), &args.server_command).await?;
+        let seed = args
+            .seed
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis() as u64);
+        let mut target = StdioTarget::new(
+            server_config.clone(),
+            Duration::from_millis(args.timeout_ms),
+        )?;
+
+        let tools = target.list_tools().await?;
+        if let Some(unknown) = args
+            .tools
+            .iter()
+            .find(|name| !tools.iter().any(|tool| &tool.name == *name))
+        {
+            target.shutdown().await;
+            return Err(crate::error::Error::config(format!(
+                "Server has no tool named '{}'",
+                unknown
+            )));
+        }
+
+        println!(
+            "🐛 Fuzzing {} tools with seed {} ({} calls each)",
+            if args.tools.is_empty() {
+                tools.len()
+            } else {
+                args.tools.len()
+            },
+            seed,
+            args.iterations
+        );
+        let fuzzer = Fuzzer::new(FuzzConfig {
+            seed,
+            iterations: args.iterations,
+            tools: args.tools.clone(),
+            ..FuzzConfig::default()
+        });
+        let report = fuzzer.run(&mut target, &tools).await;
+        target.shutdown().await;
+
+        if report.findings.is_empty() {
+            println!("✅ No failures in {} calls", report.calls);
+            return Ok(0);
+        }
+
+        let paths = save_reproducers(&args.output_dir, &server_config, &report).await?;
+        println!(
+            "❌ {} failures in {} calls:",
+            report.findings.len(),
+            report.calls
+        );
+        for (finding, path) in report.findings.iter().zip(&paths) {
+            println!(
+                "  {} in '{}': {}",
+                finding.kind, finding.tool, finding.message
+            );
+            println!("    arguments: {}", finding.arguments);
+            println!("    reproducer: {}", path.display());
+        }
+        println!("Re-run with --seed {} to reproduce", seed);
+        Ok(1)
+    }
+
+    async fn handle_load_command(&self, args: &LoadArgs) -> Result<i32> {
+        let spec = SpecificationLoader::new()?
+            .load_from_file(&args.config)
+            .await?;
+        let pacing = match args.rate {
+            Some(rate) => LoadPacing::Rate(rate),
+            None => LoadPacing::Concurrency(args.concurrency as usize),
+        };
+        let config = LoadConfig::new()
+            .with_pacing(pacing)
+            .with_duration(Duration::from_secs(args.duration))
+            .with_sessions(args.sessions as usize)
+            .with_request_timeout(Duration::from_millis(args.timeout_ms));
+
+        println!(
+            "🏋️  Applying load to '{}' for {}s over {} session(s)",
+            spec.name, args.duration, args.sessions
+        );
+        let report = LoadRunner::new(config).run(&spec).await?;
+
+        if let Some(output) = &args.output {
+            tokio::fs::write(output, serde_json::to_string_pretty(&report)?).await?;
+            println!("  📄 Wrote load report: {}", output.display());
+        }
+        self.display_load_report(&report);
+
+        let error_rate = report.metrics.overall.error_rate * 100.0;
+        Ok(match args.max_error_rate {
+            Some(max) if error_rate > max => {
+                println!(
+                    "❌ Error rate {:.2}% exceeds the maximum of {}%",
+                    error_rate, max
+                );
+                1
+            }
+            _ => 0,
+        })
+    }
+
+    fn display_load_report(&self, report: &LoadReport) {
+        let metrics = &report.metrics;
+        println!("\n✅ Load Run Finished ✅");
+        println!("Suite: {}", report.suite_name);
+        println!("Duration: {:.2}s", metrics.elapsed.as_secs_f64());
+        println!(
+            "\n  {:<32} {:>8} {:>9} {:>8} {:>9} {:>9} {:>9}",
+            "Test", "Requests", "Req/s", "Errors", "p50 ms", "p95 ms", "p99 ms"
+        );
+        for stats in metrics.per_test.iter().chain([&metrics.overall]) {
+            println!(
+                "  {:<32} {:>8} {:>9.1} {:>7.2}% {:>9.1} {:>9.1} {:>9.1}",
+                stats.name,
+                stats.requests,
+                stats.throughput,
+                stats.error_rate * 100.0,
+                stats.p50_ms,
+                stats.p95_ms,
+                stats.p99_ms
+            );
+        }
+        let overall = &metrics.overall;
+        println!(
+            "\nFailed: {}, Errors: {}, Timeouts: {}",
+            overall.failed, overall.errors, overall.timeouts
+        );
+        match &metrics.server_memory {
+            Some(memory) => println!(
+                "Server RSS: {}MB initial, {}MB peak, {}MB final ({} samples)",
+                memory.initial_mb, memory.peak_mb, memory.final_mb, memory.samples
+            ),
+            None => println!("Server RSS: not available for this server"),
+        }
+        for skipped in &report.skipped {
+            println!("⏩ Skipped {}", skipped);
+        }
     }
 
     fn display_summary(&self, result: &TestSuiteResult) {
@@ -270,6 +582,31 @@ impl CliApp {
         println!("Duration: {:.2}s", result.total_duration.as_secs_f64());
     }
 
+    fn display_baseline_changes(&self, comparison: &BaselineComparison) {
+        println!(
+            "\n📊 Changes since baseline {} ({} tests compared)",
+            comparison.baseline_file.display(),
+            comparison.compared
+        );
+        if comparison.changes.is_empty() {
+            println!("  No changes");
+        }
+        for change in &comparison.changes {
+            let icon = if change.kind.is_regression() {
+                "⚠️ "
+            } else {
+                "ℹ️ "
+            };
+            println!(
+                "  {} {} {}: {}",
+                icon,
+                change.test_name,
+                change.kind.label(),
+                change.message
+            );
+        }
+    }
+
     async fn handle_validate_command(&self, args: &ValidateArgs) -> Result<i32> {
         // 1. Load and parse configuration file
         let spec_loader = SpecificationLoader::new()?;
@@ -913,6 +1250,12 @@ impl CliApp {
                     response_time_ms: tr.duration.as_millis() as u64,
                     retry_attempts: tr.retry_attempts as u32,
                 },
+                snapshot_diffs: tr.snapshot_diffs.clone(),
+                latency_samples_ms: tr
+                    .latency_samples
+                    .iter()
+                    .map(|sample| sample.as_secs_f64() * 1000.0)
+                    .collect(),
             })
             .collect();
 
@@ -6,14 +6,22 @@
 use crate::error::{Error, Result};
 use rmcp::model::*;
 use rmcp::service::{RoleClient, ServiceExt};
+use rmcp::transport::common::client_side_sse::{ExponentialBackoff, SseRetryPolicy};
+use rmcp::transport::sse_client::SseClientConfig;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use rmcp::transport::{SseClientTransport, StreamableHttpClientTransport};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::process::{Child, Command};
 use tokio::time::timeout;
 use tracing::{debug, info, warn};
 
+/// Delay between connection attempts while a network server is starting up
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
 /// Configuration for connecting to an MCP server
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -42,10 +50,56 @@ pub struct ServerConfig {
 pub enum Transport {
     /// Standard input/output transport
     Stdio,
-    /// HTTP transport
-    Http { url: String },
+    /// Streamable HTTP transport
+    Http {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
     /// Server-Sent Events transport
-    Sse { url: String },
+    Sse {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+}
+
+impl Transport {
+    /// Whether the transport talks to the server over the network
+    pub fn is_network(&self) -> bool {
+        !matches!(self, Transport::Stdio)
+    }
+}
+
+impl TryFrom<&crate::spec::ServerConfig> for Transport {
+    type Error = Error;
+
+    fn try_from(spec_config: &crate::spec::ServerConfig) -> Result<Self> {
+        let network_url = || {
+            spec_config.url.clone().ok_or_else(|| {
+                Error::spec(format!(
+                    "The '{}' transport requires a server url",
+                    spec_config.transport
+                ))
+            })
+        };
+
+        match spec_config.transport.to_ascii_lowercase().as_str() {
+            "stdio" => Ok(Transport::Stdio),
+            "http" | "streamable-http" | "streamable_http" => Ok(Transport::Http {
+                url: network_url()?,
+                headers: spec_config.headers.clone(),
+            }),
+            "sse" => Ok(Transport::Sse {
+                url: network_url()?,
+                headers: spec_config.headers.clone(),
+            }),
+            other => Err(Error::spec(format!(
+                "Unsupported transport '{}' (expected stdio, http or sse)",
+                other
+            ))),
+        }
+    }
 }
 
 /// Connection state of the MCP client
@@ -86,6 +140,8 @@ pub struct McpClient {
     connection_state: ConnectionState,
     /// Server information (available after connection)
     server_info: Option<ServerInfo>,
+    /// Process ID of the server serving this session, when the client launched it
+    server_pid: Option<u32>,
 }
 
 /// Manages external MCP server processes
@@ -112,19 +168,22 @@ impl Default for ServerConfig {
     }
 }
 
-impl From<crate::spec::ServerConfig> for ServerConfig {
-    fn from(spec_config: crate::spec::ServerConfig) -> Self {
-        Self {
+impl TryFrom<crate::spec::ServerConfig> for ServerConfig {
+    type Error = Error;
+
+    fn try_from(spec_config: crate::spec::ServerConfig) -> Result<Self> {
+        let transport = Transport::try_from(&spec_config)?;
+        Ok(Self {
             command: spec_config.command,
             args: spec_config.args,
             env: spec_config.env,
             working_dir: spec_config.working_dir.map(PathBuf::from),
-            transport: Transport::Stdio, // NOTE: Hardcoded for now
+            transport,
             startup_timeout: Duration::from_secs(spec_config.startup_timeout_seconds as u64),
             shutdown_timeout: Duration::from_secs(spec_config.shutdown_timeout_seconds as u64),
             operation_timeout: Duration::from_secs(30), // Default value
             max_retries: 3,                             // Default value
-        }
+        })
     }
 }
 
@@ -139,6 +198,7 @@ impl McpClient {
             config,
             connection_state: ConnectionState::Disconnected,
             server_info: None,
+            server_pid: None,
         })
     }
 
@@ -149,25 +209,31 @@ impl McpClient {
         }
 
         self.connection_state = ConnectionState::Connecting;
-        info!(
-            "Connecting to MCP server: {} {:?}",
-            self.config.command, self.config.args
-        );
 
-        match &self.config.transport {
+        let connected = match self.config.transport.clone() {
             Transport::Stdio => {
-                self.connect_stdio().await?;
+                info!(
+                    "Connecting to MCP server: {} {:?}",
+                    self.config.command, self.config.args
+                );
+                self.connect_stdio().await
             }
-            Transport::Http { url: _ } => {
-                return Err(Error::connection(
-                    "HTTP transport not yet supported - use stdio transport".to_string(),
-                ));
+            Transport::Http { url, headers } => {
+                info!("Connecting to MCP server over streamable HTTP: {}", url);
+                self.connect_http(&url, &headers).await
             }
-            Transport::Sse { url: _ } => {
-                return Err(Error::connection(
-                    "SSE transport not yet supported - use stdio transport".to_string(),
-                ));
+            Transport::Sse { url, headers } => {
+                info!("Connecting to MCP server over SSE: {}", url);
+                self.connect_sse(&url, &headers).await
             }
+        };
+
+        if let Err(e) = connected {
+            self.connection_state = ConnectionState::Error(e.to_string());
+            if let Some(mut process) = self.server_process.take() {
+                let _ = process.stop().await;
+            }
+            return Err(e);
         }
 
         // After connection, get server info and capabilities
@@ -200,6 +266,7 @@ impl McpClient {
 
         let transport = rmcp::transport::TokioChildProcess::new(cmd)
             .map_err(|e| Error::connection(format!("Failed to create stdio transport: {}", e)))?;
+        self.server_pid = transport.id();
 
         // Create service using the correct pattern
         let service = ()
@@ -211,20 +278,117 @@ impl McpClient {
         Ok(())
     }
 
-    /// Connect using HTTP transport (FUTURE: requires HTTP transport feature)
-    #[allow(dead_code)]
-    async fn connect_http(&mut self, _url: &str) -> Result<()> {
-        Err(Error::connection(
-            "HTTP transport not yet implemented".to_string(),
-        ))
+    /// Connect using the streamable HTTP transport
+    async fn connect_http(&mut self, url: &str, headers: &HashMap<String, String>) -> Result<()> {
+        self.start_network_server().await?;
+
+        let http_client = build_http_client(headers)?;
+        let retry_policy = self.retry_policy();
+        let service = self
+            .serve_until_ready(|| {
+                let transport = StreamableHttpClientTransport::with_client(
+                    http_client.clone(),
+                    StreamableHttpClientTransportConfig {
+                        uri: url.into(),
+                        retry_config: retry_policy.clone(),
+                        ..Default::default()
+                    },
+                );
+                async move {
+                    ().serve(transport).await.map_err(|e| {
+                        Error::connection(format!("Failed to create MCP service: {}", e))
+                    })
+                }
+            })
+            .await?;
+
+        self.service = Some(service);
+        Ok(())
     }
 
-    /// Connect using SSE transport (FUTURE: requires SSE transport feature)
-    #[allow(dead_code)]
-    async fn connect_sse(&mut self, _url: &str) -> Result<()> {
-        Err(Error::connection(
-            "SSE transport not yet implemented".to_string(),
-        ))
+    /// Connect using the Server-Sent Events transport
+    async fn connect_sse(&mut self, url: &str, headers: &HashMap<String, String>) -> Result<()> {
+        self.start_network_server().await?;
+
+        let http_client = build_http_client(headers)?;
+        let retry_policy = self.retry_policy();
+        let service = self
+            .serve_until_ready(|| {
+                let config = SseClientConfig {
+                    sse_endpoint: url.into(),
+                    retry_policy: retry_policy.clone(),
+                    ..Default::default()
+                };
+                let http_client = http_client.clone();
+                async move {
+                    let transport = SseClientTransport::start_with_client(http_client, config)
+                        .await
+                        .map_err(|e| {
+                            Error::connection(format!("Failed to create SSE transport: {}", e))
+                        })?;
+                    ().serve(transport).await.map_err(|e| {
+                        Error::connection(format!("Failed to create MCP service: {}", e))
+                    })
+                }
+            })
+            .await?;
+
+        self.service = Some(service);
+        Ok(())
+    }
+
+    /// Launch the server process for a network transport when a command is configured.
+    ///
+    /// Remote servers that are already running are addressed by URL only and
+    /// leave `command` empty.
+    async fn start_network_server(&mut self) -> Result<()> {
+        if self.config.command.is_empty() {
+            return Ok(());
+        }
+
+        let process = ServerProcess::start(&self.config).await?;
+        self.server_pid = process.id();
+        self.server_process = Some(process);
+        Ok(())
+    }
+
+    /// Reconnect policy for network transports, bounded by `max_retries`
+    fn retry_policy(&self) -> Arc<dyn SseRetryPolicy> {
+        Arc::new(ExponentialBackoff {
+            max_times: Some(self.config.max_retries as usize),
+            base_duration: ExponentialBackoff::DEFAULT_DURATION,
+        })
+    }
+
+    /// Run the MCP handshake, retrying until the server accepts connections or
+    /// `startup_timeout` elapses.
+    ///
+    /// A freshly launched HTTP server usually needs a moment before it binds
+    /// its port, so early connection failures are expected.
+    async fn serve_until_ready<F, Fut>(
+        &self,
+        mut attempt: F,
+    ) -> Result<rmcp::service::RunningService<RoleClient, ()>>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<rmcp::service::RunningService<RoleClient, ()>>>,
+    {
+        let deadline = Instant::now() + self.config.startup_timeout;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let error = match timeout(remaining, attempt()).await {
+                Ok(Ok(service)) => return Ok(service),
+                Ok(Err(e)) => e,
+                Err(_) => Error::connection("Server startup timeout"),
+            };
+
+            if Instant::now() + CONNECT_RETRY_INTERVAL >= deadline {
+                return Err(error);
+            }
+            debug!("Server not ready yet ({}), retrying", error);
+            tokio::time::sleep(CONNECT_RETRY_INTERVAL).await;
+        }
     }
 
     /// Discover server capabilities after connection
@@ -275,6 +439,7 @@ impl McpClient {
         }
 
         self.server_info = None;
+        self.server_pid = None;
         self.connection_state = ConnectionState::Disconnected;
 
         info!("Successfully disconnected from MCP server");
@@ -286,6 +451,19 @@ impl McpClient {
         &self.connection_state
     }
 
+    /// Process ID of the server, when the client launched it
+    pub fn server_pid(&self) -> Option<u32> {
+        self.server_pid
+    }
+
+    /// Resident set size of the server process in bytes
+    ///
+    /// Only available for servers the client launched, on platforms that
+    /// report it (currently Linux).
+    pub fn server_memory_bytes(&self) -> Option<u64> {
+        self.server_pid.and_then(resident_memory_bytes)
+    }
+
     /// Get server information (if connected)
     pub fn server_info(&self) -> Option<&ServerInfo> {
         self.server_info.as_ref()
@@ -307,7 +485,7 @@ impl McpClient {
             .peer()
             .list_all_tools()
             .await
-            .map_err(|e| Error::execution(format!("Failed to list tools: {}", e)))?;
+            .map_err(|e| request_error("Failed to list tools", e))?;
 
         debug!("Listed {} tools", tools.len());
         Ok(tools)
@@ -343,8 +521,13 @@ impl McpClient {
             }),
         )
         .await
-        .map_err(|_| Error::execution("Tool call timeout"))?
-        .map_err(|e| Error::execution(format!("Tool call failed: {}", e)))?;
+        .map_err(|_| Error::connection("Tool call timeout"))?
+        .map_err(|e| match e {
+            // The server answered with a JSON-RPC error
+            rmcp::ServiceError::McpError(error) => Error::Mcp(error),
+            // The server never answered: it exited, closed the pipe or stalled
+            other => Error::connection(format!("Tool call failed: {}", other)),
+        })?;
 
         debug!("Tool call result: {:?}", result);
         Ok(result)
@@ -384,7 +567,7 @@ impl McpClient {
             .peer()
             .list_all_resources()
             .await
-            .map_err(|e| Error::execution(format!("Failed to list resources: {}", e)))?;
+            .map_err(|e| request_error("Failed to list resources", e))?;
 
         debug!("Listed {} resources", resources.len());
         Ok(resources)
@@ -407,7 +590,7 @@ impl McpClient {
         )
         .await
         .map_err(|_| Error::execution("Resource read timeout"))?
-        .map_err(|e| Error::execution(format!("Resource read failed: {}", e)))?;
+        .map_err(|e| request_error("Resource read failed", e))?;
 
         debug!("Resource read result: {:?}", result);
         Ok(result)
@@ -424,7 +607,7 @@ impl McpClient {
             .peer()
             .list_all_prompts()
             .await
-            .map_err(|e| Error::execution(format!("Failed to list prompts: {}", e)))?;
+            .map_err(|e| request_error("Failed to list prompts", e))?;
 
         debug!("Listed {} prompts", prompts.len());
         Ok(prompts)
@@ -461,7 +644,7 @@ impl McpClient {
         )
         .await
         .map_err(|_| Error::execution("Prompt get timeout"))?
-        .map_err(|e| Error::execution(format!("Prompt get failed: {}", e)))?;
+        .map_err(|e| request_error("Prompt get failed", e))?;
 
         debug!("Prompt get result: {:?}", result);
         Ok(result)
@@ -532,10 +715,17 @@ impl ServerProcess {
             cmd.current_dir(working_dir);
         }
 
-        // Configure stdio for MCP communication
-        cmd.stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .stderr(std::process::Stdio::piped());
+        // Configure stdio for MCP communication; network servers talk over
+        // their own socket, so their output is discarded instead of piped
+        if config.transport.is_network() {
+            cmd.stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null());
+        } else {
+            cmd.stdin(std::process::Stdio::piped())
+                .stdout(std::process::Stdio::piped())
+                .stderr(std::process::Stdio::piped());
+        }
 
         // Spawn the process
         let child = cmd
@@ -583,6 +773,16 @@ impl ServerProcess {
         }
     }
 
+    /// Process ID, while the process is running
+    pub fn id(&self) -> Option<u32> {
+        self.child.as_ref().and_then(|child| child.id())
+    }
+
+    /// Resident set size of the process in bytes, where the platform reports it
+    pub fn resident_memory_bytes(&self) -> Option<u64> {
+        self.id().and_then(resident_memory_bytes)
+    }
+
     /// Check if the server process is still running
     pub fn is_running(&mut self) -> bool {
         if let Some(child) = &mut self.child {
@@ -597,6 +797,53 @@ impl ServerProcess {
     }
 }
 
+/// Resident set size of process `pid` in bytes, read from `/proc` on Linux
+pub fn resident_memory_bytes(pid: u32) -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        let kb = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))?
+            .split_whitespace()
+            .next()?
+            .parse::<u64>()
+            .ok()?;
+        Some(kb * 1024)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Error for a failed request; a JSON-RPC error from the server is kept as is
+/// so that its code and data reach the caller
+fn request_error(context: &str, error: rmcp::ServiceError) -> Error {
+    match error {
+        rmcp::ServiceError::McpError(error) => Error::Mcp(error),
+        other => Error::execution(format!("{}: {}", context, other)),
+    }
+}
+
+/// Build the HTTP client used by network transports, attaching configured headers
+fn build_http_client(headers: &HashMap<String, String>) -> Result<reqwest::Client> {
+    let mut header_map = reqwest::header::HeaderMap::new();
+    for (name, value) in headers {
+        let name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| Error::config(format!("Invalid header name '{}': {}", name, e)))?;
+        let value = reqwest::header::HeaderValue::from_str(value)
+            .map_err(|e| Error::config(format!("Invalid value for header '{}': {}", name, e)))?;
+        header_map.insert(name, value);
+    }
+
+    reqwest::Client::builder()
+        .default_headers(header_map)
+        .build()
+        .map_err(|e| Error::connection(format!("Failed to build HTTP client: {}", e)))
+}
+
 impl Drop for McpClient {
     fn drop(&mut self) {
         if self.is_connected() {
@@ -801,6 +1048,13 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_resident_memory_bytes() {
+        assert!(resident_memory_bytes(std::process::id()).unwrap() > 0);
+        assert_eq!(resident_memory_bytes(u32::MAX), None);
+    }
+
     #[test]
     fn test_server_config_default() {
         let config = ServerConfig::default();
@@ -839,9 +1093,11 @@ mod tests {
             Transport::Stdio,
             Transport::Http {
                 url: "http://localhost:8080".to_string(),
+                headers: [("Authorization".to_string(), "Bearer token".to_string())].into(),
             },
             Transport::Sse {
                 url: "http://localhost:8080/events".to_string(),
+                headers: HashMap::new(),
             },
         ];
 
@@ -851,14 +1107,125 @@ mod tests {
 
             match (&transport, &deserialized) {
                 (Transport::Stdio, Transport::Stdio) => {}
-                (Transport::Http { url: url1 }, Transport::Http { url: url2 }) => {
+                (
+                    Transport::Http {
+                        url: url1,
+                        headers: headers1,
+                    },
+                    Transport::Http {
+                        url: url2,
+                        headers: headers2,
+                    },
+                ) => {
                     assert_eq!(url1, url2);
+                    assert_eq!(headers1, headers2);
                 }
-                (Transport::Sse { url: url1 }, Transport::Sse { url: url2 }) => {
+                (Transport::Sse { url: url1, .. }, Transport::Sse { url: url2, .. }) => {
                     assert_eq!(url1, url2);
                 }
                 _ => panic!("Transport serialization mismatch"),
             }
         }
     }
+
+    fn spec_server_config(transport: &str, url: Option<&str>) -> crate::spec::ServerConfig {
+        crate::spec::ServerConfig {
+            command: String::new(),
+            args: vec![],
+            env: HashMap::new(),
+            working_dir: None,
+            transport: transport.to_string(),
+            url: url.map(str::to_string),
+            headers: [("X-Api-Key".to_string(), "secret".to_string())].into(),
+            startup_timeout_seconds: 5,
+            shutdown_timeout_seconds: 2,
+        }
+    }
+
+    #[test]
+    fn test_transport_from_spec() {
+        let transport = Transport::try_from(&spec_server_config("stdio", None)).unwrap();
+        assert!(matches!(transport, Transport::Stdio));
+        assert!(!transport.is_network());
+
+        for name in ["http", "streamable-http", "HTTP"] {
+            let transport =
+                Transport::try_from(&spec_server_config(name, Some("http://localhost:8080/mcp")))
+                    .unwrap();
+            match transport {
+                Transport::Http { url, headers } => {
+                    assert_eq!(url, "http://localhost:8080/mcp");
+                    assert_eq!(headers.get("X-Api-Key"), Some(&"secret".to_string()));
+                }
+                other => panic!("expected HTTP transport, got {:?}", other),
+            }
+        }
+
+        let transport = Transport::try_from(&spec_server_config(
+            "sse",
+            Some("http://localhost:8080/sse"),
+        ))
+        .unwrap();
+        assert!(matches!(transport, Transport::Sse { .. }));
+        assert!(transport.is_network());
+    }
+
+    #[test]
+    fn test_transport_from_spec_errors() {
+        let missing_url = Transport::try_from(&spec_server_config("http", None));
+        assert!(matches!(missing_url, Err(Error::Spec(_))));
+
+        let unknown = Transport::try_from(&spec_server_config("websocket", Some("ws://x")));
+        assert!(matches!(unknown, Err(Error::Spec(_))));
+    }
+
+    #[test]
+    fn test_build_http_client_rejects_invalid_headers() {
+        let valid =
+            build_http_client(&[("Authorization".to_string(), "Bearer x".to_string())].into());
+        assert!(valid.is_ok());
+
+        let invalid = build_http_client(&[("bad header".to_string(), "x".to_string())].into());
+        assert!(matches!(invalid, Err(Error::Config(_))));
+    }
+
+    #[tokio::test]
+    async fn test_http_connect_failure_respects_startup_timeout() {
+        // Reserve a free port and release it so nothing is listening there
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        for transport in [
+            Transport::Http {
+                url: format!("http://127.0.0.1:{}/mcp", port),
+                headers: HashMap::new(),
+            },
+            Transport::Sse {
+                url: format!("http://127.0.0.1:{}/sse", port),
+                headers: HashMap::new(),
+            },
+        ] {
+            let config = ServerConfig {
+                command: String::new(),
+                transport,
+                startup_timeout: Duration::from_secs(1),
+                max_retries: 0,
+                ..create_test_config()
+            };
+            let mut client = McpClient::new(config).await.unwrap();
+
+            let started = Instant::now();
+            let result = client.connect().await;
+            assert!(matches!(result, Err(Error::Connection(_))));
+            assert!(started.elapsed() < Duration::from_secs(5));
+            assert!(matches!(
+                client.connection_state(),
+                ConnectionState::Error(_)
+            ));
+            assert!(!client.is_connected());
+        }
+    }
 }
@@ -1,12 +1,20 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use crate::client::McpClient;
 
-use crate::spec::{ExpectedOutput, TestCase};
-use crate::validation::{ValidationEngine, ValidationResult};
+use crate::snapshot::redact::parse_text_content;
+use crate::snapshot::{SnapshotDiff, SnapshotOutcome, SnapshotStore, SNAPSHOT_DIR};
+
+use crate::script_engines::{
+    self, utilities, ScriptConfig, ScriptContext, ScriptLanguage, ScriptPhase,
+};
+use crate::spec::{expand, ExpectedOutput, TestCase, ValidationScript};
+use crate::validation::{JsonPathEvaluator, ValidationEngine, ValidationError, ValidationResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TestStatus {
@@ -27,6 +35,12 @@ pub struct TestResult {
     pub duration: Duration,
     pub response_data: Option<serde_json::Value>,
     pub performance: PerformanceMetrics,
+    /// Differences from the test's snapshot when it did not match
+    #[serde(default)]
+    pub snapshot_diffs: Vec<SnapshotDiff>,
+    /// Latency of each repetition in milliseconds (see `moth run --repeat`)
+    #[serde(default)]
+    pub latency_samples_ms: Vec<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,6 +119,12 @@ pub enum ExecutorError {
     #[error("Tool call failed: {0}")]
     ToolCallError(String),
 
+    #[error("Resource read failed: {0}")]
+    ResourceReadError(String),
+
+    #[error("Prompt request failed: {0}")]
+    PromptGetError(String),
+
     #[error("Response validation failed: {0}")]
     ValidationError(String),
 
@@ -115,11 +135,142 @@ pub enum ExecutorError {
     ConfigError(String),
 }
 
+impl ExecutorError {
+    /// Whether the server answered the request with an error, as opposed to
+    /// the harness failing to issue it
+    pub fn is_server_error(&self) -> bool {
+        matches!(
+            self,
+            Self::ToolCallError(_) | Self::ResourceReadError(_) | Self::PromptGetError(_)
+        )
+    }
+}
+
+/// MCP request issued for a single test case
+#[derive(Debug, Clone)]
+pub(crate) enum McpRequest {
+    CallTool {
+        name: String,
+        arguments: Option<serde_json::Value>,
+    },
+    ReadResource {
+        uri: String,
+    },
+    GetPrompt {
+        name: String,
+        arguments: Option<serde_json::Value>,
+    },
+}
+
+impl McpRequest {
+    /// Request as exposed to validation scripts: `method`, target and `params`
+    fn to_script_value(&self) -> serde_json::Value {
+        match self {
+            Self::CallTool { name, arguments } => serde_json::json!({
+                "method": "tools/call",
+                "name": name,
+                "params": arguments.clone().unwrap_or_else(|| serde_json::json!({})),
+            }),
+            Self::ReadResource { uri } => serde_json::json!({
+                "method": "resources/read",
+                "params": {"uri": uri},
+            }),
+            Self::GetPrompt { name, arguments } => serde_json::json!({
+                "method": "prompts/get",
+                "name": name,
+                "params": arguments.clone().unwrap_or_else(|| serde_json::json!({})),
+            }),
+        }
+    }
+}
+
+/// A test case's validation script, ready to run
+struct ResolvedScript {
+    name: String,
+    language: ScriptLanguage,
+    phase: ScriptPhase,
+    required: bool,
+    source: String,
+}
+
+impl ResolvedScript {
+    /// Scripts run after the response and are required unless stated otherwise
+    fn new(script: &ValidationScript) -> std::result::Result<Self, ExecutorError> {
+        let invalid = |e: script_engines::ScriptError| {
+            ExecutorError::ConfigError(format!("Validation script '{}': {}", script.name, e))
+        };
+
+        Ok(Self {
+            name: script.name.clone(),
+            language: script.language.parse().map_err(invalid)?,
+            phase: script
+                .execution_phase
+                .as_deref()
+                .unwrap_or("after")
+                .parse()
+                .map_err(invalid)?,
+            required: script.required.unwrap_or(true),
+            source: script.source.clone().ok_or_else(|| {
+                ExecutorError::ConfigError(format!(
+                    "Validation script '{}' has no source",
+                    script.name
+                ))
+            })?,
+        })
+    }
+}
+
+/// Expand a resource `uri_template` with the values from a test case input
+///
+/// An explicit `uri` string in the input takes precedence over the template.
+/// Otherwise every `{name}` placeholder is replaced with `input[name]`;
+/// strings are inserted verbatim and other scalars in their JSON form.
+pub fn expand_uri_template(
+    template: &str,
+    input: &serde_json::Value,
+) -> std::result::Result<String, ExecutorError> {
+    if let Some(uri) = input.get("uri").and_then(|uri| uri.as_str()) {
+        return Ok(uri.to_string());
+    }
+
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        expanded.push_str(&rest[..open]);
+        let close = rest[open..].find('}').ok_or_else(|| {
+            ExecutorError::ConfigError(format!("Unterminated placeholder in '{}'", template))
+        })? + open;
+
+        let variable = &rest[open + 1..close];
+        let value = match input.get(variable) {
+            Some(serde_json::Value::String(value)) => value.clone(),
+            Some(value) if !value.is_null() && !value.is_object() && !value.is_array() => {
+                value.to_string()
+            }
+            _ => {
+                return Err(ExecutorError::ConfigError(format!(
+                    "Input does not provide a value for '{}' in '{}'",
+                    variable, template
+                )))
+            }
+        };
+        expanded.push_str(&value);
+        rest = &rest[close + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
 /// Main test case executor
 pub struct TestCaseExecutor {
     client: Arc<Mutex<McpClient>>,
     validation_engine: ValidationEngine,
     config: ExecutorConfig,
+    validation_scripts: HashMap<String, ValidationScript>,
+    snapshots: SnapshotStore,
+    /// Values captured by earlier test cases, by capture name
+    captured: serde_json::Map<String, serde_json::Value>,
 }
 
 impl std::fmt::Debug for TestCaseExecutor {
@@ -128,6 +279,9 @@ impl std::fmt::Debug for TestCaseExecutor {
             .field("config", &self.config)
             .field("client", &"<McpClient>")
             .field("validation_engine", &"<ValidationEngine>")
+            .field("validation_scripts", &self.validation_scripts.len())
+            .field("snapshots", &self.snapshots)
+            .field("captured", &self.captured)
             .finish()
     }
 }
@@ -139,27 +293,150 @@ impl TestCaseExecutor {
             client,
             validation_engine: ValidationEngine::default(),
             config,
+            validation_scripts: HashMap::new(),
+            snapshots: SnapshotStore::new(SNAPSHOT_DIR),
+            captured: serde_json::Map::new(),
         }
     }
 
+    /// Make a specification's validation scripts available to test cases
+    ///
+    /// Test cases reference these scripts by name in `validation_scripts`.
+    pub fn set_validation_scripts(&mut self, scripts: Vec<ValidationScript>) {
+        self.validation_scripts = scripts
+            .into_iter()
+            .map(|script| (script.name.clone(), script))
+            .collect();
+    }
+
+    /// Use `snapshots` for test cases with `expected.snapshot`
+    pub fn set_snapshot_store(&mut self, snapshots: SnapshotStore) {
+        self.snapshots = snapshots;
+    }
+
+    /// Values captured so far, available to test cases as `${captured.<name>}`
+    pub fn captured(&self) -> &serde_json::Map<String, serde_json::Value> {
+        &self.captured
+    }
+
+    /// Forget captured values, e.g. before running another suite
+    pub fn clear_captured(&mut self) {
+        self.captured.clear();
+    }
+
     /// Execute a single test case and return comprehensive results
     pub async fn execute_test_case(
         &mut self,
         tool_name: &str,
         test_case: &TestCase,
     ) -> std::result::Result<TestCaseResult, ExecutorError> {
-        let start_time = Instant::now();
+        let test_case = &self.resolve_captured(test_case)?;
 
         // 1. Prepare MCP tool request from test case input
-        let (_tool_name, arguments) = self.prepare_tool_request(tool_name, &test_case.input)?;
+        let (name, arguments) = self.prepare_tool_request(tool_name, &test_case.input)?;
+
+        self.run_test_case(
+            tool_name,
+            McpRequest::CallTool { name, arguments },
+            test_case,
+        )
+        .await
+    }
+
+    /// Execute a resource test case by reading the expanded `uri_template`
+    pub async fn execute_resource_test(
+        &mut self,
+        uri_template: &str,
+        test_case: &TestCase,
+    ) -> std::result::Result<TestCaseResult, ExecutorError> {
+        let test_case = &self.resolve_captured(test_case)?;
+        let uri = expand_uri_template(uri_template, &test_case.input)?;
 
-        // 2. Execute MCP tool call
-        let response = self.execute_mcp_call(tool_name, arguments).await?;
+        self.run_test_case(uri_template, McpRequest::ReadResource { uri }, test_case)
+            .await
+    }
 
-        // 3. Validate response against expected output
-        let validation_result = self
+    /// Execute a prompt test case by getting `prompt_name` with the test input as arguments
+    pub async fn execute_prompt_test(
+        &mut self,
+        prompt_name: &str,
+        test_case: &TestCase,
+    ) -> std::result::Result<TestCaseResult, ExecutorError> {
+        let test_case = &self.resolve_captured(test_case)?;
+        let arguments = self.prepare_prompt_arguments(&test_case.input)?;
+        let request = McpRequest::GetPrompt {
+            name: prompt_name.to_string(),
+            arguments,
+        };
+
+        self.run_test_case(prompt_name, request, test_case).await
+    }
+
+    /// Issue `request` for `test_case`, run its validation scripts and validate the response
+    async fn run_test_case(
+        &mut self,
+        target_name: &str,
+        request: McpRequest,
+        test_case: &TestCase,
+    ) -> std::result::Result<TestCaseResult, ExecutorError> {
+        let start_time = Instant::now();
+        let scripts = self.scripts_for(test_case)?;
+        let script_request = request.to_script_value();
+
+        // Scripts in the "before" phase only see the request
+        let mut script_errors = self
+            .run_validation_scripts(
+                &scripts,
+                ScriptPhase::Before,
+                target_name,
+                test_case,
+                &script_request,
+                None,
+            )
+            .await;
+
+        // 2. Execute MCP request; when the test expects an error, the server
+        // rejecting the request is the response under test
+        let response = match self.execute_request(request).await {
+            Ok(response) => response,
+            Err(error) if test_case.expected.error && error.is_server_error() => {
+                rejection_response(&error)
+            }
+            Err(error) => return Err(error),
+        };
+
+        // 3. Validate response against expected output and "after" scripts
+        let mut validation_result = self
             .validate_response(&response, &test_case.expected)
             .await?;
+        if let Some(error) = check_error_expectation(&response, &test_case.expected) {
+            validation_result.is_valid = false;
+            validation_result.validation_errors.push(error);
+        }
+        if let Some(error) = self.check_snapshot(test_case, &response).await? {
+            validation_result.is_valid = false;
+            validation_result.validation_errors.push(error);
+        }
+        let capture_errors = self.capture_values(test_case, &response);
+        if !capture_errors.is_empty() {
+            validation_result.is_valid = false;
+            validation_result.validation_errors.extend(capture_errors);
+        }
+        script_errors.extend(
+            self.run_validation_scripts(
+                &scripts,
+                ScriptPhase::After,
+                target_name,
+                test_case,
+                &script_request,
+                Some(&response),
+            )
+            .await,
+        );
+        if !script_errors.is_empty() {
+            validation_result.is_valid = false;
+            validation_result.validation_errors.extend(script_errors);
+        }
 
         // 4. Collect performance metrics
         let metrics = self.collect_metrics(start_time, &response);
@@ -169,16 +446,22 @@ impl TestCaseExecutor {
         let error_message = if success {
             None
         } else {
+            let errors: Vec<_> = validation_result
+                .validation_errors
+                .iter()
+                .map(ToString::to_string)
+                .collect();
             Some(format!(
-                "Validation failed: {} errors",
-                validation_result.validation_errors.len()
+                "Validation failed: {} errors: {}",
+                errors.len(),
+                errors.join("; ")
             ))
         };
 
         // 6. Return comprehensive test result
         Ok(TestCaseResult {
             test_name: test_case.name.clone(),
-            tool_name: tool_name.to_string(),
+            tool_name: target_name.to_string(),
             success,
             execution_time: metrics.duration,
             validation: validation_result,
@@ -187,6 +470,168 @@ impl TestCaseExecutor {
         })
     }
 
+    /// `test_case` with `${captured.<name>}` in its input and expected output resolved
+    fn resolve_captured<'a>(
+        &self,
+        test_case: &'a TestCase,
+    ) -> std::result::Result<Cow<'a, TestCase>, ExecutorError> {
+        let invalid = |e: String| {
+            ExecutorError::ConfigError(format!("Test case '{}': {}", test_case.name, e))
+        };
+        let expected =
+            serde_json::to_value(&test_case.expected).map_err(|e| invalid(e.to_string()))?;
+        if expand::captured_references(&test_case.input).is_empty()
+            && expand::captured_references(&expected).is_empty()
+        {
+            return Ok(Cow::Borrowed(test_case));
+        }
+
+        let mut resolved = test_case.clone();
+        resolved.input =
+            expand::resolve_captured(&test_case.input, &self.captured).map_err(invalid)?;
+        let expected = expand::resolve_captured(&expected, &self.captured).map_err(invalid)?;
+        resolved.expected = serde_json::from_value(expected).map_err(|e| invalid(e.to_string()))?;
+        Ok(Cow::Owned(resolved))
+    }
+
+    /// Store the values `test_case` captures from `response`, returning failed captures
+    ///
+    /// Paths are tried against the response as returned, then with JSON text
+    /// content parsed so they can reach into a tool's structured output.
+    fn capture_values(
+        &mut self,
+        test_case: &TestCase,
+        response: &serde_json::Value,
+    ) -> Vec<ValidationError> {
+        let Some(rules) = &test_case.capture else {
+            return Vec::new();
+        };
+        let evaluator = JsonPathEvaluator::default();
+        let parsed = parse_text_content(response.clone());
+
+        let mut errors = Vec::new();
+        for (name, path) in rules {
+            match evaluator
+                .extract_values(response, path)
+                .or_else(|_| evaluator.extract_values(&parsed, path))
+            {
+                Ok(mut values) => {
+                    let value = if values.len() == 1 {
+                        values.remove(0)
+                    } else {
+                        serde_json::Value::Array(values)
+                    };
+                    tracing::debug!("Captured '{}' from '{}': {}", name, test_case.name, value);
+                    self.captured.insert(name.clone(), value);
+                }
+                Err(e) => errors.push(ValidationError::CaptureError {
+                    name: name.clone(),
+                    path: path.clone(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+        errors
+    }
+
+    /// Compare `response` with the snapshot named in `expected.snapshot`
+    async fn check_snapshot(
+        &self,
+        test_case: &TestCase,
+        response: &serde_json::Value,
+    ) -> std::result::Result<Option<ValidationError>, ExecutorError> {
+        let Some(name) = &test_case.expected.snapshot else {
+            return Ok(None);
+        };
+        let outcome = self
+            .snapshots
+            .check(
+                name,
+                &test_case.name,
+                response,
+                &test_case.expected.redactions,
+            )
+            .await
+            .map_err(|e| ExecutorError::ValidationError(e.to_string()))?;
+
+        Ok(match outcome {
+            SnapshotOutcome::Matched => None,
+            SnapshotOutcome::Written(file) => {
+                tracing::info!("Updated snapshot {}", file.display());
+                None
+            }
+            SnapshotOutcome::Missing(file) => Some(ValidationError::SnapshotMissing {
+                snapshot: name.clone(),
+                file,
+            }),
+            SnapshotOutcome::Mismatch(diff) => Some(ValidationError::SnapshotMismatch(diff)),
+        })
+    }
+
+    /// Resolve the validation scripts referenced by `test_case`
+    fn scripts_for(
+        &self,
+        test_case: &TestCase,
+    ) -> std::result::Result<Vec<ResolvedScript>, ExecutorError> {
+        let names = test_case.validation_scripts.iter().flatten();
+        names
+            .map(|name| {
+                let script = self.validation_scripts.get(name).ok_or_else(|| {
+                    ExecutorError::ConfigError(format!(
+                        "Test case '{}' references unknown validation script '{}'",
+                        test_case.name, name
+                    ))
+                })?;
+                ResolvedScript::new(script)
+            })
+            .collect()
+    }
+
+    /// Run the scripts of one phase, returning failures of required scripts
+    async fn run_validation_scripts(
+        &self,
+        scripts: &[ResolvedScript],
+        phase: ScriptPhase,
+        target_name: &str,
+        test_case: &TestCase,
+        request: &serde_json::Value,
+        response: Option<&serde_json::Value>,
+    ) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        for script in scripts.iter().filter(|script| script.phase == phase) {
+            let mut context = ScriptContext::new(
+                request.clone(),
+                test_case.name.clone(),
+                target_name.to_string(),
+                ScriptConfig::default(),
+            );
+            if let Some(response) = response {
+                context = context.with_response(response.clone());
+            }
+
+            let result =
+                script_engines::execute_script(script.language, &script.source, context).await;
+            if let Some(message) = utilities::validation_failure(&result) {
+                if script.required {
+                    errors.push(ValidationError::ScriptError {
+                        script: script.name.clone(),
+                        message,
+                    });
+                } else {
+                    tracing::warn!(
+                        "Optional validation script '{}' failed for '{}': {}",
+                        script.name,
+                        test_case.name,
+                        message
+                    );
+                }
+            }
+        }
+
+        errors
+    }
+
     /// Prepare MCP tool request from test case input
     fn prepare_tool_request(
         &self,
@@ -206,11 +651,18 @@ impl TestCaseExecutor {
         Ok((tool_name, arguments))
     }
 
-    /// Execute MCP tool call with timeout
-    async fn execute_mcp_call(
+    /// Prepare prompt arguments from test case input
+    fn prepare_prompt_arguments(
         &self,
-        tool_name: &str,
-        arguments: Option<serde_json::Value>,
+        input: &serde_json::Value,
+    ) -> std::result::Result<Option<serde_json::Value>, ExecutorError> {
+        prompt_arguments(input)
+    }
+
+    /// Issue an MCP request with timeout and return the response as JSON
+    async fn execute_request(
+        &self,
+        request: McpRequest,
     ) -> std::result::Result<serde_json::Value, ExecutorError> {
         // Check connection status first (scope the lock)
         let is_connected = {
@@ -226,39 +678,23 @@ impl TestCaseExecutor {
             ));
         }
 
-        // Execute tool call with timeout - clone client for async operation
+        // Execute request with timeout - clone client for async operation
         let client_clone = Arc::clone(&self.client);
         let timeout_duration = self.config.timeout;
-        let tool_name_owned = tool_name.to_string();
 
         #[allow(clippy::await_holding_lock)]
-        let call_result = tokio::time::timeout(timeout_duration, async move {
+        let response = tokio::time::timeout(timeout_duration, async move {
             let client = client_clone.lock().map_err(|e| {
-                crate::error::Error::connection(format!("Failed to acquire client lock: {}", e))
+                ExecutorError::ConnectionError(format!("Failed to acquire client lock: {}", e))
             })?;
-            client.call_tool(&tool_name_owned, arguments).await
+            send_request(&client, request).await
         })
         .await
         .map_err(|_| ExecutorError::TimeoutError {
             timeout_ms: timeout_duration.as_millis() as u64,
-        })?
-        .map_err(|e| ExecutorError::ToolCallError(format!("Tool call failed: {}", e)))?;
-
-        // Convert CallToolResult to JSON
-        let response_json = serde_json::to_value(call_result).map_err(|e| {
-            ExecutorError::ToolCallError(format!("Failed to serialize response: {}", e))
-        })?;
-
-        // Extract the result field from JSON-RPC response for validation
-        // This allows test specifications to use simpler JSONPath expressions
-        let validation_json = if let Some(result_field) = response_json.get("result") {
-            result_field.clone()
-        } else {
-            // If no result field, pass the full response (for error cases)
-            response_json
-        };
+        })??;
 
-        Ok(validation_json)
+        Ok(response)
     }
 
     /// Validate response against expected output
@@ -306,6 +742,126 @@ impl TestCaseExecutor {
     }
 }
 
+/// Issue `request` over `client` and return the response as JSON
+pub(crate) async fn send_request(
+    client: &McpClient,
+    request: McpRequest,
+) -> std::result::Result<serde_json::Value, ExecutorError> {
+    match request {
+        McpRequest::CallTool { name, arguments } => client
+            .call_tool(&name, arguments)
+            .await
+            .map_err(|e| match e {
+                crate::error::Error::Connection(_) => ExecutorError::ConnectionError(e.to_string()),
+                _ => ExecutorError::ToolCallError(format!("Tool call failed: {}", e)),
+            })
+            .and_then(|result| to_response_json(result, ExecutorError::ToolCallError))
+            .map(|response| {
+                // Extract the result field from JSON-RPC response for validation
+                // This allows test specifications to use simpler JSONPath expressions
+                match response.get("result") {
+                    Some(result_field) => result_field.clone(),
+                    // If no result field, pass the full response (for error cases)
+                    None => response,
+                }
+            }),
+        McpRequest::ReadResource { uri } => client
+            .read_resource(&uri)
+            .await
+            .map_err(|e| ExecutorError::ResourceReadError(format!("{}: {}", uri, e)))
+            .and_then(|result| to_response_json(result, ExecutorError::ResourceReadError)),
+        McpRequest::GetPrompt { name, arguments } => client
+            .get_prompt(&name, arguments)
+            .await
+            .map_err(|e| ExecutorError::PromptGetError(format!("{}: {}", name, e)))
+            .and_then(|result| to_response_json(result, ExecutorError::PromptGetError)),
+    }
+}
+
+/// The response validated when a test expecting an error had its request rejected
+pub(crate) fn rejection_response(error: &ExecutorError) -> serde_json::Value {
+    serde_json::json!({"isError": true, "error": {"message": error.to_string()}})
+}
+
+/// Prompt arguments from a test case input
+///
+/// Prompt arguments are string-valued, so scalars are converted to their
+/// string form and nested values to JSON text.
+pub(crate) fn prompt_arguments(
+    input: &serde_json::Value,
+) -> std::result::Result<Option<serde_json::Value>, ExecutorError> {
+    let arguments = match input {
+        serde_json::Value::Null => return Ok(None),
+        serde_json::Value::Object(arguments) => arguments,
+        other => {
+            return Err(ExecutorError::ConfigError(format!(
+                "Prompt arguments must be an object, got: {}",
+                other
+            )))
+        }
+    };
+
+    let arguments = arguments
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                serde_json::Value::String(value) => value.clone(),
+                other => other.to_string(),
+            };
+            (name.clone(), serde_json::Value::String(value))
+        })
+        .collect();
+
+    Ok(Some(serde_json::Value::Object(arguments)))
+}
+
+/// Check `expected.error` and `error_message_contains` against a response
+///
+/// A response is an error when the server rejected the request or a tool
+/// reported `isError: true`. Responses of tests that do not expect an error
+/// are left to the field checks.
+pub(crate) fn check_error_expectation(
+    response: &serde_json::Value,
+    expected: &ExpectedOutput,
+) -> Option<ValidationError> {
+    if !expected.error {
+        return None;
+    }
+
+    let is_error = response.get("isError").and_then(|v| v.as_bool()) == Some(true)
+        || response.get("error").is_some();
+    if !is_error {
+        return Some(ValidationError::ErrorExpectation {
+            message: "expected an error response, got a successful result".to_string(),
+        });
+    }
+
+    let needle = expected.error_message_contains.as_deref()?;
+    let message = match response.pointer("/error/message").and_then(|m| m.as_str()) {
+        Some(message) => message.to_string(),
+        // Tool errors carry their message in the text content
+        None => response
+            .get("content")
+            .and_then(|content| content.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item.get("text").and_then(|text| text.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    (!message.contains(needle)).then(|| ValidationError::ErrorExpectation {
+        message: format!("error message '{}' does not contain '{}'", message, needle),
+    })
+}
+
+/// Serialize an MCP result for validation
+fn to_response_json<T: Serialize>(
+    result: T,
+    error: fn(String) -> ExecutorError,
+) -> std::result::Result<serde_json::Value, ExecutorError> {
+    serde_json::to_value(result).map_err(|e| error(format!("Failed to serialize response: {}", e)))
+}
+
 // ============================================================================
 // COMPREHENSIVE UNIT TESTS
 // ============================================================================
@@ -350,6 +906,9 @@ mod tests {
             skip: false,
             tags: vec!["unit_test".to_string()],
             validation_scripts: None,
+            parameters: None,
+            matrix: None,
+            capture: None,
         }
     }
 
@@ -559,6 +1118,185 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_expand_uri_template() {
+        let input = serde_json::json!({"id": 2, "name": "notes.txt"});
+        assert_eq!(
+            expand_uri_template("test://static/resource/{id}", &input).unwrap(),
+            "test://static/resource/2"
+        );
+        assert_eq!(
+            expand_uri_template("file:///{name}", &input).unwrap(),
+            "file:///notes.txt"
+        );
+        assert_eq!(
+            expand_uri_template("test://static", &input).unwrap(),
+            "test://static"
+        );
+
+        // An explicit URI wins over the template
+        let input = serde_json::json!({"uri": "test://static/resource/3"});
+        assert_eq!(
+            expand_uri_template("test://static/resource/{id}", &input).unwrap(),
+            "test://static/resource/3"
+        );
+
+        assert!(matches!(
+            expand_uri_template("test://static/resource/{id}", &serde_json::json!({})),
+            Err(ExecutorError::ConfigError(_))
+        ));
+        assert!(matches!(
+            expand_uri_template("test://static/resource/{id", &serde_json::json!({"id": 1})),
+            Err(ExecutorError::ConfigError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_prompt_arguments() {
+        let executor = create_test_executor().await;
+
+        let arguments = executor
+            .prepare_prompt_arguments(&serde_json::json!({"temperature": 0.7, "style": "brief"}))
+            .unwrap();
+        assert_eq!(
+            arguments,
+            Some(serde_json::json!({"temperature": "0.7", "style": "brief"}))
+        );
+        assert_eq!(
+            executor
+                .prepare_prompt_arguments(&serde_json::Value::Null)
+                .unwrap(),
+            None
+        );
+        assert!(executor
+            .prepare_prompt_arguments(&serde_json::json!(["a"]))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resource_and_prompt_tests_require_connection() {
+        let mut executor = create_test_executor().await;
+        let mut test_case = create_test_case();
+        test_case.input = serde_json::json!({"id": 1});
+
+        let result = executor
+            .execute_resource_test("test://static/resource/{id}", &test_case)
+            .await;
+        assert!(matches!(result, Err(ExecutorError::ConnectionError(_))));
+
+        let result = executor
+            .execute_prompt_test("simple_prompt", &test_case)
+            .await;
+        assert!(matches!(result, Err(ExecutorError::ConnectionError(_))));
+    }
+
+    fn validation_script(
+        name: &str,
+        language: &str,
+        phase: &str,
+        source: &str,
+    ) -> ValidationScript {
+        ValidationScript {
+            name: name.to_string(),
+            language: language.to_string(),
+            execution_phase: Some(phase.to_string()),
+            required: None,
+            source: Some(source.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validation_scripts_run_in_phases() {
+        let mut executor = create_test_executor().await;
+        executor.set_validation_scripts(vec![
+            validation_script(
+                "has_message",
+                "lua",
+                "before",
+                "result = { success = context.request.params.message ~= nil }",
+            ),
+            validation_script(
+                "echoes_message",
+                "javascript",
+                "after",
+                r#"result = { success: context.response.content[0].text === context.request.params.message,
+                              message: "response does not echo the message" }"#,
+            ),
+            ValidationScript {
+                required: Some(false),
+                ..validation_script("optional", "python", "after", "result = {'success': False}")
+            },
+        ]);
+
+        let mut test_case = create_test_case();
+        test_case.validation_scripts = Some(vec![
+            "has_message".to_string(),
+            "echoes_message".to_string(),
+            "optional".to_string(),
+        ]);
+        let scripts = executor.scripts_for(&test_case).unwrap();
+        let request = McpRequest::CallTool {
+            name: "echo".to_string(),
+            arguments: Some(test_case.input.clone()),
+        }
+        .to_script_value();
+
+        let before = executor
+            .run_validation_scripts(
+                &scripts,
+                ScriptPhase::Before,
+                "echo",
+                &test_case,
+                &request,
+                None,
+            )
+            .await;
+        assert!(before.is_empty(), "{:?}", before);
+
+        let response = serde_json::json!({"content": [{"type": "text", "text": "Hello, world!"}]});
+        let after = executor
+            .run_validation_scripts(
+                &scripts,
+                ScriptPhase::After,
+                "echo",
+                &test_case,
+                &request,
+                Some(&response),
+            )
+            .await;
+        assert!(after.is_empty(), "{:?}", after);
+
+        let response = serde_json::json!({"content": [{"type": "text", "text": "something else"}]});
+        let after = executor
+            .run_validation_scripts(
+                &scripts,
+                ScriptPhase::After,
+                "echo",
+                &test_case,
+                &request,
+                Some(&response),
+            )
+            .await;
+        assert_eq!(after.len(), 1);
+        assert!(after[0]
+            .to_string()
+            .contains("echoes_message - response does not echo the message"));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_validation_script_is_a_config_error() {
+        let mut executor = create_test_executor().await;
+        let mut test_case = create_test_case();
+        test_case.validation_scripts = Some(vec!["missing".to_string()]);
+
+        let result = executor.execute_test_case("echo", &test_case).await;
+        assert!(matches!(result, Err(ExecutorError::ConfigError(_))));
+
+        executor.set_validation_scripts(vec![validation_script("missing", "ruby", "after", "")]);
+        let result = executor.execute_test_case("echo", &test_case).await;
+        assert!(matches!(result, Err(ExecutorError::ConfigError(_))));
+    }
+
     #[test]
     fn test_executor_config_default() {
         let config = ExecutorConfig::default();
@@ -575,5 +1313,80 @@ mod tests {
 
         let connection_error = ExecutorError::ConnectionError("Connection failed".to_string());
         assert!(connection_error.to_string().contains("Connection failed"));
+        assert!(!connection_error.is_server_error());
+        assert!(ExecutorError::ToolCallError("bad".to_string()).is_server_error());
+    }
+
+    #[test]
+    fn test_check_error_expectation() {
+        let expected = ExpectedOutput {
+            error: true,
+            error_message_contains: Some("required".to_string()),
+            ..ExpectedOutput::default()
+        };
+
+        let rejected = serde_json::json!({"isError": true, "error": {"message": "Tool call failed: -32602 'text' is required"}});
+        assert!(check_error_expectation(&rejected, &expected).is_none());
+
+        let tool_error = serde_json::json!({"isError": true, "content": [{"type": "text", "text": "a is required"}]});
+        assert!(check_error_expectation(&tool_error, &expected).is_none());
+
+        let wrong_message =
+            serde_json::json!({"isError": true, "content": [{"type": "text", "text": "boom"}]});
+        assert!(check_error_expectation(&wrong_message, &expected).is_some());
+
+        let success = serde_json::json!({"content": [], "isError": false});
+        assert!(check_error_expectation(&success, &expected).is_some());
+        assert!(check_error_expectation(&success, &ExpectedOutput::default()).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_captured_values_chain_into_later_inputs() {
+        let mut executor = create_test_executor().await;
+        let search = TestCase {
+            name: "search".to_string(),
+            capture: Some(
+                [
+                    ("node_id", "$.content[0].text.results[0].node_id"),
+                    ("raw", "$.content[0].text"),
+                    ("missing", "$.content[0].text.nothing"),
+                ]
+                .into_iter()
+                .map(|(name, path)| (name.to_string(), path.to_string()))
+                .collect(),
+            ),
+            ..TestCase::default()
+        };
+        let response = serde_json::json!({"content": [{"type": "text", "text": r#"{"results": [{"node_id": "n42"}]}"#}]});
+
+        let errors = executor.capture_values(&search, &response);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            matches!(&errors[0], ValidationError::CaptureError { name, .. } if name == "missing")
+        );
+        assert_eq!(executor.captured()["node_id"], "n42");
+        assert!(executor.captured()["raw"].is_string());
+
+        let mut explain = create_test_case();
+        explain.input = serde_json::json!({"symbol_id": "${captured.node_id}", "note": "from ${captured.node_id}"});
+        explain.expected.fields[0].value = Some(serde_json::json!("${captured.node_id}"));
+        let resolved = executor.resolve_captured(&explain).unwrap();
+        assert_eq!(
+            resolved.input,
+            serde_json::json!({"symbol_id": "n42", "note": "from n42"})
+        );
+        assert_eq!(
+            resolved.expected.fields[0].value,
+            Some(serde_json::json!("n42"))
+        );
+
+        // Cases without references are used as they are
+        assert!(matches!(
+            executor.resolve_captured(&create_test_case()).unwrap(),
+            Cow::Borrowed(_)
+        ));
+
+        executor.clear_captured();
+        assert!(executor.resolve_captured(&explain).is_err());
     }
 }
@@ -0,0 +1,411 @@
+//! Randomized tool arguments
+//!
+//! [`ArgumentGenerator`] draws values from a tool's input schema: random
+//! lengths, numbers anywhere within the bounds, random `enum` members and a
+//! random subset of optional properties. Violating arguments start from a
+//! conformant value and break it in one way, usually at a boundary: one past
+//! a `minimum` or `maxLength`, a missing required field, a wrong type, or an
+//! extreme value the schema happens to allow.
+
+use crate::generator::schema::{
+    self, allowed_types, merge_all_of, primary_type, sample_string, SchemaSampler,
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde_json::{json, Map, Value};
+
+/// Nesting depth after which recursive schemas are cut off with `null`
+const MAX_DEPTH: usize = 8;
+
+/// How far past their minimum random strings and arrays may grow
+const LENGTH_SPREAD: u64 = 16;
+
+/// Values that tend to break input handling, whatever the schema says
+fn extreme_values() -> Vec<Value> {
+    vec![
+        Value::Null,
+        json!(""),
+        json!(-1),
+        json!(i64::MAX),
+        json!(i64::MIN),
+        json!(1e308),
+        json!("\u{0}"),
+        json!("../../../../../../etc/passwd"),
+        json!("'; DROP TABLE users; --"),
+        json!("%s%s%s%n"),
+        json!("🦀\u{202e}\u{fffd}"),
+        json!("a".repeat(100_000)),
+        json!([]),
+        json!({}),
+        deeply_nested(64),
+    ]
+}
+
+fn deeply_nested(depth: usize) -> Value {
+    (0..depth).fold(json!(0), |inner, _| json!([inner]))
+}
+
+/// Draws random arguments for one tool's input schema
+pub struct ArgumentGenerator<'a> {
+    sampler: SchemaSampler<'a>,
+    schema: &'a Value,
+}
+
+impl<'a> ArgumentGenerator<'a> {
+    pub fn new(schema: &'a Value) -> Self {
+        Self {
+            sampler: SchemaSampler::new(schema),
+            schema,
+        }
+    }
+
+    /// Random arguments that satisfy the schema
+    pub fn conformant(&self, rng: &mut StdRng) -> Value {
+        self.random_value(self.schema, 0, rng)
+    }
+
+    /// Random arguments that break the schema in one way
+    pub fn violating(&self, rng: &mut StdRng) -> Value {
+        let mut arguments = self.conformant(rng);
+        let schema = self.sampler.resolve(self.schema);
+
+        let Some(object) = arguments.as_object_mut() else {
+            return schema::wrong_type_value(schema).unwrap_or(Value::Null);
+        };
+        let fields: Vec<_> = schema::properties(schema).collect();
+        let required = schema::required_fields(schema);
+
+        let mut mutations: Vec<Mutation> = vec![Mutation::NotAnObject, Mutation::Extreme];
+        if !required.is_empty() {
+            mutations.push(Mutation::DropRequired);
+        }
+        if !fields.is_empty() {
+            mutations.push(Mutation::Boundary);
+            mutations.push(Mutation::WrongType);
+        }
+        if schema.get("additionalProperties") == Some(&Value::Bool(false)) {
+            mutations.push(Mutation::UnknownField);
+        }
+
+        let mutation = mutations.choose(rng).copied().unwrap_or(Mutation::Extreme);
+        match mutation {
+            Mutation::NotAnObject => {
+                return [json!([]), json!("arguments"), json!(0), Value::Null]
+                    .choose(rng)
+                    .cloned()
+                    .unwrap_or(Value::Null);
+            }
+            Mutation::DropRequired => {
+                if let Some(field) = required.choose(rng) {
+                    object.remove(*field);
+                }
+            }
+            Mutation::UnknownField => {
+                object.insert("__moth_unknown".to_string(), json!("unexpected"));
+            }
+            Mutation::Boundary | Mutation::WrongType => {
+                if let Some((field, property)) = fields.choose(rng) {
+                    let property = self.sampler.resolve(property);
+                    let boundary = match mutation {
+                        Mutation::Boundary => boundary_violations(property).choose(rng).cloned(),
+                        _ => None,
+                    };
+                    let value = boundary
+                        .or_else(|| schema::wrong_type_value(property))
+                        .unwrap_or_else(|| random_extreme(rng));
+                    object.insert((*field).clone(), value);
+                }
+            }
+            Mutation::Extreme => {
+                let field = fields
+                    .choose(rng)
+                    .map(|(field, _)| (*field).clone())
+                    .unwrap_or_else(|| "value".to_string());
+                object.insert(field, random_extreme(rng));
+            }
+        }
+        arguments
+    }
+
+    fn random_value(&self, schema: &Value, depth: usize, rng: &mut StdRng) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        let schema = self.sampler.resolve(schema);
+
+        if let Some(value) = schema.get("const") {
+            return value.clone();
+        }
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if let Some(value) = values.choose(rng) {
+                return value.clone();
+            }
+        }
+        if let Some(alternatives) = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            if let Some(alternative) = alternatives.choose(rng) {
+                return self.random_value(alternative, depth + 1, rng);
+            }
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            let merged = merge_all_of(&self.sampler, schema, parts);
+            return self.random_value(&merged, depth + 1, rng);
+        }
+
+        let types = allowed_types(schema);
+        let kind = match types.choose(rng) {
+            Some(kind) => *kind,
+            None => primary_type(schema).unwrap_or("string"),
+        };
+        match kind {
+            "object" => {
+                let required = schema::required_fields(schema);
+                let mut object = Map::new();
+                for (name, property) in schema::properties(schema) {
+                    if required.contains(&name.as_str()) || rng.gen_bool(0.5) {
+                        object.insert(name.clone(), self.random_value(property, depth + 1, rng));
+                    }
+                }
+                Value::Object(object)
+            }
+            "array" => {
+                let count = random_length(schema, "minItems", "maxItems", rng);
+                let items = match schema.get("items") {
+                    Some(Value::Array(tuple)) => tuple.first(),
+                    other => other,
+                };
+                Value::Array(
+                    (0..count)
+                        .map(|_| match items {
+                            Some(items) => self.random_value(items, depth + 1, rng),
+                            None => json!(random_text(rng, 8)),
+                        })
+                        .collect(),
+                )
+            }
+            "string" => json!(random_string(schema, rng)),
+            "integer" => {
+                let (min, max) = number_range(schema, true);
+                json!(rng.gen_range(min as i64..=max as i64))
+            }
+            "number" => {
+                let (min, max) = number_range(schema, false);
+                json!(rng.gen_range(min..=max))
+            }
+            "boolean" => json!(rng.gen_bool(0.5)),
+            "null" => Value::Null,
+            _ => json!(random_text(rng, 8)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Mutation {
+    /// Replace the whole arguments object
+    NotAnObject,
+    DropRequired,
+    UnknownField,
+    /// One step past a declared bound
+    Boundary,
+    WrongType,
+    /// A value from [`extreme_values`]
+    Extreme,
+}
+
+fn random_extreme(rng: &mut StdRng) -> Value {
+    extreme_values().choose(rng).cloned().unwrap_or(Value::Null)
+}
+
+/// Values one step outside the bounds `schema` declares
+fn boundary_violations(schema: &Value) -> Vec<Value> {
+    let integer = primary_type(schema) == Some("integer");
+    let step = if integer { 1.0 } else { 0.5 };
+    let number = |value: f64| {
+        if integer {
+            json!(value as i64)
+        } else {
+            json!(value)
+        }
+    };
+
+    let mut values = Vec::new();
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        values.push(number(min - step));
+    }
+    if let Some(min) = schema.get("exclusiveMinimum").and_then(Value::as_f64) {
+        values.push(number(min));
+    }
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        values.push(number(max + step));
+    }
+    if let Some(max) = schema.get("exclusiveMaximum").and_then(Value::as_f64) {
+        values.push(number(max));
+    }
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if min > 0 {
+            values.push(json!("x".repeat(min as usize - 1)));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        values.push(json!("x".repeat(max as usize + 1)));
+    }
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if min > 0 {
+            values.push(Value::Array(vec![json!("x"); min as usize - 1]));
+        }
+    }
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        values.push(Value::Array(vec![json!("x"); max as usize + 1]));
+    }
+    values.extend(schema::invalid_enum_value(schema));
+    values
+}
+
+/// Inclusive range for random numbers, `-1000..=1000` unless bounded
+fn number_range(schema: &Value, integer: bool) -> (f64, f64) {
+    let step = if integer { 1.0 } else { f64::EPSILON };
+    let min = schema
+        .get("minimum")
+        .and_then(Value::as_f64)
+        .or_else(|| {
+            schema
+                .get("exclusiveMinimum")
+                .and_then(Value::as_f64)
+                .map(|bound| bound + step)
+        })
+        .unwrap_or(-1000.0);
+    let max = schema
+        .get("maximum")
+        .and_then(Value::as_f64)
+        .or_else(|| {
+            schema
+                .get("exclusiveMaximum")
+                .and_then(Value::as_f64)
+                .map(|bound| bound - step)
+        })
+        .unwrap_or(min.max(0.0) + 1000.0);
+    let (min, max) = if integer {
+        (min.ceil(), max.floor())
+    } else {
+        (min, max)
+    };
+    (min, max.max(min))
+}
+
+fn random_length(schema: &Value, min_key: &str, max_key: &str, rng: &mut StdRng) -> u64 {
+    let min = schema.get(min_key).and_then(Value::as_u64).unwrap_or(0);
+    let max = schema
+        .get(max_key)
+        .and_then(Value::as_u64)
+        .unwrap_or(u64::MAX)
+        .min(min + LENGTH_SPREAD)
+        .max(min);
+    rng.gen_range(min..=max)
+}
+
+fn random_string(schema: &Value, rng: &mut StdRng) -> String {
+    // Formats and patterns constrain the text too much to invent it
+    if schema.get("format").is_some() || schema.get("pattern").is_some() {
+        return sample_string(schema);
+    }
+    let length = random_length(schema, "minLength", "maxLength", rng);
+    random_text(rng, length as usize)
+}
+
+fn random_text(rng: &mut StdRng, length: usize) -> String {
+    const ALPHABET: &[char] = &[
+        'a', 'b', 'z', 'A', 'Z', '0', '9', ' ', '-', '_', '.', '/', '\\', '"', '\'', '\n', '\t',
+        '{', '}', '<', '>', '%', '$', 'é', 'ß', '中', '🦀',
+    ];
+    (0..length)
+        .map(|_| *ALPHABET.choose(rng).unwrap_or(&'a'))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::SchemaValidator;
+    use rand::SeedableRng;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string", "minLength": 1, "maxLength": 20},
+                "count": {"type": "integer", "minimum": 1, "maximum": 10},
+                "mode": {"type": "string", "enum": ["fast", "slow"]},
+                "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 3},
+                "point": {"$ref": "#/$defs/Point"}
+            },
+            "required": ["path", "count"],
+            "additionalProperties": false,
+            "$defs": {
+                "Point": {
+                    "type": "object",
+                    "properties": {"x": {"type": "number", "exclusiveMinimum": 0}},
+                    "required": ["x"]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_conformant_arguments_satisfy_the_schema() {
+        let schema = schema();
+        let generator = ArgumentGenerator::new(&schema);
+        let validator = SchemaValidator::new();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..200 {
+            let arguments = generator.conformant(&mut rng);
+            let result = validator.validate_with_inline_schema(&arguments, &schema);
+            assert!(result.is_valid, "{} {:?}", arguments, result.violations);
+        }
+    }
+
+    #[test]
+    fn test_violating_arguments_break_the_schema() {
+        let schema = schema();
+        let generator = ArgumentGenerator::new(&schema);
+        let validator = SchemaValidator::new();
+        let mut rng = StdRng::seed_from_u64(7);
+
+        let invalid = (0..200)
+            .map(|_| generator.violating(&mut rng))
+            .filter(|arguments| {
+                !validator
+                    .validate_with_inline_schema(arguments, &schema)
+                    .is_valid
+            })
+            .count();
+        // Extreme values are sometimes allowed, e.g. "" for an optional enum-free field
+        assert!(invalid > 150, "only {} of 200 were invalid", invalid);
+    }
+
+    #[test]
+    fn test_same_seed_same_arguments() {
+        let schema = schema();
+        let generator = ArgumentGenerator::new(&schema);
+        let draw = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..10)
+                .map(|_| generator.violating(&mut rng))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(43));
+    }
+
+    #[test]
+    fn test_boundary_violations() {
+        let values = boundary_violations(&json!({"type": "integer", "minimum": 1, "maximum": 10}));
+        assert_eq!(values, vec![json!(0), json!(11)]);
+        let values = boundary_violations(&json!({"type": "string", "maxLength": 2}));
+        assert_eq!(values, vec![json!("xxx")]);
+    }
+}
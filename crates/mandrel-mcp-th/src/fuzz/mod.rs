@@ -0,0 +1,638 @@
+//! Fuzz testing of a server's tools
+//!
+//! `moth fuzz` calls every tool with random arguments drawn from its input
+//! schema, half of them conformant and half breaking the schema at a
+//! boundary. Calls are made over a raw JSON-RPC session so the fuzzer sees
+//! what the server actually sends. Four kinds of failure are reported:
+//! crashes, hangs past the timeout, lines that are not JSON-RPC, and
+//! responses [`ProtocolValidator`] rejects. Each finding's arguments are
+//! shrunk while the failure still reproduces and saved as a runnable spec.
+//!
+//! Runs are deterministic: every tool draws from its own generator seeded
+//! from the run's seed and the tool name, so a finding reproduces with the
+//! same seed even when other tools are added or filtered out.
+
+use crate::error::Result;
+use crate::generator::identifier;
+use crate::spec::{
+    ExpectedOutput, FieldValidation, PerformanceRequirements, ServerCapabilities, ServerConfig,
+    TestCase, TestSpecification, ToolSpec,
+};
+use crate::validation::{
+    ProtocolRequirements, ProtocolValidator, SchemaValidator, ValidationSeverity,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rmcp::model::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tracing::{debug, info};
+
+pub mod generate;
+pub mod transport;
+
+pub use generate::ArgumentGenerator;
+pub use transport::StdioTarget;
+
+/// Reproduction attempts spent shrinking a hang, each costing a full timeout
+const HANG_MINIMIZE_ATTEMPTS: usize = 8;
+
+/// How a fuzzed call went wrong
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// The server exited or closed its output
+    Crash,
+    /// No response within the timeout
+    Hang,
+    /// A JSON-RPC response that breaks the MCP protocol
+    ProtocolViolation,
+    /// Output that is not a JSON-RPC message
+    NonJsonRpc,
+}
+
+impl FindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Crash => "crash",
+            Self::Hang => "hang",
+            Self::ProtocolViolation => "protocol_violation",
+            Self::NonJsonRpc => "non_json_rpc",
+        }
+    }
+}
+
+impl fmt::Display for FindingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Whether fuzzed arguments satisfy the tool's input schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    Conformant,
+    Violating,
+}
+
+/// Result of one `tools/call` against a [`FuzzTarget`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The JSON-RPC response message carrying the request's id
+    Response(Value),
+    /// The call failed before a response arrived
+    Failure { kind: FindingKind, message: String },
+}
+
+impl Outcome {
+    pub fn failure(kind: FindingKind, message: impl Into<String>) -> Self {
+        Self::Failure {
+            kind,
+            message: message.into(),
+        }
+    }
+}
+
+/// Server under fuzz
+pub trait FuzzTarget {
+    /// Send `tools/call` for `name` with `arguments`, restarting the server
+    /// first if an earlier call left it unusable
+    fn call_tool(&mut self, name: &str, arguments: &Value) -> impl Future<Output = Outcome>;
+}
+
+/// Failure found while fuzzing one tool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub tool: String,
+    pub kind: FindingKind,
+    pub input: InputKind,
+    /// Minimized arguments that still trigger the failure
+    pub arguments: Value,
+    /// Arguments as first generated
+    pub original_arguments: Value,
+    pub message: String,
+    /// Iteration of the tool's run that found the failure
+    pub iteration: usize,
+    /// How long the failing call took
+    pub duration_ms: u64,
+}
+
+/// Settings for a fuzz run
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    pub seed: u64,
+    /// Calls per tool
+    pub iterations: usize,
+    /// Tools to fuzz; all tools when empty
+    pub tools: Vec<String>,
+    /// Reproduction attempts spent shrinking each finding
+    pub minimize_attempts: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            iterations: 100,
+            tools: Vec::new(),
+            minimize_attempts: 64,
+        }
+    }
+}
+
+/// Outcome of a fuzz run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FuzzReport {
+    pub seed: u64,
+    pub tools: Vec<String>,
+    /// Calls made, including minimization attempts
+    pub calls: usize,
+    pub findings: Vec<Finding>,
+}
+
+/// Runs fuzzed calls and classifies the outcomes
+pub struct Fuzzer {
+    config: FuzzConfig,
+    protocol: ProtocolValidator,
+    schemas: SchemaValidator,
+}
+
+impl Fuzzer {
+    pub fn new(config: FuzzConfig) -> Self {
+        Self {
+            config,
+            protocol: ProtocolValidator::new(crate::MCP_PROTOCOL_VERSION.to_string()),
+            schemas: SchemaValidator::new(),
+        }
+    }
+
+    /// Fuzz each selected tool for the configured number of iterations
+    pub async fn run<T: FuzzTarget>(&self, target: &mut T, tools: &[Tool]) -> FuzzReport {
+        let mut report = FuzzReport {
+            seed: self.config.seed,
+            tools: Vec::new(),
+            calls: 0,
+            findings: Vec::new(),
+        };
+
+        for tool in tools {
+            if !self.config.tools.is_empty() && !self.config.tools.iter().any(|t| t == &tool.name) {
+                continue;
+            }
+            info!("Fuzzing tool '{}'", tool.name);
+            report.tools.push(tool.name.to_string());
+            self.fuzz_tool(target, tool, &mut report).await;
+        }
+        report
+    }
+
+    async fn fuzz_tool<T: FuzzTarget>(&self, target: &mut T, tool: &Tool, report: &mut FuzzReport) {
+        let schema = Value::Object(tool.input_schema.as_ref().clone());
+        let generator = ArgumentGenerator::new(&schema);
+        let mut rng = StdRng::seed_from_u64(tool_seed(self.config.seed, &tool.name));
+        // One finding per kind is enough to act on; repeats add noise
+        let mut found = HashSet::new();
+
+        for iteration in 0..self.config.iterations {
+            let arguments = if rng.gen_bool(0.5) {
+                generator.conformant(&mut rng)
+            } else {
+                generator.violating(&mut rng)
+            };
+
+            let started = Instant::now();
+            let outcome = target.call_tool(&tool.name, &arguments).await;
+            let duration = started.elapsed();
+            report.calls += 1;
+
+            let Some((kind, message)) = self.check(outcome) else {
+                continue;
+            };
+            if !found.insert(kind) {
+                debug!("Repeated {} in '{}': {}", kind, tool.name, message);
+                continue;
+            }
+            info!("Found {} in '{}': {}", kind, tool.name, message);
+
+            let minimized = self
+                .minimize(target, &tool.name, &arguments, kind, &mut report.calls)
+                .await;
+            let input = if self
+                .schemas
+                .validate_with_inline_schema(&minimized, &schema)
+                .is_valid
+            {
+                InputKind::Conformant
+            } else {
+                InputKind::Violating
+            };
+            report.findings.push(Finding {
+                tool: tool.name.to_string(),
+                kind,
+                input,
+                arguments: minimized,
+                original_arguments: arguments,
+                message,
+                iteration,
+                duration_ms: duration.as_millis() as u64,
+            });
+        }
+    }
+
+    /// The failure an outcome represents, if any
+    pub fn check(&self, outcome: Outcome) -> Option<(FindingKind, String)> {
+        let response = match outcome {
+            Outcome::Response(response) => response,
+            Outcome::Failure { kind, message } => return Some((kind, message)),
+        };
+
+        let requirements = ProtocolRequirements {
+            method: "tools/call".to_string(),
+            required_fields: vec!["content".to_string()],
+            optional_fields: vec!["isError".to_string(), "structuredContent".to_string()],
+            expected_error_codes: Vec::new(),
+            capability_requirements: Vec::new(),
+        };
+        let issues: Vec<_> = self
+            .protocol
+            .validate_mcp_method_response("tools/call", &response, &requirements)
+            .into_iter()
+            .filter(|issue| issue.severity == ValidationSeverity::Error)
+            .map(|issue| issue.message)
+            .collect();
+        (!issues.is_empty()).then(|| (FindingKind::ProtocolViolation, issues.join("; ")))
+    }
+
+    /// Shrink `arguments` while the call still fails with `kind`
+    async fn minimize<T: FuzzTarget>(
+        &self,
+        target: &mut T,
+        tool: &str,
+        arguments: &Value,
+        kind: FindingKind,
+        calls: &mut usize,
+    ) -> Value {
+        let mut budget = match kind {
+            FindingKind::Hang => self.config.minimize_attempts.min(HANG_MINIMIZE_ATTEMPTS),
+            _ => self.config.minimize_attempts,
+        };
+        let mut current = arguments.clone();
+
+        'shrink: while budget > 0 {
+            for candidate in shrink_candidates(&current) {
+                if budget == 0 {
+                    break 'shrink;
+                }
+                budget -= 1;
+                *calls += 1;
+                let outcome = target.call_tool(tool, &candidate).await;
+                if matches!(self.check(outcome), Some((found, _)) if found == kind) {
+                    current = candidate;
+                    continue 'shrink;
+                }
+            }
+            break;
+        }
+        current
+    }
+}
+
+/// Seed for one tool's generator, stable across runs and tool sets
+pub fn tool_seed(seed: u64, tool: &str) -> u64 {
+    // FNV-1a; std's hashers are not guaranteed stable between releases
+    tool.bytes()
+        .fold(seed ^ 0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Smaller variants of `value`, simplest first
+fn shrink_candidates(value: &Value) -> Vec<Value> {
+    let mut candidates = Vec::new();
+    match value {
+        Value::Object(object) => {
+            for key in object.keys() {
+                let mut smaller = object.clone();
+                smaller.remove(key);
+                candidates.push(Value::Object(smaller));
+            }
+            for (key, child) in object {
+                for shrunk in shrink_candidates(child) {
+                    let mut smaller = object.clone();
+                    smaller.insert(key.clone(), shrunk);
+                    candidates.push(Value::Object(smaller));
+                }
+            }
+        }
+        Value::Array(items) => {
+            if items.len() > 1 {
+                candidates.push(Value::Array(items[..items.len() / 2].to_vec()));
+            }
+            for index in 0..items.len() {
+                let mut smaller = items.clone();
+                smaller.remove(index);
+                candidates.push(Value::Array(smaller));
+            }
+            for (index, item) in items.iter().enumerate() {
+                for shrunk in shrink_candidates(item) {
+                    let mut smaller = items.clone();
+                    smaller[index] = shrunk;
+                    candidates.push(Value::Array(smaller));
+                }
+            }
+        }
+        Value::String(text) if !text.is_empty() => {
+            candidates.push(json!(""));
+            let length = text.chars().count();
+            if length > 1 {
+                candidates.push(json!(text.chars().take(length / 2).collect::<String>()));
+            }
+        }
+        Value::Number(number) => {
+            if let Some(integer) = number.as_i64() {
+                if integer != 0 {
+                    candidates.push(json!(0));
+                }
+                if integer.abs() > 1 {
+                    candidates.push(json!(integer / 2));
+                }
+            } else if number.as_f64() != Some(0.0) {
+                candidates.push(json!(0));
+            }
+        }
+        Value::Bool(true) => candidates.push(json!(false)),
+        _ => {}
+    }
+    candidates
+}
+
+/// Runnable specification that reproduces `finding` against `server`
+///
+/// Conformant arguments are expected to succeed and violating ones to be
+/// rejected, so the case fails until the server handles the input. A hang's
+/// case is additionally held to the duration that timed out.
+pub fn reproducer(server: &ServerConfig, finding: &Finding, seed: u64) -> TestSpecification {
+    let expected = match finding.input {
+        InputKind::Conformant => ExpectedOutput {
+            fields: vec![FieldValidation {
+                path: "$.content".to_string(),
+                value: None,
+                field_type: Some("array".to_string()),
+                required: true,
+                pattern: None,
+                min: None,
+                max: None,
+            }],
+            ..ExpectedOutput::default()
+        },
+        InputKind::Violating => ExpectedOutput {
+            error: true,
+            ..ExpectedOutput::default()
+        },
+    };
+    let performance = (finding.kind == FindingKind::Hang).then_some(PerformanceRequirements {
+        max_duration_ms: Some(finding.duration_ms as u32),
+        max_memory_mb: None,
+        min_ops_per_sec: None,
+    });
+
+    let metadata = HashMap::from([
+        ("fuzz_seed".to_string(), json!(seed)),
+        ("fuzz_iteration".to_string(), json!(finding.iteration)),
+        ("fuzz_finding".to_string(), json!(finding.message)),
+        (
+            "fuzz_original_arguments".to_string(),
+            finding.original_arguments.clone(),
+        ),
+    ]);
+
+    TestSpecification {
+        name: format!("Fuzz reproducer: {} {}", finding.tool, finding.kind),
+        version: "1.0.0".to_string(),
+        description: Some(format!(
+            "{} in tool '{}' found by `moth fuzz --seed {}`: {}",
+            finding.kind, finding.tool, seed, finding.message
+        )),
+        capabilities: ServerCapabilities {
+            tools: true,
+            ..ServerCapabilities::default()
+        },
+        server: server.clone(),
+        tools: Some(vec![ToolSpec {
+            name: finding.tool.clone(),
+            description: None,
+            input_schema: None,
+            output_schema: None,
+            parameters: None,
+            matrix: None,
+            tests: vec![TestCase {
+                name: reproducer_name(finding),
+                description: Some(finding.message.clone()),
+                input: finding.arguments.clone(),
+                expected,
+                performance,
+                tags: vec!["fuzz".to_string(), finding.kind.to_string()],
+                ..TestCase::default()
+            }],
+        }]),
+        resources: None,
+        prompts: None,
+        test_config: None,
+        metadata: Some(metadata),
+        validation_scripts: None,
+    }
+}
+
+fn reproducer_name(finding: &Finding) -> String {
+    identifier(&format!("fuzz_{}_{}", finding.tool, finding.kind))
+}
+
+/// Write one reproducer spec per finding into `dir`, returning their paths
+pub async fn save_reproducers(
+    dir: &Path,
+    server: &ServerConfig,
+    report: &FuzzReport,
+) -> Result<Vec<PathBuf>> {
+    tokio::fs::create_dir_all(dir).await?;
+    let mut paths = Vec::new();
+    for finding in &report.findings {
+        let path = dir.join(format!("{}.yaml", reproducer_name(finding)));
+        let spec = reproducer(server, finding, report.seed);
+        tokio::fs::write(&path, serde_yml::to_string(&spec)?).await?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::SpecificationLoader;
+    use std::sync::Arc;
+
+    /// Crashes when `count` exceeds 5, answers `mode: "raw"` with a
+    /// malformed result and stalls on an empty `path`
+    struct BuggyServer;
+
+    impl FuzzTarget for BuggyServer {
+        async fn call_tool(&mut self, _name: &str, arguments: &Value) -> Outcome {
+            if arguments["count"].as_i64().is_some_and(|count| count > 5) {
+                return Outcome::failure(FindingKind::Crash, "server exited with status 101");
+            }
+            if arguments["path"] == json!("") {
+                return Outcome::failure(FindingKind::Hang, "no response within 100ms");
+            }
+            let result = if arguments["mode"] == json!("raw") {
+                json!({"content": "raw text"})
+            } else {
+                json!({"content": [{"type": "text", "text": "ok"}]})
+            };
+            Outcome::Response(json!({"jsonrpc": "2.0", "id": 1, "result": result}))
+        }
+    }
+
+    fn tool() -> Tool {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string", "minLength": 1},
+                "count": {"type": "integer", "minimum": 0, "maximum": 10},
+                "mode": {"type": "string", "enum": ["text", "raw"]},
+                "verbose": {"type": "boolean"}
+            },
+            "required": ["path", "count"]
+        });
+        Tool {
+            name: "read".into(),
+            description: None,
+            input_schema: Arc::new(schema.as_object().unwrap().clone()),
+            annotations: None,
+        }
+    }
+
+    fn config(seed: u64) -> FuzzConfig {
+        FuzzConfig {
+            seed,
+            iterations: 200,
+            ..FuzzConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fuzzer_finds_and_minimizes_failures() {
+        let fuzzer = Fuzzer::new(config(1));
+        let report = fuzzer.run(&mut BuggyServer, &[tool()]).await;
+
+        assert_eq!(report.tools, vec!["read"]);
+        let findings: HashMap<_, _> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.kind, finding))
+            .collect();
+        assert_eq!(findings.len(), 3, "{:#?}", report.findings);
+
+        // Shrunk to the least input that still fails
+        let crash = findings[&FindingKind::Crash];
+        let fields: Vec<_> = crash.arguments.as_object().unwrap().keys().collect();
+        assert_eq!(fields, vec!["count"]);
+        assert!(crash.arguments["count"].as_i64().unwrap() > 5);
+        assert_eq!(crash.input, InputKind::Violating);
+        assert_eq!(findings[&FindingKind::Hang].arguments, json!({"path": ""}));
+        let violation = findings[&FindingKind::ProtocolViolation];
+        assert_eq!(violation.arguments, json!({"mode": "raw"}));
+        assert!(violation.message.contains("content field must be an array"));
+        assert!(report.calls > 200);
+    }
+
+    #[tokio::test]
+    async fn test_same_seed_same_findings() {
+        let run = |seed| async move {
+            let report = Fuzzer::new(config(seed))
+                .run(&mut BuggyServer, &[tool()])
+                .await;
+            serde_json::to_value(report.findings).unwrap()
+        };
+        assert_eq!(run(9).await, run(9).await);
+    }
+
+    #[tokio::test]
+    async fn test_tool_filter() {
+        let fuzzer = Fuzzer::new(FuzzConfig {
+            tools: vec!["other".to_string()],
+            ..config(1)
+        });
+        let report = fuzzer.run(&mut BuggyServer, &[tool()]).await;
+        assert!(report.tools.is_empty());
+        assert_eq!(report.calls, 0);
+    }
+
+    #[test]
+    fn test_check_classifies_responses() {
+        let fuzzer = Fuzzer::new(FuzzConfig::default());
+        let ok = json!({"jsonrpc": "2.0", "id": 1, "result": {"content": [], "isError": true}});
+        assert_eq!(fuzzer.check(Outcome::Response(ok)), None);
+        let rejected =
+            json!({"jsonrpc": "2.0", "id": 1, "error": {"code": -32602, "message": "bad"}});
+        assert_eq!(fuzzer.check(Outcome::Response(rejected)), None);
+
+        let (kind, message) = fuzzer
+            .check(Outcome::Response(
+                json!({"jsonrpc": "2.0", "id": 1, "error": {"message": "bad"}}),
+            ))
+            .unwrap();
+        assert_eq!(kind, FindingKind::ProtocolViolation);
+        assert!(message.contains("code"));
+    }
+
+    #[test]
+    fn test_shrink_candidates() {
+        let candidates = shrink_candidates(&json!({"a": "xyz", "b": [1, 2]}));
+        assert_eq!(candidates[0], json!({"b": [1, 2]}));
+        assert_eq!(candidates[1], json!({"a": "xyz"}));
+        assert!(candidates.contains(&json!({"a": "", "b": [1, 2]})));
+        assert!(candidates.contains(&json!({"a": "xyz", "b": [1]})));
+        assert!(shrink_candidates(&json!(0)).is_empty());
+    }
+
+    #[test]
+    fn test_tool_seed_is_stable() {
+        assert_eq!(tool_seed(0, "read"), tool_seed(0, "read"));
+        assert_ne!(tool_seed(0, "read"), tool_seed(0, "write"));
+        assert_ne!(tool_seed(0, "read"), tool_seed(1, "read"));
+    }
+
+    #[test]
+    fn test_reproducer_is_a_loadable_spec() {
+        let server = ServerConfig::stdio("node", vec!["server.js".to_string()]);
+        let finding = Finding {
+            tool: "read-file".to_string(),
+            kind: FindingKind::Hang,
+            input: InputKind::Violating,
+            arguments: json!({"path": ""}),
+            original_arguments: json!({"path": "", "count": 3}),
+            message: "no response within 5000ms".to_string(),
+            iteration: 12,
+            duration_ms: 5000,
+        };
+        let spec = reproducer(&server, &finding, 42);
+
+        let test = &spec.tools.as_ref().unwrap()[0].tests[0];
+        assert_eq!(test.name, "fuzz_read_file_hang");
+        assert!(test.expected.error);
+        assert_eq!(
+            test.performance.as_ref().unwrap().max_duration_ms,
+            Some(5000)
+        );
+        assert_eq!(spec.metadata.as_ref().unwrap()["fuzz_seed"], json!(42));
+
+        let yaml = serde_yml::to_string(&spec).unwrap();
+        let loader = SpecificationLoader::new().unwrap();
+        assert_eq!(loader.parse_yaml(&yaml).unwrap(), spec);
+    }
+}
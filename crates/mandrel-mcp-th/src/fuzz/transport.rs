@@ -0,0 +1,399 @@
+//! Raw JSON-RPC session with a stdio server
+//!
+//! The fuzzer talks to the server without the SDK client, which would
+//! reject malformed output before the fuzzer could see it. Each line the
+//! server writes is checked as it arrives: output that does not parse or is
+//! not a JSON-RPC message is a finding, as are end of output and silence
+//! past the timeout. After any of those the server is killed and restarted
+//! on the next call, so one failure does not mask the ones after it.
+
+use super::{FindingKind, FuzzTarget, Outcome};
+use crate::cassette::session::codes;
+use crate::error::{Error, Result};
+use crate::spec::ServerConfig;
+use rmcp::model::Tool;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::task::JoinHandle;
+use tokio::time::{timeout_at, Instant};
+use tracing::debug;
+
+/// Stderr lines kept to explain a crash
+const STDERR_TAIL_LINES: usize = 20;
+
+/// Longest stretch of unparseable output quoted in a finding
+const QUOTED_OUTPUT_CHARS: usize = 200;
+
+/// A stdio server launched from a spec's server configuration
+pub struct StdioTarget {
+    server: ServerConfig,
+    timeout: Duration,
+    session: Option<Session>,
+}
+
+impl StdioTarget {
+    /// Target for `server`, which must use the stdio transport; each request
+    /// may take up to `timeout`
+    pub fn new(server: ServerConfig, timeout: Duration) -> Result<Self> {
+        if server.transport != "stdio" {
+            return Err(Error::config(format!(
+                "Fuzzing needs a stdio server, got transport '{}'",
+                server.transport
+            )));
+        }
+        if server.command.is_empty() {
+            return Err(Error::config("Server configuration has no command"));
+        }
+        Ok(Self {
+            server,
+            timeout,
+            session: None,
+        })
+    }
+
+    /// All tools the server offers, following `nextCursor`
+    pub async fn list_tools(&mut self) -> Result<Vec<Tool>> {
+        let mut tools = Vec::new();
+        let mut cursor: Option<Value> = None;
+        loop {
+            let params = match cursor.take() {
+                Some(cursor) => json!({"cursor": cursor}),
+                None => json!({}),
+            };
+            let result = self.request("tools/list", params).await?;
+            let page: Vec<Tool> =
+                serde_json::from_value(result.get("tools").cloned().unwrap_or(json!([])))?;
+            tools.extend(page);
+            match result.get("nextCursor") {
+                Some(next) if !next.is_null() => cursor = Some(next.clone()),
+                _ => return Ok(tools),
+            }
+        }
+    }
+
+    /// Stop the server
+    pub async fn shutdown(&mut self) {
+        if let Some(mut session) = self.session.take() {
+            let _ = session.child.kill().await;
+        }
+    }
+
+    async fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let timeout = self.timeout;
+        let session = self.session().await.map_err(Error::connection)?;
+        match session.request(method, params, timeout).await {
+            Outcome::Response(response) => match response.get("error") {
+                Some(error) => Err(Error::execution(format!("{} failed: {}", method, error))),
+                None => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+            },
+            Outcome::Failure { message, .. } => {
+                self.session = None;
+                Err(Error::connection(format!("{} failed: {}", method, message)))
+            }
+        }
+    }
+
+    /// The running session, starting the server if needed
+    async fn session(&mut self) -> std::result::Result<&mut Session, String> {
+        if self.session.is_none() {
+            self.session = Some(Session::start(&self.server, self.timeout).await?);
+        }
+        Ok(self.session.as_mut().expect("session was just started"))
+    }
+}
+
+impl FuzzTarget for StdioTarget {
+    async fn call_tool(&mut self, name: &str, arguments: &Value) -> Outcome {
+        let timeout = self.timeout;
+        let session = match self.session().await {
+            Ok(session) => session,
+            Err(message) => {
+                return Outcome::failure(FindingKind::Crash, format!("restart failed: {}", message))
+            }
+        };
+
+        let params = json!({"name": name, "arguments": arguments});
+        let outcome = session.request("tools/call", params, timeout).await;
+        if matches!(outcome, Outcome::Failure { .. }) {
+            // The stream may be out of step or the process stuck; start over
+            self.shutdown().await;
+        }
+        outcome
+    }
+}
+
+/// One running server process
+struct Session {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+    stderr: Arc<Mutex<VecDeque<String>>>,
+    stderr_reader: Option<JoinHandle<()>>,
+    next_id: u64,
+}
+
+impl Session {
+    /// Launch the server and complete the `initialize` handshake
+    async fn start(server: &ServerConfig, timeout: Duration) -> std::result::Result<Self, String> {
+        let mut command = Command::new(&server.command);
+        command
+            .args(&server.args)
+            .envs(&server.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        if let Some(dir) = &server.working_dir {
+            command.current_dir(dir);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("failed to launch '{}': {}", server.command, e))?;
+
+        let stdin = child.stdin.take().ok_or("server stdin unavailable")?;
+        let stdout = child.stdout.take().ok_or("server stdout unavailable")?;
+        let stderr = Arc::new(Mutex::new(VecDeque::new()));
+        let stderr_reader = child.stderr.take().map(|pipe| {
+            let tail = Arc::clone(&stderr);
+            tokio::spawn(async move {
+                let mut lines = BufReader::new(pipe).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                    if tail.len() == STDERR_TAIL_LINES {
+                        tail.pop_front();
+                    }
+                    tail.push_back(line);
+                }
+            })
+        });
+
+        let mut session = Self {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+            stderr,
+            stderr_reader,
+            next_id: 0,
+        };
+        let params = json!({
+            "protocolVersion": crate::MCP_PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": {"name": "moth-fuzz", "version": crate::VERSION},
+        });
+        match session.request("initialize", params, timeout).await {
+            Outcome::Response(response) if response.get("result").is_some() => {}
+            Outcome::Response(response) => {
+                return Err(format!("initialize rejected: {}", response["error"]))
+            }
+            Outcome::Failure { kind, message } => {
+                return Err(format!("initialize failed ({}): {}", kind, message))
+            }
+        }
+        session
+            .send(&json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await
+            .map_err(|e| format!("failed to send initialized notification: {}", e))?;
+        Ok(session)
+    }
+
+    /// Send a request and wait for the response with its id
+    async fn request(&mut self, method: &str, params: Value, timeout: Duration) -> Outcome {
+        self.next_id += 1;
+        let id = json!(self.next_id);
+        let deadline = Instant::now() + timeout;
+        let message = json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params});
+
+        // A server that stops reading can block the write once the pipe fills
+        match timeout_at(deadline, self.send(&message)).await {
+            Err(_) => return self.hang(timeout),
+            Ok(Err(e)) => {
+                let reason = format!("request could not be written: {}", e);
+                return Outcome::failure(FindingKind::Crash, self.exit_report(&reason).await);
+            }
+            Ok(Ok(())) => {}
+        }
+
+        loop {
+            let line = match timeout_at(deadline, self.stdout.next_line()).await {
+                Err(_) => return self.hang(timeout),
+                Ok(Ok(Some(line))) => line,
+                Ok(Ok(None)) => {
+                    let report = self.exit_report("output closed").await;
+                    return Outcome::failure(FindingKind::Crash, report);
+                }
+                Ok(Err(e)) => {
+                    let report = self.exit_report(&format!("output unreadable: {}", e)).await;
+                    return Outcome::failure(FindingKind::Crash, report);
+                }
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                return Outcome::failure(
+                    FindingKind::NonJsonRpc,
+                    format!("output is not JSON: {}", quote(&line)),
+                );
+            };
+            if message.get("jsonrpc") != Some(&json!("2.0")) || !message.is_object() {
+                return Outcome::failure(
+                    FindingKind::NonJsonRpc,
+                    format!("output is not a JSON-RPC 2.0 message: {}", quote(&line)),
+                );
+            }
+
+            if let Some(request) = message.get("method").and_then(Value::as_str) {
+                // Notifications need no answer; the fuzzer offers no client features
+                if let Some(request_id) = message.get("id") {
+                    debug!("Declining server request '{}'", request);
+                    let reply = json!({
+                        "jsonrpc": "2.0",
+                        "id": request_id,
+                        "error": {"code": codes::METHOD_NOT_FOUND, "message": "Not supported"},
+                    });
+                    if self.send(&reply).await.is_err() {
+                        let report = self.exit_report("request could not be declined").await;
+                        return Outcome::failure(FindingKind::Crash, report);
+                    }
+                }
+                continue;
+            }
+            if message.get("id") == Some(&id) {
+                return Outcome::Response(message);
+            }
+            return Outcome::failure(
+                FindingKind::ProtocolViolation,
+                format!(
+                    "response for id {} while waiting for id {}: {}",
+                    message.get("id").unwrap_or(&Value::Null),
+                    id,
+                    quote(&line)
+                ),
+            );
+        }
+    }
+
+    async fn send(&mut self, message: &Value) -> std::io::Result<()> {
+        let mut encoded = serde_json::to_vec(message)?;
+        encoded.push(b'\n');
+        self.stdin.write_all(&encoded).await?;
+        self.stdin.flush().await
+    }
+
+    fn hang(&self, timeout: Duration) -> Outcome {
+        Outcome::failure(
+            FindingKind::Hang,
+            format!("no response within {}ms", timeout.as_millis()),
+        )
+    }
+
+    /// Why the server stopped: exit status and the tail of its stderr
+    async fn exit_report(&mut self, reason: &str) -> String {
+        // Give the process a moment to finish exiting and flush stderr
+        let status = tokio::time::timeout(Duration::from_millis(500), self.child.wait())
+            .await
+            .ok()
+            .and_then(|status| status.ok());
+        if let (Some(_), Some(reader)) = (status, self.stderr_reader.take()) {
+            // stderr closes with the process; collect its last lines
+            let _ = tokio::time::timeout(Duration::from_millis(500), reader).await;
+        }
+        let mut report = match status {
+            Some(status) => format!("{}; server exited with {}", reason, status),
+            None => reason.to_string(),
+        };
+        let tail = self.stderr.lock().unwrap_or_else(|e| e.into_inner());
+        if !tail.is_empty() {
+            report.push_str("; stderr: ");
+            report.push_str(&tail.iter().cloned().collect::<Vec<_>>().join("\n"));
+        }
+        report
+    }
+}
+
+fn quote(line: &str) -> String {
+    let mut quoted: String = line.chars().take(QUOTED_OUTPUT_CHARS).collect();
+    if quoted.len() < line.len() {
+        quoted.push('…');
+    }
+    quoted
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    /// A shell MCP server with one bug per trigger word in the arguments
+    const SERVER: &str = r#"
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9]*\).*/\1/p')
+  [ -z "$id" ] && continue
+  case "$line" in
+    *'"method":"initialize"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"sh","version":"1"}}}\n' "$id" ;;
+    *'"method":"tools/list"'*)
+      printf '{"jsonrpc":"2.0","id":%s,"result":{"tools":[{"name":"echo","inputSchema":{"type":"object","properties":{"text":{"type":"string"}}}}]}}\n' "$id" ;;
+    *crash*) echo "panicked at 'index out of bounds'" >&2; exit 101 ;;
+    *garbage*) echo "debug: handling call" ;;
+    *stall*) sleep 5 ;;
+    *) printf '{"jsonrpc":"2.0","method":"notifications/message","params":{}}\n{"jsonrpc":"2.0","id":%s,"result":{"content":[]}}\n' "$id" ;;
+  esac
+done
+"#;
+
+    fn target() -> StdioTarget {
+        let server = ServerConfig::stdio("sh", vec!["-c".to_string(), SERVER.to_string()]);
+        StdioTarget::new(server, Duration::from_millis(500)).unwrap()
+    }
+
+    fn failure_kind(outcome: &Outcome) -> Option<FindingKind> {
+        match outcome {
+            Outcome::Failure { kind, .. } => Some(*kind),
+            Outcome::Response(_) => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stdio_target_classifies_failures_and_restarts() {
+        let mut target = target();
+        let tools = target.list_tools().await.unwrap();
+        assert_eq!(tools[0].name, "echo");
+
+        let ok = target.call_tool("echo", &json!({"text": "hi"})).await;
+        assert_eq!(
+            ok,
+            Outcome::Response(json!({"jsonrpc": "2.0", "id": 3, "result": {"content": []}}))
+        );
+
+        let crash = target.call_tool("echo", &json!({"text": "crash"})).await;
+        assert_eq!(failure_kind(&crash), Some(FindingKind::Crash));
+        let Outcome::Failure { message, .. } = crash else {
+            unreachable!()
+        };
+        assert!(message.contains("index out of bounds"), "{}", message);
+
+        // The server is restarted for the next call
+        let garbage = target.call_tool("echo", &json!({"text": "garbage"})).await;
+        assert_eq!(failure_kind(&garbage), Some(FindingKind::NonJsonRpc));
+        let stall = target.call_tool("echo", &json!({"text": "stall"})).await;
+        assert_eq!(failure_kind(&stall), Some(FindingKind::Hang));
+        let ok = target.call_tool("echo", &json!({"text": "again"})).await;
+        assert_eq!(failure_kind(&ok), None);
+        target.shutdown().await;
+    }
+
+    #[test]
+    fn test_only_stdio_servers_can_be_fuzzed() {
+        let server = ServerConfig {
+            transport: "http".to_string(),
+            ..ServerConfig::stdio("", Vec::new())
+        };
+        assert!(StdioTarget::new(server, Duration::from_secs(1)).is_err());
+    }
+}
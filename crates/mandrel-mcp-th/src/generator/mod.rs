@@ -0,0 +1,295 @@
+//! Test specification generation from a server's tool schemas
+//!
+//! `moth generate` lists a server's tools and builds a skeleton
+//! [`TestSpecification`]: one case per tool with valid input synthesized from
+//! its input schema, plus negative cases that omit a required field, pass a
+//! value of the wrong type, or pass a value outside an `enum`. Negative cases
+//! expect `error: true`.
+
+use crate::spec::{
+    ExpectedOutput, FieldValidation, ServerCapabilities, ServerConfig, TestCase, TestSpecification,
+    ToolSpec,
+};
+use rmcp::model::Tool;
+use serde_json::Value;
+
+pub mod schema;
+
+pub use schema::SchemaSampler;
+
+/// Builds a skeleton specification from `tools/list` results
+#[derive(Debug, Clone)]
+pub struct SpecGenerator {
+    server: ServerConfig,
+    name: String,
+    version: String,
+    negative_cases: bool,
+}
+
+impl SpecGenerator {
+    /// Generator for a spec that launches the server with `server`
+    pub fn new(server: ServerConfig) -> Self {
+        Self {
+            server,
+            name: "Generated MCP Server".to_string(),
+            version: "1.0.0".to_string(),
+            negative_cases: true,
+        }
+    }
+
+    /// Use the name and version the server reported
+    pub fn with_server_info(mut self, name: impl Into<String>, version: impl Into<String>) -> Self {
+        self.name = name.into();
+        self.version = version.into();
+        self
+    }
+
+    /// Whether to generate negative cases (default: true)
+    pub fn with_negative_cases(mut self, enabled: bool) -> Self {
+        self.negative_cases = enabled;
+        self
+    }
+
+    /// Build the specification for `tools`
+    pub fn generate(&self, tools: &[Tool]) -> TestSpecification {
+        TestSpecification {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            description: Some(format!(
+                "Generated from the input schemas of {} tools",
+                tools.len()
+            )),
+            capabilities: ServerCapabilities {
+                tools: true,
+                ..ServerCapabilities::default()
+            },
+            server: self.server.clone(),
+            tools: Some(tools.iter().map(|tool| self.tool_spec(tool)).collect()),
+            resources: None,
+            prompts: None,
+            test_config: None,
+            metadata: None,
+            validation_scripts: None,
+        }
+    }
+
+    fn tool_spec(&self, tool: &Tool) -> ToolSpec {
+        let schema = Value::Object(tool.input_schema.as_ref().clone());
+        let sampler = SchemaSampler::new(&schema);
+        let valid = sampler.sample();
+
+        let mut tests = vec![TestCase {
+            name: identifier(&format!("{}_valid_input", tool.name)),
+            description: Some("Valid input built from the schema".to_string()),
+            input: valid.clone(),
+            expected: ExpectedOutput {
+                fields: vec![FieldValidation {
+                    path: "$.content".to_string(),
+                    value: None,
+                    field_type: Some("array".to_string()),
+                    required: true,
+                    pattern: None,
+                    min: None,
+                    max: None,
+                }],
+                ..ExpectedOutput::default()
+            },
+            tags: tags("valid"),
+            ..TestCase::default()
+        }];
+        if self.negative_cases {
+            tests.extend(negative_cases(&tool.name, &sampler, &schema, &valid));
+        }
+
+        ToolSpec {
+            name: tool.name.to_string(),
+            description: tool.description.as_ref().map(|d| d.to_string()),
+            input_schema: None,
+            output_schema: None,
+            parameters: None,
+            matrix: None,
+            tests,
+        }
+    }
+}
+
+/// Cases that break the top-level input one way at a time
+fn negative_cases(
+    tool_name: &str,
+    sampler: &SchemaSampler<'_>,
+    schema: &Value,
+    valid: &Value,
+) -> Vec<TestCase> {
+    let mut cases = Vec::new();
+    let mut case = |kind: &str, field: &str, description: String, input: Value| {
+        cases.push(TestCase {
+            name: identifier(&format!("{}_{}_{}", tool_name, kind, field)),
+            description: Some(description),
+            input,
+            expected: ExpectedOutput {
+                error: true,
+                ..ExpectedOutput::default()
+            },
+            tags: tags("negative"),
+            ..TestCase::default()
+        });
+    };
+
+    for field in schema::required_fields(schema) {
+        let mut input = valid.clone();
+        if let Some(object) = input.as_object_mut() {
+            object.remove(field);
+        }
+        case(
+            "missing",
+            field,
+            format!("Omits required field '{}'", field),
+            input,
+        );
+    }
+
+    for (field, property) in schema::properties(schema) {
+        let property = sampler.resolve(property);
+        let with_value = |value: Value| {
+            let mut input = valid.clone();
+            if let Some(object) = input.as_object_mut() {
+                object.insert(field.clone(), value);
+            }
+            input
+        };
+
+        if let Some(value) = schema::wrong_type_value(property) {
+            let description = format!("Passes {} for '{}'", value, field);
+            case("wrong_type", field, description, with_value(value));
+        }
+        if let Some(value) = schema::invalid_enum_value(property) {
+            let description = format!("Passes {} outside the enum of '{}'", value, field);
+            case("invalid_enum", field, description, with_value(value));
+        }
+    }
+
+    cases
+}
+
+fn tags(kind: &str) -> Vec<String> {
+    vec!["generated".to_string(), kind.to_string()]
+}
+
+/// Lowercase identifier built from `text`, for test names
+pub fn identifier(text: &str) -> String {
+    let name: String = text
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    name.trim_matches('_').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::SpecificationLoader;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn tool(name: &str, schema: Value) -> Tool {
+        Tool {
+            name: name.to_string().into(),
+            description: Some("Reads a file".into()),
+            input_schema: Arc::new(schema.as_object().unwrap().clone()),
+            annotations: None,
+        }
+    }
+
+    fn server() -> ServerConfig {
+        ServerConfig::stdio("node", vec!["server.js".to_string()])
+    }
+
+    #[test]
+    fn test_generate_valid_and_negative_cases() {
+        let tools = [tool(
+            "read-file",
+            json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "default": "/tmp/a.txt"},
+                    "encoding": {"type": "string", "enum": ["utf8", "base64"]}
+                },
+                "required": ["path"]
+            }),
+        )];
+        let spec = SpecGenerator::new(server())
+            .with_server_info("files", "2.0.0")
+            .generate(&tools);
+
+        assert_eq!(spec.name, "files");
+        assert!(spec.capabilities.tools);
+        let tool = &spec.tools.as_ref().unwrap()[0];
+        assert_eq!(tool.description.as_deref(), Some("Reads a file"));
+
+        let cases: HashMap<_, _> = tool
+            .tests
+            .iter()
+            .map(|test| (test.name.as_str(), test))
+            .collect();
+        let mut names: Vec<_> = cases.keys().copied().collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "read_file_invalid_enum_encoding",
+                "read_file_missing_path",
+                "read_file_valid_input",
+                "read_file_wrong_type_encoding",
+                "read_file_wrong_type_path",
+            ]
+        );
+
+        let valid = cases["read_file_valid_input"];
+        assert_eq!(valid.input, json!({"path": "/tmp/a.txt"}));
+        assert!(!valid.expected.error);
+        assert!(cases["read_file_missing_path"].input.get("path").is_none());
+        assert_eq!(
+            cases["read_file_invalid_enum_encoding"].input["encoding"],
+            json!("utf8_invalid")
+        );
+        assert_eq!(
+            cases["read_file_wrong_type_path"].input["path"],
+            json!(12345)
+        );
+        assert!(tool
+            .tests
+            .iter()
+            .filter(|test| test.tags.contains(&"negative".to_string()))
+            .all(|test| test.expected.error));
+
+        // The skeleton is a loadable specification
+        let yaml = serde_yml::to_string(&spec).unwrap();
+        let loader = SpecificationLoader::new().unwrap();
+        assert_eq!(loader.parse_yaml(&yaml).unwrap(), spec);
+    }
+
+    #[test]
+    fn test_negative_cases_can_be_disabled() {
+        let tools = [tool("ping", json!({"type": "object", "properties": {}}))];
+        let spec = SpecGenerator::new(server())
+            .with_negative_cases(false)
+            .generate(&tools);
+
+        let tests = &spec.tools.as_ref().unwrap()[0].tests;
+        assert_eq!(tests.len(), 1);
+        assert_eq!(tests[0].input, json!({}));
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("Read File"), "read_file");
+        assert_eq!(identifier("file:///a.txt"), "file____a_txt");
+    }
+}
@@ -0,0 +1,436 @@
+//! Test inputs synthesized from JSON Schemas
+//!
+//! [`SchemaSampler`] builds a value that satisfies a tool's input schema,
+//! preferring the schema's own `default`, `examples` and `enum` values over
+//! invented ones. The free functions derive values that violate a schema in
+//! one specific way, for negative test cases.
+
+use serde_json::{json, Map, Value};
+
+/// Nesting depth after which recursive schemas are cut off with `null`
+const MAX_DEPTH: usize = 16;
+
+/// Longest string `minLength` padding produces
+///
+/// Schemas sometimes use a huge `minLength` to mean "long"; samples for those
+/// stop here, below the bound, rather than allocating the full length.
+const MAX_SAMPLE_STRING_LENGTH: usize = 4096;
+
+/// Builds values that satisfy a schema
+pub struct SchemaSampler<'a> {
+    /// Document `$ref`s are resolved against
+    root: &'a Value,
+}
+
+impl<'a> SchemaSampler<'a> {
+    pub fn new(root: &'a Value) -> Self {
+        Self { root }
+    }
+
+    /// A value valid against the root schema
+    pub fn sample(&self) -> Value {
+        self.valid_value(self.root)
+    }
+
+    /// A value valid against `schema`, a subschema of the root
+    pub fn valid_value(&self, schema: &Value) -> Value {
+        self.value_at_depth(schema, 0)
+    }
+
+    /// Follow local `$ref`s such as `#/$defs/Point` to the schema they name
+    pub fn resolve<'s>(&'s self, schema: &'s Value) -> &'s Value {
+        let mut schema = schema;
+        // Bounded so a reference cycle cannot loop forever
+        for _ in 0..MAX_DEPTH {
+            match schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.strip_prefix('#'))
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        schema
+    }
+
+    fn value_at_depth(&self, schema: &Value, depth: usize) -> Value {
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        let schema = self.resolve(schema);
+
+        if let Some(value) = declared_value(schema) {
+            return value;
+        }
+
+        if let Some(alternatives) = schema
+            .get("anyOf")
+            .or_else(|| schema.get("oneOf"))
+            .and_then(Value::as_array)
+        {
+            let preferred = alternatives
+                .iter()
+                .find(|alternative| primary_type(self.resolve(alternative)) != Some("null"))
+                .or_else(|| alternatives.first());
+            if let Some(alternative) = preferred {
+                return self.value_at_depth(alternative, depth + 1);
+            }
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            return self.value_at_depth(&merge_all_of(self, schema, parts), depth + 1);
+        }
+
+        match primary_type(schema) {
+            Some("object") => {
+                let required = required_fields(schema);
+                let mut object = Map::new();
+                for (name, property) in properties(schema) {
+                    let property = self.resolve(property);
+                    // Optional properties are only sent when the schema suggests a value
+                    let suggested = ["default", "examples", "example"]
+                        .iter()
+                        .any(|key| property.get(key).is_some());
+                    if required.contains(&name.as_str()) || suggested {
+                        object.insert(name.clone(), self.value_at_depth(property, depth + 1));
+                    }
+                }
+                Value::Object(object)
+            }
+            Some("array") => {
+                let max_items = schema.get("maxItems").and_then(Value::as_u64);
+                let count = schema
+                    .get("minItems")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(1)
+                    .max(1)
+                    .min(max_items.unwrap_or(u64::MAX));
+                let items = match schema.get("items") {
+                    Some(Value::Array(tuple)) => tuple.first(),
+                    other => other,
+                };
+                let item = items
+                    .map(|items| self.value_at_depth(items, depth + 1))
+                    .unwrap_or_else(|| json!("example"));
+                Value::Array(vec![item; count as usize])
+            }
+            Some("string") => json!(sample_string(schema)),
+            Some("integer") => json!(sample_number(schema, true) as i64),
+            Some("number") => json!(sample_number(schema, false)),
+            Some("boolean") => json!(true),
+            Some("null") => Value::Null,
+            _ => json!("example"),
+        }
+    }
+}
+
+/// Value the schema itself suggests: `default`, `examples`, `const` or `enum`
+fn declared_value(schema: &Value) -> Option<Value> {
+    schema
+        .get("default")
+        .or_else(|| {
+            schema
+                .get("examples")
+                .and_then(Value::as_array)
+                .and_then(|examples| examples.first())
+        })
+        .or_else(|| schema.get("example"))
+        .or_else(|| schema.get("const"))
+        .or_else(|| {
+            schema
+                .get("enum")
+                .and_then(Value::as_array)
+                .and_then(|values| values.first())
+        })
+        .cloned()
+}
+
+/// Combine `allOf` object schemas into one
+pub(crate) fn merge_all_of(sampler: &SchemaSampler<'_>, schema: &Value, parts: &[Value]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for part in parts {
+        let part = sampler.resolve(part);
+        properties.extend(
+            part.get("properties")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+        );
+        required.extend(required_fields(part).into_iter().map(str::to_string));
+    }
+    properties.extend(
+        schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default(),
+    );
+    required.extend(required_fields(schema).into_iter().map(str::to_string));
+
+    json!({"type": "object", "properties": properties, "required": required})
+}
+
+/// The schema's type, ignoring `null` in type unions and inferring objects
+/// and arrays from their keywords
+pub fn primary_type(schema: &Value) -> Option<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => Some(name.as_str()),
+        Some(Value::Array(names)) => {
+            let mut names = names.iter().filter_map(Value::as_str);
+            let first = names.clone().next();
+            names.find(|name| *name != "null").or(first)
+        }
+        _ if schema.get("properties").is_some() => Some("object"),
+        _ if schema.get("items").is_some() => Some("array"),
+        _ => None,
+    }
+}
+
+/// All types the schema allows
+pub(crate) fn allowed_types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(name)) => vec![name.as_str()],
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => primary_type(schema).into_iter().collect(),
+    }
+}
+
+/// Properties of an object schema
+pub fn properties(schema: &Value) -> impl Iterator<Item = (&String, &Value)> {
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+}
+
+/// Names listed in the schema's `required`
+pub fn required_fields(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect()
+}
+
+/// A string matching `format`, padded to `minLength` (up to
+/// [`MAX_SAMPLE_STRING_LENGTH`]) and cut to `maxLength`
+pub(crate) fn sample_string(schema: &Value) -> String {
+    let base = match schema.get("format").and_then(Value::as_str) {
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "00:00:00",
+        Some("email") => "user@example.com",
+        Some("uri") | Some("url") => "https://example.com",
+        Some("uuid") => "00000000-0000-4000-8000-000000000000",
+        Some("ipv4") => "127.0.0.1",
+        Some("ipv6") => "::1",
+        Some("hostname") => "example.com",
+        _ => "example",
+    };
+
+    let mut text = base.to_string();
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        let target = min.min(MAX_SAMPLE_STRING_LENGTH as u64) as usize;
+        let missing = target.saturating_sub(text.chars().count());
+        text.extend(std::iter::repeat_n('x', missing));
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        text = text.chars().take(max as usize).collect();
+    }
+    text
+}
+
+/// 1, moved into the schema's bounds
+fn sample_number(schema: &Value, integer: bool) -> f64 {
+    let bound = |inclusive: &str, exclusive: &str, step: f64| {
+        schema.get(inclusive).and_then(Value::as_f64).or_else(|| {
+            schema
+                .get(exclusive)
+                .and_then(Value::as_f64)
+                .map(|b| b + step)
+        })
+    };
+    let step = if integer { 1.0 } else { 0.5 };
+    let minimum = bound("minimum", "exclusiveMinimum", step);
+    let maximum = bound("maximum", "exclusiveMaximum", -step);
+
+    let mut value = 1.0_f64;
+    if let Some(minimum) = minimum {
+        value = value.max(if integer { minimum.ceil() } else { minimum });
+    }
+    if let Some(maximum) = maximum {
+        value = value.min(if integer { maximum.floor() } else { maximum });
+    }
+    value
+}
+
+/// A value of a type the schema does not allow
+pub fn wrong_type_value(schema: &Value) -> Option<Value> {
+    let allowed = allowed_types(schema);
+    if allowed.is_empty() {
+        return None;
+    }
+
+    let candidates = [
+        json!(12345),
+        json!("not-a-valid-value"),
+        json!(true),
+        json!(["not-a-valid-value"]),
+        json!({"not": "valid"}),
+    ];
+    candidates
+        .into_iter()
+        .find(|candidate| !allowed.iter().any(|name| matches_type(candidate, name)))
+}
+
+/// A value outside the schema's `enum`
+pub fn invalid_enum_value(schema: &Value) -> Option<Value> {
+    let values = schema.get("enum").and_then(Value::as_array)?;
+
+    if values.iter().all(Value::is_string) {
+        let mut candidate = format!(
+            "{}_invalid",
+            values.first().and_then(Value::as_str).unwrap_or("value")
+        );
+        while values
+            .iter()
+            .any(|value| value.as_str() == Some(&candidate))
+        {
+            candidate.push_str("_x");
+        }
+        return Some(json!(candidate));
+    }
+    if values.iter().all(Value::is_i64) {
+        let integers = values.iter().filter_map(Value::as_i64);
+        let (min, max) = (integers.clone().min()?, integers.max()?);
+        // An enum spanning the whole i64 range only leaves non-integers
+        return Some(match max.checked_add(1).or_else(|| min.checked_sub(1)) {
+            Some(value) => json!(value),
+            None => json!(0.5),
+        });
+    }
+    None
+}
+
+pub(crate) fn matches_type(value: &Value, name: &str) -> bool {
+    match name {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_prefers_declared_values_and_respects_bounds() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "path": {"type": "string", "examples": ["/tmp/a.txt"]},
+                "mode": {"type": "string", "enum": ["read", "write"]},
+                "limit": {"type": "integer", "minimum": 10, "maximum": 20},
+                "ratio": {"type": "number", "exclusiveMaximum": 0},
+                "id": {"type": "string", "format": "uuid"},
+                "code": {"type": "string", "minLength": 10},
+                "tags": {"type": "array", "items": {"type": "string"}, "minItems": 2},
+                "verbose": {"type": "boolean", "default": false},
+                "optional": {"type": "string"}
+            },
+            "required": ["path", "mode", "limit", "ratio", "id", "code", "tags"]
+        });
+
+        assert_eq!(
+            SchemaSampler::new(&schema).sample(),
+            json!({
+                "path": "/tmp/a.txt",
+                "mode": "read",
+                "limit": 10,
+                "ratio": -0.5,
+                "id": "00000000-0000-4000-8000-000000000000",
+                "code": "examplexxx",
+                "tags": ["example", "example"],
+                "verbose": false
+            })
+        );
+    }
+
+    #[test]
+    fn test_sample_string_caps_min_length_padding() {
+        let sample = sample_string(&json!({"type": "string", "minLength": u64::MAX}));
+        assert_eq!(sample.len(), MAX_SAMPLE_STRING_LENGTH);
+        assert!(sample.starts_with("example"));
+
+        let sample = sample_string(&json!({"minLength": 5000, "maxLength": 8}));
+        assert_eq!(sample, "examplex");
+    }
+
+    #[test]
+    fn test_sample_follows_refs_and_combinators() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "point": {"$ref": "#/$defs/Point"},
+                "label": {"anyOf": [{"type": "null"}, {"type": "string"}]},
+                "node": {"$ref": "#/$defs/Node"}
+            },
+            "required": ["point", "label", "node"],
+            "$defs": {
+                "Point": {"allOf": [
+                    {"properties": {"x": {"type": "integer"}}, "required": ["x"]},
+                    {"properties": {"y": {"type": "integer"}}, "required": ["y"]}
+                ]},
+                "Node": {"type": "object", "properties": {"next": {"$ref": "#/$defs/Node"}}, "required": ["next"]}
+            }
+        });
+
+        let value = SchemaSampler::new(&schema).sample();
+        assert_eq!(value["point"], json!({"x": 1, "y": 1}));
+        assert_eq!(value["label"], json!("example"));
+        // Recursive schemas terminate
+        assert!(value["node"]["next"]["next"].is_object());
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert_eq!(
+            wrong_type_value(&json!({"type": "string"})),
+            Some(json!(12345))
+        );
+        assert_eq!(
+            wrong_type_value(&json!({"type": ["integer", "string"]})),
+            Some(json!(true))
+        );
+        assert_eq!(wrong_type_value(&json!({})), None);
+
+        assert_eq!(
+            invalid_enum_value(&json!({"enum": ["a", "a_invalid"]})),
+            Some(json!("a_invalid_x"))
+        );
+        assert_eq!(
+            invalid_enum_value(&json!({"enum": [1, 5, 3]})),
+            Some(json!(6))
+        );
+        assert_eq!(
+            invalid_enum_value(&json!({"enum": [0, i64::MAX]})),
+            Some(json!(-1))
+        );
+        assert_eq!(
+            invalid_enum_value(&json!({"enum": [i64::MIN, i64::MAX]})),
+            Some(json!(0.5))
+        );
+        assert_eq!(invalid_enum_value(&json!({"type": "string"})), None);
+    }
+}
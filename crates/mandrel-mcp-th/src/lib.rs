@@ -56,19 +56,43 @@
 //!         // File watching and auto-generation
 //!         println!("Starting file watch mode");
 //!     }
+//!     Commands::Record(record_args) => {
+//!         // Session recording
+//!         println!("Recording session to: {}", record_args.output.display());
+//!     }
+//!     Commands::Replay(replay_args) => {
+//!         // Session replay
+//!         println!("Replaying cassette: {}", replay_args.cassette.display());
+//!     }
+//!     Commands::Generate(generate_args) => {
+//!         // Spec generation from tool schemas
+//!         println!("Generating spec to: {}", generate_args.output.display());
+//!     }
+//!     Commands::Fuzz(fuzz_args) => {
+//!         // Randomized tool calls
+//!         println!("Saving fuzz findings to: {}", fuzz_args.output_dir.display());
+//!     }
+//!     Commands::Load(load_args) => {
+//!         // Load and soak testing
+//!         println!("Applying load with: {}", load_args.config.display());
+//!     }
 //! }
 //! # Ok(())
 //! # }
 //! ```
 
+pub mod cassette;
 pub mod cli;
 pub mod client;
 pub mod error;
 pub mod error_handling;
 pub mod executor;
+pub mod fuzz;
+pub mod generator;
 pub mod reporting;
 pub mod runner;
 pub mod script_engines;
+pub mod snapshot;
 pub mod spec;
 pub mod testing;
 pub mod validation;
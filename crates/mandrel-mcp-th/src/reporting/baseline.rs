@@ -0,0 +1,606 @@
+//! Comparison of a test run with a stored baseline run
+//!
+//! `moth run --baseline <report.json>` loads the JSON report of an earlier
+//! run and compares every test case with it: tests whose status flipped are
+//! reported as newly failing or newly passing, and tests that passed both
+//! times have their latency samples compared.
+//!
+//! A slowdown is reported when the median latency grew by more than both
+//! [`BaselineConfig::min_slowdown_ratio`] and [`BaselineConfig::min_slowdown_ms`]
+//! and, when both runs have at least [`MIN_SAMPLES_FOR_TEST`] samples per
+//! test (see `--repeat`), a one-sided Mann-Whitney U test rejects "not
+//! slower" at [`BaselineConfig::significance`]. With fewer samples only the
+//! thresholds apply and the test is reported as
+//! [`BaselineChangeKind::PossiblySlower`], which is not a regression.
+
+use crate::error::{Error, Result};
+use crate::executor::{SuiteResult, TestResult, TestStatus};
+use crate::reporting::TestReport;
+use crate::runner::TestSuiteResult;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Samples each run needs before a slowdown is tested for significance
+pub const MIN_SAMPLES_FOR_TEST: usize = 3;
+
+/// Thresholds deciding which latency changes are reported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineConfig {
+    /// Largest p-value accepted as a significant slowdown
+    pub significance: f64,
+    /// Minimum relative growth of the median, e.g. `0.2` for 20%
+    pub min_slowdown_ratio: f64,
+    /// Minimum absolute growth of the median in milliseconds
+    pub min_slowdown_ms: f64,
+}
+
+impl Default for BaselineConfig {
+    fn default() -> Self {
+        Self {
+            significance: 0.05,
+            min_slowdown_ratio: 0.2,
+            min_slowdown_ms: 5.0,
+        }
+    }
+}
+
+/// How a test changed since the baseline run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BaselineChangeKind {
+    /// Significantly slower
+    Slower,
+    /// Slower by the thresholds, but with too few samples to test significance
+    PossiblySlower,
+    NewlyFailing,
+    NewlyPassing,
+    /// Not present in the baseline run
+    Added,
+    /// Present in the baseline run only
+    Removed,
+}
+
+impl BaselineChangeKind {
+    /// Whether the change makes the current run worse than the baseline
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Self::Slower | Self::NewlyFailing)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Slower => "slower",
+            Self::PossiblySlower => "possibly slower",
+            Self::NewlyFailing => "newly failing",
+            Self::NewlyPassing => "newly passing",
+            Self::Added => "added",
+            Self::Removed => "removed",
+        }
+    }
+}
+
+/// Latency of a test in both runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatencyChange {
+    pub baseline_median_ms: f64,
+    pub current_median_ms: f64,
+    pub baseline_samples: usize,
+    pub current_samples: usize,
+    /// Mann-Whitney p-value, absent when there were too few samples
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p_value: Option<f64>,
+}
+
+impl LatencyChange {
+    /// Relative growth of the median, e.g. `0.5` for 50% slower
+    pub fn relative_change(&self) -> f64 {
+        if self.baseline_median_ms > 0.0 {
+            self.current_median_ms / self.baseline_median_ms - 1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// One test whose outcome differs from the baseline run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineChange {
+    pub test_name: String,
+    pub kind: BaselineChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyChange>,
+    /// Human-readable description, e.g. `median 10.0ms -> 25.0ms (+150%, p=0.004)`
+    pub message: String,
+}
+
+/// Changes of a run relative to a baseline run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BaselineComparison {
+    /// Report the run was compared with
+    pub baseline_file: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline_generated_at: Option<DateTime<Utc>>,
+    /// Number of tests present in both runs
+    pub compared: usize,
+    pub config: BaselineConfig,
+    pub changes: Vec<BaselineChange>,
+}
+
+impl BaselineComparison {
+    /// Changes that make the current run worse than the baseline
+    pub fn regressions(&self) -> impl Iterator<Item = &BaselineChange> {
+        self.changes
+            .iter()
+            .filter(|change| change.kind.is_regression())
+    }
+
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+}
+
+/// Outcome of one test in a run
+#[derive(Debug, Clone, PartialEq)]
+struct TestOutcome {
+    /// `None` for skipped tests
+    passed: Option<bool>,
+    latency_ms: Vec<f64>,
+}
+
+impl TestOutcome {
+    fn of(test: &TestResult) -> Self {
+        let passed = match test.status {
+            TestStatus::Passed => Some(true),
+            TestStatus::Skipped => None,
+            _ => Some(false),
+        };
+        let latency_ms = if test.latency_samples_ms.is_empty() {
+            vec![duration_ms(test.duration)]
+        } else {
+            test.latency_samples_ms.clone()
+        };
+        Self { passed, latency_ms }
+    }
+}
+
+/// Per-test outcomes of a stored run
+#[derive(Debug, Clone)]
+pub struct Baseline {
+    file: PathBuf,
+    generated_at: Option<DateTime<Utc>>,
+    tests: BTreeMap<String, TestOutcome>,
+}
+
+impl Baseline {
+    /// Load a JSON report written by `moth run`, or a serialized runner result
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+            Error::config(format!("Failed to read baseline {}: {}", path.display(), e))
+        })?;
+        Self::parse(&content, path)
+    }
+
+    fn parse(content: &str, path: &Path) -> Result<Self> {
+        if let Ok(report) = serde_json::from_str::<TestReport>(content) {
+            return Ok(Self::from_report(&report, path));
+        }
+        match serde_json::from_str::<TestSuiteResult>(content) {
+            Ok(suite) => Ok(Self::from_suite(&suite, path)),
+            Err(e) => Err(Error::config(format!(
+                "Baseline {} is not a test report: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Baseline from a report of the run stored at `file`
+    pub fn from_report(report: &TestReport, file: &Path) -> Self {
+        let tests = report
+            .test_results
+            .iter()
+            .map(|test| (test.test_name.clone(), TestOutcome::of(test)))
+            .collect();
+        Self {
+            file: file.to_path_buf(),
+            generated_at: Some(report.metadata.generated_at),
+            tests,
+        }
+    }
+
+    fn from_suite(suite: &TestSuiteResult, file: &Path) -> Self {
+        let tests = suite
+            .test_results
+            .iter()
+            .map(|test| {
+                let latency_ms = if test.latency_samples.is_empty() {
+                    vec![duration_ms(test.duration)]
+                } else {
+                    test.latency_samples
+                        .iter()
+                        .copied()
+                        .map(duration_ms)
+                        .collect()
+                };
+                let outcome = TestOutcome {
+                    passed: Some(test.success),
+                    latency_ms,
+                };
+                (test.test_name.clone(), outcome)
+            })
+            .collect();
+        Self {
+            file: file.to_path_buf(),
+            generated_at: Some(DateTime::from(suite.execution_start)),
+            tests,
+        }
+    }
+
+    /// Compare `current` with this baseline
+    pub fn compare(&self, current: &SuiteResult, config: &BaselineConfig) -> BaselineComparison {
+        let mut changes = Vec::new();
+        let mut compared = 0;
+
+        for test in &current.test_results {
+            let name = &test.test_name;
+            let Some(before) = self.tests.get(name) else {
+                changes.push(BaselineChange {
+                    test_name: name.clone(),
+                    kind: BaselineChangeKind::Added,
+                    latency: None,
+                    message: "not in the baseline run".to_string(),
+                });
+                continue;
+            };
+            compared += 1;
+
+            let after = TestOutcome::of(test);
+            match (before.passed, after.passed) {
+                (Some(true), Some(false)) => changes.push(BaselineChange {
+                    test_name: name.clone(),
+                    kind: BaselineChangeKind::NewlyFailing,
+                    latency: None,
+                    message: test
+                        .error_message
+                        .clone()
+                        .unwrap_or_else(|| format!("{:?} after passing", test.status)),
+                }),
+                (Some(false), Some(true)) => changes.push(BaselineChange {
+                    test_name: name.clone(),
+                    kind: BaselineChangeKind::NewlyPassing,
+                    latency: None,
+                    message: "passed after failing".to_string(),
+                }),
+                (Some(true), Some(true)) => {
+                    changes.extend(slowdown(
+                        name,
+                        &before.latency_ms,
+                        &after.latency_ms,
+                        config,
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        let current_names: std::collections::HashSet<_> = current
+            .test_results
+            .iter()
+            .map(|test| test.test_name.as_str())
+            .collect();
+        for name in self.tests.keys() {
+            if !current_names.contains(name.as_str()) {
+                changes.push(BaselineChange {
+                    test_name: name.clone(),
+                    kind: BaselineChangeKind::Removed,
+                    latency: None,
+                    message: "not in this run".to_string(),
+                });
+            }
+        }
+
+        BaselineComparison {
+            baseline_file: self.file.clone(),
+            baseline_generated_at: self.generated_at,
+            compared,
+            config: config.clone(),
+            changes,
+        }
+    }
+}
+
+fn duration_ms(duration: std::time::Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// A slowdown change if `current` is slower than `baseline`
+///
+/// [`BaselineChangeKind::Slower`] when the slowdown is significant, or
+/// [`BaselineChangeKind::PossiblySlower`] when there were too few samples to
+/// test it.
+fn slowdown(
+    test_name: &str,
+    baseline: &[f64],
+    current: &[f64],
+    config: &BaselineConfig,
+) -> Option<BaselineChange> {
+    let before = median(baseline)?;
+    let after = median(current)?;
+    if after - before < config.min_slowdown_ms || after < before * (1.0 + config.min_slowdown_ratio)
+    {
+        return None;
+    }
+
+    let tested = baseline.len() >= MIN_SAMPLES_FOR_TEST && current.len() >= MIN_SAMPLES_FOR_TEST;
+    let p_value = tested.then(|| mann_whitney_p(baseline, current));
+    if p_value.is_some_and(|p| p > config.significance) {
+        return None;
+    }
+
+    let latency = LatencyChange {
+        baseline_median_ms: before,
+        current_median_ms: after,
+        baseline_samples: baseline.len(),
+        current_samples: current.len(),
+        p_value,
+    };
+    let (kind, evidence) = match p_value {
+        Some(p) => (BaselineChangeKind::Slower, format!("p={:.3}", p)),
+        None => (
+            BaselineChangeKind::PossiblySlower,
+            format!(
+                "not significance-tested, {} vs {} samples",
+                baseline.len(),
+                current.len()
+            ),
+        ),
+    };
+    Some(BaselineChange {
+        test_name: test_name.to_string(),
+        kind,
+        message: format!(
+            "median {:.1}ms -> {:.1}ms (+{:.0}%, {})",
+            before,
+            after,
+            latency.relative_change() * 100.0,
+            evidence
+        ),
+        latency: Some(latency),
+    })
+}
+
+fn median(samples: &[f64]) -> Option<f64> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let middle = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        n if n % 2 == 0 => Some((sorted[middle - 1] + sorted[middle]) / 2.0),
+        _ => Some(sorted[middle]),
+    }
+}
+
+/// One-sided p-value that `current` tends to be larger than `baseline`
+///
+/// Mann-Whitney U with the normal approximation and a continuity correction;
+/// ties count as half a win and do not adjust the variance.
+fn mann_whitney_p(baseline: &[f64], current: &[f64]) -> f64 {
+    let u: f64 = current
+        .iter()
+        .flat_map(|c| baseline.iter().map(move |b| (c, b)))
+        .map(|(c, b)| match c.total_cmp(b) {
+            std::cmp::Ordering::Greater => 1.0,
+            std::cmp::Ordering::Equal => 0.5,
+            std::cmp::Ordering::Less => 0.0,
+        })
+        .sum();
+    let (n1, n2) = (baseline.len() as f64, current.len() as f64);
+    let mean = n1 * n2 / 2.0;
+    let deviation = (n1 * n2 * (n1 + n2 + 1.0) / 12.0).sqrt();
+    let z = (u - mean - 0.5) / deviation;
+    1.0 - normal_cdf(z)
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// Abramowitz and Stegun 7.1.26, accurate to 1.5e-7
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let value = 1.0 - poly * (-x * x).exp();
+    value.copysign(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::PerformanceMetrics;
+    use std::time::Duration;
+
+    fn result(name: &str, status: TestStatus, samples_ms: &[f64]) -> TestResult {
+        TestResult {
+            test_name: name.to_string(),
+            suite_name: "suite".to_string(),
+            status,
+            error_message: None,
+            start_time: Utc::now(),
+            duration: Duration::from_secs_f64(samples_ms.iter().sum::<f64>() / 1000.0),
+            response_data: None,
+            performance: PerformanceMetrics::default(),
+            snapshot_diffs: Vec::new(),
+            latency_samples_ms: samples_ms.to_vec(),
+        }
+    }
+
+    fn suite(test_results: Vec<TestResult>) -> SuiteResult {
+        SuiteResult {
+            suite_name: "suite".to_string(),
+            start_time: Utc::now(),
+            duration: Duration::from_secs(1),
+            passed: 0,
+            failed: 0,
+            errors: 0,
+            skipped: 0,
+            total_tests: test_results.len(),
+            test_results,
+        }
+    }
+
+    fn baseline(test_results: Vec<TestResult>) -> Baseline {
+        let report = crate::reporting::ReportGenerator::new(Default::default())
+            .unwrap()
+            .generate_json(&suite(test_results))
+            .unwrap();
+        Baseline::parse(&report, Path::new("before.json")).unwrap()
+    }
+
+    #[test]
+    fn test_status_changes_and_membership() {
+        let before = baseline(vec![
+            result("fixed", TestStatus::Failed, &[10.0]),
+            result("broken", TestStatus::Passed, &[10.0]),
+            result("gone", TestStatus::Passed, &[10.0]),
+            result("skipped", TestStatus::Skipped, &[0.0]),
+        ]);
+        let mut broken = result("broken", TestStatus::Failed, &[10.0]);
+        broken.error_message = Some("Validation failed".to_string());
+        let comparison = before.compare(
+            &suite(vec![
+                result("fixed", TestStatus::Passed, &[10.0]),
+                broken,
+                result("skipped", TestStatus::Failed, &[10.0]),
+                result("new", TestStatus::Passed, &[10.0]),
+            ]),
+            &BaselineConfig::default(),
+        );
+
+        let changes: Vec<_> = comparison
+            .changes
+            .iter()
+            .map(|change| (change.test_name.as_str(), change.kind))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("fixed", BaselineChangeKind::NewlyPassing),
+                ("broken", BaselineChangeKind::NewlyFailing),
+                ("new", BaselineChangeKind::Added),
+                ("gone", BaselineChangeKind::Removed),
+            ]
+        );
+        assert_eq!(comparison.compared, 3);
+        assert_eq!(comparison.changes[1].message, "Validation failed");
+        assert_eq!(comparison.regressions().count(), 1);
+    }
+
+    #[test]
+    fn test_significant_slowdown_is_reported() {
+        let before = baseline(vec![
+            result("slow", TestStatus::Passed, &[10.0, 11.0, 9.0, 10.5, 10.2]),
+            result("noisy", TestStatus::Passed, &[10.0, 40.0, 9.0, 35.0, 12.0]),
+            result("steady", TestStatus::Passed, &[10.0, 11.0, 9.0, 10.5, 10.2]),
+        ]);
+        let comparison = before.compare(
+            &suite(vec![
+                result("slow", TestStatus::Passed, &[25.0, 27.0, 24.0, 26.0, 30.0]),
+                result("noisy", TestStatus::Passed, &[30.0, 11.0, 38.0, 9.5, 33.0]),
+                result("steady", TestStatus::Passed, &[10.1, 11.2, 9.4, 10.0, 10.9]),
+            ]),
+            &BaselineConfig::default(),
+        );
+
+        assert_eq!(comparison.changes.len(), 1, "{:?}", comparison.changes);
+        let change = &comparison.changes[0];
+        assert_eq!(change.test_name, "slow");
+        assert_eq!(change.kind, BaselineChangeKind::Slower);
+        let latency = change.latency.as_ref().unwrap();
+        assert_eq!(latency.baseline_median_ms, 10.2);
+        assert_eq!(latency.current_median_ms, 26.0);
+        assert!(latency.p_value.unwrap() < 0.01);
+        assert!(change
+            .message
+            .starts_with("median 10.2ms -> 26.0ms (+155%, p="));
+    }
+
+    #[test]
+    fn test_single_samples_use_thresholds_only() {
+        let before = baseline(vec![
+            result("slower", TestStatus::Passed, &[10.0]),
+            result("tiny", TestStatus::Passed, &[1.0]),
+        ]);
+        let comparison = before.compare(
+            &suite(vec![
+                result("slower", TestStatus::Passed, &[20.0]),
+                // Doubled, but by less than min_slowdown_ms
+                result("tiny", TestStatus::Passed, &[2.0]),
+            ]),
+            &BaselineConfig::default(),
+        );
+
+        assert_eq!(comparison.changes.len(), 1);
+        assert_eq!(
+            comparison.changes[0].kind,
+            BaselineChangeKind::PossiblySlower
+        );
+        // Untested slowdowns are reported but do not fail --fail-on-regression
+        assert!(!comparison.has_regressions());
+        let latency = comparison.changes[0].latency.as_ref().unwrap();
+        assert_eq!(latency.p_value, None);
+        assert_eq!(
+            comparison.changes[0].message,
+            "median 10.0ms -> 20.0ms (+100%, not significance-tested, 1 vs 1 samples)"
+        );
+    }
+
+    #[test]
+    fn test_mann_whitney_and_median() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-9);
+        assert!((normal_cdf(1.959_964) - 0.975).abs() < 1e-6);
+
+        let low = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let high = [6.0, 7.0, 8.0, 9.0, 10.0];
+        assert!(mann_whitney_p(&low, &high) < 0.01);
+        assert!(mann_whitney_p(&high, &low) > 0.99);
+        assert!((mann_whitney_p(&low, &low) - 0.5).abs() < 0.1);
+
+        assert_eq!(median(&[3.0, 1.0, 2.0]), Some(2.0));
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), Some(2.5));
+        assert_eq!(median(&[]), None);
+    }
+
+    #[test]
+    fn test_reports_include_changes_since_baseline() {
+        let before = baseline(vec![result("broken", TestStatus::Passed, &[10.0])]);
+        let current = suite(vec![result("broken", TestStatus::Failed, &[10.0])]);
+        let comparison = before.compare(&current, &BaselineConfig::default());
+
+        let generator = crate::reporting::ReportGenerator::new(Default::default())
+            .unwrap()
+            .with_baseline(Some(comparison.clone()));
+        let markdown = generator.generate_markdown(&current).unwrap();
+        assert!(markdown.contains("- [Changes Since Baseline](#changes-since-baseline)"));
+        assert!(markdown.contains("| broken | ⚠️ newly failing | Failed after passing |"));
+
+        let html = generator.generate_html(&current).unwrap();
+        assert!(html.contains("Changes Since Baseline"));
+        assert!(html.contains("newly failing"));
+        assert!(html.contains("<code>before.json</code>"));
+
+        // The comparison is kept in the JSON report
+        let json = generator.generate_json(&current).unwrap();
+        let report: TestReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.baseline, Some(comparison));
+    }
+
+    #[test]
+    fn test_parse_rejects_other_json() {
+        let error = Baseline::parse("{\"name\": 1}", Path::new("x.json")).unwrap_err();
+        assert!(error.to_string().contains("x.json is not a test report"));
+    }
+}
@@ -14,6 +14,7 @@
 //! - Safe template execution with sandboxing
 //! - Template validation and security
 
+pub mod baseline;
 mod templates;
 
 use crate::error::Result;
@@ -25,6 +26,7 @@ use std::path::PathBuf;
 use std::time::Duration;
 use uuid::Uuid;
 
+pub use baseline::{Baseline, BaselineComparison, BaselineConfig};
 pub use templates::TemplateRenderer;
 
 /// Comprehensive test report containing all execution results and metadata
@@ -36,6 +38,9 @@ pub struct TestReport {
     pub server_info: ServerInfo,
     pub validation_details: Vec<ValidationDetail>,
     pub performance_metrics: PerformanceReport,
+    /// Changes since the baseline run, when one was given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineComparison>,
 }
 
 /// Report metadata and generation information
@@ -115,6 +120,7 @@ pub struct TemplateContext {
     pub summary: ExecutionSummary,
     pub test_results: Vec<TestResult>,
     pub performance_metrics: PerformanceReport,
+    pub baseline: Option<BaselineComparison>,
 
     // Environment info
     pub environment: EnvironmentInfo,
@@ -189,11 +195,21 @@ pub enum MarkdownStyle {
 /// Main report generator with enhanced template support
 pub struct ReportGenerator {
     config: ReportConfig,
+    baseline: Option<BaselineComparison>,
 }
 
 impl ReportGenerator {
     pub fn new(config: ReportConfig) -> Result<Self> {
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            baseline: None,
+        })
+    }
+
+    /// Include the changes since a baseline run in the reports
+    pub fn with_baseline(mut self, baseline: Option<BaselineComparison>) -> Self {
+        self.baseline = baseline;
+        self
     }
 
     /// Generate JSON report with full test data
@@ -406,6 +422,9 @@ impl ReportGenerator {
         // Table of Contents
         markdown.push_str("## Table of Contents\n\n");
         markdown.push_str("- [Summary](#summary)\n");
+        if self.baseline.is_some() {
+            markdown.push_str("- [Changes Since Baseline](#changes-since-baseline)\n");
+        }
         markdown.push_str("- [Test Results](#test-results)\n");
         if self.config.include_performance_metrics {
             markdown.push_str("- [Performance Metrics](#performance-metrics)\n");
@@ -490,6 +509,10 @@ impl ReportGenerator {
             markdown.push_str("```\n\n");
         }
 
+        if let Some(baseline) = &self.baseline {
+            self.push_baseline_markdown(&mut markdown, baseline);
+        }
+
         // Handle empty results
         if results.test_results.is_empty() {
             markdown.push_str("**No tests were executed.**\n\n");
@@ -625,6 +648,19 @@ impl ReportGenerator {
                         markdown.push_str("```\n");
                     }
 
+                    for diff in &test_result.snapshot_diffs {
+                        markdown.push_str(&format!(
+                            "\nSnapshot `{}` differs from `{}`:\n\n",
+                            diff.snapshot,
+                            diff.file.display()
+                        ));
+                        markdown.push_str("```json\n");
+                        markdown.push_str(
+                            &serde_json::to_string_pretty(&diff.changes).unwrap_or_default(),
+                        );
+                        markdown.push_str("\n```\n");
+                    }
+
                     markdown.push_str("</details>\n\n");
                 }
             }
@@ -712,6 +748,46 @@ impl ReportGenerator {
         Ok(markdown)
     }
 
+    /// Append the "Changes Since Baseline" section
+    fn push_baseline_markdown(&self, markdown: &mut String, baseline: &BaselineComparison) {
+        markdown.push_str("## Changes Since Baseline {#changes-since-baseline}\n\n");
+        markdown.push_str(&format!(
+            "Compared {} tests with `{}`",
+            baseline.compared,
+            baseline.baseline_file.display()
+        ));
+        if let Some(generated_at) = baseline.baseline_generated_at {
+            markdown.push_str(&format!(
+                " from {}",
+                generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+            ));
+        }
+        markdown.push_str(".\n\n");
+
+        if baseline.changes.is_empty() {
+            markdown.push_str("No changes since the baseline run.\n\n");
+            return;
+        }
+
+        markdown.push_str("| Test Name | Change | Details |\n");
+        markdown.push_str("|-----------|--------|---------|\n");
+        for change in &baseline.changes {
+            let icon = if change.kind.is_regression() {
+                "⚠️"
+            } else {
+                "ℹ️"
+            };
+            markdown.push_str(&format!(
+                "| {} | {} {} | {} |\n",
+                self.escape_markdown_if_needed(&change.test_name),
+                icon,
+                change.kind.label(),
+                change.message.replace('|', r"\|").replace('\n', " ")
+            ));
+        }
+        markdown.push('\n');
+    }
+
     /// Escape special Markdown characters only if they exist
     fn escape_markdown_if_needed(&self, text: &str) -> String {
         // Check if text contains any problematic Markdown characters
@@ -767,6 +843,7 @@ impl ReportGenerator {
             },
             validation_details: ValidationDetail::from_suite(results),
             performance_metrics: PerformanceReport::from_results(&results.test_results),
+            baseline: self.baseline.clone(),
         })
     }
 
@@ -779,6 +856,7 @@ impl ReportGenerator {
             summary: report.summary.clone(),
             test_results: report.test_results.clone(),
             performance_metrics: report.performance_metrics.clone(),
+            baseline: report.baseline.clone(),
             environment: report.metadata.environment.clone(),
             server_config: report.server_info.clone(),
             branding: self.config.branding.clone(),
@@ -963,6 +1041,15 @@ impl ReportMetadata {
 
 impl ValidationDetail {
     pub fn from_suite(suite: &SuiteResult) -> Vec<Self> {
+        let snapshot_details = suite.test_results.iter().flat_map(|result| {
+            result.snapshot_diffs.iter().map(|diff| ValidationDetail {
+                test_id: result.test_name.clone(),
+                validation_type: "snapshot".to_string(),
+                status: "failed".to_string(),
+                details: serde_json::to_string(diff).unwrap_or_else(|_| diff.summary()),
+            })
+        });
+
         suite
             .test_results
             .iter()
@@ -981,6 +1068,7 @@ impl ValidationDetail {
                     .clone()
                     .unwrap_or_else(|| "No details available".to_string()),
             })
+            .chain(snapshot_details)
             .collect()
     }
 }
@@ -94,6 +94,7 @@ impl TemplateRenderer {
         tera_context.insert("summary", &context.summary);
         tera_context.insert("test_results", &context.test_results);
         tera_context.insert("performance_metrics", &context.performance_metrics);
+        tera_context.insert("baseline", &context.baseline);
         tera_context.insert("environment", &context.environment);
         tera_context.insert("server_config", &context.server_config);
         tera_context.insert("branding", &context.branding);
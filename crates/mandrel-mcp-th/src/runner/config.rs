@@ -28,6 +28,10 @@ pub struct RunnerConfig {
     pub teardown_timeout: Duration,
     /// Timeout for dependency resolution
     pub dependency_timeout: Duration,
+    /// Write snapshots instead of failing tests whose snapshot differs
+    pub update_snapshots: bool,
+    /// Times each test is executed to collect latency samples
+    pub repetitions: usize,
 }
 
 impl Default for RunnerConfig {
@@ -39,6 +43,8 @@ impl Default for RunnerConfig {
             setup_timeout: Duration::from_secs(30),
             teardown_timeout: Duration::from_secs(10),
             dependency_timeout: Duration::from_secs(5),
+            update_snapshots: false,
+            repetitions: 1,
         }
     }
 }
@@ -89,6 +95,18 @@ impl RunnerConfig {
         self.dependency_timeout = timeout;
         self
     }
+
+    /// Enable or disable updating snapshot files
+    pub fn with_update_snapshots(mut self, enabled: bool) -> Self {
+        self.update_snapshots = enabled;
+        self
+    }
+
+    /// Execute each test `count` times (at least once)
+    pub fn with_repetitions(mut self, count: usize) -> Self {
+        self.repetitions = count.max(1);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -103,6 +121,7 @@ mod tests {
         assert!(!config.fail_fast);
         assert_eq!(config.setup_timeout, Duration::from_secs(30));
         assert_eq!(config.teardown_timeout, Duration::from_secs(10));
+        assert_eq!(config.repetitions, 1);
     }
 
     #[test]
@@ -127,5 +146,8 @@ mod tests {
 
         let config = RunnerConfig::new().with_max_concurrency(20);
         assert_eq!(config.max_concurrency, 16); // Should be clamped to maximum
+
+        let config = RunnerConfig::new().with_repetitions(0);
+        assert_eq!(config.repetitions, 1);
     }
 }
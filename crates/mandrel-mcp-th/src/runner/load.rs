@@ -0,0 +1,478 @@
+//! Load and soak testing
+//!
+//! [`LoadRunner`] replays a specification's test cases for a fixed duration,
+//! either keeping a number of requests in flight, like the workers of
+//! [`ExecutionMode::Parallel`](super::ExecutionMode::Parallel), or starting
+//! them at a fixed rate regardless of how fast the server answers. Requests
+//! are spread round-robin over one or more client sessions; each stdio
+//! session runs its own server process, while network sessions share the
+//! server started by the first one.
+//!
+//! Every response is checked against the test's expected output, so the
+//! error rate counts wrong answers as well as failed requests. Validation
+//! scripts and snapshots are not run under load, and test cases that use
+//! `${captured.*}` values are skipped because their input depends on an
+//! earlier response.
+
+use super::metrics::{LoadMetrics, MetricsCollector, RequestOutcome};
+use crate::client::{McpClient, ServerConfig as ClientServerConfig};
+use crate::error::{Error, Result};
+use crate::executor::{
+    check_error_expectation, expand_uri_template, prompt_arguments, rejection_response,
+    send_request, ExecutorError, McpRequest,
+};
+use crate::spec::{expand, ExpectedOutput, TestCase, TestSpecification};
+use crate::validation::ValidationEngine;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+/// How requests are issued during a load run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LoadPacing {
+    /// Keep this many requests in flight (closed loop)
+    Concurrency(usize),
+    /// Start this many requests per second (open loop)
+    Rate(f64),
+}
+
+/// Configuration of a load run
+#[derive(Debug, Clone)]
+pub struct LoadConfig {
+    pub pacing: LoadPacing,
+    /// How long requests are issued
+    pub duration: Duration,
+    /// Client sessions requests are spread over
+    pub sessions: usize,
+    /// Time after which a request counts as timed out
+    pub request_timeout: Duration,
+    /// Interval between samples of the server's resident memory
+    pub memory_sample_interval: Duration,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            pacing: LoadPacing::Concurrency(4),
+            duration: Duration::from_secs(30),
+            sessions: 1,
+            request_timeout: Duration::from_secs(30),
+            memory_sample_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl LoadConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_pacing(mut self, pacing: LoadPacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Spread requests over `sessions` client sessions (at least one)
+    pub fn with_sessions(mut self, sessions: usize) -> Self {
+        self.sessions = sessions.max(1);
+        self
+    }
+
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self.pacing {
+            LoadPacing::Concurrency(0) => Err(Error::config("Load concurrency must be at least 1")),
+            LoadPacing::Rate(rate) if !(rate > 0.0 && rate.is_finite()) => Err(Error::config(
+                format!("Load rate must be a positive number, got {}", rate),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Result of a load run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadReport {
+    pub suite_name: String,
+    pub pacing: LoadPacing,
+    pub sessions: usize,
+    pub metrics: LoadMetrics,
+    /// Test cases that were not replayed, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// A test case's request, ready to be issued repeatedly
+#[derive(Debug, Clone)]
+struct LoadRequest {
+    test_name: String,
+    request: McpRequest,
+    expected: ExpectedOutput,
+}
+
+/// Replays a specification's test cases under load
+#[derive(Debug, Clone)]
+pub struct LoadRunner {
+    config: LoadConfig,
+}
+
+impl LoadRunner {
+    pub fn new(config: LoadConfig) -> Self {
+        Self { config }
+    }
+
+    /// Connect to the specification's server and apply load to it
+    pub async fn run(&self, specification: &TestSpecification) -> Result<LoadReport> {
+        self.config.validate()?;
+        let (requests, skipped) = load_requests(specification)?;
+        if requests.is_empty() {
+            return Err(Error::spec(format!(
+                "Specification '{}' has no test cases that can be replayed under load",
+                specification.name
+            )));
+        }
+
+        let sessions = connect_sessions(&specification.server, self.config.sessions).await?;
+        let metrics = self.drive(Arc::new(requests), &sessions).await;
+        disconnect(sessions).await;
+
+        Ok(LoadReport {
+            suite_name: specification.name.clone(),
+            pacing: self.config.pacing.clone(),
+            sessions: self.config.sessions,
+            metrics,
+            skipped,
+        })
+    }
+
+    async fn drive(
+        &self,
+        requests: Arc<Vec<LoadRequest>>,
+        sessions: &[Arc<McpClient>],
+    ) -> LoadMetrics {
+        let collector = Arc::new(Mutex::new(MetricsCollector::new()));
+        let timeout = self.config.request_timeout;
+        let started = Instant::now();
+        let deadline = started + self.config.duration;
+
+        let sampler = tokio::spawn(sample_server_memory(
+            sessions.to_vec(),
+            Arc::clone(&collector),
+            self.config.memory_sample_interval,
+        ));
+
+        let mut in_flight = JoinSet::new();
+        match self.config.pacing {
+            LoadPacing::Concurrency(workers) => {
+                let next = Arc::new(AtomicUsize::new(0));
+                for worker in 0..workers {
+                    let client = Arc::clone(&sessions[worker % sessions.len()]);
+                    let (requests, collector, next) = (
+                        Arc::clone(&requests),
+                        Arc::clone(&collector),
+                        Arc::clone(&next),
+                    );
+                    in_flight.spawn(async move {
+                        while Instant::now() < deadline {
+                            let index = next.fetch_add(1, Ordering::Relaxed) % requests.len();
+                            issue(&client, &requests[index], timeout, &collector).await;
+                        }
+                    });
+                }
+            }
+            LoadPacing::Rate(rate) => {
+                let mut ticks = tokio::time::interval(Duration::from_secs_f64(1.0 / rate));
+                for index in 0.. {
+                    if ticks.tick().await >= deadline {
+                        break;
+                    }
+                    let client = Arc::clone(&sessions[index % sessions.len()]);
+                    let (requests, collector) = (Arc::clone(&requests), Arc::clone(&collector));
+                    in_flight.spawn(async move {
+                        let request = &requests[index % requests.len()];
+                        issue(&client, request, timeout, &collector).await;
+                    });
+                }
+            }
+        }
+        while in_flight.join_next().await.is_some() {}
+        let elapsed = started.elapsed();
+
+        sampler.abort();
+        let _ = sampler.await;
+        let mut collector = collector.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(memory) = total_server_memory(sessions) {
+            collector.sample_server_memory(memory);
+        }
+        collector.load_metrics(elapsed)
+    }
+}
+
+/// Issue one request and record its latency and outcome
+async fn issue(
+    client: &McpClient,
+    request: &LoadRequest,
+    timeout: Duration,
+    collector: &Mutex<MetricsCollector>,
+) {
+    let start = Instant::now();
+    let response =
+        tokio::time::timeout(timeout, send_request(client, request.request.clone())).await;
+    let latency = start.elapsed();
+
+    let outcome = match response {
+        Err(_) => RequestOutcome::Timeout,
+        Ok(Ok(response)) => check_response(&response, &request.expected).await,
+        Ok(Err(error)) if request.expected.error && error.is_server_error() => {
+            check_response(&rejection_response(&error), &request.expected).await
+        }
+        Ok(Err(_)) => RequestOutcome::Error,
+    };
+
+    let mut collector = collector.lock().unwrap_or_else(|e| e.into_inner());
+    collector.record_request(&request.test_name, latency, outcome);
+}
+
+async fn check_response(response: &serde_json::Value, expected: &ExpectedOutput) -> RequestOutcome {
+    let valid = ValidationEngine::default()
+        .validate_response(response, expected)
+        .await
+        .is_ok_and(|result| result.is_valid)
+        && check_error_expectation(response, expected).is_none();
+    if valid {
+        RequestOutcome::Passed
+    } else {
+        RequestOutcome::Failed
+    }
+}
+
+/// Sample the combined resident memory of the sessions' servers until aborted
+async fn sample_server_memory(
+    sessions: Vec<Arc<McpClient>>,
+    collector: Arc<Mutex<MetricsCollector>>,
+    interval: Duration,
+) {
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        ticks.tick().await;
+        if let Some(memory) = total_server_memory(&sessions) {
+            let mut collector = collector.lock().unwrap_or_else(|e| e.into_inner());
+            collector.sample_server_memory(memory);
+        }
+    }
+}
+
+fn total_server_memory(sessions: &[Arc<McpClient>]) -> Option<u64> {
+    sessions
+        .iter()
+        .filter_map(|session| session.server_memory_bytes())
+        .reduce(|total, memory| total + memory)
+}
+
+/// Requests for the specification's test cases, and the test cases skipped
+fn load_requests(specification: &TestSpecification) -> Result<(Vec<LoadRequest>, Vec<String>)> {
+    let mut requests = Vec::new();
+    let mut skipped = Vec::new();
+    let mut add =
+        |test: &TestCase, request: std::result::Result<McpRequest, ExecutorError>| -> Result<()> {
+            let expected = serde_json::to_value(&test.expected)?;
+            if !expand::captured_references(&test.input).is_empty()
+                || !expand::captured_references(&expected).is_empty()
+            {
+                skipped.push(format!("{}: uses captured values", test.name));
+                return Ok(());
+            }
+            let request =
+                request.map_err(|e| Error::spec(format!("Test case '{}': {}", test.name, e)))?;
+            requests.push(LoadRequest {
+                test_name: test.name.clone(),
+                request,
+                expected: test.expected.clone(),
+            });
+            Ok(())
+        };
+
+    for tool in specification.tools.iter().flatten() {
+        for test in &tool.tests {
+            let arguments = (!test.input.is_null()).then(|| test.input.clone());
+            let request = McpRequest::CallTool {
+                name: tool.name.clone(),
+                arguments,
+            };
+            add(test, Ok(request))?;
+        }
+    }
+    for resource in specification.resources.iter().flatten() {
+        for test in &resource.tests {
+            let request = expand_uri_template(&resource.uri_template, &test.input)
+                .map(|uri| McpRequest::ReadResource { uri });
+            add(test, request)?;
+        }
+    }
+    for prompt in specification.prompts.iter().flatten() {
+        for test in &prompt.tests {
+            let request = prompt_arguments(&test.input).map(|arguments| McpRequest::GetPrompt {
+                name: prompt.name.clone(),
+                arguments,
+            });
+            add(test, request)?;
+        }
+    }
+
+    Ok((requests, skipped))
+}
+
+/// Connect `count` client sessions to the server
+async fn connect_sessions(
+    server: &crate::spec::ServerConfig,
+    count: usize,
+) -> Result<Vec<Arc<McpClient>>> {
+    let mut sessions = Vec::with_capacity(count);
+    for index in 0..count {
+        let mut config: ClientServerConfig = server.clone().try_into()?;
+        // A network server is shared, so only the first session launches it
+        if index > 0 && config.transport.is_network() {
+            config.command.clear();
+        }
+
+        let mut client = McpClient::new(config).await?;
+        if let Err(e) = client.connect().await {
+            disconnect(sessions).await;
+            return Err(e);
+        }
+        sessions.push(Arc::new(client));
+    }
+    Ok(sessions)
+}
+
+async fn disconnect(sessions: Vec<Arc<McpClient>>) {
+    // Later sessions first, so a shared network server goes last
+    for session in sessions.into_iter().rev() {
+        if let Ok(mut client) = Arc::try_unwrap(session) {
+            if let Err(e) = client.disconnect().await {
+                tracing::warn!("Failed to disconnect load session: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::SpecificationLoader;
+
+    const SPEC: &str = r#"
+name: "Load"
+version: "1.0.0"
+capabilities: { tools: true, resources: true, prompts: true, sampling: false, logging: false }
+server: { command: "node", args: ["server.js"], transport: "stdio" }
+tools:
+  - name: "echo"
+    tests:
+      - name: "echo_hi"
+        input: { text: "hi" }
+        expected: { error: false }
+      - name: "echo_null"
+        input: null
+        expected: { error: false }
+      - name: "echo_captured"
+        input: { text: "${captured.id}" }
+        expected: { error: false }
+resources:
+  - uri_template: "file:///{path}"
+    name: "file"
+    tests:
+      - name: "read_file"
+        input: { path: "a.txt" }
+        expected: { error: false }
+prompts:
+  - name: "greet"
+    tests:
+      - name: "greet_bob"
+        input: { name: "Bob", times: 2 }
+        expected: { error: false }
+"#;
+
+    #[test]
+    fn test_load_requests_from_specification() {
+        let spec = SpecificationLoader::new()
+            .unwrap()
+            .parse_yaml(SPEC)
+            .unwrap();
+        let (requests, skipped) = load_requests(&spec).unwrap();
+
+        let names: Vec<_> = requests.iter().map(|r| r.test_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["echo_hi", "echo_null", "read_file", "greet_bob"]
+        );
+        assert_eq!(skipped, vec!["echo_captured: uses captured values"]);
+
+        assert!(matches!(
+            &requests[1].request,
+            McpRequest::CallTool {
+                arguments: None,
+                ..
+            }
+        ));
+        assert!(matches!(
+            &requests[2].request,
+            McpRequest::ReadResource { uri } if uri == "file:///a.txt"
+        ));
+        let McpRequest::GetPrompt { arguments, .. } = &requests[3].request else {
+            panic!("expected a prompt request");
+        };
+        assert_eq!(
+            arguments.as_ref().unwrap(),
+            &serde_json::json!({"name": "Bob", "times": "2"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_check_response_outcomes() {
+        let expected = ExpectedOutput {
+            error: true,
+            error_message_contains: Some("not found".to_string()),
+            ..ExpectedOutput::default()
+        };
+        let rejected = rejection_response(&ExecutorError::ToolCallError(
+            "Tool call failed: file not found".to_string(),
+        ));
+        assert_eq!(
+            check_response(&rejected, &expected).await,
+            RequestOutcome::Passed
+        );
+        assert_eq!(
+            check_response(&serde_json::json!({"content": []}), &expected).await,
+            RequestOutcome::Failed
+        );
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(LoadConfig::new().validate().is_ok());
+        assert!(LoadConfig::new()
+            .with_pacing(LoadPacing::Concurrency(0))
+            .validate()
+            .is_err());
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(LoadConfig::new()
+                .with_pacing(LoadPacing::Rate(rate))
+                .validate()
+                .is_err());
+        }
+        assert_eq!(LoadConfig::new().with_sessions(0).sessions, 1);
+    }
+}
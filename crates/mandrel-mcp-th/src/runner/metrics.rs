@@ -1,7 +1,7 @@
 //! Metrics collection and aggregation for test suite execution
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::{Duration, SystemTime};
 
 /// Collects and manages metrics during test suite execution
@@ -11,6 +11,12 @@ pub struct MetricsCollector {
     test_metrics: HashMap<String, TestMetrics>,
     memory_samples: Vec<MemorySample>,
     suite_metrics: SuiteMetrics,
+    /// Requests issued under load, see [`crate::runner::LoadRunner`]
+    requests: RequestAggregate,
+    /// Requests issued under load, by test case name
+    requests_by_test: HashMap<String, RequestAggregate>,
+    /// Resident memory of the server under test
+    server_memory: Option<ServerMemoryStats>,
 }
 
 /// Metrics for individual test case execution
@@ -33,6 +39,101 @@ pub struct MemorySample {
     pub memory_mb: u64,
 }
 
+/// Outcome of one request issued under load
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestOutcome {
+    Passed,
+    /// The response did not match the test's expected output
+    Failed,
+    /// The request could not be completed
+    Error,
+    Timeout,
+}
+
+/// Outcomes and latency histogram of a group of requests
+///
+/// Requests are aggregated as they complete, so a long load run uses a
+/// bounded amount of memory whatever the number of requests.
+#[derive(Debug, Clone, Default)]
+struct RequestAggregate {
+    passed: usize,
+    failed: usize,
+    errors: usize,
+    timeouts: usize,
+    latency: LatencyHistogram,
+}
+
+impl RequestAggregate {
+    fn record(&mut self, latency: Duration, outcome: RequestOutcome) {
+        match outcome {
+            RequestOutcome::Passed => self.passed += 1,
+            RequestOutcome::Failed => self.failed += 1,
+            RequestOutcome::Error => self.errors += 1,
+            RequestOutcome::Timeout => self.timeouts += 1,
+        }
+        self.latency.record(latency);
+    }
+}
+
+/// Bits of a latency in microseconds kept by [`LatencyHistogram`]
+const HISTOGRAM_SIGNIFICANT_BITS: u32 = 10;
+
+/// Latency histogram with logarithmic buckets
+///
+/// Latencies are counted in microseconds, rounded down to their top
+/// [`HISTOGRAM_SIGNIFICANT_BITS`] bits: exact below about 1ms and within
+/// 0.1% above, with at most 1024 buckets per power of two.
+#[derive(Debug, Clone, Default)]
+struct LatencyHistogram {
+    /// Request count by bucket, keyed by the bucket's lowest latency in µs
+    buckets: BTreeMap<u64, usize>,
+    count: usize,
+    total_ms: f64,
+    max_ms: f64,
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        let dropped_bits =
+            (u64::BITS - micros.leading_zeros()).saturating_sub(HISTOGRAM_SIGNIFICANT_BITS);
+        *self
+            .buckets
+            .entry(micros >> dropped_bits << dropped_bits)
+            .or_default() += 1;
+
+        let ms = latency.as_secs_f64() * 1000.0;
+        self.count += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    fn mean_ms(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.total_ms / self.count as f64
+        }
+    }
+
+    /// Nearest-rank percentile, as the lowest latency of its bucket
+    fn percentile_ms(&self, percent: f64) -> f64 {
+        if self.count == 0 {
+            return 0.0;
+        }
+        let rank = ((percent / 100.0 * self.count as f64).ceil() as usize).clamp(1, self.count);
+        let mut seen = 0;
+        for (&micros, &count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return micros as f64 / 1000.0;
+            }
+        }
+        self.max_ms
+    }
+}
+
 /// Aggregated metrics for the entire test suite
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuiteMetrics {
@@ -165,6 +266,51 @@ impl MetricsCollector {
         64 // Default to 64MB as a reasonable baseline
     }
 
+    /// Record a request issued under load
+    pub fn record_request(&mut self, test_name: &str, latency: Duration, outcome: RequestOutcome) {
+        self.requests.record(latency, outcome);
+        match self.requests_by_test.get_mut(test_name) {
+            Some(aggregate) => aggregate.record(latency, outcome),
+            None => {
+                let mut aggregate = RequestAggregate::default();
+                aggregate.record(latency, outcome);
+                self.requests_by_test
+                    .insert(test_name.to_string(), aggregate);
+            }
+        }
+    }
+
+    /// Record the resident memory of the server under test
+    pub fn sample_server_memory(&mut self, memory_bytes: u64) {
+        let memory_mb = memory_bytes / (1024 * 1024);
+        let stats = self.server_memory.get_or_insert(ServerMemoryStats {
+            initial_mb: memory_mb,
+            peak_mb: memory_mb,
+            final_mb: memory_mb,
+            samples: 0,
+        });
+        stats.peak_mb = stats.peak_mb.max(memory_mb);
+        stats.final_mb = memory_mb;
+        stats.samples += 1;
+    }
+
+    /// Latency, throughput and error statistics of the requests recorded over `elapsed`
+    pub fn load_metrics(&self, elapsed: Duration) -> LoadMetrics {
+        let mut per_test: Vec<_> = self
+            .requests_by_test
+            .iter()
+            .map(|(name, aggregate)| RequestStats::from_aggregate(name, aggregate, elapsed))
+            .collect();
+        per_test.sort_by(|a, b| a.name.cmp(&b.name));
+
+        LoadMetrics {
+            elapsed,
+            overall: RequestStats::from_aggregate("all", &self.requests, elapsed),
+            per_test,
+            server_memory: self.server_memory.clone(),
+        }
+    }
+
     /// Get peak memory usage since a specific time
     fn get_peak_memory_since(&self, since: SystemTime) -> u64 {
         self.memory_samples
@@ -324,6 +470,77 @@ impl MetricsCollector {
     }
 }
 
+/// Statistics of a load run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadMetrics {
+    pub elapsed: Duration,
+    pub overall: RequestStats,
+    /// Statistics per test case, by name
+    pub per_test: Vec<RequestStats>,
+    /// Absent when the server's memory could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_memory: Option<ServerMemoryStats>,
+}
+
+/// Latency, throughput and errors of a group of requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestStats {
+    pub name: String,
+    pub requests: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub timeouts: usize,
+    /// Requests per second
+    pub throughput: f64,
+    /// Fraction of requests that did not pass, from 0 to 1
+    pub error_rate: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl RequestStats {
+    fn from_aggregate(name: &str, aggregate: &RequestAggregate, elapsed: Duration) -> Self {
+        let latency = &aggregate.latency;
+        let requests = latency.count;
+        let throughput = if elapsed.is_zero() {
+            0.0
+        } else {
+            requests as f64 / elapsed.as_secs_f64()
+        };
+        let error_rate = if requests == 0 {
+            0.0
+        } else {
+            (requests - aggregate.passed) as f64 / requests as f64
+        };
+        Self {
+            name: name.to_string(),
+            requests,
+            failed: aggregate.failed,
+            errors: aggregate.errors,
+            timeouts: aggregate.timeouts,
+            throughput,
+            error_rate,
+            mean_ms: latency.mean_ms(),
+            p50_ms: latency.percentile_ms(50.0),
+            p95_ms: latency.percentile_ms(95.0),
+            p99_ms: latency.percentile_ms(99.0),
+            max_ms: latency.max_ms,
+        }
+    }
+}
+
+/// Resident memory of the server under test during a load run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerMemoryStats {
+    pub initial_mb: u64,
+    pub peak_mb: u64,
+    pub final_mb: u64,
+    pub samples: usize,
+}
+
 /// Summary of all collected metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsSummary {
@@ -443,6 +660,81 @@ mod tests {
         assert_eq!(metrics.fastest_test, Some("fast_test".to_string()));
     }
 
+    #[test]
+    fn test_load_metrics() {
+        let mut collector = MetricsCollector::new();
+        for ms in 1..=100 {
+            collector.record_request("read", Duration::from_millis(ms), RequestOutcome::Passed);
+        }
+        collector.record_request("write", Duration::from_millis(500), RequestOutcome::Failed);
+        collector.record_request("write", Duration::from_millis(5), RequestOutcome::Timeout);
+        collector.sample_server_memory(40 * 1024 * 1024);
+        collector.sample_server_memory(90 * 1024 * 1024);
+        collector.sample_server_memory(60 * 1024 * 1024);
+
+        let metrics = collector.load_metrics(Duration::from_secs(2));
+        assert_eq!(metrics.overall.requests, 102);
+        assert_eq!(metrics.overall.throughput, 51.0);
+        assert_eq!(metrics.overall.error_rate, 2.0 / 102.0);
+
+        let names: Vec<_> = metrics.per_test.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["read", "write"]);
+        let read = &metrics.per_test[0];
+        for (actual, expected) in [
+            (read.p50_ms, 50.0),
+            (read.p95_ms, 95.0),
+            (read.p99_ms, 99.0),
+        ] {
+            assert!(
+                actual <= expected && actual > expected * 0.999,
+                "{} vs {}",
+                actual,
+                expected
+            );
+        }
+        assert_eq!((read.mean_ms, read.max_ms), (50.5, 100.0));
+        let write = &metrics.per_test[1];
+        assert_eq!((write.failed, write.timeouts, write.errors), (1, 1, 0));
+        assert_eq!(write.error_rate, 1.0);
+
+        assert_eq!(
+            metrics.server_memory,
+            Some(ServerMemoryStats {
+                initial_mb: 40,
+                peak_mb: 90,
+                final_mb: 60,
+                samples: 3,
+            })
+        );
+        assert!(MetricsCollector::new()
+            .load_metrics(Duration::ZERO)
+            .server_memory
+            .is_none());
+    }
+
+    #[test]
+    fn test_latency_histogram_is_bounded() {
+        let mut histogram = LatencyHistogram::default();
+        for micros in 0..200_000 {
+            histogram.record(Duration::from_micros(micros * 7));
+        }
+
+        assert_eq!(histogram.count, 200_000);
+        assert!(
+            histogram.buckets.len() < 12 * 1024,
+            "{}",
+            histogram.buckets.len()
+        );
+        let p50 = histogram.percentile_ms(50.0);
+        assert!((699.0..=700.0).contains(&p50), "{}", p50);
+        assert_eq!(histogram.max_ms, 1399.993);
+
+        // Sub-millisecond latencies keep full resolution
+        let mut histogram = LatencyHistogram::default();
+        histogram.record(Duration::from_micros(734));
+        assert_eq!(histogram.percentile_ms(99.0), 0.734);
+    }
+
     #[test]
     fn test_efficiency_scores() {
         let mut collector = MetricsCollector::new();
@@ -8,6 +8,7 @@
 pub mod config;
 pub mod dependency;
 pub mod execution;
+pub mod load;
 pub mod metrics;
 pub mod result;
 
@@ -15,13 +16,16 @@ pub mod result;
 pub use config::{ExecutionMode, RunnerConfig};
 pub use dependency::DependencyResolver;
 pub use execution::ExecutionStrategy;
-pub use metrics::{MetricsCollector, SuiteMetrics};
+pub use load::{LoadConfig, LoadPacing, LoadReport, LoadRunner};
+pub use metrics::{LoadMetrics, MetricsCollector, RequestOutcome, RequestStats, SuiteMetrics};
 pub use result::{DependencyResolution, TestSuiteResult};
 
 use crate::error::Result;
 use crate::executor::TestCaseExecutor;
 use crate::runner::result::TestMetadata;
+use crate::snapshot::SnapshotStore;
 use crate::spec::{SpecificationLoader, TestSpecification};
+use crate::validation::ValidationError;
 use std::collections::HashMap;
 use std::path::Path;
 use std::time::{Duration, SystemTime};
@@ -117,6 +121,12 @@ impl TestSuiteRunner {
 
         // 1. Load and parse the test specification
         let specification = self.loader.load_from_file(spec_path).await?;
+        self.executor
+            .set_validation_scripts(specification.validation_scripts.clone().unwrap_or_default());
+        self.executor.clear_captured();
+        self.executor.set_snapshot_store(
+            SnapshotStore::for_specification(spec_path).with_update(self.config.update_snapshots),
+        );
 
         // 2. Extract test cases from specification
         let test_cases = self.extract_test_cases(&specification)?;
@@ -183,21 +193,29 @@ impl TestSuiteRunner {
         &self,
         specification: &TestSpecification,
     ) -> Result<Vec<crate::spec::TestCase>> {
-        // Handle empty test suites - check if tools are defined
-        let tools = match &specification.tools {
-            Some(tools) if !tools.is_empty() => tools,
-            _ => return Ok(Vec::new()), // Empty test suite - return empty Vec
-        };
-
-        // Extract real test cases from YAML tools
-        let mut test_cases = Vec::new();
-        for tool in tools {
-            for test in &tool.tests {
-                test_cases.push(test.clone());
-            }
-        }
-
-        Ok(test_cases)
+        // Extract real test cases from YAML tools, resources and prompts;
+        // sections that are not defined contribute no tests
+        let tool_tests = specification
+            .tools
+            .iter()
+            .flatten()
+            .flat_map(|tool| &tool.tests);
+        let resource_tests = specification
+            .resources
+            .iter()
+            .flatten()
+            .flat_map(|resource| &resource.tests);
+        let prompt_tests = specification
+            .prompts
+            .iter()
+            .flatten()
+            .flat_map(|prompt| &prompt.tests);
+
+        Ok(tool_tests
+            .chain(resource_tests)
+            .chain(prompt_tests)
+            .cloned()
+            .collect())
     }
 
     /// Resolve test case dependencies and determine execution order
@@ -276,6 +294,8 @@ impl TestSuiteRunner {
                         end_time: SystemTime::now(),
                         memory_usage_mb: None,
                         metadata: TestMetadata::default(),
+                        snapshot_diffs: Vec::new(),
+                        latency_samples: Vec::new(),
                     }
                 }
             };
@@ -351,6 +371,8 @@ impl TestSuiteRunner {
                 env: std::collections::HashMap::new(),
                 working_dir: None,
                 transport: "stdio".to_string(),
+                url: None,
+                headers: std::collections::HashMap::new(),
                 startup_timeout_seconds: 30,
                 shutdown_timeout_seconds: 10,
             },
@@ -384,6 +406,37 @@ impl TestSuiteRunner {
             })
     }
 
+    /// Find what a given test exercises: a tool, a resource or a prompt
+    fn find_test_target(
+        &self,
+        test_name: &str,
+        specification: &TestSpecification,
+    ) -> Result<TestTarget> {
+        let defines_test =
+            |tests: &[crate::spec::TestCase]| tests.iter().any(|t| t.name == test_name);
+
+        if let Some(resource) = specification
+            .resources
+            .iter()
+            .flatten()
+            .find(|resource| defines_test(&resource.tests))
+        {
+            return Ok(TestTarget::Resource(resource.uri_template.clone()));
+        }
+
+        if let Some(prompt) = specification
+            .prompts
+            .iter()
+            .flatten()
+            .find(|prompt| defines_test(&prompt.tests))
+        {
+            return Ok(TestTarget::Prompt(prompt.name.clone()));
+        }
+
+        self.find_tool_name_for_test(test_name, specification)
+            .map(TestTarget::Tool)
+    }
+
     /// Find tool name for a given test in the specification
     fn find_tool_name_for_test(
         &self,
@@ -421,6 +474,15 @@ impl TestSuiteRunner {
     ) -> TestResult {
         let start_time = SystemTime::now() - executor_result.execution_time;
         let end_time = SystemTime::now();
+        let snapshot_diffs = executor_result
+            .validation
+            .validation_errors
+            .iter()
+            .filter_map(|error| match error {
+                ValidationError::SnapshotMismatch(diff) => Some(diff.clone()),
+                _ => None,
+            })
+            .collect();
 
         TestResult {
             test_name: test_name.to_string(),
@@ -432,6 +494,8 @@ impl TestSuiteRunner {
             end_time,
             memory_usage_mb: executor_result.metrics.memory_usage,
             metadata: TestMetadata::default(),
+            snapshot_diffs,
+            latency_samples: Vec::new(),
         }
     }
 
@@ -445,20 +509,55 @@ impl TestSuiteRunner {
         // 1. Find the test case
         let test_case = self.find_test_case_by_name(test_name, test_cases)?;
 
-        // 2. Find the tool name for this test
-        let tool_name = self.find_tool_name_for_test(test_name, specification)?;
-
-        // 3. Execute through TestCaseExecutor
-        let executor_result = self
-            .executor
-            .execute_test_case(&tool_name, test_case)
-            .await?;
+        // 2. Find the tool, resource or prompt this test exercises
+        let target = self.find_test_target(test_name, specification)?;
+
+        // 3. Execute through TestCaseExecutor, once per repetition; a
+        //    failing repetition ends the test with its result
+        let mut latency_samples = Vec::with_capacity(self.config.repetitions);
+        loop {
+            let executor_result = match &target {
+                TestTarget::Tool(tool_name) => {
+                    self.executor
+                        .execute_test_case(tool_name, test_case)
+                        .await?
+                }
+                TestTarget::Resource(uri_template) => {
+                    self.executor
+                        .execute_resource_test(uri_template, test_case)
+                        .await?
+                }
+                TestTarget::Prompt(prompt_name) => {
+                    self.executor
+                        .execute_prompt_test(prompt_name, test_case)
+                        .await?
+                }
+            };
+            latency_samples.push(executor_result.execution_time);
 
-        // 4. Convert to TestSuiteRunner result format
-        Ok(self.convert_executor_result(executor_result, test_name))
+            if !executor_result.success || latency_samples.len() >= self.config.repetitions {
+                // 4. Convert to TestSuiteRunner result format
+                let mut result = self.convert_executor_result(executor_result, test_name);
+                if latency_samples.len() > 1 {
+                    result.latency_samples = latency_samples;
+                }
+                return Ok(result);
+            }
+        }
     }
 }
 
+/// What a test case exercises on the server under test
+#[derive(Debug, Clone, PartialEq)]
+enum TestTarget {
+    /// Tool name for `tools/call`
+    Tool(String),
+    /// URI template for `resources/read`
+    Resource(String),
+    /// Prompt name for `prompts/get`
+    Prompt(String),
+}
+
 // Re-export TestResult from result module to avoid duplication
 pub use result::TestResult;
 
@@ -486,15 +585,13 @@ mod tests {
         let specification = loader.load_from_file(spec_path).await?;
 
         // Convert spec server config to client ServerConfig
+        let transport = Transport::try_from(&specification.server)?;
         let server_config = ServerConfig {
             command: specification.server.command,
             args: specification.server.args,
             env: specification.server.env,
             working_dir: specification.server.working_dir.map(PathBuf::from),
-            transport: match specification.server.transport.as_str() {
-                "stdio" => Transport::Stdio,
-                _ => Transport::Stdio, // Default to stdio
-            },
+            transport,
             startup_timeout: Duration::from_secs(
                 specification.server.startup_timeout_seconds.into(),
             ),
@@ -1097,4 +1194,100 @@ tools:
         assert!(suite_result.all_passed()); // Vacuously true
         assert!(!suite_result.has_failures());
     }
+
+    #[tokio::test]
+    async fn test_resource_and_prompt_tests_are_collected_and_targeted() {
+        use crate::client::{McpClient, ServerConfig, Transport};
+
+        let specification = SpecificationLoader::new()
+            .unwrap()
+            .parse_yaml(
+                r#"
+name: "Resources and Prompts"
+version: "1.0.0"
+capabilities:
+  tools: true
+  resources: true
+  prompts: true
+  sampling: false
+  logging: false
+server:
+  command: "echo"
+  transport: "stdio"
+tools:
+  - name: "echo"
+    tests:
+      - name: "echo_message"
+        input: {message: "hi"}
+        expected: {error: false}
+resources:
+  - uri_template: "test://static/resource/{id}"
+    name: "Static resources"
+    tests:
+      - name: "read_resource_two"
+        input: {id: 2}
+        expected: {error: false}
+prompts:
+  - name: "simple_prompt"
+    tests:
+      - name: "simple_prompt_without_arguments"
+        input: {}
+        expected: {error: false}
+"#,
+            )
+            .unwrap();
+
+        let client = McpClient::new(ServerConfig {
+            command: "echo".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            working_dir: None,
+            transport: Transport::Stdio,
+            startup_timeout: Duration::from_secs(5),
+            shutdown_timeout: Duration::from_secs(5),
+            operation_timeout: Duration::from_secs(5),
+            max_retries: 0,
+        })
+        .await
+        .unwrap();
+        let executor = TestCaseExecutor::new(
+            Arc::new(std::sync::Mutex::new(client)),
+            ExecutorConfig::default(),
+        );
+        let runner = TestSuiteRunner::new(executor, RunnerConfig::new());
+
+        let names: Vec<String> = runner
+            .extract_test_cases(&specification)
+            .unwrap()
+            .into_iter()
+            .map(|test| test.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "echo_message",
+                "read_resource_two",
+                "simple_prompt_without_arguments"
+            ]
+        );
+
+        assert_eq!(
+            runner
+                .find_test_target("echo_message", &specification)
+                .unwrap(),
+            TestTarget::Tool("echo".to_string())
+        );
+        assert_eq!(
+            runner
+                .find_test_target("read_resource_two", &specification)
+                .unwrap(),
+            TestTarget::Resource("test://static/resource/{id}".to_string())
+        );
+        assert_eq!(
+            runner
+                .find_test_target("simple_prompt_without_arguments", &specification)
+                .unwrap(),
+            TestTarget::Prompt("simple_prompt".to_string())
+        );
+    }
 }
@@ -6,6 +6,7 @@ use std::time::{Duration, SystemTime};
 
 use super::config::ExecutionMode;
 use super::metrics::SuiteMetrics;
+use crate::snapshot::SnapshotDiff;
 
 /// Complete result of test suite execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -148,6 +149,12 @@ pub struct TestResult {
     pub memory_usage_mb: Option<u64>,
     /// Additional test metadata
     pub metadata: TestMetadata,
+    /// Differences from the test's snapshot when it did not match
+    #[serde(default)]
+    pub snapshot_diffs: Vec<SnapshotDiff>,
+    /// Duration of each repetition when the test ran more than once
+    #[serde(default)]
+    pub latency_samples: Vec<Duration>,
 }
 
 impl TestResult {
@@ -164,6 +171,8 @@ impl TestResult {
             end_time: now,
             memory_usage_mb: None,
             metadata: TestMetadata::default(),
+            snapshot_diffs: Vec::new(),
+            latency_samples: Vec::new(),
         }
     }
 
@@ -180,6 +189,8 @@ impl TestResult {
             end_time: now,
             memory_usage_mb: None,
             metadata: TestMetadata::default(),
+            snapshot_diffs: Vec::new(),
+            latency_samples: Vec::new(),
         }
     }
 
@@ -1,3 +1,300 @@
 //! JavaScript script execution engine
+//!
+//! Runs validation scripts on QuickJS via `rquickjs`. QuickJS only provides
+//! the ECMAScript built-ins: there is no `require`, `fetch` or `std`/`os`
+//! module, so scripts have no filesystem or network access regardless of
+//! the configuration.
 
-// PLANNED(#250): JavaScript engine implementation will be added in ScriptContext/ScriptResult issue
+use super::types::{ScriptConfig, ScriptContext, ScriptError, ScriptResult};
+use super::{utilities, ScriptEngine};
+use rquickjs::prelude::Rest;
+use rquickjs::{Coerced, Context, Ctx, Function, Object, Runtime, Value};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// File name QuickJS reports in stack traces of evaluated scripts
+const SCRIPT_NAME: &str = "eval_script";
+
+/// Stack size available to scripts
+const MAX_STACK_SIZE: usize = 512 * 1024;
+
+/// JavaScript validation script engine
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JavaScriptEngine;
+
+impl ScriptEngine for JavaScriptEngine {
+    fn execute(&self, source: &str, context: &ScriptContext) -> ScriptResult {
+        let start = Instant::now();
+        let logs = Rc::new(RefCell::new(Vec::new()));
+
+        match run(source, context, &logs, start) {
+            Ok((output, memory_used)) => utilities::finish(
+                output,
+                logs.take(),
+                Some(memory_used),
+                &context.config,
+                start,
+            ),
+            Err(error) => utilities::with_logs(
+                ScriptResult::failure(error, utilities::elapsed_ms(start)),
+                logs.take(),
+            ),
+        }
+    }
+}
+
+fn run(
+    source: &str,
+    context: &ScriptContext,
+    logs: &Rc<RefCell<Vec<String>>>,
+    start: Instant,
+) -> Result<(serde_json::Value, usize), ScriptError> {
+    let config = &context.config;
+    let runtime = Runtime::new().map_err(runtime_error)?;
+    if let Some(limit) = utilities::memory_limit_bytes(config) {
+        runtime.set_memory_limit(limit);
+    }
+    runtime.set_max_stack_size(MAX_STACK_SIZE);
+    let deadline = start + utilities::timeout(config);
+    runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() > deadline)));
+
+    let js_context = Context::full(&runtime).map_err(runtime_error)?;
+    let context_json = utilities::context_value(context)?.to_string();
+
+    let output = js_context.with(|ctx| {
+        install_globals(&ctx, &context_json, logs).map_err(runtime_error)?;
+
+        let returned: Value = ctx
+            .eval(source)
+            .map_err(|e| classify_error(&ctx, e, config, start))?;
+        let result: Value = ctx.globals().get("result").map_err(runtime_error)?;
+        let output = if result.is_undefined() {
+            returned
+        } else {
+            result
+        };
+
+        match ctx.json_stringify(output).map_err(runtime_error)? {
+            Some(json) => {
+                let json = json.to_string().map_err(runtime_error)?;
+                serde_json::from_str(&json).map_err(|e| ScriptError::SerializationError {
+                    message: e.to_string(),
+                })
+            }
+            None => Ok(serde_json::Value::Null),
+        }
+    })?;
+
+    let memory_used = runtime.memory_usage().memory_used_size.max(0) as usize;
+    Ok((output, memory_used))
+}
+
+/// Expose `context`, a capturing `console` and the shared helper functions
+fn install_globals<'js>(
+    ctx: &Ctx<'js>,
+    context_json: &str,
+    logs: &Rc<RefCell<Vec<String>>>,
+) -> rquickjs::Result<()> {
+    let globals = ctx.globals();
+    globals.set("context", ctx.json_parse(context_json)?)?;
+    // Scripts are evaluated in strict mode, so `result` must exist to be assigned
+    globals.set("result", rquickjs::Undefined)?;
+
+    let console = Object::new(ctx.clone())?;
+    for level in ["log", "info", "debug", "warn", "error"] {
+        let logs = Rc::clone(logs);
+        let log = Function::new(ctx.clone(), move |values: Rest<Coerced<String>>| {
+            let line = values
+                .0
+                .into_iter()
+                .map(|value| value.0)
+                .collect::<Vec<_>>()
+                .join(" ");
+            logs.borrow_mut().push(line);
+        })?;
+        console.set(level, log)?;
+    }
+    globals.set("console", console)?;
+
+    globals.set(
+        "regex_match",
+        Function::new(
+            ctx.clone(),
+            |ctx: Ctx<'js>, pattern: String, text: String| {
+                utilities::regex_match(&pattern, &text)
+                    .map_err(|e| rquickjs::Exception::throw_message(&ctx, &e.to_string()))
+            },
+        )?,
+    )?;
+    globals.set(
+        "uuid",
+        Function::new(ctx.clone(), utilities::generate_uuid)?,
+    )?;
+
+    Ok(())
+}
+
+/// Map a failed evaluation onto the script error it represents
+fn classify_error(
+    ctx: &Ctx<'_>,
+    error: rquickjs::Error,
+    config: &ScriptConfig,
+    start: Instant,
+) -> ScriptError {
+    if start.elapsed() >= utilities::timeout(config) {
+        return ScriptError::TimeoutError {
+            timeout_ms: config.timeout_ms,
+        };
+    }
+
+    let (name, message, stack) = match error {
+        rquickjs::Error::Exception => exception_details(&ctx.catch()),
+        rquickjs::Error::Allocation => (None, "out of memory".to_string(), None),
+        other => (None, other.to_string(), None),
+    };
+
+    if message.contains("out of memory") {
+        return ScriptError::MemoryLimitError {
+            used_mb: config.memory_limit_mb.unwrap_or_default() as f64,
+            limit_mb: config.memory_limit_mb.unwrap_or_default(),
+        };
+    }
+
+    match name.as_deref() {
+        Some("SyntaxError") => ScriptError::SyntaxError {
+            line: stack
+                .as_deref()
+                .map(|stack| utilities::line_number(stack, SCRIPT_NAME))
+                .unwrap_or(0),
+            message,
+        },
+        Some(name) => ScriptError::RuntimeError {
+            message: format!("{}: {}", name, message),
+        },
+        None => ScriptError::RuntimeError { message },
+    }
+}
+
+/// Name, message and stack of a thrown value
+fn exception_details(thrown: &Value<'_>) -> (Option<String>, String, Option<String>) {
+    match thrown.as_exception() {
+        Some(exception) => (
+            exception.as_object().get::<_, String>("name").ok(),
+            exception.message().unwrap_or_default(),
+            exception.stack(),
+        ),
+        None if thrown.is_null() => (None, "out of memory".to_string(), None),
+        None => (
+            None,
+            thrown
+                .get::<Coerced<String>>()
+                .map(|message| message.0)
+                .unwrap_or_else(|_| "uncaught exception".to_string()),
+            None,
+        ),
+    }
+}
+
+fn runtime_error(error: impl std::fmt::Display) -> ScriptError {
+    ScriptError::RuntimeError {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context(config: ScriptConfig) -> ScriptContext {
+        ScriptContext::new(
+            json!({"method": "tools/call", "params": {"a": 5, "b": 3}}),
+            "add_integers".to_string(),
+            "add".to_string(),
+            config,
+        )
+        .with_response(json!({"content": [{"type": "text", "text": "8"}]}))
+    }
+
+    #[test]
+    fn test_js_script_reads_context_and_sets_result() {
+        let result = JavaScriptEngine.execute(
+            r#"
+            const { a, b } = context.request.params;
+            const actual = Number(context.response.content[0].text);
+            console.log("sum", actual);
+            result = { success: a + b === actual, message: `checked ${context.metadata.test_name}` };
+            "#,
+            &context(ScriptConfig::new()),
+        );
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(
+            result.output,
+            json!({"success": true, "message": "checked add_integers"})
+        );
+        assert_eq!(result.logs[0].message, "sum 8");
+        assert!(result.memory_used_mb.is_some());
+    }
+
+    #[test]
+    fn test_js_last_expression_and_helpers() {
+        let result = JavaScriptEngine.execute(
+            r#"({ success: regex_match("^\\d+$", context.response.content[0].text), id: uuid() })"#,
+            &context(ScriptConfig::new()),
+        );
+        assert_eq!(result.output["success"], json!(true));
+        assert_eq!(result.output["id"].as_str().unwrap().len(), 36);
+    }
+
+    #[test]
+    fn test_js_errors_are_classified() {
+        let result = JavaScriptEngine.execute("let x = ;", &context(ScriptConfig::new()));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::SyntaxError { .. })
+        ));
+
+        let result =
+            JavaScriptEngine.execute("throw new TypeError('boom')", &context(ScriptConfig::new()));
+        match result.error {
+            Some(ScriptError::RuntimeError { message }) => assert_eq!(message, "TypeError: boom"),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_js_timeout_and_memory_limit() {
+        let mut config = ScriptConfig::new();
+        config.timeout_ms = 50;
+        let result = JavaScriptEngine.execute("while (true) {}", &context(config));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::TimeoutError { timeout_ms: 50 })
+        ));
+
+        let mut config = ScriptConfig::new();
+        config.memory_limit_mb = Some(1);
+        let result = JavaScriptEngine.execute(
+            "const items = []; for (let i = 0; i < 1000000; i++) items.push('x'.repeat(64) + i);",
+            &context(config),
+        );
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::MemoryLimitError { limit_mb: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_js_has_no_host_access() {
+        let result = JavaScriptEngine.execute(
+            "[typeof require, typeof fetch, typeof std, typeof os]",
+            &context(ScriptConfig::permissive()),
+        );
+        assert_eq!(
+            result.output,
+            json!(["undefined", "undefined", "undefined", "undefined"])
+        );
+    }
+}
@@ -1,3 +1,390 @@
 //! Lua script execution engine
+//!
+//! Runs validation scripts on Lua 5.4 via `mlua`. Only the `coroutine`,
+//! `table`, `string`, `utf8` and `math` libraries are loaded; `io`, `os` and
+//! `package` (plus `dofile`/`loadfile`) are only available when filesystem
+//! access is allowed. Lua has no networking library, so nothing extra is
+//! exposed when network access is allowed.
 
-// PLANNED(#250): Lua engine implementation will be added in ScriptContext/ScriptResult issue
+use super::types::{ScriptConfig, ScriptContext, ScriptError, ScriptResult};
+use super::{utilities, ScriptEngine};
+use mlua::{HookTriggers, Lua, LuaOptions, StdLib, Value as LuaValue, Variadic};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// Chunk name reported in Lua error messages
+const CHUNK_NAME: &str = "validation";
+
+/// VM instructions between deadline checks
+const HOOK_INTERVAL: u32 = 1000;
+
+/// Maximum nesting depth when converting values between Lua and JSON
+const MAX_DEPTH: usize = 64;
+
+/// Lua validation script engine
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LuaEngine;
+
+impl ScriptEngine for LuaEngine {
+    fn execute(&self, source: &str, context: &ScriptContext) -> ScriptResult {
+        let start = Instant::now();
+        let logs = Rc::new(RefCell::new(Vec::new()));
+
+        match run(source, context, &logs, start) {
+            Ok((output, memory_used)) => utilities::finish(
+                output,
+                logs.take(),
+                Some(memory_used),
+                &context.config,
+                start,
+            ),
+            Err(error) => utilities::with_logs(
+                ScriptResult::failure(error, utilities::elapsed_ms(start)),
+                logs.take(),
+            ),
+        }
+    }
+}
+
+fn run(
+    source: &str,
+    context: &ScriptContext,
+    logs: &Rc<RefCell<Vec<String>>>,
+    start: Instant,
+) -> Result<(serde_json::Value, usize), ScriptError> {
+    let config = &context.config;
+    let lua = sandboxed_lua(config).map_err(runtime_error)?;
+
+    let deadline = start + utilities::timeout(config);
+    lua.set_hook(
+        HookTriggers::new().every_nth_instruction(HOOK_INTERVAL),
+        move |_, _| {
+            if Instant::now() > deadline {
+                Err(mlua::Error::runtime("script timed out"))
+            } else {
+                Ok(())
+            }
+        },
+    );
+
+    install_globals(&lua, context, logs)?;
+
+    let returned = lua
+        .load(source)
+        .set_name(CHUNK_NAME)
+        .eval::<LuaValue>()
+        .map_err(|e| classify_error(&lua, e, config, start))?;
+
+    let result: LuaValue = lua.globals().get("result").map_err(runtime_error)?;
+    let output = match result {
+        LuaValue::Nil => returned,
+        result => result,
+    };
+
+    Ok((to_json(&output, 0)?, lua.used_memory()))
+}
+
+/// Create a Lua state with only the libraries the configuration allows
+fn sandboxed_lua(config: &ScriptConfig) -> mlua::Result<Lua> {
+    let mut libs = StdLib::COROUTINE | StdLib::TABLE | StdLib::STRING | StdLib::UTF8 | StdLib::MATH;
+    if config.allow_filesystem {
+        libs |= StdLib::IO | StdLib::OS | StdLib::PACKAGE;
+    }
+
+    let lua = Lua::new_with(libs, LuaOptions::default())?;
+    if !config.allow_filesystem {
+        let globals = lua.globals();
+        globals.set("dofile", LuaValue::Nil)?;
+        globals.set("loadfile", LuaValue::Nil)?;
+    }
+    if let Some(limit) = utilities::memory_limit_bytes(config) {
+        lua.set_memory_limit(limit)?;
+    }
+
+    Ok(lua)
+}
+
+/// Expose `context`, a capturing `print` and the shared helper functions
+fn install_globals(
+    lua: &Lua,
+    context: &ScriptContext,
+    logs: &Rc<RefCell<Vec<String>>>,
+) -> Result<(), ScriptError> {
+    let globals = lua.globals();
+    let context = utilities::context_value(context)?;
+    globals
+        .set("context", to_lua(lua, &context, 0).map_err(runtime_error)?)
+        .map_err(runtime_error)?;
+
+    let logs = Rc::clone(logs);
+    let print = lua
+        .create_function(move |lua, values: Variadic<LuaValue>| {
+            let tostring: mlua::Function = lua.globals().get("tostring")?;
+            let line = values
+                .into_iter()
+                .map(|value| tostring.call::<_, String>(value))
+                .collect::<mlua::Result<Vec<_>>>()?
+                .join("\t");
+            logs.borrow_mut().push(line);
+            Ok(())
+        })
+        .map_err(runtime_error)?;
+    let regex_match = lua
+        .create_function(|_, (pattern, text): (String, String)| {
+            utilities::regex_match(&pattern, &text).map_err(mlua::Error::external)
+        })
+        .map_err(runtime_error)?;
+    let uuid = lua
+        .create_function(|_, ()| Ok(utilities::generate_uuid()))
+        .map_err(runtime_error)?;
+
+    globals.set("print", print).map_err(runtime_error)?;
+    globals
+        .set("regex_match", regex_match)
+        .map_err(runtime_error)?;
+    globals.set("uuid", uuid).map_err(runtime_error)
+}
+
+fn to_lua<'lua>(
+    lua: &'lua Lua,
+    value: &serde_json::Value,
+    depth: usize,
+) -> mlua::Result<LuaValue<'lua>> {
+    if depth > MAX_DEPTH {
+        return Err(mlua::Error::runtime("value is nested too deeply"));
+    }
+
+    Ok(match value {
+        serde_json::Value::Null => LuaValue::Nil,
+        serde_json::Value::Bool(b) => LuaValue::Boolean(*b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => LuaValue::Integer(i),
+            None => LuaValue::Number(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(s) => LuaValue::String(lua.create_string(s)?),
+        serde_json::Value::Array(items) => {
+            let table = lua.create_table_with_capacity(items.len(), 0)?;
+            for (index, item) in items.iter().enumerate() {
+                table.raw_set(index + 1, to_lua(lua, item, depth + 1)?)?;
+            }
+            LuaValue::Table(table)
+        }
+        serde_json::Value::Object(fields) => {
+            let table = lua.create_table_with_capacity(0, fields.len())?;
+            for (key, field) in fields {
+                table.raw_set(key.as_str(), to_lua(lua, field, depth + 1)?)?;
+            }
+            LuaValue::Table(table)
+        }
+    })
+}
+
+/// Convert a Lua value to JSON; sequences become arrays, other tables objects
+fn to_json(value: &LuaValue, depth: usize) -> Result<serde_json::Value, ScriptError> {
+    if depth > MAX_DEPTH {
+        return Err(ScriptError::SerializationError {
+            message: "Script result is nested too deeply".to_string(),
+        });
+    }
+
+    Ok(match value {
+        LuaValue::Nil => serde_json::Value::Null,
+        LuaValue::Boolean(b) => serde_json::Value::Bool(*b),
+        LuaValue::Integer(i) => serde_json::Value::from(*i),
+        LuaValue::Number(n) => serde_json::Number::from_f64(*n)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        LuaValue::String(s) => serde_json::Value::String(s.to_string_lossy().into_owned()),
+        LuaValue::Table(table) => {
+            let entries = table
+                .clone()
+                .pairs::<LuaValue, LuaValue>()
+                .collect::<mlua::Result<Vec<_>>>()
+                .map_err(runtime_error)?;
+            let length = table.raw_len();
+
+            if length > 0 && entries.len() == length {
+                let mut items = vec![serde_json::Value::Null; length];
+                for (key, item) in &entries {
+                    match key {
+                        LuaValue::Integer(i) if *i >= 1 && (*i as usize) <= length => {
+                            items[*i as usize - 1] = to_json(item, depth + 1)?;
+                        }
+                        _ => return object_from_entries(&entries, depth),
+                    }
+                }
+                serde_json::Value::Array(items)
+            } else {
+                return object_from_entries(&entries, depth);
+            }
+        }
+        _ => serde_json::Value::Null,
+    })
+}
+
+fn object_from_entries(
+    entries: &[(LuaValue, LuaValue)],
+    depth: usize,
+) -> Result<serde_json::Value, ScriptError> {
+    let mut object = serde_json::Map::with_capacity(entries.len());
+    for (key, item) in entries {
+        let key = match key {
+            LuaValue::String(s) => s.to_string_lossy().into_owned(),
+            LuaValue::Integer(i) => i.to_string(),
+            LuaValue::Number(n) => n.to_string(),
+            LuaValue::Boolean(b) => b.to_string(),
+            _ => continue,
+        };
+        object.insert(key, to_json(item, depth + 1)?);
+    }
+    Ok(serde_json::Value::Object(object))
+}
+
+/// Map an mlua error onto the script error it represents
+fn classify_error(
+    lua: &Lua,
+    error: mlua::Error,
+    config: &ScriptConfig,
+    start: Instant,
+) -> ScriptError {
+    if start.elapsed() >= utilities::timeout(config) {
+        return ScriptError::TimeoutError {
+            timeout_ms: config.timeout_ms,
+        };
+    }
+    if is_memory_error(&error) {
+        return ScriptError::MemoryLimitError {
+            used_mb: lua.used_memory() as f64 / (1024.0 * 1024.0),
+            limit_mb: config.memory_limit_mb.unwrap_or_default(),
+        };
+    }
+
+    match error {
+        mlua::Error::SyntaxError { message, .. } => ScriptError::SyntaxError {
+            line: utilities::line_number(&message, CHUNK_NAME),
+            message,
+        },
+        other => runtime_error(other),
+    }
+}
+
+fn is_memory_error(error: &mlua::Error) -> bool {
+    match error {
+        mlua::Error::MemoryError(_) => true,
+        mlua::Error::CallbackError { cause, .. } => is_memory_error(cause),
+        _ => false,
+    }
+}
+
+fn runtime_error(error: mlua::Error) -> ScriptError {
+    ScriptError::RuntimeError {
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context(config: ScriptConfig) -> ScriptContext {
+        ScriptContext::new(
+            json!({"method": "tools/call", "params": {"a": 5, "b": 3}}),
+            "add_integers".to_string(),
+            "add".to_string(),
+            config,
+        )
+        .with_response(json!({"content": [{"type": "text", "text": "8"}]}))
+    }
+
+    #[test]
+    fn test_lua_script_reads_context_and_sets_result() {
+        let result = LuaEngine.execute(
+            r#"
+            local params = context.request.params
+            local actual = tonumber(context.response.content[1].text)
+            print("sum", actual)
+            result = {
+                success = params.a + params.b == actual,
+                message = "checked " .. context.metadata.test_name,
+                ids = {1, 2, 3},
+            }
+            "#,
+            &context(ScriptConfig::new()),
+        );
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output["success"], json!(true));
+        assert_eq!(result.output["message"], json!("checked add_integers"));
+        assert_eq!(result.output["ids"], json!([1, 2, 3]));
+        assert_eq!(result.logs[0].message, "sum\t8");
+        assert!(result.memory_used_mb.is_some());
+        assert_eq!(utilities::validation_failure(&result), None);
+    }
+
+    #[test]
+    fn test_lua_return_value_and_helpers() {
+        let result = LuaEngine.execute(
+            r#"return { success = regex_match("^\\d+$", context.response.content[1].text), id = uuid() }"#,
+            &context(ScriptConfig::new()),
+        );
+        assert_eq!(result.output["success"], json!(true));
+        assert_eq!(result.output["id"].as_str().unwrap().len(), 36);
+    }
+
+    #[test]
+    fn test_lua_errors_are_classified() {
+        let result = LuaEngine.execute("local x = \n(", &context(ScriptConfig::new()));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::SyntaxError { line: 2, .. })
+        ));
+
+        let result = LuaEngine.execute("error('boom')", &context(ScriptConfig::new()));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::RuntimeError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_lua_timeout_and_memory_limit() {
+        let mut config = ScriptConfig::new();
+        config.timeout_ms = 50;
+        let result = LuaEngine.execute("while true do end", &context(config));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::TimeoutError { timeout_ms: 50 })
+        ));
+
+        let mut config = ScriptConfig::new();
+        config.memory_limit_mb = Some(1);
+        let result = LuaEngine.execute(
+            "local t = {} for i = 1, 1000000 do t[i] = string.rep('x', 64) .. i end",
+            &context(config),
+        );
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::MemoryLimitError { limit_mb: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_lua_sandbox_blocks_filesystem() {
+        let result = LuaEngine.execute(
+            "result = { io = io == nil, os = os == nil, dofile = dofile == nil, require = require == nil }",
+            &context(ScriptConfig::new()),
+        );
+        assert_eq!(
+            result.output,
+            json!({"io": true, "os": true, "dofile": true, "require": true})
+        );
+
+        let result = LuaEngine.execute(
+            "result = { io = io ~= nil }",
+            &context(ScriptConfig::permissive()),
+        );
+        assert_eq!(result.output, json!({"io": true}));
+    }
+}
@@ -3,7 +3,7 @@
 //! This module provides support for executing validation scripts in multiple languages:
 //! - Lua (via mlua)
 //! - JavaScript (via quickjs)
-//! - Python (via pyo3)
+//! - Python (via a sandboxed `python3` subprocess)
 //! - Regular expressions (via regex)
 //! - UUID generation (via uuid)
 //!
@@ -51,11 +51,122 @@ pub mod types;
 pub mod utilities;
 
 // Re-export core types for easier access
+pub use js_engine::JavaScriptEngine;
+pub use lua_engine::LuaEngine;
+pub use python_engine::PythonEngine;
 pub use types::{
     ContextMetadata, LogEntry, LogLevel, ScriptConfig, ScriptContext, ScriptError, ScriptResult,
     ServerInfo,
 };
 
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Grace period for an engine to stop itself before the caller gives up on it
+const TIMEOUT_GRACE: Duration = Duration::from_millis(250);
+
+/// Execution engine for one scripting language
+///
+/// Engines run a script synchronously and must enforce the timeout, memory
+/// limit and sandboxing settings of `context.config`. Scripts see a global
+/// `context` with `request`, `response` and `metadata`, and report their
+/// verdict through a global `result` (or the value of the last expression).
+pub trait ScriptEngine: Send + Sync {
+    /// Run `source` against `context`
+    fn execute(&self, source: &str, context: &ScriptContext) -> ScriptResult;
+}
+
+/// Languages validation scripts can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptLanguage {
+    Lua,
+    JavaScript,
+    Python,
+}
+
+impl ScriptLanguage {
+    /// Engine for this language
+    pub fn engine(self) -> Box<dyn ScriptEngine> {
+        match self {
+            Self::Lua => Box::new(LuaEngine),
+            Self::JavaScript => Box::new(JavaScriptEngine),
+            Self::Python => Box::new(PythonEngine),
+        }
+    }
+}
+
+impl FromStr for ScriptLanguage {
+    type Err = ScriptError;
+
+    fn from_str(language: &str) -> Result<Self, Self::Err> {
+        match language.to_ascii_lowercase().as_str() {
+            "lua" => Ok(Self::Lua),
+            "javascript" | "js" => Ok(Self::JavaScript),
+            "python" | "py" => Ok(Self::Python),
+            other => Err(ScriptError::ExecutionError {
+                message: format!("Unsupported script language: {}", other),
+            }),
+        }
+    }
+}
+
+/// When a validation script runs relative to the MCP request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptPhase {
+    /// Before the request is sent; `context.response` is null
+    Before,
+    /// After the response is received
+    After,
+}
+
+impl FromStr for ScriptPhase {
+    type Err = ScriptError;
+
+    fn from_str(phase: &str) -> Result<Self, Self::Err> {
+        match phase.to_ascii_lowercase().as_str() {
+            "before" => Ok(Self::Before),
+            "after" => Ok(Self::After),
+            other => Err(ScriptError::ExecutionError {
+                message: format!("Unsupported script execution phase: {}", other),
+            }),
+        }
+    }
+}
+
+/// Run a script on a blocking thread, bounded by the configured timeout
+///
+/// Engines interrupt runaway scripts themselves (the Python engine kills its
+/// interpreter process); if one does not stop within a short grace period the
+/// script is abandoned and a timeout is reported.
+pub async fn execute_script(
+    language: ScriptLanguage,
+    source: &str,
+    context: ScriptContext,
+) -> ScriptResult {
+    let start = Instant::now();
+    if let Err(error) = context.config.validate() {
+        return ScriptResult::failure(error, 0);
+    }
+
+    let timeout_ms = context.config.timeout_ms;
+    let source = source.to_string();
+    let task = tokio::task::spawn_blocking(move || language.engine().execute(&source, &context));
+
+    match tokio::time::timeout(Duration::from_millis(timeout_ms) + TIMEOUT_GRACE, task).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(e)) => ScriptResult::failure(
+            ScriptError::ExecutionError {
+                message: format!("Script engine panicked: {}", e),
+            },
+            utilities::elapsed_ms(start),
+        ),
+        Err(_) => ScriptResult::failure(
+            ScriptError::TimeoutError { timeout_ms },
+            utilities::elapsed_ms(start),
+        ),
+    }
+}
+
 #[cfg(test)]
 mod dependency_tests {
     use std::time::Instant;
@@ -1,3 +1,583 @@
 //! Python script execution engine
+//!
+//! Runs validation scripts in a separate `python3` process so a runaway or
+//! hostile script can be killed without affecting the harness. The child
+//! receives the script and context as one JSON line on stdin and replies on
+//! stdout; `regex_match` calls are answered by the engine so every language
+//! shares one regex syntax.
+//!
+//! Scripts run with restricted builtins: imports are limited to pure
+//! data-processing modules (exposed as copies of their public names),
+//! attributes starting with `_` and frame attributes are rejected before the
+//! script runs, `open` is replaced unless filesystem access is allowed, and
+//! networking modules can only be imported when network access is allowed.
+//! Before the script starts the child also applies OS resource limits: CPU
+//! time, address space, no child processes, no file writes without
+//! filesystem access, and no new file descriptors at all when neither
+//! filesystem nor network access is allowed. The timeout is enforced by
+//! killing the process, which also stops work inside C code.
 
-// PLANNED(#250): Python engine implementation will be added in ScriptContext/ScriptResult issue
+use super::types::{ScriptConfig, ScriptContext, ScriptError, ScriptResult};
+use super::{utilities, ScriptEngine};
+use serde::Deserialize;
+use serde_json::json;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Interpreter that runs the scripts
+const INTERPRETER: &str = "python3";
+
+/// Time the interpreter may take to start before the script's timeout applies
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most interpreter stderr kept for an error message
+const MAX_STDERR_BYTES: u64 = 4096;
+
+/// Python side of the engine: builds the sandbox and runs the script
+const HARNESS: &str = r#"import ast
+import builtins
+import json
+import sys
+import tracemalloc
+import types
+import uuid
+
+try:
+    import resource
+except ImportError:
+    resource = None
+
+ALLOWED_MODULES = frozenset({
+    "base64", "binascii", "collections", "copy", "datetime", "decimal", "difflib",
+    "fractions", "functools", "hashlib", "itertools", "json", "math", "numbers",
+    "operator", "re", "statistics", "string", "textwrap", "time", "unicodedata",
+})
+FILESYSTEM_MODULES = frozenset({"glob", "io", "os", "pathlib", "shutil", "tempfile"})
+NETWORK_MODULES = frozenset({"http", "socket", "ssl", "urllib"})
+# Public names that evaluate strings or look attributes up by name
+HIDDEN_ATTRIBUTES = {
+    "functools": frozenset({"singledispatch", "singledispatchmethod"}),
+    "operator": frozenset({"attrgetter", "methodcaller"}),
+    "string": frozenset({"Formatter"}),
+}
+# Frame and code objects lead back to the harness and its unrestricted globals
+BLOCKED_ATTRIBUTES = frozenset({
+    "ag_code", "ag_frame", "cr_code", "cr_frame", "f_back", "f_builtins", "f_code",
+    "f_globals", "f_locals", "gi_code", "gi_frame", "tb_frame", "tb_next",
+})
+REMOVED_BUILTINS = (
+    "breakpoint", "compile", "delattr", "dir", "eval", "exec", "exit", "getattr",
+    "globals", "help", "input", "locals", "memoryview", "quit", "setattr", "vars",
+)
+ADDRESS_SPACE_SLACK = 256 * 1024 * 1024
+CHECK_INTERVAL = 64
+
+
+class ScriptMemoryLimit(BaseException):
+    pass
+
+
+class SandboxViolation(Exception):
+    pass
+
+
+def send(message):
+    sys.stdout.write(json.dumps(message, default=str) + "\n")
+    sys.stdout.flush()
+
+
+def regex_match(pattern, text):
+    # Answered by the engine so specs get the same regex syntax in every language
+    send({"kind": "regex_match", "pattern": str(pattern), "text": str(text)})
+    reply = json.loads(sys.stdin.readline())
+    if "error" in reply:
+        raise ValueError(reply["error"])
+    return reply["value"]
+
+
+def new_uuid():
+    return str(uuid.uuid4())
+
+
+def check_source(tree):
+    for node in ast.walk(tree):
+        if isinstance(node, ast.Attribute):
+            names = [node.attr]
+        elif isinstance(node, ast.MatchClass):
+            names = node.kwd_attrs
+        elif isinstance(node, ast.Name) and node.id.startswith("__"):
+            raise SandboxViolation("access to " + node.id)
+        else:
+            continue
+        for name in names:
+            if name.startswith("_") or name in BLOCKED_ATTRIBUTES:
+                raise SandboxViolation("access to attribute " + name)
+
+
+def public_view(module, allowed, views):
+    # Scripts get a copy of the module's public names; submodules that are
+    # not allowed themselves (such as `sys` or `os`) are left out
+    name = module.__name__
+    if name not in views:
+        hidden = HIDDEN_ATTRIBUTES.get(name, ())
+        view = types.ModuleType(name)
+        views[name] = view
+        for attribute, value in vars(module).items():
+            if attribute.startswith("_") or attribute in hidden:
+                continue
+            if isinstance(value, types.ModuleType):
+                if value.__name__.partition(".")[0] not in allowed:
+                    continue
+                value = public_view(value, allowed, views)
+            setattr(view, attribute, value)
+    return views[name]
+
+
+def sandbox_builtins(logs, allowed, raw_modules, allow_filesystem):
+    views = {}
+
+    def guarded_import(name, globals=None, locals=None, fromlist=(), level=0):
+        if level != 0 or name.partition(".")[0] not in allowed:
+            raise SandboxViolation("import of " + name)
+        module = builtins.__import__(name, globals, locals, fromlist, level)
+        if module.__name__ in raw_modules:
+            return module
+        return public_view(module, allowed, views)
+
+    def denied_open(*args, **kwargs):
+        raise SandboxViolation("filesystem access")
+
+    def captured_print(*values, sep=" ", **kwargs):
+        logs.append(sep.join(str(value) for value in values))
+
+    scope = dict(vars(builtins))
+    for name in REMOVED_BUILTINS:
+        scope.pop(name, None)
+    scope["__import__"] = guarded_import
+    scope["print"] = captured_print
+    if not allow_filesystem:
+        scope["open"] = denied_open
+    return scope
+
+
+def address_space():
+    try:
+        with open("/proc/self/statm") as statm:
+            return int(statm.read().split()[0]) * resource.getpagesize()
+    except (OSError, ValueError):
+        return None
+
+
+def limit_resources(memory_limit, timeout_s, allow_filesystem, allow_network):
+    if resource is None:
+        return
+    cpu_seconds = int(timeout_s) + 1
+    resource.setrlimit(resource.RLIMIT_CPU, (cpu_seconds, cpu_seconds))
+    resource.setrlimit(resource.RLIMIT_NPROC, (0, 0))
+    if memory_limit is not None:
+        used = address_space()
+        if used is not None:
+            limit = used + memory_limit + ADDRESS_SPACE_SLACK
+            resource.setrlimit(resource.RLIMIT_AS, (limit, limit))
+    if not allow_filesystem:
+        resource.setrlimit(resource.RLIMIT_FSIZE, (0, 0))
+        if not allow_network:
+            # No new file descriptors: files, sockets and pipes all fail to open
+            resource.setrlimit(resource.RLIMIT_NOFILE, (3, 3))
+
+
+def run(request, logs):
+    allow_filesystem = request["allow_filesystem"]
+    allow_network = request["allow_network"]
+    memory_limit = request["memory_limit"]
+
+    raw_modules = set()
+    if allow_filesystem:
+        raw_modules |= FILESYSTEM_MODULES
+    if allow_network:
+        raw_modules |= NETWORK_MODULES
+    allowed = ALLOWED_MODULES | raw_modules
+    for name in sorted(allowed):
+        __import__(name)
+
+    tree = ast.parse(request["source"], "<validation>")
+    check_source(tree)
+    code = compile(tree, "<validation>", "exec")
+    scope = {
+        "__builtins__": sandbox_builtins(logs, allowed, raw_modules, allow_filesystem),
+        "__name__": "__validation__",
+        "context": request["context"],
+        "regex_match": regex_match,
+        "uuid": new_uuid,
+    }
+
+    limit_resources(memory_limit, request["timeout_s"], allow_filesystem, allow_network)
+    tracemalloc.start()
+    events = 0
+
+    def check(frame, event, arg):
+        nonlocal events
+        events += 1
+        if events % CHECK_INTERVAL == 0 and memory_limit is not None:
+            if tracemalloc.get_traced_memory()[0] > memory_limit:
+                raise ScriptMemoryLimit()
+        return check
+
+    send({"kind": "started"})
+    sys.settrace(check)
+    try:
+        exec(code, scope)
+    finally:
+        sys.settrace(None)
+        peak = tracemalloc.get_traced_memory()[1]
+        tracemalloc.stop()
+
+    return scope.get("result"), peak
+
+
+def main():
+    request = json.loads(sys.stdin.readline())
+    logs = []
+    try:
+        result, memory_used = run(request, logs)
+        send({"kind": "done", "result": result, "logs": logs, "memory_used": memory_used})
+    except BaseException as error:
+        syntax = isinstance(error, SyntaxError)
+        send({
+            "kind": "failed",
+            "error_type": type(error).__name__,
+            "message": error.msg if syntax else str(error),
+            "line": error.lineno if syntax else None,
+            "logs": logs,
+        })
+
+
+main()
+"#;
+
+/// Python validation script engine
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PythonEngine;
+
+/// Message from the harness process
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Message {
+    Started,
+    RegexMatch {
+        pattern: String,
+        text: String,
+    },
+    Done {
+        result: serde_json::Value,
+        logs: Vec<String>,
+        memory_used: Option<usize>,
+    },
+    Failed {
+        error_type: String,
+        message: String,
+        line: Option<u32>,
+        logs: Vec<String>,
+    },
+}
+
+impl ScriptEngine for PythonEngine {
+    fn execute(&self, source: &str, context: &ScriptContext) -> ScriptResult {
+        let start = Instant::now();
+
+        match run(source, context) {
+            Ok(Message::Done {
+                result,
+                logs,
+                memory_used,
+            }) => utilities::finish(result, logs, memory_used, &context.config, start),
+            Ok(Message::Failed {
+                error_type,
+                message,
+                line,
+                logs,
+            }) => utilities::with_logs(
+                ScriptResult::failure(
+                    classify_error(&error_type, message, line, &context.config),
+                    utilities::elapsed_ms(start),
+                ),
+                logs,
+            ),
+            Ok(Message::Started | Message::RegexMatch { .. }) => {
+                unreachable!("run only returns the final message")
+            }
+            Err(error) => ScriptResult::failure(error, utilities::elapsed_ms(start)),
+        }
+    }
+}
+
+/// Run the script in a fresh interpreter and wait for its final message
+fn run(source: &str, context: &ScriptContext) -> Result<Message, ScriptError> {
+    let config = &context.config;
+    let request = json!({
+        "source": source,
+        "context": utilities::context_value(context)?,
+        "timeout_s": config.timeout_ms as f64 / 1000.0,
+        "memory_limit": utilities::memory_limit_bytes(config),
+        "allow_filesystem": config.allow_filesystem,
+        "allow_network": config.allow_network,
+    });
+
+    let mut child = Command::new(INTERPRETER)
+        .args(["-I", "-S", "-c", HARNESS])
+        .current_dir(std::env::temp_dir())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| ScriptError::ExecutionError {
+            message: format!("Failed to start {}: {}", INTERPRETER, e),
+        })?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let stdout = child.stdout.take().expect("stdout is piped");
+    if let Err(e) = writeln!(stdin, "{}", request) {
+        stop(&mut child);
+        return Err(ScriptError::ExecutionError {
+            message: format!("Failed to send the script to {}: {}", INTERPRETER, e),
+        });
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            match serde_json::from_str(&line) {
+                Ok(Message::RegexMatch { pattern, text }) => {
+                    let reply = match utilities::regex_match(&pattern, &text) {
+                        Ok(matched) => json!({ "value": matched }),
+                        Err(e) => json!({ "error": e.to_string() }),
+                    };
+                    if writeln!(stdin, "{}", reply).is_err() {
+                        break;
+                    }
+                }
+                Ok(Message::Started) => {
+                    let _ = sender.send(Ok(Message::Started));
+                }
+                Ok(message) => {
+                    let _ = sender.send(Ok(message));
+                    break;
+                }
+                Err(e) => {
+                    let _ = sender.send(Err(ScriptError::SerializationError {
+                        message: e.to_string(),
+                    }));
+                    break;
+                }
+            }
+        }
+    });
+
+    // The timeout covers the script, not interpreter startup
+    let outcome = match receiver.recv_timeout(STARTUP_TIMEOUT) {
+        Ok(Ok(Message::Started)) => receiver.recv_timeout(utilities::timeout(config)),
+        other => other,
+    };
+    // Never leave the interpreter running, whether it finished or not
+    stop(&mut child);
+
+    match outcome {
+        Ok(Ok(Message::Started | Message::RegexMatch { .. })) => Err(ScriptError::ExecutionError {
+            message: format!("{} sent an unexpected message", INTERPRETER),
+        }),
+        Ok(message) => message,
+        Err(RecvTimeoutError::Timeout) => Err(ScriptError::TimeoutError {
+            timeout_ms: config.timeout_ms,
+        }),
+        Err(RecvTimeoutError::Disconnected) => {
+            let mut stderr = String::new();
+            if let Some(pipe) = child.stderr.take() {
+                let _ = pipe.take(MAX_STDERR_BYTES).read_to_string(&mut stderr);
+            }
+            Err(ScriptError::ExecutionError {
+                message: format!("{} exited without a result: {}", INTERPRETER, stderr.trim()),
+            })
+        }
+    }
+}
+
+fn stop(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Map a Python exception onto the script error it represents
+fn classify_error(
+    type_name: &str,
+    message: String,
+    line: Option<u32>,
+    config: &ScriptConfig,
+) -> ScriptError {
+    match type_name {
+        "ScriptMemoryLimit" | "MemoryError" => ScriptError::MemoryLimitError {
+            used_mb: config.memory_limit_mb.unwrap_or_default() as f64,
+            limit_mb: config.memory_limit_mb.unwrap_or_default(),
+        },
+        "SandboxViolation" => ScriptError::SecurityError { operation: message },
+        "SyntaxError" | "IndentationError" | "TabError" => ScriptError::SyntaxError {
+            message,
+            line: line.unwrap_or(0),
+        },
+        _ => ScriptError::RuntimeError {
+            message: format!("{}: {}", type_name, message),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn context(config: ScriptConfig) -> ScriptContext {
+        ScriptContext::new(
+            json!({"method": "tools/call", "params": {"a": 5, "b": 3}}),
+            "add_integers".to_string(),
+            "add".to_string(),
+            config,
+        )
+        .with_response(json!({"content": [{"type": "text", "text": "8"}]}))
+    }
+
+    #[test]
+    fn test_python_script_reads_context_and_sets_result() {
+        let result = PythonEngine.execute(
+            r#"
+import math
+params = context["request"]["params"]
+actual = int(context["response"]["content"][0]["text"])
+print("sum", actual)
+result = {
+    "success": math.isclose(params["a"] + params["b"], actual),
+    "message": "checked " + context["metadata"]["test_name"],
+    "matches": regex_match(r"^\d+$", str(actual)),
+    "id": uuid(),
+}
+"#,
+            &context(ScriptConfig::new()),
+        );
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output["success"], json!(true));
+        assert_eq!(result.output["message"], json!("checked add_integers"));
+        assert_eq!(result.output["matches"], json!(true));
+        assert_eq!(result.output["id"].as_str().unwrap().len(), 36);
+        assert_eq!(result.logs[0].message, "sum 8");
+        assert!(result.memory_used_mb.is_some());
+    }
+
+    #[test]
+    fn test_python_errors_are_classified() {
+        let result = PythonEngine.execute("x = 1\nif x\n", &context(ScriptConfig::new()));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::SyntaxError { line: 2, .. })
+        ));
+
+        let result = PythonEngine.execute("raise KeyError('boom')", &context(ScriptConfig::new()));
+        match result.error {
+            Some(ScriptError::RuntimeError { message }) => assert!(message.starts_with("KeyError")),
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_python_timeout_and_memory_limit() {
+        let mut config = ScriptConfig::new();
+        config.timeout_ms = 50;
+        let result = PythonEngine.execute("while True:\n    pass\n", &context(config));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::TimeoutError { timeout_ms: 50 })
+        ));
+
+        let mut config = ScriptConfig::new();
+        config.memory_limit_mb = Some(1);
+        let result = PythonEngine.execute(
+            "items = []\nfor i in range(1000000):\n    items.append('x' * 64 + str(i))\n",
+            &context(config),
+        );
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::MemoryLimitError { limit_mb: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_python_sandbox_blocks_filesystem_and_network() {
+        for source in ["import os", "import socket", "open('/etc/hostname')"] {
+            let result = PythonEngine.execute(source, &context(ScriptConfig::new()));
+            assert!(
+                matches!(result.error, Some(ScriptError::SecurityError { .. })),
+                "{} was not blocked: {:?}",
+                source,
+                result.error
+            );
+        }
+
+        let result = PythonEngine.execute(
+            "import os, socket\nresult = os.sep",
+            &context(ScriptConfig::permissive()),
+        );
+        assert_eq!(result.output, json!("/"));
+    }
+
+    #[test]
+    fn test_python_sandbox_blocks_escapes_through_allowed_modules() {
+        for source in [
+            "import collections\nresult = collections._sys.modules['os'].listdir('/')",
+            "result = ().__class__.__mro__[1].__subclasses__()",
+            "def frames():\n    yield gen.gi_frame.f_back\ngen = frames()\nresult = next(gen)",
+            "result = getattr(len, '__self__')",
+        ] {
+            let result = PythonEngine.execute(source, &context(ScriptConfig::new()));
+            assert!(!result.success, "{} escaped the sandbox", source);
+            assert!(
+                matches!(
+                    result.error,
+                    Some(ScriptError::SecurityError { .. } | ScriptError::RuntimeError { .. })
+                ),
+                "{}: {:?}",
+                source,
+                result.error
+            );
+        }
+
+        let result = PythonEngine.execute(
+            "import re\nresult = re.functools.sys.modules",
+            &context(ScriptConfig::new()),
+        );
+        match result.error {
+            Some(ScriptError::RuntimeError { message }) => {
+                assert!(message.starts_with("AttributeError"), "{}", message)
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_python_timeout_interrupts_native_code() {
+        let mut config = ScriptConfig::new();
+        config.timeout_ms = 200;
+        let start = Instant::now();
+        let result = PythonEngine.execute("x = 10**10**8", &context(config));
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::TimeoutError { timeout_ms: 200 })
+        ));
+        assert!(start.elapsed() < STARTUP_TIMEOUT);
+
+        // The killed interpreter leaves nothing behind for the next script
+        let result = PythonEngine.execute("result = 1 + 1", &context(ScriptConfig::new()));
+        assert_eq!(result.output, json!(2));
+    }
+}
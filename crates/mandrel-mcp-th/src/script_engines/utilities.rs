@@ -1,3 +1,231 @@
 //! Utilities for script execution (regex, uuid, etc.)
+//!
+//! Helpers shared by the Lua, JavaScript and Python engines: deadlines,
+//! building a [`ScriptResult`] from a finished script, and interpreting the
+//! `result` value a validation script produces.
 
-// PLANNED(#250): Utility functions will be added in ScriptContext/ScriptResult issue
+use super::types::{LogLevel, ScriptConfig, ScriptContext, ScriptError, ScriptResult};
+use std::time::{Duration, Instant};
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// Script timeout from the configuration
+pub fn timeout(config: &ScriptConfig) -> Duration {
+    Duration::from_millis(config.timeout_ms)
+}
+
+/// Memory limit in bytes from the configuration
+pub fn memory_limit_bytes(config: &ScriptConfig) -> Option<usize> {
+    config
+        .memory_limit_mb
+        .map(|limit| (limit as usize).saturating_mul(1024 * 1024))
+}
+
+/// Milliseconds elapsed since `start`
+pub fn elapsed_ms(start: Instant) -> u64 {
+    start.elapsed().as_millis() as u64
+}
+
+/// Data exposed to scripts as the global `context`
+///
+/// Contains `request`, `response` (null in the "before" phase) and `metadata`.
+pub fn context_value(context: &ScriptContext) -> Result<serde_json::Value, ScriptError> {
+    serde_json::to_value(context)
+        .map(|mut value| {
+            if let Some(object) = value.as_object_mut() {
+                // Scripts see the data they validate, not the engine settings
+                object.remove("config");
+            }
+            value
+        })
+        .map_err(|e| ScriptError::SerializationError {
+            message: e.to_string(),
+        })
+}
+
+/// Build the result of a script that ran to completion
+///
+/// Fails the run if the output exceeds `max_output_size` or the measured
+/// memory exceeds the configured limit.
+pub fn finish(
+    output: serde_json::Value,
+    logs: Vec<String>,
+    memory_used_bytes: Option<usize>,
+    config: &ScriptConfig,
+    start: Instant,
+) -> ScriptResult {
+    let duration_ms = elapsed_ms(start);
+
+    if let (Some(used), Some(limit)) = (memory_used_bytes, config.memory_limit_mb) {
+        let used_mb = used as f64 / BYTES_PER_MB;
+        if used_mb > limit as f64 {
+            return with_logs(
+                ScriptResult::failure(
+                    ScriptError::MemoryLimitError {
+                        used_mb,
+                        limit_mb: limit,
+                    },
+                    duration_ms,
+                ),
+                logs,
+            );
+        }
+    }
+
+    let output_size = output.to_string().len();
+    if output_size > config.max_output_size {
+        return with_logs(
+            ScriptResult::failure(
+                ScriptError::ExecutionError {
+                    message: format!(
+                        "Script output of {} bytes exceeds the {} byte limit",
+                        output_size, config.max_output_size
+                    ),
+                },
+                duration_ms,
+            ),
+            logs,
+        );
+    }
+
+    let result = with_logs(ScriptResult::success(output, duration_ms), logs);
+    match memory_used_bytes {
+        Some(used) => result.with_memory_usage(used as f64 / BYTES_PER_MB),
+        None => result,
+    }
+}
+
+/// Attach captured script output as log entries
+pub fn with_logs(result: ScriptResult, logs: Vec<String>) -> ScriptResult {
+    logs.into_iter()
+        .fold(result, |result, line| result.add_log(LogLevel::Info, line))
+}
+
+/// Reason a validation script rejected the response, if it did
+///
+/// A script fails validation when it could not run, or when its `result` is
+/// an object with `success = false`; the object's `message` is used as the
+/// reason when present.
+pub fn validation_failure(result: &ScriptResult) -> Option<String> {
+    if let Some(error) = &result.error {
+        return Some(error.to_string());
+    }
+    if !result.success {
+        return Some("Script execution failed".to_string());
+    }
+
+    match result.output.get("success") {
+        Some(serde_json::Value::Bool(false)) => Some(
+            result
+                .output
+                .get("message")
+                .and_then(|message| message.as_str())
+                .unwrap_or("Script reported failure")
+                .to_string(),
+        ),
+        _ => None,
+    }
+}
+
+/// Parse the line number from an engine error message such as `chunk:3: ...`
+pub fn line_number(message: &str, chunk_name: &str) -> u32 {
+    message
+        .split(chunk_name)
+        .nth(1)
+        .and_then(|rest| {
+            rest.trim_start_matches([':', '"', ']'])
+                .split(|c: char| !c.is_ascii_digit())
+                .next()
+        })
+        .and_then(|digits| digits.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Validate that `pattern` matches `text`
+///
+/// Exposed to scripts as `regex_match(pattern, text)`; each language's native
+/// patterns differ, this gives specs one portable syntax.
+pub fn regex_match(pattern: &str, text: &str) -> Result<bool, ScriptError> {
+    regex::Regex::new(pattern)
+        .map(|re| re.is_match(text))
+        .map_err(|e| ScriptError::RuntimeError {
+            message: format!("Invalid regex '{}': {}", pattern, e),
+        })
+}
+
+/// Generate a random UUID string, exposed to scripts as `uuid()`
+pub fn generate_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validation_failure_from_result_object() {
+        let passed = ScriptResult::success(json!({"success": true}), 1);
+        assert_eq!(validation_failure(&passed), None);
+
+        let no_verdict = ScriptResult::success(json!(null), 1);
+        assert_eq!(validation_failure(&no_verdict), None);
+
+        let rejected = ScriptResult::success(json!({"success": false, "message": "bad sum"}), 1);
+        assert_eq!(validation_failure(&rejected).as_deref(), Some("bad sum"));
+
+        let crashed = ScriptResult::failure(ScriptError::TimeoutError { timeout_ms: 10 }, 10);
+        assert!(validation_failure(&crashed).unwrap().contains("10ms"));
+    }
+
+    #[test]
+    fn test_finish_enforces_limits() {
+        let mut config = ScriptConfig::new();
+        config.max_output_size = 8;
+        let result = finish(
+            json!({"a": "long output"}),
+            vec![],
+            None,
+            &config,
+            Instant::now(),
+        );
+        assert!(!result.success);
+
+        let config = ScriptConfig::new();
+        let result = finish(
+            json!(true),
+            vec!["hello".to_string()],
+            Some(200 * 1024 * 1024),
+            &config,
+            Instant::now(),
+        );
+        assert!(matches!(
+            result.error,
+            Some(ScriptError::MemoryLimitError { limit_mb: 100, .. })
+        ));
+        assert_eq!(result.logs.len(), 1);
+
+        let result = finish(
+            json!(true),
+            vec![],
+            Some(1024 * 1024),
+            &config,
+            Instant::now(),
+        );
+        assert!(result.success);
+        assert_eq!(result.memory_used_mb, Some(1.0));
+    }
+
+    #[test]
+    fn test_line_number_and_utilities() {
+        assert_eq!(
+            line_number("[string \"validation\"]:3: unexpected", "validation"),
+            3
+        );
+        assert_eq!(line_number("no location", "validation"), 0);
+
+        assert!(regex_match(r"^\d+$", "42").unwrap());
+        assert!(regex_match("[", "x").is_err());
+        assert_eq!(generate_uuid().len(), 36);
+    }
+}
@@ -0,0 +1,366 @@
+//! Snapshot (golden file) assertions
+//!
+//! A test with `expected.snapshot: <name>` compares its whole response,
+//! normalized by [`Redactor`], with `__snapshots__/<name>.snap` next to the
+//! specification. Files use the `insta` layout: a short YAML header followed
+//! by the pretty-printed JSON value.
+//!
+//! As with `insta`, a missing or different snapshot fails the test and the
+//! new value is written beside it as `<name>.snap.new` for review. Running
+//! with `--update-snapshots` writes the `.snap` files instead.
+
+use crate::cassette::draft::child_path;
+use crate::error::{Error, Result};
+use crate::spec::Redaction;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub mod redact;
+
+pub use redact::Redactor;
+
+/// Directory holding the snapshots of a specification
+pub const SNAPSHOT_DIR: &str = "__snapshots__";
+
+/// How a value differs from its snapshot at one path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// Present in the response only
+    Added,
+    /// Present in the snapshot only
+    Removed,
+    Changed,
+}
+
+/// One difference between a snapshot and a response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotChange {
+    /// JSONPath of the differing value
+    pub path: String,
+    pub kind: ChangeKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Value>,
+}
+
+impl fmt::Display for SnapshotChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |value: &Option<Value>| value.as_ref().map(Value::to_string).unwrap_or_default();
+        match self.kind {
+            ChangeKind::Added => write!(f, "+ {}: {}", self.path, show(&self.actual)),
+            ChangeKind::Removed => write!(f, "- {}: {}", self.path, show(&self.expected)),
+            ChangeKind::Changed => write!(
+                f,
+                "~ {}: {} -> {}",
+                self.path,
+                show(&self.expected),
+                show(&self.actual)
+            ),
+        }
+    }
+}
+
+/// Structured diff of a snapshot that did not match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub snapshot: String,
+    /// The `.snap` file compared against
+    pub file: PathBuf,
+    pub changes: Vec<SnapshotChange>,
+}
+
+impl SnapshotDiff {
+    /// One-line description, e.g. `3 changes, first ~ $.count: 1 -> 2`
+    pub fn summary(&self) -> String {
+        match self.changes.first() {
+            Some(first) => format!(
+                "{} change{}, first {}",
+                self.changes.len(),
+                if self.changes.len() == 1 { "" } else { "s" },
+                first
+            ),
+            None => "no changes".to_string(),
+        }
+    }
+}
+
+/// Result of comparing a response with its snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotOutcome {
+    Matched,
+    /// The snapshot was created or updated
+    Written(PathBuf),
+    /// No snapshot exists yet; the response was saved as `.snap.new`
+    Missing(PathBuf),
+    Mismatch(SnapshotDiff),
+}
+
+/// Reads, compares and writes the snapshots of one specification
+#[derive(Debug, Clone)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+    /// Specification recorded in the file headers
+    source: Option<String>,
+    update: bool,
+    redactor: Redactor,
+}
+
+impl SnapshotStore {
+    /// Store in `dir`, redacting the current, home and temp directories
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let mut redactor = Redactor::new().with_root(&std::env::temp_dir(), "[tmp]");
+        if let Some(home) = dirs::home_dir() {
+            redactor = redactor.with_root(&home, "[home]");
+        }
+        if let Ok(cwd) = std::env::current_dir() {
+            redactor = redactor.with_root(&cwd, "[cwd]");
+        }
+        Self {
+            dir: dir.into(),
+            source: None,
+            update: false,
+            redactor,
+        }
+    }
+
+    /// Store for the specification at `spec_path`, whose directory is also redacted
+    pub fn for_specification(spec_path: &Path) -> Self {
+        let spec_dir = spec_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let mut store = Self::new(spec_dir.join(SNAPSHOT_DIR));
+        if let Ok(absolute) = spec_dir.canonicalize() {
+            store.redactor = store.redactor.with_root(&absolute, "[spec_dir]");
+        }
+        store.source = Some(spec_path.display().to_string());
+        store
+    }
+
+    /// Write snapshots instead of failing on differences
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// The `.snap` file for `name`
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.snap", name))
+    }
+
+    /// Normalize `response` and compare it with snapshot `name`
+    pub async fn check(
+        &self,
+        name: &str,
+        test_name: &str,
+        response: &Value,
+        redactions: &[Redaction],
+    ) -> Result<SnapshotOutcome> {
+        let actual = self.redactor.normalize(response, redactions)?;
+        let path = self.path_for(name);
+        let pending = path.with_extension("snap.new");
+
+        let expected = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => Some(parse_snapshot(&content).map_err(|e| {
+                Error::validation(format!("Corrupt snapshot {}: {}", path.display(), e))
+            })?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        let changes = expected
+            .as_ref()
+            .map(|expected| diff(expected, &actual))
+            .unwrap_or_default();
+
+        if expected.is_some() && changes.is_empty() {
+            remove_if_present(&pending).await?;
+            return Ok(SnapshotOutcome::Matched);
+        }
+
+        let content = self.render(test_name, &actual)?;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        if self.update {
+            tokio::fs::write(&path, content).await?;
+            remove_if_present(&pending).await?;
+            return Ok(SnapshotOutcome::Written(path));
+        }
+
+        tokio::fs::write(&pending, content).await?;
+        Ok(match expected {
+            None => SnapshotOutcome::Missing(path),
+            Some(_) => SnapshotOutcome::Mismatch(SnapshotDiff {
+                snapshot: name.to_string(),
+                file: path,
+                changes,
+            }),
+        })
+    }
+
+    fn render(&self, test_name: &str, value: &Value) -> Result<String> {
+        let mut content = String::from("---\n");
+        if let Some(source) = &self.source {
+            content.push_str(&format!("source: {}\n", source));
+        }
+        content.push_str(&format!("test: {}\n---\n", test_name));
+        content.push_str(&serde_json::to_string_pretty(value)?);
+        content.push('\n');
+        Ok(content)
+    }
+}
+
+async fn remove_if_present(path: &Path) -> Result<()> {
+    match tokio::fs::remove_file(path).await {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// The value stored in a snapshot file, after its header if it has one
+fn parse_snapshot(content: &str) -> serde_json::Result<Value> {
+    let body = content
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n"))
+        .map_or(content, |(_, body)| body);
+    serde_json::from_str(body)
+}
+
+/// Differences between `expected` and `actual`, in document order
+pub fn diff(expected: &Value, actual: &Value) -> Vec<SnapshotChange> {
+    let mut changes = Vec::new();
+    collect_changes(expected, actual, "$".to_string(), &mut changes);
+    changes
+}
+
+fn collect_changes(
+    expected: &Value,
+    actual: &Value,
+    path: String,
+    changes: &mut Vec<SnapshotChange>,
+) {
+    let change =
+        |path: String, kind, expected: Option<&Value>, actual: Option<&Value>| SnapshotChange {
+            path,
+            kind,
+            expected: expected.cloned(),
+            actual: actual.cloned(),
+        };
+
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, value) in expected {
+                let path = child_path(&path, key);
+                match actual.get(key) {
+                    Some(other) => collect_changes(value, other, path, changes),
+                    None => changes.push(change(path, ChangeKind::Removed, Some(value), None)),
+                }
+            }
+            for (key, value) in actual {
+                if !expected.contains_key(key) {
+                    let path = child_path(&path, key);
+                    changes.push(change(path, ChangeKind::Added, None, Some(value)));
+                }
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for index in 0..expected.len().max(actual.len()) {
+                let path = format!("{}[{}]", path, index);
+                match (expected.get(index), actual.get(index)) {
+                    (Some(e), Some(a)) => collect_changes(e, a, path, changes),
+                    (Some(e), None) => {
+                        changes.push(change(path, ChangeKind::Removed, Some(e), None))
+                    }
+                    (None, a) => changes.push(change(path, ChangeKind::Added, None, a)),
+                }
+            }
+        }
+        (expected, actual) if expected != actual => changes.push(change(
+            path,
+            ChangeKind::Changed,
+            Some(expected),
+            Some(actual),
+        )),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(count: u64) -> Value {
+        json!({"content": [{"type": "text", "text": format!("{{\"count\": {}, \"took_ms\": 5}}", count)}]})
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = SnapshotStore::new(dir.path().join(SNAPSHOT_DIR));
+        let file = store.path_for("stats");
+        let pending = file.with_extension("snap.new");
+
+        // A missing snapshot fails and leaves the value for review
+        let outcome = store.check("stats", "t", &response(1), &[]).await.unwrap();
+        assert_eq!(outcome, SnapshotOutcome::Missing(file.clone()));
+        assert!(pending.exists());
+
+        let updating = store.clone().with_update(true);
+        let outcome = updating
+            .check("stats", "t", &response(1), &[])
+            .await
+            .unwrap();
+        assert_eq!(outcome, SnapshotOutcome::Written(file.clone()));
+        assert!(!pending.exists());
+        let content = std::fs::read_to_string(&file).unwrap();
+        assert!(content.starts_with("---\ntest: t\n---\n"));
+        assert!(content.contains("\"took_ms\": \"[duration]\""));
+
+        let outcome = store.check("stats", "t", &response(1), &[]).await.unwrap();
+        assert_eq!(outcome, SnapshotOutcome::Matched);
+
+        let SnapshotOutcome::Mismatch(diff) =
+            store.check("stats", "t", &response(2), &[]).await.unwrap()
+        else {
+            panic!("expected a mismatch");
+        };
+        assert_eq!(diff.file, file);
+        assert_eq!(
+            diff.changes,
+            vec![SnapshotChange {
+                path: "$.content[0].text.count".to_string(),
+                kind: ChangeKind::Changed,
+                expected: Some(json!(1)),
+                actual: Some(json!(2)),
+            }]
+        );
+        assert_eq!(
+            diff.summary(),
+            "1 change, first ~ $.content[0].text.count: 1 -> 2"
+        );
+        assert!(pending.exists());
+    }
+
+    #[test]
+    fn test_diff_reports_added_and_removed_values() {
+        let changes = diff(
+            &json!({"a": 1, "list": [1, 2], "gone": true}),
+            &json!({"a": 1, "list": [1], "new-key": null}),
+        );
+        let rendered: Vec<_> = changes.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered,
+            vec!["- $.list[1]: 2", "- $.gone: true", "+ $['new-key']: null"]
+        );
+    }
+
+    #[test]
+    fn test_parse_snapshot_with_and_without_header() {
+        let with_header = "---\nsource: spec.yaml\ntest: t\n---\n{\"a\": 1}\n";
+        assert_eq!(parse_snapshot(with_header).unwrap(), json!({"a": 1}));
+        assert_eq!(parse_snapshot("[1]").unwrap(), json!([1]));
+    }
+}
@@ -0,0 +1,279 @@
+//! Response normalization for snapshots
+//!
+//! Before a response is compared with its golden file, text content that
+//! holds JSON is parsed so the snapshot records structure rather than one
+//! long string, and values that change between runs are replaced with
+//! placeholders. Built-in rules cover timestamps, durations and the paths
+//! of the machine the suite runs on; a test's `redactions` add to them.
+
+use crate::error::{Error, Result};
+use crate::spec::Redaction;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::path::Path;
+use std::sync::OnceLock;
+
+pub const TIMESTAMP: &str = "[timestamp]";
+pub const DURATION: &str = "[duration]";
+
+fn timestamp_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?")
+            .expect("valid timestamp pattern")
+    })
+}
+
+fn duration_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\b\d+(?:\.\d+)?\s?(?:ns|µs|us|ms)\b|\b\d+\.\d+\s?s\b")
+            .expect("valid duration pattern")
+    })
+}
+
+/// Placeholder for a number stored under `key`, judged by the key's name
+fn numeric_placeholder(key: &str) -> Option<&'static str> {
+    let key = key.to_ascii_lowercase();
+    if key == "timestamp" || key.ends_with("_at") || key.ends_with("timestamp") {
+        Some(TIMESTAMP)
+    } else if ["duration", "elapsed", "latency", "took"]
+        .iter()
+        .any(|word| key.contains(word))
+        || key == "ms"
+        || key.ends_with("_ms")
+        || key.ends_with("_time")
+    {
+        Some(DURATION)
+    } else {
+        None
+    }
+}
+
+/// Applies the built-in and a test's own redactions
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Directories replaced by placeholders, longest first
+    roots: Vec<(String, String)>,
+}
+
+impl Redactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace `root` and anything below it with `placeholder` in strings
+    pub fn with_root(mut self, root: &Path, placeholder: impl Into<String>) -> Self {
+        let root = root
+            .to_string_lossy()
+            .trim_end_matches(['/', '\\'])
+            .to_string();
+        // "/" would swallow every path
+        if !root.is_empty() {
+            self.roots.push((root, placeholder.into()));
+            self.roots
+                .sort_by_key(|(root, _)| std::cmp::Reverse(root.len()));
+        }
+        self
+    }
+
+    /// Normalized copy of `response`
+    pub fn normalize(&self, response: &Value, rules: &[Redaction]) -> Result<Value> {
+        let mut value = parse_text_content(response.clone());
+
+        let mut patterns = Vec::new();
+        for rule in rules {
+            match (&rule.path, &rule.pattern) {
+                (Some(path), None) => {
+                    let replacement = Value::String(rule.replacement.clone());
+                    value =
+                        jsonpath_lib::replace_with(value, path, &mut |_| Some(replacement.clone()))
+                            .map_err(|e| {
+                                Error::validation(format!(
+                                    "Invalid redaction path '{}': {:?}",
+                                    path, e
+                                ))
+                            })?;
+                }
+                (None, Some(pattern)) => {
+                    let regex = Regex::new(pattern).map_err(|e| {
+                        Error::validation(format!("Invalid redaction pattern '{}': {}", pattern, e))
+                    })?;
+                    patterns.push((regex, rule.replacement.as_str()));
+                }
+                _ => {
+                    return Err(Error::validation(
+                        "A redaction needs exactly one of 'path' and 'pattern'",
+                    ))
+                }
+            }
+        }
+
+        Ok(self.redact(value, None, &patterns))
+    }
+
+    fn redact(&self, value: Value, key: Option<&str>, patterns: &[(Regex, &str)]) -> Value {
+        match value {
+            Value::Object(object) => {
+                // Sorted so snapshots do not depend on the server's key order
+                let mut entries: Vec<_> = object.into_iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                let object: Map<_, _> = entries
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let v = self.redact(v, Some(&k), patterns);
+                        (k, v)
+                    })
+                    .collect();
+                Value::Object(object)
+            }
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .map(|item| self.redact(item, None, patterns))
+                    .collect(),
+            ),
+            Value::String(text) => Value::String(self.redact_text(&text, patterns)),
+            Value::Number(number) => match key.and_then(numeric_placeholder) {
+                Some(placeholder) => Value::String(placeholder.to_string()),
+                None => Value::Number(number),
+            },
+            other => other,
+        }
+    }
+
+    fn redact_text(&self, text: &str, patterns: &[(Regex, &str)]) -> String {
+        let mut text = text.to_string();
+        for (regex, replacement) in patterns {
+            text = regex.replace_all(&text, *replacement).into_owned();
+        }
+        text = timestamp_pattern()
+            .replace_all(&text, TIMESTAMP)
+            .into_owned();
+        text = duration_pattern().replace_all(&text, DURATION).into_owned();
+        for (root, placeholder) in &self.roots {
+            text = replace_root(&text, root, placeholder);
+        }
+        text
+    }
+}
+
+/// Replace `root` where it appears as a whole path prefix
+fn replace_root(text: &str, root: &str, placeholder: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find(root) {
+        let after = &rest[index + root.len()..];
+        let ends_component = after
+            .chars()
+            .next()
+            .is_none_or(|c| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.')));
+        result.push_str(&rest[..index]);
+        result.push_str(if ends_component { placeholder } else { root });
+        rest = after;
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Parse `text` items of MCP content that hold a JSON object or array
+pub(crate) fn parse_text_content(mut response: Value) -> Value {
+    let items = response
+        .get_mut("content")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten();
+    for item in items {
+        let parsed = item
+            .get("text")
+            .and_then(Value::as_str)
+            .filter(|text| text.trim_start().starts_with(['{', '[']))
+            .and_then(|text| serde_json::from_str::<Value>(text).ok());
+        if let (Some(parsed), Some(object)) = (parsed, item.as_object_mut()) {
+            object.insert("text".to_string(), parsed);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_parses_text_and_applies_builtin_redactions() {
+        let response = json!({
+            "content": [{
+                "type": "text",
+                "text": r#"{"file": "/home/dev/repo/src/lib.rs", "generated_at": 1700000000, "stats": {"elapsed_ms": 12, "count": 3}, "note": "took 1.5s at 2024-05-01T10:00:00.123Z"}"#
+            }],
+            "isError": false
+        });
+        let redactor = Redactor::new().with_root(Path::new("/home/dev/repo/"), "[spec_dir]");
+
+        let normalized = redactor.normalize(&response, &[]).unwrap();
+        assert_eq!(
+            normalized,
+            json!({
+                "content": [{
+                    "text": {
+                        "file": "[spec_dir]/src/lib.rs",
+                        "generated_at": "[timestamp]",
+                        "note": "took [duration] at [timestamp]",
+                        "stats": {"count": 3, "elapsed_ms": "[duration]"}
+                    },
+                    "type": "text"
+                }],
+                "isError": false
+            })
+        );
+        // Keys are sorted whatever order the server used
+        let keys: Vec<_> = normalized["content"][0]["text"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(keys, vec!["file", "generated_at", "note", "stats"]);
+    }
+
+    #[test]
+    fn test_custom_redactions() {
+        let response =
+            json!({"content": [{"type": "text", "text": "{\"id\": \"req-42af\", \"seed\": 7}"}]});
+        let rules = [
+            Redaction {
+                path: Some("$.content[0].text.seed".to_string()),
+                pattern: None,
+                replacement: "[seed]".to_string(),
+            },
+            Redaction {
+                path: None,
+                pattern: Some("req-[0-9a-f]+".to_string()),
+                replacement: "[request]".to_string(),
+            },
+        ];
+
+        let normalized = Redactor::new().normalize(&response, &rules).unwrap();
+        assert_eq!(
+            normalized["content"][0]["text"],
+            json!({"id": "[request]", "seed": "[seed]"})
+        );
+
+        let invalid = Redaction {
+            path: None,
+            pattern: None,
+            replacement: "x".to_string(),
+        };
+        assert!(Redactor::new().normalize(&response, &[invalid]).is_err());
+    }
+
+    #[test]
+    fn test_replace_root_only_matches_whole_components() {
+        assert_eq!(
+            replace_root("/tmp/a and /tmpfiles/b and /tmp", "/tmp", "[tmp]"),
+            "[tmp]/a and /tmpfiles/b and [tmp]"
+        );
+    }
+}
@@ -0,0 +1,637 @@
+//! Parameterized test case expansion
+//!
+//! A test case with `parameters` (a list of variable sets) or `matrix` (a
+//! list of values per variable, combined as a cartesian product) stands for
+//! one case per variable set; on a tool they apply to each of its test
+//! cases. `${var}` in a case's input, expected output, description and
+//! dependencies is replaced with the variable's value, and a string that is
+//! exactly `${var}` takes the value with its JSON type. `${var.field}`
+//! reaches into object and array values.
+//!
+//! Expanded cases are named `<name>[var=value,...]`, or by interpolating the
+//! name when it contains `${...}`. A dependency on the original name becomes
+//! a dependency on every case it expanded into.
+//!
+//! `${captured.name}` refers to a value an earlier test case captured; it is
+//! left alone here and resolved by the executor when the case runs.
+
+use crate::error::{Error, Result};
+use crate::spec::{TestCase, TestSpecification};
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
+
+/// Values of a test case's variables for one expanded case
+pub type Parameters = BTreeMap<String, Value>;
+
+/// Root of the values captured from earlier test cases, resolved at run time
+pub const CAPTURED: &str = "captured";
+
+/// Most cases a single test case may expand into
+pub const MAX_EXPANDED_CASES: usize = 1000;
+
+fn variable_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*(?:\.[A-Za-z0-9_]+)*)\}")
+            .expect("valid variable pattern")
+    })
+}
+
+/// Replace every parameterized test case of `spec` by its expansion
+pub fn expand_specification(spec: &mut TestSpecification) -> Result<()> {
+    let mut expanded = HashMap::new();
+
+    for tool in spec.tools.iter_mut().flatten() {
+        let shared = parameter_sets(
+            &format!("Tool '{}'", tool.name),
+            tool.parameters.take(),
+            tool.matrix.take(),
+        )?;
+        tool.tests = expand_cases(
+            std::mem::take(&mut tool.tests),
+            shared.as_deref(),
+            &mut expanded,
+        )?;
+    }
+    for resource in spec.resources.iter_mut().flatten() {
+        resource.tests = expand_cases(std::mem::take(&mut resource.tests), None, &mut expanded)?;
+    }
+    for prompt in spec.prompts.iter_mut().flatten() {
+        prompt.tests = expand_cases(std::mem::take(&mut prompt.tests), None, &mut expanded)?;
+    }
+
+    if expanded.is_empty() {
+        return Ok(());
+    }
+
+    let mut cases: Vec<&mut TestCase> = spec
+        .tools
+        .iter_mut()
+        .flatten()
+        .flat_map(|tool| tool.tests.iter_mut())
+        .chain(
+            spec.resources
+                .iter_mut()
+                .flatten()
+                .flat_map(|resource| resource.tests.iter_mut()),
+        )
+        .chain(
+            spec.prompts
+                .iter_mut()
+                .flatten()
+                .flat_map(|prompt| prompt.tests.iter_mut()),
+        )
+        .collect();
+
+    let generated: HashSet<&String> = expanded.values().flatten().collect();
+    let mut seen = HashSet::new();
+    for case in &cases {
+        if !seen.insert(case.name.as_str()) && generated.contains(&case.name) {
+            return Err(Error::spec(format!(
+                "Parameter expansion produced test name '{}' more than once",
+                case.name
+            )));
+        }
+    }
+
+    // Names that still exist are real test cases, not expanded ones
+    let concrete: HashSet<String> = seen.into_iter().map(str::to_string).collect();
+    for case in cases.iter_mut() {
+        if let Some(dependencies) = &mut case.dependencies {
+            *dependencies = dependencies
+                .drain(..)
+                .flat_map(|dependency| match expanded.get(&dependency) {
+                    Some(names) if !concrete.contains(&dependency) => names.clone(),
+                    _ => vec![dependency],
+                })
+                .collect();
+        }
+    }
+
+    Ok(())
+}
+
+/// Expand `cases`, each combined with the `shared` sets of its tool
+fn expand_cases(
+    cases: Vec<TestCase>,
+    shared: Option<&[Parameters]>,
+    expanded: &mut HashMap<String, Vec<String>>,
+) -> Result<Vec<TestCase>> {
+    let mut result = Vec::with_capacity(cases.len());
+    for mut case in cases {
+        let own = parameter_sets(
+            &format!("Test '{}'", case.name),
+            case.parameters.take(),
+            case.matrix.take(),
+        )?;
+        let sets = match (shared, own) {
+            (None, None) => {
+                result.push(case);
+                continue;
+            }
+            (Some(shared), None) => shared.to_vec(),
+            (None, Some(own)) => own,
+            (Some(shared), Some(own)) => {
+                check_size(&case.name, shared.len() * own.len())?;
+                // The test case's own values win over its tool's
+                shared
+                    .iter()
+                    .flat_map(|base| {
+                        own.iter().map(move |vars| {
+                            let mut merged = base.clone();
+                            merged.extend(vars.clone());
+                            merged
+                        })
+                    })
+                    .collect()
+            }
+        };
+
+        let names = expanded.entry(case.name.clone()).or_default();
+        for vars in &sets {
+            let instance = instantiate(&case, vars)
+                .map_err(|e| Error::spec(format!("Test '{}': {}", case.name, e)))?;
+            names.push(instance.name.clone());
+            result.push(instance);
+        }
+    }
+    Ok(result)
+}
+
+/// Variable sets described by `parameters` and `matrix`, if either is set
+fn parameter_sets(
+    owner: &str,
+    parameters: Option<Vec<Parameters>>,
+    matrix: Option<BTreeMap<String, Vec<Value>>>,
+) -> Result<Option<Vec<Parameters>>> {
+    if parameters.is_none() && matrix.is_none() {
+        return Ok(None);
+    }
+
+    let mut sets = match parameters {
+        Some(rows) if rows.is_empty() => {
+            return Err(Error::spec(format!(
+                "{} has an empty 'parameters' list",
+                owner
+            )))
+        }
+        Some(rows) => rows,
+        None => vec![Parameters::new()],
+    };
+    for (name, values) in matrix.into_iter().flatten() {
+        if values.is_empty() {
+            return Err(Error::spec(format!(
+                "{} has no values for matrix variable '{}'",
+                owner, name
+            )));
+        }
+        if sets.iter().any(|vars| vars.contains_key(&name)) {
+            return Err(Error::spec(format!(
+                "{} sets '{}' in both 'parameters' and 'matrix'",
+                owner, name
+            )));
+        }
+        check_size(owner, sets.len() * values.len())?;
+        sets = sets
+            .iter()
+            .flat_map(|vars| {
+                values.iter().map(|value| {
+                    let mut vars = vars.clone();
+                    vars.insert(name.clone(), value.clone());
+                    vars
+                })
+            })
+            .collect();
+    }
+    Ok(Some(sets))
+}
+
+fn check_size(owner: &str, count: usize) -> Result<()> {
+    if count > MAX_EXPANDED_CASES {
+        return Err(Error::spec(format!(
+            "{} expands into {} test cases, more than the limit of {}",
+            owner, count, MAX_EXPANDED_CASES
+        )));
+    }
+    Ok(())
+}
+
+/// The case `template` stands for with `vars`
+fn instantiate(template: &TestCase, vars: &Parameters) -> std::result::Result<TestCase, String> {
+    let mut case = template.clone();
+    case.name = if template.name.contains("${") {
+        interpolate_text(&template.name, vars)?
+    } else {
+        derived_name(&template.name, vars)
+    };
+    case.description = template
+        .description
+        .as_deref()
+        .map(|text| interpolate_text(text, vars))
+        .transpose()?;
+    case.dependencies = template
+        .dependencies
+        .as_ref()
+        .map(|names| {
+            names
+                .iter()
+                .map(|name| interpolate_text(name, vars))
+                .collect::<std::result::Result<_, _>>()
+        })
+        .transpose()?;
+    case.input = interpolate(&template.input, vars)?;
+
+    let expected = serde_json::to_value(&template.expected).map_err(|e| e.to_string())?;
+    case.expected = serde_json::from_value(interpolate(&expected, vars)?)
+        .map_err(|e| format!("invalid expected output after interpolation: {}", e))?;
+    Ok(case)
+}
+
+/// `name[var=value,...]` with the variables in name order
+fn derived_name(name: &str, vars: &Parameters) -> String {
+    let values: Vec<_> = vars
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, display(value)))
+        .collect();
+    format!("{}[{}]", name, values.join(","))
+}
+
+/// `value` with `${var}` references replaced
+///
+/// `${captured.*}` references are left for [`resolve_captured`] unless
+/// `vars` has a `captured` entry.
+pub fn interpolate(value: &Value, vars: &Parameters) -> std::result::Result<Value, String> {
+    interpolate_with(value, &parameter(vars))
+}
+
+/// `text` with each `${var}` replaced by the variable's value
+pub fn interpolate_text(text: &str, vars: &Parameters) -> std::result::Result<String, String> {
+    interpolate_text_with(text, &parameter(vars))
+}
+
+/// `value` with `${captured.name}` references replaced by `captured` values
+///
+/// Other references are left as written.
+pub fn resolve_captured(
+    value: &Value,
+    captured: &serde_json::Map<String, Value>,
+) -> std::result::Result<Value, String> {
+    let vars = Parameters::from([(CAPTURED.to_string(), Value::Object(captured.clone()))]);
+    interpolate_with(value, &|path| {
+        if root(path) != CAPTURED {
+            return Ok(None);
+        }
+        lookup(&vars, path)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| format!("'${{{}}}' has not been captured", path))
+    })
+}
+
+/// Names referenced as `${captured.name}` anywhere in `value`
+pub fn captured_references(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(text) => variable_pattern()
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let mut segments = captures.get(1)?.as_str().split('.');
+                (segments.next() == Some(CAPTURED)).then(|| segments.next().map(str::to_string))?
+            })
+            .collect(),
+        Value::Array(items) => items.iter().flat_map(captured_references).collect(),
+        Value::Object(object) => object.values().flat_map(captured_references).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Value for a `${path}` reference, or `None` to leave it as written
+type Resolver<'a> = dyn Fn(&str) -> std::result::Result<Option<Value>, String> + 'a;
+
+fn parameter(
+    vars: &Parameters,
+) -> impl Fn(&str) -> std::result::Result<Option<Value>, String> + '_ {
+    move |path| match lookup(vars, path) {
+        Some(value) => Ok(Some(value.clone())),
+        None if root(path) == CAPTURED && !vars.contains_key(CAPTURED) => Ok(None),
+        None => Err(undefined(path)),
+    }
+}
+
+fn interpolate_with(value: &Value, resolve: &Resolver<'_>) -> std::result::Result<Value, String> {
+    Ok(match value {
+        Value::String(text) => match whole_reference(text) {
+            Some(path) => resolve(path)?.unwrap_or_else(|| value.clone()),
+            None => Value::String(interpolate_text_with(text, resolve)?),
+        },
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| interpolate_with(item, resolve))
+                .collect::<std::result::Result<_, _>>()?,
+        ),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), interpolate_with(value, resolve)?)))
+                .collect::<std::result::Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn interpolate_text_with(
+    text: &str,
+    resolve: &Resolver<'_>,
+) -> std::result::Result<String, String> {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for captures in variable_pattern().captures_iter(text) {
+        let (Some(whole), Some(path)) = (captures.get(0), captures.get(1)) else {
+            continue;
+        };
+        result.push_str(&text[last..whole.start()]);
+        match resolve(path.as_str())? {
+            Some(value) => result.push_str(&display(&value)),
+            None => result.push_str(whole.as_str()),
+        }
+        last = whole.end();
+    }
+    result.push_str(&text[last..]);
+    Ok(result)
+}
+
+/// The variable path when `text` is nothing but one `${...}` reference
+fn whole_reference(text: &str) -> Option<&str> {
+    let captures = variable_pattern().captures(text)?;
+    let whole = captures.get(0)?;
+    (whole.start() == 0 && whole.end() == text.len())
+        .then(|| captures.get(1).map(|m| m.as_str()))?
+}
+
+fn root(path: &str) -> &str {
+    path.split('.').next().unwrap_or(path)
+}
+
+fn lookup<'a>(vars: &'a Parameters, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let mut value = vars.get(segments.next()?)?;
+    for segment in segments {
+        value = match value {
+            Value::Object(object) => object.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn undefined(path: &str) -> String {
+    format!("'${{{}}}' refers to an undefined parameter", path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::SpecificationLoader;
+    use serde_json::json;
+
+    fn load(tools: &str) -> Result<TestSpecification> {
+        let yaml = format!(
+            r#"
+name: "Matrix Server"
+version: "1.0.0"
+capabilities:
+  tools: true
+  resources: false
+  prompts: false
+  sampling: false
+  logging: false
+server:
+  command: "server"
+  transport: "stdio"
+tools:
+{}
+"#,
+            tools
+        );
+        let mut spec = SpecificationLoader::new()?.parse_yaml(&yaml)?;
+        expand_specification(&mut spec)?;
+        Ok(spec)
+    }
+
+    fn names(spec: &TestSpecification) -> Vec<&str> {
+        spec.tools.as_ref().unwrap()[0]
+            .tests
+            .iter()
+            .map(|test| test.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_matrix_expands_cartesian_product_with_derived_names() {
+        let spec = load(
+            r#"
+  - name: "analyze"
+    matrix:
+      language: ["rust", "python"]
+      depth: [1, 2]
+    tests:
+      - name: "analyze_file"
+        input:
+          path: "fixtures/${language}/main"
+          depth: "${depth}"
+        expected:
+          fields:
+            - path: "$.language"
+              value: "${language}"
+              required: true
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            names(&spec),
+            vec![
+                "analyze_file[depth=1,language=rust]",
+                "analyze_file[depth=1,language=python]",
+                "analyze_file[depth=2,language=rust]",
+                "analyze_file[depth=2,language=python]",
+            ]
+        );
+        let first = &spec.tools.as_ref().unwrap()[0].tests[0];
+        assert_eq!(
+            first.input,
+            json!({"path": "fixtures/rust/main", "depth": 1})
+        );
+        assert_eq!(first.expected.fields[0].value, Some(json!("rust")));
+        assert!(first.parameters.is_none() && first.matrix.is_none());
+    }
+
+    #[test]
+    fn test_parameters_with_name_template_and_dependencies() {
+        let spec = load(
+            r#"
+  - name: "index"
+    tests:
+      - name: "index_${lang}"
+        parameters:
+          - { lang: "rust", file: { path: "src/lib.rs" } }
+          - { lang: "java", file: { path: "Main.java" } }
+        input: { path: "${file.path}" }
+        expected: {}
+      - name: "search"
+        dependencies: ["index_${lang}"]
+        parameters:
+          - { lang: "rust" }
+        input: {}
+        expected: {}
+      - name: "summary"
+        dependencies: ["index_${lang}", "search"]
+        input: {}
+        expected: {}
+"#,
+        )
+        .unwrap();
+
+        let tests = &spec.tools.as_ref().unwrap()[0].tests;
+        assert_eq!(
+            names(&spec),
+            vec!["index_rust", "index_java", "search[lang=rust]", "summary"]
+        );
+        assert_eq!(tests[1].input, json!({"path": "Main.java"}));
+        assert_eq!(tests[2].dependencies, Some(vec!["index_rust".to_string()]));
+        // A dependency on an expanded case covers every case it became
+        assert_eq!(
+            tests[3].dependencies,
+            Some(vec![
+                "index_rust".to_string(),
+                "index_java".to_string(),
+                "search[lang=rust]".to_string()
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_expansions_are_rejected() {
+        let cases = [
+            (
+                "undefined parameter",
+                r#"
+  - name: "t"
+    tests:
+      - name: "a"
+        matrix: { x: [1] }
+        input: { v: "${y}" }
+        expected: {}
+"#,
+            ),
+            (
+                "no values for matrix variable 'x'",
+                r#"
+  - name: "t"
+    tests:
+      - name: "a"
+        matrix: { x: [] }
+        input: {}
+        expected: {}
+"#,
+            ),
+            (
+                "'same[x=2]' more than once",
+                r#"
+  - name: "t"
+    tests:
+      - name: "same"
+        parameters: [{ x: 1 }, { x: 2 }]
+        input: {}
+        expected: {}
+      - name: "same[x=2]"
+        input: {}
+        expected: {}
+"#,
+            ),
+            (
+                "'fixed_1' more than once",
+                r#"
+  - name: "t"
+    tests:
+      - name: "fixed_${x}"
+        parameters: [{ x: 1, y: 1 }, { x: 1, y: 2 }]
+        input: {}
+        expected: {}
+"#,
+            ),
+        ];
+        for (message, tools) in cases {
+            let error = load(tools).expect_err(tools).to_string();
+            assert!(error.contains(message), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_tool_matrix_combines_with_test_parameters() {
+        let spec = load(
+            r#"
+  - name: "t"
+    matrix: { lang: ["rust", "go"] }
+    tests:
+      - name: "a"
+        parameters: [{ mode: "fast" }, { mode: "full" }]
+        input: { lang: "${lang}", mode: "${mode}" }
+        expected: {}
+      - name: "b"
+        input: { lang: "${lang}" }
+        expected: {}
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            names(&spec),
+            vec![
+                "a[lang=rust,mode=fast]",
+                "a[lang=rust,mode=full]",
+                "a[lang=go,mode=fast]",
+                "a[lang=go,mode=full]",
+                "b[lang=rust]",
+                "b[lang=go]",
+            ]
+        );
+        assert_eq!(
+            spec.tools.as_ref().unwrap()[0].tests[3].input,
+            json!({"lang": "go", "mode": "full"})
+        );
+    }
+
+    #[test]
+    fn test_captured_references_are_left_for_run_time() {
+        let vars = Parameters::from([("lang".to_string(), json!("rust"))]);
+        let input = json!({"id": "${captured.node_id}", "path": "${lang}/${captured.file.path}"});
+        assert_eq!(
+            interpolate(&input, &vars).unwrap(),
+            json!({"id": "${captured.node_id}", "path": "rust/${captured.file.path}"})
+        );
+        assert_eq!(captured_references(&input), vec!["node_id", "file"]);
+
+        let captured = serde_json::Map::from_iter([
+            ("node_id".to_string(), json!(7)),
+            ("file".to_string(), json!({"path": "src/lib.rs"})),
+        ]);
+        let resolved = resolve_captured(&interpolate(&input, &vars).unwrap(), &captured).unwrap();
+        assert_eq!(resolved, json!({"id": 7, "path": "rust/src/lib.rs"}));
+        assert!(resolve_captured(&json!("${captured.other}"), &captured).is_err());
+        // Other references are not the executor's business
+        assert_eq!(
+            resolve_captured(&json!("${HOME}"), &captured).unwrap(),
+            json!("${HOME}")
+        );
+    }
+}
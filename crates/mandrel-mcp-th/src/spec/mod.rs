@@ -6,9 +6,13 @@
 
 use crate::error::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
+pub mod expand;
+
+pub use expand::Parameters;
+
 /// Complete test specification parsed from YAML
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TestSpecification {
@@ -57,6 +61,9 @@ pub struct ServerCapabilities {
 /// Server startup and connection configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ServerConfig {
+    /// Command used to launch the server; optional when connecting to an
+    /// already running server over `http` or `sse`
+    #[serde(default)]
     pub command: String,
     #[serde(default)]
     pub args: Vec<String>,
@@ -64,13 +71,37 @@ pub struct ServerConfig {
     pub env: HashMap<String, String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub working_dir: Option<String>,
+    /// One of `stdio`, `http` (streamable HTTP) or `sse`
     pub transport: String,
+    /// Endpoint URL for the `http` and `sse` transports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Extra HTTP headers sent with every request on network transports
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
     #[serde(default = "default_startup_timeout")]
     pub startup_timeout_seconds: u32,
     #[serde(default = "default_shutdown_timeout")]
     pub shutdown_timeout_seconds: u32,
 }
 
+impl ServerConfig {
+    /// Launch `command` with `args` over the stdio transport, with default timeouts
+    pub fn stdio(command: impl Into<String>, args: Vec<String>) -> Self {
+        Self {
+            command: command.into(),
+            args,
+            env: HashMap::new(),
+            working_dir: None,
+            transport: "stdio".to_string(),
+            url: None,
+            headers: HashMap::new(),
+            startup_timeout_seconds: default_startup_timeout(),
+            shutdown_timeout_seconds: default_shutdown_timeout(),
+        }
+    }
+}
+
 /// Tool specification
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ToolSpec {
@@ -81,6 +112,12 @@ pub struct ToolSpec {
     pub input_schema: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub output_schema: Option<String>,
+    /// Variable sets every test case of the tool is repeated with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<Parameters>>,
+    /// Values per variable, repeated over every combination
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<BTreeMap<String, Vec<serde_json::Value>>>,
     #[serde(default)]
     pub tests: Vec<TestCase>,
 }
@@ -136,6 +173,15 @@ pub struct TestCase {
     /// Validation scripts to run after this test case
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub validation_scripts: Option<Vec<String>>,
+    /// Variable sets this test case is repeated with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<Parameters>>,
+    /// Values per variable, repeated over every combination
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<BTreeMap<String, Vec<serde_json::Value>>>,
+    /// JSONPaths of response values later test cases use as `${captured.<name>}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capture: Option<BTreeMap<String, String>>,
 }
 
 /// Expected output specification
@@ -155,6 +201,29 @@ pub struct ExpectedOutput {
     pub fields: Vec<FieldValidation>,
     #[serde(default = "default_allow_extra_fields")]
     pub allow_extra_fields: bool,
+    /// Name of the golden file the normalized response must match, stored
+    /// as `__snapshots__/<name>.snap` next to the specification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot: Option<String>,
+    /// Redactions applied on top of the built-in ones before the snapshot
+    /// comparison
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redactions: Vec<Redaction>,
+}
+
+/// Replaces volatile values in a response before a snapshot comparison
+///
+/// Exactly one of `path` and `pattern` is set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Redaction {
+    /// JSONPath of the values to replace, e.g. `$.content[0].text.generated_at`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Regular expression replaced wherever it matches in a string value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    #[serde(default = "default_redaction_replacement")]
+    pub replacement: String,
 }
 
 /// Field validation specification
@@ -308,8 +377,10 @@ impl SpecificationLoader {
             .await
             .map_err(|e| crate::error::Error::spec(format!("Failed to read file: {}", e)))?;
 
-        // Parse YAML content
-        self.parse_yaml(&content)
+        // Parse YAML content and expand parameterized test cases
+        let mut spec = self.parse_yaml(&content)?;
+        expand::expand_specification(&mut spec)?;
+        Ok(spec)
     }
 
     /// Load multiple specifications from a directory
@@ -350,6 +421,13 @@ impl SpecificationLoader {
 
     /// Validate a specification against schema
     pub fn validate_specification(&self, spec: &TestSpecification) -> Result<()> {
+        let transport = crate::client::Transport::try_from(&spec.server)?;
+        if matches!(transport, crate::client::Transport::Stdio) && spec.server.command.is_empty() {
+            return Err(crate::error::Error::spec(
+                "The stdio transport requires a server command".to_string(),
+            ));
+        }
+
         // Basic validation: check for inconsistent capabilities
         if spec.capabilities.tools && spec.tools.is_none() {
             return Err(crate::error::Error::spec(
@@ -369,6 +447,139 @@ impl SpecificationLoader {
             ));
         }
 
+        self.validate_snapshots(spec)?;
+        self.validate_captures(spec)
+    }
+
+    /// Check capture rules and that `${captured.<name>}` references are captured
+    /// by a test case the referencing case depends on
+    fn validate_captures(&self, spec: &TestSpecification) -> Result<()> {
+        let tests: HashMap<&str, &TestCase> = spec
+            .tools
+            .iter()
+            .flatten()
+            .flat_map(|tool| &tool.tests)
+            .chain(spec.resources.iter().flatten().flat_map(|r| &r.tests))
+            .chain(spec.prompts.iter().flatten().flat_map(|p| &p.tests))
+            .map(|test| (test.name.as_str(), test))
+            .collect();
+
+        for test in tests.values() {
+            for (name, path) in test.capture.iter().flatten() {
+                let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid_name {
+                    return Err(crate::error::Error::spec(format!(
+                        "Test '{}' has invalid capture name '{}': use letters, digits and '_'",
+                        test.name, name
+                    )));
+                }
+                jsonpath_lib::Compiled::compile(path).map_err(|e| {
+                    crate::error::Error::spec(format!(
+                        "Test '{}' captures '{}' with invalid JSONPath '{}': {}",
+                        test.name, name, path, e
+                    ))
+                })?;
+            }
+
+            let expected = serde_json::to_value(&test.expected)?;
+            let mut references = expand::captured_references(&test.input);
+            references.extend(expand::captured_references(&expected));
+            if references.is_empty() {
+                continue;
+            }
+
+            // Everything `test` transitively depends on, whose captures it can use
+            let mut available = std::collections::HashSet::new();
+            let mut visited = std::collections::HashSet::new();
+            let mut pending: Vec<&str> = test
+                .dependencies
+                .iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            while let Some(dependency) = pending.pop() {
+                let Some(source) = tests.get(dependency).filter(|_| visited.insert(dependency))
+                else {
+                    continue;
+                };
+                available.extend(
+                    source
+                        .capture
+                        .iter()
+                        .flatten()
+                        .map(|(name, _)| name.as_str()),
+                );
+                pending.extend(source.dependencies.iter().flatten().map(String::as_str));
+            }
+
+            if let Some(missing) = references
+                .iter()
+                .find(|name| !available.contains(name.as_str()))
+            {
+                return Err(crate::error::Error::spec(format!(
+                    "Test '{}' uses '${{captured.{}}}', which none of its dependencies capture",
+                    test.name, missing
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check snapshot names are unique file names and redaction rules are well formed
+    fn validate_snapshots(&self, spec: &TestSpecification) -> Result<()> {
+        let tool_tests = spec.tools.iter().flatten().flat_map(|tool| &tool.tests);
+        let resource_tests = spec.resources.iter().flatten().flat_map(|r| &r.tests);
+        let prompt_tests = spec
+            .prompts
+            .iter()
+            .flatten()
+            .flat_map(|prompt| &prompt.tests);
+
+        let mut names = std::collections::HashMap::new();
+        for test in tool_tests.chain(resource_tests).chain(prompt_tests) {
+            if let Some(name) = &test.expected.snapshot {
+                let valid = !name.is_empty()
+                    && !name.starts_with('.')
+                    && name
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+                if !valid {
+                    return Err(crate::error::Error::spec(format!(
+                        "Test '{}' has invalid snapshot name '{}': use letters, digits, '_', '-' and '.'",
+                        test.name, name
+                    )));
+                }
+                if let Some(other) = names.insert(name.as_str(), test.name.as_str()) {
+                    return Err(crate::error::Error::spec(format!(
+                        "Tests '{}' and '{}' use the same snapshot '{}'",
+                        other, test.name, name
+                    )));
+                }
+            }
+
+            for redaction in &test.expected.redactions {
+                match (&redaction.path, &redaction.pattern) {
+                    (Some(_), None) => {}
+                    (None, Some(pattern)) => {
+                        regex::Regex::new(pattern).map_err(|e| {
+                            crate::error::Error::spec(format!(
+                                "Test '{}' has invalid redaction pattern '{}': {}",
+                                test.name, pattern, e
+                            ))
+                        })?;
+                    }
+                    _ => {
+                        return Err(crate::error::Error::spec(format!(
+                            "Test '{}' has a redaction without exactly one of 'path' and 'pattern'",
+                            test.name
+                        )))
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -404,6 +615,10 @@ fn default_shutdown_timeout() -> u32 {
 fn default_allow_extra_fields() -> bool {
     true
 }
+fn default_redaction_replacement() -> String {
+    "[redacted]".to_string()
+}
+
 fn default_field_required() -> bool {
     true
 }
@@ -437,6 +652,8 @@ impl Default for ExpectedOutput {
             schema: None,
             fields: Vec::new(),
             allow_extra_fields: true,
+            snapshot: None,
+            redactions: Vec::new(),
         }
     }
 }
@@ -453,6 +670,9 @@ impl Default for TestCase {
             skip: false,
             tags: Vec::new(),
             validation_scripts: None,
+            parameters: None,
+            matrix: None,
+            capture: None,
         }
     }
 }
@@ -811,6 +1031,8 @@ server:
                 env: HashMap::new(),
                 working_dir: None,
                 transport: "stdio".to_string(),
+                url: None,
+                headers: HashMap::new(),
                 startup_timeout_seconds: 30,
                 shutdown_timeout_seconds: 10,
             },
@@ -819,6 +1041,8 @@ server:
                 description: None,
                 input_schema: None,
                 output_schema: None,
+                parameters: None,
+                matrix: None,
                 tests: vec![],
             }]),
             resources: None,
@@ -851,6 +1075,8 @@ server:
                 env: HashMap::new(),
                 working_dir: None,
                 transport: "stdio".to_string(),
+                url: None,
+                headers: HashMap::new(),
                 startup_timeout_seconds: 30,
                 shutdown_timeout_seconds: 10,
             },
@@ -866,6 +1092,119 @@ server:
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_snapshot_names_and_redactions() {
+        let loader = SpecificationLoader::new().expect("Failed to create loader");
+        let spec_with = |second: &str| {
+            let yaml = format!(
+                r#"
+name: "Snapshot Server"
+version: "1.0.0"
+capabilities:
+  tools: true
+  resources: false
+  prompts: false
+  sampling: false
+  logging: false
+server:
+  command: "server"
+  transport: "stdio"
+tools:
+  - name: "stats"
+    tests:
+      - name: "first"
+        input: {{}}
+        expected:
+          snapshot: "stats"
+          redactions:
+            - path: "$.content[0].text.id"
+      - name: "second"
+        input: {{}}
+        expected:
+{}
+"#,
+                second
+            );
+            loader.parse_yaml(&yaml).unwrap()
+        };
+
+        let valid = spec_with("          snapshot: \"stats-empty\"");
+        assert!(loader.validate_specification(&valid).is_ok());
+        assert_eq!(
+            valid.tools.unwrap()[0].tests[0].expected.redactions[0].replacement,
+            "[redacted]"
+        );
+
+        for invalid in [
+            "          snapshot: \"stats\"",
+            "          snapshot: \"../stats\"",
+            "          redactions:\n            - pattern: \"(unclosed\"",
+            "          redactions:\n            - replacement: \"x\"",
+        ] {
+            let spec = spec_with(invalid);
+            assert!(
+                loader.validate_specification(&spec).is_err(),
+                "accepted {}",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_captured_references_need_a_capturing_dependency() {
+        let loader = SpecificationLoader::new().expect("Failed to create loader");
+        let spec_with = |dependencies: &str, path: &str| {
+            let yaml = format!(
+                r#"
+name: "Capture Server"
+version: "1.0.0"
+capabilities:
+  tools: true
+  resources: false
+  prompts: false
+  sampling: false
+  logging: false
+server:
+  command: "server"
+  transport: "stdio"
+tools:
+  - name: "search"
+    tests:
+      - name: "search_symbols"
+        input: {{ query: "main" }}
+        expected: {{}}
+        capture:
+          node_id: "{}"
+      - name: "index"
+        input: {{}}
+        expected: {{}}
+        dependencies: ["search_symbols"]
+  - name: "explain_symbol"
+    tests:
+      - name: "explain_first_result"
+        input: {{ symbol_id: "${{captured.node_id}}" }}
+        expected: {{}}
+        dependencies: {}
+"#,
+                path, dependencies
+            );
+            loader.parse_yaml(&yaml).unwrap()
+        };
+        let path = "$.content[0].text.results[0].node_id";
+
+        // Captures reach through chains of dependencies
+        for dependencies in [r#"["search_symbols"]"#, r#"["index"]"#] {
+            let spec = spec_with(dependencies, path);
+            assert!(loader.validate_specification(&spec).is_ok());
+        }
+        assert!(loader
+            .validate_specification(&spec_with("[]", path))
+            .is_err());
+        assert!(loader
+            .validate_specification(&spec_with(r#"["search_symbols"]"#, "$.["))
+            .is_err());
+    }
+
     // ========================================================================
     // PHASE 4: Directory Loading Tests (Should FAIL until GREEN phase)
     // ========================================================================
@@ -91,51 +91,12 @@ impl JsonPathEvaluator {
 
     /// Extract values from data using JSONPath expression
     pub fn extract_values(&self, data: &Value, path: &str) -> Result<Vec<Value>, JsonPathError> {
-        // Simple JSONPath implementation for common patterns
-        // ENHANCEMENT: Use jsonpath_lib crate for full JSONPath support in Phase 2
-
-        if path == "$" {
-            return Ok(vec![data.clone()]);
-        }
-
-        if let Some(stripped) = path.strip_prefix("$.") {
-            let path_parts: Vec<&str> = stripped.split('.').collect();
-            let mut current = data;
-
-            for part in path_parts {
-                if part.contains('[') && part.ends_with(']') {
-                    // Handle array access like "content[0]"
-                    let field_name = part.split('[').next().unwrap();
-                    let index_str = part.split('[').nth(1).unwrap().trim_end_matches(']');
-
-                    current = current.get(field_name).ok_or_else(|| {
-                        JsonPathError::PathNotFound(format!("Field {} not found", field_name))
-                    })?;
-
-                    if let Ok(index) = index_str.parse::<usize>() {
-                        current = current.get(index).ok_or_else(|| {
-                            JsonPathError::PathNotFound(format!("Array index {} not found", index))
-                        })?;
-                    } else {
-                        return Err(JsonPathError::InvalidExpression(format!(
-                            "Invalid array index: {}",
-                            index_str
-                        )));
-                    }
-                } else {
-                    current = current.get(part).ok_or_else(|| {
-                        JsonPathError::PathNotFound(format!("Field {} not found", part))
-                    })?;
-                }
-            }
-
-            Ok(vec![current.clone()])
-        } else {
-            Err(JsonPathError::InvalidExpression(format!(
-                "Unsupported JSONPath: {}",
-                path
-            )))
+        let values = jsonpath_lib::select(data, path)
+            .map_err(|e| JsonPathError::InvalidExpression(format!("{}: {:?}", path, e)))?;
+        if values.is_empty() {
+            return Err(JsonPathError::PathNotFound(path.to_string()));
         }
+        Ok(values.into_iter().cloned().collect())
     }
 
     /// Apply a constraint to extracted values
@@ -12,9 +12,11 @@
 //! - Detailed validation failure diagnostics
 
 use crate::error::Result;
+use crate::snapshot::SnapshotDiff;
 use crate::spec::{ExpectedOutput, FieldValidation, FieldValidationType};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 // Re-export validation components
@@ -156,6 +158,25 @@ pub enum ValidationError {
 
     #[error("Cache overflow: maximum cache size {max_size} exceeded")]
     CacheOverflow { max_size: usize },
+
+    #[error("Validation script failed: {script} - {message}")]
+    ScriptError { script: String, message: String },
+
+    #[error("Error expectation failed: {message}")]
+    ErrorExpectation { message: String },
+
+    #[error("Capture '{name}' failed: {path} - {message}")]
+    CaptureError {
+        name: String,
+        path: String,
+        message: String,
+    },
+
+    #[error("Snapshot '{}' does not match: {}", .0.snapshot, .0.summary())]
+    SnapshotMismatch(SnapshotDiff),
+
+    #[error("Snapshot '{snapshot}' does not exist: run with --update-snapshots to create {}", file.display())]
+    SnapshotMissing { snapshot: String, file: PathBuf },
 }
 
 /// Main validation result containing comprehensive diagnostics
@@ -726,6 +747,8 @@ mod tests {
             schema: None,
             fields: vec![create_test_field_validation()],
             allow_extra_fields: true,
+            snapshot: None,
+            redactions: Vec::new(),
         }
     }
 